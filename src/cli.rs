@@ -0,0 +1,602 @@
+use clap::Parser;
+use console::Term;
+
+use crate::cmd::{
+    alias::handle_alias_cmd,
+    config::{
+        auth::handle_auth_cmd, credentials::init_credential_store, handle_config_cmd,
+        logs::init_logging, record::RecordLayer,
+    },
+    groups::handle_groups_cmd,
+    handle_error, init_base_url,
+    jobs::run_job,
+    journal::Journal,
+    models::{DcCmd, DcCmdCommand, DcCmdError, FoldersCommand, ListOptions, PasswordAuth},
+    nodes::{
+        copy_nodes, create_folder, create_room, delete_node,
+        download::download,
+        expiration::set_expiration,
+        list_nodes,
+        migrate::migrate,
+        models::{
+            CmdCopyOptions, CmdDownloadOptions, CmdExpireOptions, CmdListNodesOptions,
+            CmdMigrateOptions, CmdMkRoomOptions, CmdMkdirOptions, CmdRmOptions, CmdSyncOptions,
+            CmdTransferOptions, CmdUpdateFolderOptions, CmdUploadOptions, CmdWatchOptions,
+            ShareNotification,
+        },
+        move_nodes,
+        sync::sync,
+        transfer::transfer_node,
+        update::update_folder,
+        upload::{upload, watch_upload},
+    },
+    print_version, read_encryption_password_file,
+    reports::handle_reports_cmd,
+    rooms::handle_rooms_cmd,
+    selftest::selftest,
+    undo::undo_journal,
+    users::handle_users_cmd,
+    utils::{
+        cancel::run_cancellable,
+        confirm::init_confirmation,
+        dates::{parse_cutoff, parse_duration, parse_expiration},
+        deadline::{finish_with_deadline, init_deadline},
+        fields::init_csv_fields,
+        rate_limit::init_rate_limit,
+        strings::{parse_size, with_node_id},
+    },
+    webhooks::handle_webhooks_cmd,
+};
+
+#[cfg(feature = "mock-server")]
+use crate::cmd::selftest::selftest_mock;
+
+/// parses the command line arguments and runs the command (entry point of the binary)
+#[allow(clippy::too_many_lines)]
+pub async fn run() {
+    let opt = DcCmd::parse();
+
+    let term = Term::stdout();
+    let err_term = Term::stderr();
+
+    let record = match opt
+        .record
+        .as_deref()
+        .map(|path| RecordLayer::new(path, opt.record_bodies))
+        .transpose()
+    {
+        Ok(record) => record,
+        Err(e) => {
+            handle_error(&err_term, &e);
+            return;
+        }
+    };
+
+    init_logging(&err_term, opt.debug, record);
+
+    if opt.dry_run && !opt.cmd.supports_dry_run() {
+        handle_error(
+            &err_term,
+            &DcCmdError::InvalidArgument("--dry-run is not supported by this command.".to_string()),
+        );
+        return;
+    }
+
+    let password_auth = match (opt.username, opt.password) {
+        (Some(username), Some(password)) => Some(PasswordAuth(username, password)),
+        _ => None,
+    };
+
+    let journal = Journal::new(opt.journal, opt.dry_run);
+
+    let encryption_password = match opt
+        .encryption_password_file
+        .as_deref()
+        .map(read_encryption_password_file)
+        .transpose()
+    {
+        Ok(password) => password.or(opt.encryption_password),
+        Err(e) => {
+            handle_error(&err_term, &e);
+            return;
+        }
+    };
+
+    let confirm_size = match opt.confirm_size.as_deref().map(parse_size).transpose() {
+        Ok(confirm_size) => confirm_size,
+        Err(e) => {
+            handle_error(&err_term, &e);
+            return;
+        }
+    };
+
+    init_confirmation(opt.confirm_files, confirm_size, opt.yes);
+
+    if let Some(fields) = opt.fields.as_deref() {
+        init_csv_fields(fields);
+    }
+
+    if let Some(rate_limit) = opt.rate_limit {
+        init_rate_limit(rate_limit);
+    }
+
+    match opt.deadline.as_deref().map(parse_duration).transpose() {
+        Ok(Some(deadline)) => init_deadline(deadline),
+        Ok(None) => (),
+        Err(e) => {
+            handle_error(&err_term, &e);
+            return;
+        }
+    }
+
+    init_credential_store(opt.credential_store, opt.credential_key_file);
+
+    if let Some(base_url) = opt.base_url.as_deref() {
+        if let Err(e) = init_base_url(base_url) {
+            handle_error(&err_term, &e);
+            return;
+        }
+    }
+
+    let res = match opt.cmd {
+        DcCmdCommand::Download {
+            source,
+            target,
+            velocity,
+            recursive,
+            share_password,
+            include_rooms,
+            rename_invalid,
+            flat,
+            min_free_space,
+            no_space_check,
+            include,
+            exclude,
+            file_type,
+            continue_on_error,
+            rescue_secret,
+            resume,
+            node_id,
+        } => {
+            run_cancellable(
+                &err_term,
+                download(
+                    with_node_id(source, node_id),
+                    target,
+                    CmdDownloadOptions::new(
+                        recursive,
+                        velocity,
+                        password_auth,
+                        encryption_password,
+                        share_password,
+                        include_rooms,
+                        rename_invalid,
+                        flat,
+                        min_free_space,
+                        no_space_check,
+                        include,
+                        exclude,
+                        &file_type,
+                        continue_on_error,
+                        rescue_secret,
+                        resume,
+                    ),
+                ),
+            )
+            .await
+        }
+        DcCmdCommand::Upload {
+            source,
+            target,
+            overwrite,
+            keep_share_links,
+            classification,
+            expiration,
+            velocity,
+            recursive,
+            skip_root,
+            share,
+            share_password,
+            share_expiration,
+            notify_emails,
+            notify_message,
+            generate_password,
+            password_file,
+            skip_unchanged,
+            continue_on_error,
+            verify,
+            dedupe,
+            rename,
+            prefix,
+            suffix,
+            no_direct_s3,
+            resume,
+            watch,
+            debounce,
+            exclude,
+        } => {
+            let expiration = match expiration.as_deref().map(parse_expiration).transpose() {
+                Ok(expiration) => expiration,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let share_expiration = match share_expiration
+                .as_deref()
+                .map(parse_expiration)
+                .transpose()
+            {
+                Ok(share_expiration) => share_expiration,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let share_notification = match ShareNotification::try_new(notify_emails, notify_message)
+            {
+                Ok(share_notification) => share_notification,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let opts = CmdUploadOptions::new(
+                overwrite,
+                keep_share_links,
+                recursive,
+                skip_root,
+                share,
+                classification,
+                expiration,
+                velocity,
+                password_auth,
+                encryption_password,
+                share_password,
+                share_expiration,
+                share_notification,
+                generate_password,
+                password_file,
+                skip_unchanged,
+                continue_on_error,
+                verify,
+                dedupe,
+                rename,
+                prefix,
+                suffix,
+                no_direct_s3,
+                resume,
+            );
+
+            if watch {
+                run_cancellable(
+                    &err_term,
+                    watch_upload(
+                        term,
+                        source.into(),
+                        target,
+                        opts,
+                        CmdWatchOptions::new(debounce, exclude),
+                    ),
+                )
+                .await
+            } else {
+                run_cancellable(&err_term, upload(term, source.into(), target, opts)).await
+            }
+        }
+        DcCmdCommand::Transfer {
+            source,
+            target,
+            overwrite,
+            keep_share_links,
+            classification,
+            expiration,
+            velocity,
+            share,
+            share_password,
+            share_expiration,
+            notify_emails,
+            notify_message,
+            generate_password,
+            password_file,
+        } => {
+            let expiration = match expiration.as_deref().map(parse_expiration).transpose() {
+                Ok(expiration) => expiration,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let share_expiration = match share_expiration
+                .as_deref()
+                .map(parse_expiration)
+                .transpose()
+            {
+                Ok(share_expiration) => share_expiration,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let share_notification = match ShareNotification::try_new(notify_emails, notify_message)
+            {
+                Ok(share_notification) => share_notification,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            run_cancellable(
+                &err_term,
+                transfer_node(
+                    term,
+                    source,
+                    target,
+                    CmdTransferOptions::new(
+                        overwrite,
+                        keep_share_links,
+                        share,
+                        classification,
+                        expiration,
+                        velocity,
+                        share_password,
+                        share_expiration,
+                        share_notification,
+                        generate_password,
+                        password_file,
+                    ),
+                ),
+            )
+            .await
+        }
+        DcCmdCommand::Migrate {
+            source,
+            target,
+            path,
+            velocity,
+            skip_permissions,
+            continue_on_error,
+        } => {
+            run_cancellable(
+                &err_term,
+                migrate(
+                    term,
+                    source,
+                    target,
+                    CmdMigrateOptions::new(path, velocity, skip_permissions, continue_on_error),
+                ),
+            )
+            .await
+        }
+        DcCmdCommand::Sync {
+            source,
+            target,
+            classification,
+            velocity,
+            daemon: _,
+            interval,
+        } => {
+            let interval = match interval.as_deref().map(parse_duration).transpose() {
+                Ok(interval) => interval,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            run_cancellable(
+                &err_term,
+                sync(
+                    term,
+                    source.into(),
+                    target,
+                    CmdSyncOptions::new(
+                        interval,
+                        classification,
+                        velocity,
+                        password_auth,
+                        encryption_password,
+                    ),
+                ),
+            )
+            .await
+        }
+        DcCmdCommand::Ls {
+            source,
+            filter,
+            long,
+            human_readable,
+            managed,
+            all,
+            offset,
+            limit,
+            acl,
+            deleted,
+            du,
+            ids,
+            no_header,
+            quiet,
+            no_pager,
+            output,
+            file_type,
+        } => {
+            let list_opts = ListOptions::new(filter, offset, limit, all, false);
+            let opts = CmdListNodesOptions::new(
+                list_opts,
+                human_readable,
+                long,
+                managed,
+                acl,
+                deleted,
+                du,
+                ids,
+                no_header,
+                quiet,
+                no_pager,
+                output,
+                &file_type,
+                password_auth,
+            );
+
+            list_nodes(term, source, opts).await
+        }
+        DcCmdCommand::Cp {
+            source,
+            target,
+            resolution,
+            keep_share_links,
+            node_id,
+        } => {
+            let opts = CmdCopyOptions::new(password_auth, resolution, keep_share_links, journal);
+            copy_nodes(term, with_node_id(source, node_id), target, opts).await
+        }
+        DcCmdCommand::Mv {
+            source,
+            target,
+            resolution,
+            keep_share_links,
+        } => {
+            let opts = CmdCopyOptions::new(password_auth, resolution, keep_share_links, journal);
+            move_nodes(term, source, target, opts).await
+        }
+        DcCmdCommand::Mkdir {
+            source,
+            classification,
+            notes,
+            parents,
+        } => {
+            create_folder(
+                term,
+                source,
+                CmdMkdirOptions::new(classification, notes, parents, password_auth, journal),
+            )
+            .await
+        }
+        DcCmdCommand::Mkroom {
+            inherit_permissions,
+            source,
+            classification,
+            admin_users,
+            admin_groups,
+            parents,
+        } => {
+            create_room(
+                term,
+                source,
+                CmdMkRoomOptions::new(
+                    inherit_permissions,
+                    classification,
+                    password_auth,
+                    admin_users,
+                    admin_groups,
+                    parents,
+                    journal,
+                ),
+            )
+            .await
+        }
+        DcCmdCommand::Rm {
+            source,
+            recursive,
+            permanent,
+            older_than,
+            newer_than,
+            node_id,
+        } => {
+            let older_than = match older_than.as_deref().map(parse_cutoff).transpose() {
+                Ok(older_than) => older_than,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            let newer_than = match newer_than.as_deref().map(parse_cutoff).transpose() {
+                Ok(newer_than) => newer_than,
+                Err(e) => {
+                    handle_error(&err_term, &e);
+                    return;
+                }
+            };
+
+            delete_node(
+                term,
+                with_node_id(source, node_id),
+                CmdRmOptions::new(
+                    recursive,
+                    permanent,
+                    older_than,
+                    newer_than,
+                    password_auth,
+                    journal,
+                ),
+            )
+            .await
+        }
+        // --at and --clear are exclusive (one is required)
+        DcCmdCommand::Expire {
+            source,
+            at,
+            clear: _,
+        } => set_expiration(term, source, CmdExpireOptions::new(at, password_auth)).await,
+        DcCmdCommand::Folders { cmd } => match cmd {
+            FoldersCommand::Update {
+                source,
+                name,
+                notes,
+                classification,
+            } => {
+                update_folder(
+                    term,
+                    source,
+                    CmdUpdateFolderOptions::new(name, notes, classification, password_auth),
+                )
+                .await
+            }
+        },
+        DcCmdCommand::Users { cmd } => handle_users_cmd(cmd, term, journal).await,
+        DcCmdCommand::Groups { cmd } => handle_groups_cmd(cmd, term, journal).await,
+        DcCmdCommand::Rooms { cmd } => handle_rooms_cmd(cmd, term, journal).await,
+        DcCmdCommand::Webhooks { cmd } => handle_webhooks_cmd(cmd, term).await,
+        DcCmdCommand::Run {
+            source,
+            var,
+            continue_on_error,
+        } => {
+            run_job(
+                term,
+                source,
+                var,
+                continue_on_error,
+                password_auth,
+                encryption_password,
+                journal,
+            )
+            .await
+        }
+        DcCmdCommand::Undo { target, source } => undo_journal(term, target, source, journal).await,
+        #[cfg(feature = "mock-server")]
+        DcCmdCommand::Selftest { mock: true, .. } => selftest_mock(term).await,
+        DcCmdCommand::Selftest { target, .. } => match target {
+            Some(target) => selftest(term, target, password_auth, encryption_password).await,
+            None => Err(DcCmdError::InvalidArgument(
+                "Missing target (room or folder).".to_string(),
+            )),
+        },
+        DcCmdCommand::Version => print_version(&term),
+        DcCmdCommand::Auth { cmd } => handle_auth_cmd(cmd, term, password_auth).await,
+        DcCmdCommand::Alias { cmd } => handle_alias_cmd(cmd, &term),
+        DcCmdCommand::Config { cmd } => handle_config_cmd(cmd, term).await,
+        DcCmdCommand::Reports { cmd } => handle_reports_cmd(cmd, term).await,
+    };
+
+    if let Err(e) = finish_with_deadline(res) {
+        handle_error(&err_term, &e);
+    }
+}
@@ -0,0 +1,236 @@
+use std::{borrow::Cow, collections::BTreeMap, path::Path, sync::OnceLock};
+
+use console::Term;
+use tracing::{error, warn};
+
+use super::{
+    config::get_or_create_config_dir,
+    models::{AliasCommand, DcCmdError},
+    utils::strings::format_success_message,
+};
+
+pub const ALIASES_FILE: &str = "aliases.json";
+
+/// alias name -> DRACOON url (domain and path, e.g. `dracoon.team/rooms/projects/p1`)
+type AliasIndex = BTreeMap<String, String>;
+
+static ALIASES: OnceLock<AliasIndex> = OnceLock::new();
+
+/// expands an alias path (e.g. `proj1:/subfolder`) into the full url - other paths are returned as they are
+pub fn resolve_alias(path: &str) -> Cow<'_, str> {
+    let Some((name, _)) = split_alias(path) else {
+        return Cow::Borrowed(path);
+    };
+
+    let aliases = ALIASES.get_or_init(|| {
+        load_aliases().unwrap_or_else(|e| {
+            warn!("Failed to load aliases: {}", e);
+            AliasIndex::new()
+        })
+    });
+
+    match aliases.get(name) {
+        Some(url) => Cow::Owned(expand_alias(path, url)),
+        None => Cow::Borrowed(path),
+    }
+}
+
+/// splits `name:/rest` into alias name and path - urls (`https://`) and domains are no alias
+fn split_alias(path: &str) -> Option<(&str, &str)> {
+    let (name, rest) = path.trim().split_once(':')?;
+
+    if rest.starts_with("//") || !is_valid_alias_name(name) {
+        return None;
+    }
+
+    Some((name, rest))
+}
+
+/// alias names are at least two characters (no drive letters) and contain no dots (no domains)
+fn is_valid_alias_name(name: &str) -> bool {
+    name.len() > 1
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn expand_alias(path: &str, url: &str) -> String {
+    let rest = split_alias(path).map_or("", |(_, rest)| rest);
+
+    format!(
+        "{}/{}",
+        url.trim_end_matches('/'),
+        rest.trim_start_matches('/')
+    )
+}
+
+/// normalizes the url of an alias (no scheme, no trailing slash)
+fn normalize_alias_url(url: &str) -> Result<String, DcCmdError> {
+    let url = url.trim();
+
+    if url.starts_with("http://") {
+        error!("HTTP is not supported.");
+        return Err(DcCmdError::InvalidUrl(url.to_string()));
+    }
+
+    let url = url.trim_start_matches("https://").trim_end_matches('/');
+
+    if url.is_empty() || split_alias(url).is_some() {
+        return Err(DcCmdError::InvalidUrl(url.to_string()));
+    }
+
+    Ok(url.to_string())
+}
+
+fn load_aliases() -> Result<AliasIndex, DcCmdError> {
+    read_aliases(&get_or_create_config_dir().join(ALIASES_FILE))
+}
+
+fn read_aliases(path: &Path) -> Result<AliasIndex, DcCmdError> {
+    if !path.exists() {
+        return Ok(AliasIndex::new());
+    }
+
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        error!("Error reading {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        error!("Invalid aliases file {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })
+}
+
+fn write_aliases(path: &Path, aliases: &AliasIndex) -> Result<(), DcCmdError> {
+    let content = serde_json::to_string_pretty(aliases).map_err(|_| DcCmdError::IoError)?;
+
+    std::fs::write(path, content).map_err(|e| {
+        error!("Error writing {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })
+}
+
+fn add_alias(name: &str, url: &str) -> Result<String, DcCmdError> {
+    if !is_valid_alias_name(name) {
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Invalid alias name: {name} (at least 2 characters, only letters, digits, - and _)"
+        )));
+    }
+
+    let url = normalize_alias_url(url)?;
+    let path = get_or_create_config_dir().join(ALIASES_FILE);
+
+    let mut aliases = read_aliases(&path)?;
+    aliases.insert(name.to_string(), url.clone());
+    write_aliases(&path, &aliases)?;
+
+    Ok(url)
+}
+
+fn remove_alias(name: &str) -> Result<(), DcCmdError> {
+    let path = get_or_create_config_dir().join(ALIASES_FILE);
+
+    let mut aliases = read_aliases(&path)?;
+    if aliases.remove(name).is_none() {
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Alias not found: {name}"
+        )));
+    }
+
+    write_aliases(&path, &aliases)
+}
+
+fn list_aliases(term: &Term) -> Result<(), DcCmdError> {
+    let aliases = load_aliases()?;
+
+    if aliases.is_empty() {
+        term.write_line("No aliases found.")
+            .map_err(|_| DcCmdError::IoError)?;
+        return Ok(());
+    }
+
+    term.write_line(&format!("{:<20} {}", "Alias", "Target"))
+        .map_err(|_| DcCmdError::IoError)?;
+
+    for (name, url) in &aliases {
+        term.write_line(&format!("{name:<20} {url}"))
+            .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(())
+}
+
+pub fn handle_alias_cmd(cmd: AliasCommand, term: &Term) -> Result<(), DcCmdError> {
+    match cmd {
+        AliasCommand::Add { name, target } => {
+            let url = add_alias(&name, &target)?;
+            term.write_line(&format_success_message(&format!(
+                "Added alias {name} ({url})."
+            )))
+            .map_err(|_| DcCmdError::IoError)
+        }
+        AliasCommand::Rm { name } => {
+            remove_alias(&name)?;
+            term.write_line(&format_success_message(&format!("Removed alias {name}.")))
+                .map_err(|_| DcCmdError::IoError)
+        }
+        AliasCommand::Ls => list_aliases(term),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_alias() {
+        assert_eq!(
+            Some(("proj1", "/subfolder")),
+            split_alias("proj1:/subfolder")
+        );
+        assert_eq!(Some(("proj1", "")), split_alias("proj1:"));
+        assert_eq!(None, split_alias("https://dracoon.team/rooms"));
+        assert_eq!(None, split_alias("dracoon://dracoon.team/123"));
+        assert_eq!(None, split_alias("dracoon.team:443/rooms"));
+        assert_eq!(None, split_alias("C:/Users/foo"));
+        assert_eq!(None, split_alias("dracoon.team/rooms"));
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let url = "dracoon.team/rooms/projects/p1";
+
+        assert_eq!(
+            "dracoon.team/rooms/projects/p1/subfolder",
+            expand_alias("proj1:/subfolder", url)
+        );
+        assert_eq!(
+            "dracoon.team/rooms/projects/p1/subfolder/",
+            expand_alias("proj1:subfolder/", url)
+        );
+        assert_eq!(
+            "dracoon.team/rooms/projects/p1/",
+            expand_alias("proj1:", url)
+        );
+    }
+
+    #[test]
+    fn test_normalize_alias_url() {
+        assert_eq!(
+            "dracoon.team/rooms/projects/p1",
+            normalize_alias_url("https://dracoon.team/rooms/projects/p1/").unwrap()
+        );
+        assert!(normalize_alias_url("http://dracoon.team/rooms").is_err());
+        assert!(normalize_alias_url("proj2:/sub").is_err());
+        assert!(normalize_alias_url("").is_err());
+    }
+
+    #[test]
+    fn test_resolve_unknown_path() {
+        assert_eq!(
+            "dracoon.team/rooms/projects",
+            resolve_alias("dracoon.team/rooms/projects")
+        );
+    }
+}
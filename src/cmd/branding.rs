@@ -0,0 +1,153 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::cmd::utils::rate_limit::send_rate_limited;
+
+/// branding is optional - prompts are shown without instance name if it takes too long
+const BRANDING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// instance names (product name of the branding) by base url
+static INSTANCE_NAMES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicBranding {
+    product_name: Option<String>,
+}
+
+fn instance_names() -> &'static Mutex<HashMap<String, String>> {
+    INSTANCE_NAMES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn to_key(base_url: &str) -> String {
+    base_url.trim_end_matches('/').to_lowercase()
+}
+
+/// fetches the instance name from the public branding (cached per base url)
+pub async fn load_instance_name(base_url: &str) -> Option<String> {
+    if let Some(name) = get_instance_name(base_url) {
+        return Some(name);
+    }
+
+    let url = format!(
+        "{}/branding/api/v1/public/branding",
+        base_url.trim_end_matches('/')
+    );
+
+    let request = Client::builder()
+        .timeout(BRANDING_TIMEOUT)
+        .build()
+        .ok()?
+        .get(url);
+
+    let response = send_rate_limited(request)
+        .await
+        .and_then(reqwest::Response::error_for_status);
+
+    let branding = match response {
+        Ok(response) => response.json::<PublicBranding>().await,
+        Err(e) => Err(e),
+    };
+
+    let name = match branding {
+        Ok(branding) => parse_instance_name(branding.product_name)?,
+        Err(e) => {
+            debug!("Error fetching branding of {}: {}", base_url, e);
+            return None;
+        }
+    };
+
+    instance_names()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(to_key(base_url), name.clone());
+
+    Some(name)
+}
+
+/// returns the cached instance name (see `load_instance_name`)
+pub fn get_instance_name(base_url: &str) -> Option<String> {
+    instance_names()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(&to_key(base_url))
+        .cloned()
+}
+
+/// prefixes a prompt with the instance name (if known)
+pub fn instance_prompt(base_url: &str, prompt: &str) -> String {
+    format_prompt(get_instance_name(base_url).as_deref(), prompt)
+}
+
+/// instance name and base url for log lines (base url only if name is unknown)
+pub fn instance_label(base_url: &str) -> String {
+    format_label(get_instance_name(base_url).as_deref(), base_url)
+}
+
+fn parse_instance_name(product_name: Option<String>) -> Option<String> {
+    product_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn format_prompt(name: Option<&str>, prompt: &str) -> String {
+    match name {
+        Some(name) => format!("[{name}] {prompt}"),
+        None => prompt.to_string(),
+    }
+}
+
+fn format_label(name: Option<&str>, base_url: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+
+    match name {
+        Some(name) => format!("{name} ({base_url})"),
+        None => base_url.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instance_name() {
+        assert_eq!(
+            Some("ACME Cloud".to_string()),
+            parse_instance_name(Some(" ACME Cloud ".to_string()))
+        );
+        assert_eq!(None, parse_instance_name(Some("  ".to_string())));
+        assert_eq!(None, parse_instance_name(None));
+    }
+
+    #[test]
+    fn test_format_prompt() {
+        assert_eq!(
+            "[ACME Cloud] Please enter authorization code",
+            format_prompt(Some("ACME Cloud"), "Please enter authorization code")
+        );
+        assert_eq!(
+            "Please enter authorization code",
+            format_prompt(None, "Please enter authorization code")
+        );
+    }
+
+    #[test]
+    fn test_format_label() {
+        assert_eq!(
+            "ACME Cloud (https://dracoon.example.com)",
+            format_label(Some("ACME Cloud"), "https://dracoon.example.com/")
+        );
+        assert_eq!(
+            "https://dracoon.example.com",
+            format_label(None, "https://dracoon.example.com/")
+        );
+    }
+}
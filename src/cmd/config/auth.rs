@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use console::Term;
+use dco3::OAuth2Flow;
+use reqwest::Client;
+use tracing::{debug, error, info, warn};
+
+use super::{
+    credentials::{
+        load_account_index, open_credentials, selected_credential_store, Credentials,
+        HandleCredentials, StoredAccount, CRYPTO_ACCOUNT_SUFFIX,
+    },
+    device::authenticate_device_flow,
+    prepare_config_cmd, CLIENT_ID, CLIENT_SECRET,
+};
+use crate::cmd::{
+    authenticate_auth_code_flow, authenticate_password_flow,
+    branding::{instance_label, load_instance_name},
+    build_dracoon,
+    models::{AuthCommand, DcCmdError, PasswordAuth},
+    utils::{
+        rate_limit::send_rate_limited,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+pub async fn handle_auth_cmd(
+    cmd: AuthCommand,
+    term: Term,
+    password_auth: Option<PasswordAuth>,
+) -> Result<(), DcCmdError> {
+    match cmd {
+        AuthCommand::Login { target, device } => login(&term, &target, password_auth, device).await,
+        AuthCommand::Logout { target } => logout(&term, &target).await,
+    }
+}
+
+/// authenticates (password flow if credentials are passed, otherwise auth code flow)
+/// and stores the refresh token
+async fn login(
+    term: &Term,
+    target: &str,
+    password_auth: Option<PasswordAuth>,
+    device: bool,
+) -> Result<(), DcCmdError> {
+    let (base_url, entry) = prepare_config_cmd(target, term, false)?;
+
+    let dracoon = build_dracoon(&base_url, 1)?;
+    load_instance_name(&base_url).await;
+
+    let dracoon = match (password_auth, device) {
+        (Some(_), true) => {
+            return Err(DcCmdError::InvalidArgument(
+                "Device login does not accept username and password.".to_string(),
+            ))
+        }
+        (Some(password_auth), false) => {
+            let dracoon = authenticate_password_flow(dracoon, password_auth).await?;
+            entry.set_dracoon_env(&dracoon.get_refresh_token().await)?;
+            dracoon
+        }
+        (None, true) => {
+            let dracoon = authenticate_device_flow(term, dracoon, &base_url).await?;
+            entry.set_dracoon_env(&dracoon.get_refresh_token().await)?;
+            dracoon
+        }
+        (None, false) => authenticate_auth_code_flow(dracoon, &base_url, entry).await?,
+    };
+
+    let user_info = dracoon.get_user_info().await?;
+
+    info!(
+        "Logged in to {} as {}.",
+        instance_label(&base_url),
+        user_info.user_name
+    );
+
+    term.write_line(&format_success_message(&format!(
+        "Logged in to {base_url} as {}.",
+        user_info.user_name
+    )))
+    .map_err(|_| DcCmdError::IoError)
+}
+
+/// revokes the stored refresh token and removes all stored credentials
+/// (refresh token and encryption secret)
+async fn logout(term: &Term, target: &str) -> Result<(), DcCmdError> {
+    let (base_url, entry) = prepare_config_cmd(target, term, false)?;
+
+    let Ok(refresh_token) = entry.get_dracoon_env() else {
+        let msg = format_error_message(&format!("No token found for this DRACOON url: {target}."));
+        term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+        return Err(DcCmdError::InvalidAccount);
+    };
+
+    // an already invalid token is removed anyway
+    if let Err(e) = revoke_refresh_token(&base_url, refresh_token).await {
+        warn!("Failed to revoke tokens for {}: {}", base_url, e);
+    }
+
+    entry.delete_dracoon_env()?;
+
+    let (_, crypto_entry) = prepare_config_cmd(target, term, true)?;
+    remove_if_present(&crypto_entry)?;
+
+    info!("Logged out from {}.", base_url);
+
+    term.write_line(&format_success_message(&format!(
+        "Logged out from {base_url}."
+    )))
+    .map_err(|_| DcCmdError::IoError)
+}
+
+/// revokes a stored refresh token (OAuth token revocation - RFC 7009)
+pub async fn revoke_refresh_token(base_url: &str, refresh_token: String) -> Result<(), DcCmdError> {
+    let base_url = base_url.trim_end_matches('/');
+
+    let request = Client::new()
+        .post(format!("{base_url}/oauth/revoke"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&[
+            ("token", refresh_token.as_str()),
+            ("token_type_hint", "refresh_token"),
+        ]);
+
+    send_rate_limited(request)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            error!("Error revoking refresh token: {}", e);
+            DcCmdError::ConnectionFailed
+        })?;
+
+    Ok(())
+}
+
+fn remove_if_present(entry: &Credentials) -> Result<(), DcCmdError> {
+    if entry.get_dracoon_env().is_ok() {
+        entry.delete_dracoon_env()?;
+    }
+
+    Ok(())
+}
+
+/// prints all stored accounts (instance, user, secret type, store and age of the secret)
+///
+/// user names are only resolved for refresh tokens in the selected credential store
+pub async fn list_stored_accounts(term: &Term) -> Result<(), DcCmdError> {
+    let index = load_account_index()?;
+
+    if index.is_empty() {
+        term.write_line("No stored accounts found.")
+            .map_err(|_| DcCmdError::IoError)?;
+        return Ok(());
+    }
+
+    term.write_line(&format!(
+        "{:<40} {:<24} {:<18} {:<8} {}",
+        "Instance", "User", "Type", "Store", "Age"
+    ))
+    .map_err(|_| DcCmdError::IoError)?;
+
+    let now = Utc::now();
+
+    for (account, stored) in &index {
+        let (instance, secret_type) = match account.strip_suffix(CRYPTO_ACCOUNT_SUFFIX) {
+            Some(instance) => (instance.trim_end_matches('/'), "encryption secret"),
+            None => (account.as_str(), "refresh token"),
+        };
+
+        let user_name = if secret_type == "refresh token" {
+            get_stored_user_name(account, stored).await
+        } else {
+            None
+        };
+
+        let age = stored
+            .stored_at()
+            .map_or_else(|| "n/a".to_string(), |stored_at| format_age(now, stored_at));
+
+        term.write_line(&format!(
+            "{:<40} {:<24} {:<18} {:<8} {}",
+            instance,
+            user_name.as_deref().unwrap_or("-"),
+            secret_type,
+            format!("{:?}", stored.store).to_lowercase(),
+            age
+        ))
+        .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(())
+}
+
+async fn get_stored_user_name(account: &str, stored: &StoredAccount) -> Option<String> {
+    if stored.store != selected_credential_store() {
+        return None;
+    }
+
+    let refresh_token = open_credentials(account).ok()?.get_dracoon_env().ok()?;
+
+    let user_info = async {
+        let dracoon = build_dracoon(account, 1)?
+            .connect(OAuth2Flow::refresh_token(refresh_token))
+            .await?;
+
+        Ok::<_, DcCmdError>(dracoon.get_user_info().await?)
+    };
+
+    match user_info.await {
+        Ok(user_info) => Some(user_info.user_name),
+        Err(e) => {
+            debug!("Error getting user info for {}: {}", account, e);
+            Some("n/a (invalid token)".to_string())
+        }
+    }
+}
+
+/// formats the age of a stored secret (e.g. `3d 4h`)
+fn format_age(now: DateTime<Utc>, stored_at: DateTime<Utc>) -> String {
+    let age = now.signed_duration_since(stored_at);
+
+    match (age.num_days(), age.num_hours() % 24, age.num_minutes() % 60) {
+        (days, hours, _) if days > 0 => format!("{days}d {hours}h"),
+        (_, hours, minutes) if hours > 0 => format!("{hours}h {minutes}m"),
+        (_, _, minutes) if minutes > 0 => format!("{minutes}m"),
+        _ => "<1m".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::format_age;
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc.with_ymd_and_hms(2025, 1, 10, 12, 0, 0).unwrap();
+
+        assert_eq!("3d 4h", format_age(now, now - Duration::hours(76)));
+        assert_eq!("2h 30m", format_age(now, now - Duration::minutes(150)));
+        assert_eq!("5m", format_age(now, now - Duration::minutes(5)));
+        assert_eq!("<1m", format_age(now, now - Duration::seconds(30)));
+        assert_eq!("<1m", format_age(now, now + Duration::minutes(5)));
+    }
+}
@@ -1,6 +1,29 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::OnceLock};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
 use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, warn};
+
+use crate::cmd::{models::DcCmdError, SERVICE_NAME};
+
+use super::{
+    get_or_create_config_dir,
+    secrets::{decrypt_secret, encrypt_secret},
+};
+
+/// name of the encrypted credential file (in the config dir)
+pub const CREDENTIALS_FILE: &str = "credentials.json";
 
-use crate::cmd::models::DcCmdError;
+/// name of the index of stored accounts (in the config dir) - secrets are not part of it
+pub const ACCOUNTS_FILE: &str = "accounts.json";
+
+/// suffix of accounts holding an encryption secret
+pub const CRYPTO_ACCOUNT_SUFFIX: &str = "-crypto";
+
+/// environment variable holding the passphrase of the credential file
+pub const CREDENTIALS_PASSPHRASE_ENV: &str = "DCCMD_CREDENTIALS_PASSPHRASE";
 
 pub trait HandleCredentials {
     fn set_dracoon_env(&self, secret: &str) -> Result<(), DcCmdError>;
@@ -8,6 +31,8 @@ pub trait HandleCredentials {
     fn delete_dracoon_env(&self) -> Result<(), DcCmdError>;
 }
 
+pub type Credentials = Box<dyn HandleCredentials + Send + Sync>;
+
 impl HandleCredentials for Entry {
     fn set_dracoon_env(&self, secret: &str) -> Result<(), DcCmdError> {
         match self.set_password(secret) {
@@ -32,3 +57,357 @@ impl HandleCredentials for Entry {
         }
     }
 }
+
+impl<T: HandleCredentials + ?Sized> HandleCredentials for Box<T> {
+    fn set_dracoon_env(&self, secret: &str) -> Result<(), DcCmdError> {
+        (**self).set_dracoon_env(secret)
+    }
+    fn get_dracoon_env(&self) -> Result<String, DcCmdError> {
+        (**self).get_dracoon_env()
+    }
+    fn delete_dracoon_env(&self) -> Result<(), DcCmdError> {
+        (**self).delete_dracoon_env()
+    }
+}
+
+/// backend used to store credentials (refresh tokens, encryption secrets)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStore {
+    /// OS keyring (default)
+    #[default]
+    Keyring,
+    /// passphrase encrypted file in the config dir (e.g. headless servers without keyring)
+    File,
+}
+
+struct CredentialStoreConfig {
+    store: CredentialStore,
+    key_file: Option<PathBuf>,
+}
+
+static CREDENTIAL_STORE: OnceLock<CredentialStoreConfig> = OnceLock::new();
+static FILE_PASSPHRASE: OnceLock<String> = OnceLock::new();
+
+/// selects the credential store for all commands (can only be set once - default: keyring)
+pub fn init_credential_store(store: CredentialStore, key_file: Option<PathBuf>) {
+    debug!("Using credential store: {:?}", store);
+    let _ = CREDENTIAL_STORE.set(CredentialStoreConfig { store, key_file });
+}
+
+/// returns the selected credential store
+pub fn selected_credential_store() -> CredentialStore {
+    CREDENTIAL_STORE
+        .get()
+        .map_or_else(CredentialStore::default, |config| config.store)
+}
+
+/// opens the credentials of an account (DRACOON url) in the selected credential store
+pub fn open_credentials(account: &str) -> Result<Credentials, DcCmdError> {
+    let config = CREDENTIAL_STORE.get_or_init(|| CredentialStoreConfig {
+        store: CredentialStore::default(),
+        key_file: None,
+    });
+
+    let inner = match config.store {
+        CredentialStore::Keyring => Entry::new(SERVICE_NAME, account)
+            .map(|entry| Box::new(entry) as Credentials)
+            .map_err(|_| DcCmdError::CredentialStorageFailed)?,
+        CredentialStore::File => Box::new(FileCredentials::new(
+            account,
+            get_or_create_config_dir().join(CREDENTIALS_FILE),
+            config.key_file.clone(),
+        )),
+    };
+
+    Ok(Box::new(IndexedCredentials {
+        account: account.to_string(),
+        store: config.store,
+        inner,
+    }))
+}
+
+/// an account in the index of stored accounts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StoredAccount {
+    pub store: CredentialStore,
+    /// last time the secret was stored (RFC3339)
+    pub stored_at: String,
+}
+
+impl StoredAccount {
+    pub fn stored_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.stored_at)
+            .ok()
+            .map(|stored_at| stored_at.with_timezone(&Utc))
+    }
+}
+
+/// index of all accounts stored by dccmd-rs (keyring entries cannot be enumerated)
+pub type AccountIndex = BTreeMap<String, StoredAccount>;
+
+/// loads the index of stored accounts (empty if not present)
+pub fn load_account_index() -> Result<AccountIndex, DcCmdError> {
+    read_account_index(&get_or_create_config_dir().join(ACCOUNTS_FILE))
+}
+
+fn read_account_index(path: &std::path::Path) -> Result<AccountIndex, DcCmdError> {
+    if !path.exists() {
+        return Ok(AccountIndex::new());
+    }
+
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        error!("Error reading {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    serde_json::from_str(&content).map_err(|e| {
+        error!("Invalid account index {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })
+}
+
+fn write_account_index(path: &std::path::Path, index: &AccountIndex) -> Result<(), DcCmdError> {
+    let content = serde_json::to_string_pretty(index).map_err(|_| DcCmdError::IoError)?;
+
+    std::fs::write(path, content).map_err(|e| {
+        error!("Error writing {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })
+}
+
+/// adds (or updates) an account in the index file
+fn update_account_index(
+    path: &std::path::Path,
+    account: &str,
+    stored: Option<StoredAccount>,
+) -> Result<(), DcCmdError> {
+    let mut index = read_account_index(path)?;
+
+    match stored {
+        Some(stored) => index.insert(account.to_string(), stored),
+        None => index.remove(account),
+    };
+
+    write_account_index(path, &index)
+}
+
+/// credentials tracked in the index of stored accounts
+struct IndexedCredentials {
+    account: String,
+    store: CredentialStore,
+    inner: Credentials,
+}
+
+impl IndexedCredentials {
+    /// the index is informational only - failing to update it does not fail storing secrets
+    fn update_index(&self, stored: Option<StoredAccount>) {
+        let path = get_or_create_config_dir().join(ACCOUNTS_FILE);
+
+        if let Err(e) = update_account_index(&path, &self.account, stored) {
+            warn!("Failed to update account index: {}", e);
+        }
+    }
+}
+
+impl HandleCredentials for IndexedCredentials {
+    fn set_dracoon_env(&self, secret: &str) -> Result<(), DcCmdError> {
+        self.inner.set_dracoon_env(secret)?;
+        self.update_index(Some(StoredAccount {
+            store: self.store,
+            stored_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        }));
+        Ok(())
+    }
+    fn get_dracoon_env(&self) -> Result<String, DcCmdError> {
+        self.inner.get_dracoon_env()
+    }
+    fn delete_dracoon_env(&self) -> Result<(), DcCmdError> {
+        self.inner.delete_dracoon_env()?;
+        self.update_index(None);
+        Ok(())
+    }
+}
+
+/// credentials stored in a file - each secret is encrypted with a passphrase
+/// (read from key file, `DCCMD_CREDENTIALS_PASSPHRASE` or prompted)
+pub struct FileCredentials {
+    account: String,
+    path: PathBuf,
+    key_file: Option<PathBuf>,
+}
+
+impl FileCredentials {
+    pub fn new(account: &str, path: PathBuf, key_file: Option<PathBuf>) -> Self {
+        Self {
+            account: account.to_string(),
+            path,
+            key_file,
+        }
+    }
+
+    fn passphrase(&self) -> Result<String, DcCmdError> {
+        if let Some(passphrase) = FILE_PASSPHRASE.get() {
+            return Ok(passphrase.clone());
+        }
+
+        let passphrase = if let Some(key_file) = &self.key_file {
+            std::fs::read_to_string(key_file)
+                .map_err(|e| {
+                    error!("Error reading key file {}: {}", key_file.display(), e);
+                    DcCmdError::InvalidPath(key_file.display().to_string())
+                })?
+                .trim()
+                .to_string()
+        } else if let Ok(passphrase) = std::env::var(CREDENTIALS_PASSPHRASE_ENV) {
+            passphrase
+        } else {
+            dialoguer::Password::new()
+                .with_prompt("Please enter the passphrase of the credential store")
+                .interact()
+                .or(Err(DcCmdError::IoError))?
+        };
+
+        if passphrase.is_empty() {
+            return Err(DcCmdError::InvalidArgument(
+                "Passphrase of the credential store must not be empty.".to_string(),
+            ));
+        }
+
+        // all secrets share the passphrase - reject a wrong one before storing anything
+        if let Some(secret) = self.load()?.values().next() {
+            decrypt_secret(secret, &passphrase).map_err(|_| {
+                DcCmdError::InvalidArgument("Invalid passphrase of the credential store.".into())
+            })?;
+        }
+
+        Ok(FILE_PASSPHRASE.get_or_init(|| passphrase).clone())
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, String>, DcCmdError> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let content = std::fs::read_to_string(&self.path).map_err(|e| {
+            error!("Error reading {}: {}", self.path.display(), e);
+            DcCmdError::CredentialStorageFailed
+        })?;
+
+        serde_json::from_str(&content).map_err(|e| {
+            error!("Invalid credential file {}: {}", self.path.display(), e);
+            DcCmdError::CredentialStorageFailed
+        })
+    }
+
+    fn save(&self, credentials: &BTreeMap<String, String>) -> Result<(), DcCmdError> {
+        let content = serde_json::to_string_pretty(credentials).map_err(|_| DcCmdError::IoError)?;
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(&self.path).map_err(|e| {
+            error!("Error writing {}: {}", self.path.display(), e);
+            DcCmdError::CredentialStorageFailed
+        })?;
+
+        std::io::Write::write_all(&mut file, content.as_bytes())
+            .map_err(|_| DcCmdError::CredentialStorageFailed)
+    }
+}
+
+impl HandleCredentials for FileCredentials {
+    fn set_dracoon_env(&self, secret: &str) -> Result<(), DcCmdError> {
+        let mut credentials = self.load()?;
+        let secret = encrypt_secret(secret, &self.passphrase()?)?;
+        credentials.insert(self.account.clone(), secret);
+
+        self.save(&credentials)
+    }
+    fn get_dracoon_env(&self) -> Result<String, DcCmdError> {
+        let credentials = self.load()?;
+
+        let Some(secret) = credentials.get(&self.account) else {
+            return Err(DcCmdError::InvalidAccount);
+        };
+
+        decrypt_secret(secret, &self.passphrase()?)
+    }
+    fn delete_dracoon_env(&self) -> Result<(), DcCmdError> {
+        let mut credentials = self.load()?;
+
+        if credentials.remove(&self.account).is_none() {
+            return Err(DcCmdError::InvalidAccount);
+        }
+
+        self.save(&credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_account_index, update_account_index, CredentialStore, FileCredentials,
+        HandleCredentials, StoredAccount,
+    };
+
+    #[test]
+    fn test_file_credentials() {
+        let dir = std::env::temp_dir().join(format!("dccmd-credentials-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key_file = dir.join("key");
+        std::fs::write(&key_file, "passphrase\n").unwrap();
+
+        let path = dir.join("credentials.json");
+        let credentials =
+            FileCredentials::new("https://foo.dracoon.com", path.clone(), Some(key_file));
+
+        assert!(credentials.get_dracoon_env().is_err());
+
+        credentials.set_dracoon_env("some-refresh-token").unwrap();
+        assert_eq!(credentials.get_dracoon_env().unwrap(), "some-refresh-token");
+        assert!(!std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("some-refresh-token"));
+
+        credentials.delete_dracoon_env().unwrap();
+        assert!(credentials.get_dracoon_env().is_err());
+        assert!(credentials.delete_dracoon_env().is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_account_index() {
+        let dir = std::env::temp_dir().join(format!("dccmd-accounts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("accounts.json");
+
+        assert!(read_account_index(&path).unwrap().is_empty());
+
+        let stored = StoredAccount {
+            store: CredentialStore::File,
+            stored_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+
+        update_account_index(&path, "https://foo.dracoon.com", Some(stored.clone())).unwrap();
+        update_account_index(&path, "https://bar.dracoon.com", Some(stored.clone())).unwrap();
+        update_account_index(&path, "https://bar.dracoon.com", None).unwrap();
+
+        let index = read_account_index(&path).unwrap();
+        assert_eq!(1, index.len());
+        assert_eq!(Some(&stored), index.get("https://foo.dracoon.com"));
+        assert_eq!(
+            "2025-01-01T00:00:00+00:00",
+            stored.stored_at().unwrap().to_rfc3339()
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::time::Duration;
+
+use console::Term;
+use dco3::{
+    auth::{Connected, Disconnected},
+    Dracoon, OAuth2Flow,
+};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{debug, error, info};
+
+use crate::cmd::{
+    branding::instance_label,
+    config::{CLIENT_ID, CLIENT_SECRET},
+    models::DcCmdError,
+    utils::rate_limit::send_rate_limited,
+};
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// default polling interval if not provided by the instance (RFC 8628)
+const DEFAULT_POLL_INTERVAL: u64 = 5;
+
+/// added to the polling interval if the instance asks to slow down (RFC 8628)
+const SLOW_DOWN_INTERVAL: u64 = 5;
+
+#[derive(Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// state of a pending device authorization (response of the token endpoint)
+#[derive(Debug, PartialEq)]
+enum DevicePollResult {
+    Authorized(String),
+    Pending,
+    SlowDown,
+    Failed(String),
+}
+
+/// authenticates via OAuth device code flow (user code is entered on another device)
+///
+/// the refresh token obtained is used to connect - fails if the flow is not enabled
+pub async fn authenticate_device_flow(
+    term: &Term,
+    dracoon: Dracoon<Disconnected>,
+    base_url: &str,
+) -> Result<Dracoon<Connected>, DcCmdError> {
+    let client = Client::new();
+    let base_url = base_url.trim_end_matches('/');
+
+    let request = client
+        .post(format!("{base_url}/oauth/device_authorization"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&[("client_id", CLIENT_ID)]);
+
+    let response = send_rate_limited(request)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            error!("Device authorization not available: {}", e);
+            DcCmdError::InvalidArgument(
+                "Device authorization is not enabled on this instance.".to_string(),
+            )
+        })?
+        .json::<DeviceAuthorizationResponse>()
+        .await
+        .map_err(|e| {
+            error!("Error parsing device authorization: {}", e);
+            DcCmdError::ConnectionFailed
+        })?;
+
+    let verification_uri = response
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&response.verification_uri);
+
+    term.write_line(&format!(
+        "To log in to {}, open {} on any device and enter the code: {}",
+        instance_label(base_url),
+        verification_uri,
+        response.user_code
+    ))
+    .map_err(|_| DcCmdError::IoError)?;
+
+    let mut interval = response.interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(response.expires_in);
+
+    let refresh_token = loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+
+        if tokio::time::Instant::now() > deadline {
+            return Err(DcCmdError::InvalidArgument(
+                "Device code expired - please log in again.".to_string(),
+            ));
+        }
+
+        match poll_token(&client, base_url, &response.device_code).await? {
+            DevicePollResult::Authorized(refresh_token) => break refresh_token,
+            DevicePollResult::Pending => debug!("Device authorization pending."),
+            DevicePollResult::SlowDown => interval += SLOW_DOWN_INTERVAL,
+            DevicePollResult::Failed(reason) => {
+                error!("Device authorization failed: {}", reason);
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Device authorization failed: {reason}"
+                )));
+            }
+        }
+    };
+
+    info!("Device authorized for {}.", instance_label(base_url));
+
+    let dracoon = dracoon
+        .connect(OAuth2Flow::RefreshToken(refresh_token))
+        .await?;
+
+    Ok(dracoon)
+}
+
+async fn poll_token(
+    client: &Client,
+    base_url: &str,
+    device_code: &str,
+) -> Result<DevicePollResult, DcCmdError> {
+    let request = client
+        .post(format!("{base_url}/oauth/token"))
+        .basic_auth(CLIENT_ID, Some(CLIENT_SECRET))
+        .form(&[
+            ("grant_type", DEVICE_CODE_GRANT_TYPE),
+            ("device_code", device_code),
+        ]);
+
+    let response = send_rate_limited(request).await.map_err(|e| {
+        error!("Error polling device authorization: {}", e);
+        DcCmdError::ConnectionFailed
+    })?;
+
+    let is_success = response.status().is_success();
+    let body = response.text().await.map_err(|e| {
+        error!("Error reading token response: {}", e);
+        DcCmdError::ConnectionFailed
+    })?;
+
+    Ok(parse_poll_response(is_success, &body))
+}
+
+fn parse_poll_response(is_success: bool, body: &str) -> DevicePollResult {
+    if is_success {
+        return match serde_json::from_str::<TokenResponse>(body) {
+            Ok(token) => DevicePollResult::Authorized(token.refresh_token),
+            Err(_) => DevicePollResult::Failed("No refresh token received.".to_string()),
+        };
+    }
+
+    let Ok(error) = serde_json::from_str::<TokenErrorResponse>(body) else {
+        return DevicePollResult::Failed(format!("Unexpected response: {body}"));
+    };
+
+    match error.error.as_str() {
+        "authorization_pending" => DevicePollResult::Pending,
+        "slow_down" => DevicePollResult::SlowDown,
+        "access_denied" => DevicePollResult::Failed("Access denied.".to_string()),
+        "expired_token" => DevicePollResult::Failed("Device code expired.".to_string()),
+        _ => DevicePollResult::Failed(error.error_description.unwrap_or(error.error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_poll_response_authorized() {
+        let body = r#"{"access_token":"a","refresh_token":"r","token_type":"bearer"}"#;
+
+        assert_eq!(
+            DevicePollResult::Authorized("r".to_string()),
+            parse_poll_response(true, body)
+        );
+    }
+
+    #[test]
+    fn test_parse_poll_response_pending() {
+        assert_eq!(
+            DevicePollResult::Pending,
+            parse_poll_response(false, r#"{"error":"authorization_pending"}"#)
+        );
+        assert_eq!(
+            DevicePollResult::SlowDown,
+            parse_poll_response(false, r#"{"error":"slow_down"}"#)
+        );
+    }
+
+    #[test]
+    fn test_parse_poll_response_failed() {
+        assert_eq!(
+            DevicePollResult::Failed("Access denied.".to_string()),
+            parse_poll_response(false, r#"{"error":"access_denied"}"#)
+        );
+        assert_eq!(
+            DevicePollResult::Failed("Bad client".to_string()),
+            parse_poll_response(
+                false,
+                r#"{"error":"invalid_client","error_description":"Bad client"}"#
+            )
+        );
+        assert!(matches!(
+            parse_poll_response(false, "<html></html>"),
+            DevicePollResult::Failed(_)
+        ));
+    }
+}
@@ -0,0 +1,349 @@
+use dco3::{
+    auth::Connected,
+    nodes::{MissingKeysResponse, UserFileKeySetBatchRequest},
+    Dracoon,
+};
+use dco3_crypto::{
+    DracoonCrypto, DracoonRSACrypto, PlainUserKeyPairContainer, UserKeyPairContainer,
+    UserKeyPairVersion,
+};
+use reqwest::Method;
+use tracing::{error, info, warn};
+
+use crate::cmd::{
+    models::DcCmdError,
+    utils::{
+        api::{api_request, send_api_json, send_api_request},
+        file_keys::{distribute_missing_keys_batched, MISSING_KEYS_BATCH_SIZE},
+        rate_limit::with_rate_limit,
+    },
+};
+
+/// keypairs with versions are not provided by dco3 and requested directly
+const KEYPAIR_PATH: &str = "user/account/keypair";
+
+/// upper bound of rotation steps (create, migrate and remove - twice for RSA-4096 keypairs)
+const MAX_ROTATION_STEPS: usize = 6;
+
+/// result of a keypair rotation
+#[derive(Debug, Default)]
+pub struct KeypairRotation {
+    pub reencrypted: u64,
+    pub failed: u64,
+}
+
+/// secret a keypair of the user is encrypted with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum KeypairSecret {
+    Current,
+    New,
+    Unknown,
+}
+
+struct UserKeypair {
+    version: UserKeyPairVersion,
+    secret: KeypairSecret,
+    plain: Option<PlainUserKeyPairContainer>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum RotationStep {
+    /// create a keypair of the version (encrypted with the new secret)
+    Create(UserKeyPairVersion),
+    /// re-encrypt the file keys of the keypair of the version for the other keypair and remove it
+    Migrate(UserKeyPairVersion),
+    Done,
+}
+
+/// replaces the keypair of the current user with a new one (encrypted with the new secret)
+///
+/// DRACOON holds at most one keypair per version - the new keypair is set next to the old one,
+/// the file keys of the user are re-encrypted in batches before the old keypair is removed.
+/// RSA-4096 keypairs are rotated via a temporary RSA-2048 keypair. Every step is derived from
+/// the keypairs stored in DRACOON, so an interrupted rotation is resumed when re-run.
+pub async fn rotate_keypair(
+    dracoon: &Dracoon<Connected>,
+    secret: &str,
+    new_secret: &str,
+) -> Result<KeypairRotation, DcCmdError> {
+    let user_id = dracoon.get_user_info().await?.id;
+    let mut rotation = KeypairRotation::default();
+
+    for _ in 0..MAX_ROTATION_STEPS {
+        let keypairs = get_user_keypairs(dracoon, secret, new_secret).await?;
+        let states = keypairs
+            .iter()
+            .map(|keypair| (keypair.version.clone(), keypair.secret))
+            .collect::<Vec<_>>();
+
+        match next_rotation_step(&states)? {
+            RotationStep::Create(version) => {
+                create_keypair(dracoon, version.clone(), new_secret).await?;
+                ensure_keypair_version(dracoon, &version, true).await?;
+                info!("New keypair ({:?}) created for user {}.", version, user_id);
+            }
+            RotationStep::Migrate(version) => {
+                let Some(source) = keypairs
+                    .iter()
+                    .find(|keypair| keypair.version == version)
+                    .and_then(|keypair| keypair.plain.as_ref())
+                else {
+                    return Err(DcCmdError::Unknown);
+                };
+
+                let distribution = distribute_missing_keys_batched(|| {
+                    with_rate_limit(|| distribute_keys_from(dracoon, user_id, source))
+                })
+                .await?;
+
+                rotation.reencrypted += distribution.distributed;
+
+                // keep the old keypair if file keys could not be migrated
+                if distribution.remaining > 0 {
+                    warn!(
+                        "{} file key(s) could not be re-encrypted - old keypair kept.",
+                        distribution.remaining
+                    );
+                    rotation.failed = distribution.remaining;
+                    return Ok(rotation);
+                }
+
+                delete_keypair(dracoon, &version).await?;
+                ensure_keypair_version(dracoon, &version, false).await?;
+                info!("Old keypair ({:?}) removed for user {}.", version, user_id);
+            }
+            RotationStep::Done => {
+                info!(
+                    "Keypair rotated for user {} ({} file key(s) re-encrypted).",
+                    user_id, rotation.reencrypted
+                );
+                return Ok(rotation);
+            }
+        }
+    }
+
+    error!("Keypair rotation did not finish after {MAX_ROTATION_STEPS} steps.");
+    Err(DcCmdError::Unknown)
+}
+
+/// derives the next rotation step from the keypairs of the user (version and secret)
+fn next_rotation_step(
+    keypairs: &[(UserKeyPairVersion, KeypairSecret)],
+) -> Result<RotationStep, DcCmdError> {
+    match keypairs {
+        // start: the new keypair uses the free version (temporary RSA-2048 for RSA-4096)
+        [(UserKeyPairVersion::RSA2048, KeypairSecret::Current)] => {
+            Ok(RotationStep::Create(UserKeyPairVersion::RSA4096))
+        }
+        [(UserKeyPairVersion::RSA4096, KeypairSecret::Current)] => {
+            Ok(RotationStep::Create(UserKeyPairVersion::RSA2048))
+        }
+        // old keypair removed - a temporary RSA-2048 keypair is replaced by an RSA-4096 keypair
+        [(UserKeyPairVersion::RSA2048, KeypairSecret::New)] => {
+            Ok(RotationStep::Create(UserKeyPairVersion::RSA4096))
+        }
+        [(UserKeyPairVersion::RSA4096, KeypairSecret::New)] => Ok(RotationStep::Done),
+        [(version, KeypairSecret::Current), (_, KeypairSecret::New)]
+        | [(_, KeypairSecret::New), (version, KeypairSecret::Current)] => {
+            Ok(RotationStep::Migrate(version.clone()))
+        }
+        [(_, KeypairSecret::New), (_, KeypairSecret::New)] => {
+            Ok(RotationStep::Migrate(UserKeyPairVersion::RSA2048))
+        }
+        [] => Err(DcCmdError::InvalidArgument(
+            "No keypair found (rotation requires an existing keypair).".to_string(),
+        )),
+        _ => Err(DcCmdError::InvalidArgument(
+            "Invalid encryption secret.".to_string(),
+        )),
+    }
+}
+
+/// fetches all keypairs of the user and decrypts them with the current or the new secret
+async fn get_user_keypairs(
+    dracoon: &Dracoon<Connected>,
+    secret: &str,
+    new_secret: &str,
+) -> Result<Vec<UserKeypair>, DcCmdError> {
+    let request = api_request(dracoon, Method::GET, "user/account/keypairs").await?;
+    let keypairs = send_api_json::<Vec<UserKeyPairContainer>>(request).await?;
+
+    let keypairs = keypairs
+        .into_iter()
+        .map(|keypair| {
+            let version = keypair.private_key_container.version.clone();
+
+            let (secret, plain) = match DracoonCrypto::decrypt_keypair(secret, keypair.clone()) {
+                Ok(plain) => (KeypairSecret::Current, Some(plain)),
+                Err(_) => match DracoonCrypto::decrypt_keypair(new_secret, keypair) {
+                    Ok(plain) => (KeypairSecret::New, Some(plain)),
+                    Err(_) => (KeypairSecret::Unknown, None),
+                },
+            };
+
+            UserKeypair {
+                version,
+                secret,
+                plain,
+            }
+        })
+        .collect();
+
+    Ok(keypairs)
+}
+
+async fn create_keypair(
+    dracoon: &Dracoon<Connected>,
+    version: UserKeyPairVersion,
+    secret: &str,
+) -> Result<(), DcCmdError> {
+    let keypair = DracoonCrypto::create_plain_user_keypair(version)
+        .and_then(|keypair| DracoonCrypto::encrypt_private_key(secret, keypair))
+        .map_err(|e| {
+            error!("Error creating keypair: {:?}", e);
+            DcCmdError::Unknown
+        })?;
+
+    let request = api_request(dracoon, Method::POST, KEYPAIR_PATH)
+        .await?
+        .json(&keypair);
+    send_api_request(request).await?;
+
+    Ok(())
+}
+
+async fn delete_keypair(
+    dracoon: &Dracoon<Connected>,
+    version: &UserKeyPairVersion,
+) -> Result<(), DcCmdError> {
+    let request = api_request(dracoon, Method::DELETE, KEYPAIR_PATH)
+        .await?
+        .query(&[("version", version_name(version))]);
+    send_api_request(request).await?;
+
+    Ok(())
+}
+
+/// re-fetches the keypairs of the user to verify a created or removed keypair
+async fn ensure_keypair_version(
+    dracoon: &Dracoon<Connected>,
+    version: &UserKeyPairVersion,
+    exists: bool,
+) -> Result<(), DcCmdError> {
+    let request = api_request(dracoon, Method::GET, "user/account/keypairs").await?;
+    let keypairs = send_api_json::<Vec<UserKeyPairContainer>>(request).await?;
+
+    let found = keypairs
+        .iter()
+        .any(|keypair| keypair.private_key_container.version == *version);
+
+    if found != exists {
+        error!(
+            "Keypair {:?} {} after update.",
+            version,
+            if exists { "missing" } else { "still present" }
+        );
+        return Err(DcCmdError::Unknown);
+    }
+
+    Ok(())
+}
+
+/// re-encrypts up to `MISSING_KEYS_BATCH_SIZE` file keys of the user with the source keypair
+/// for the other keypair of the user - returns the total of missing keys before the call
+async fn distribute_keys_from(
+    dracoon: &Dracoon<Connected>,
+    user_id: u64,
+    source: &PlainUserKeyPairContainer,
+) -> Result<u64, DcCmdError> {
+    let request = api_request(dracoon, Method::GET, "nodes/missingFileKeys")
+        .await?
+        .query(&[("user_id", user_id), ("limit", MISSING_KEYS_BATCH_SIZE)])
+        .query(&[("use_key", "previous_user_key")]);
+    let missing_keys = send_api_json::<MissingKeysResponse>(request).await?;
+
+    let total = missing_keys.range.as_ref().map_or(0, |range| range.total);
+
+    let keys = UserFileKeySetBatchRequest::try_new_from_missing_keys(missing_keys, source)?;
+
+    if !keys.is_empty() {
+        let request = api_request(dracoon, Method::POST, "nodes/files/keys")
+            .await?
+            .json(&keys);
+        send_api_request(request).await?;
+    }
+
+    Ok(total)
+}
+
+/// API name of a keypair version
+fn version_name(version: &UserKeyPairVersion) -> &'static str {
+    match version {
+        UserKeyPairVersion::RSA2048 => "A",
+        UserKeyPairVersion::RSA4096 => "RSA-4096",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use KeypairSecret::{Current, New, Unknown};
+    use UserKeyPairVersion::{RSA2048, RSA4096};
+
+    #[test]
+    fn test_rotation_rsa2048() {
+        assert_eq!(
+            next_rotation_step(&[(RSA2048, Current)]).unwrap(),
+            RotationStep::Create(RSA4096)
+        );
+        assert_eq!(
+            next_rotation_step(&[(RSA2048, Current), (RSA4096, New)]).unwrap(),
+            RotationStep::Migrate(RSA2048)
+        );
+        assert_eq!(
+            next_rotation_step(&[(RSA4096, New)]).unwrap(),
+            RotationStep::Done
+        );
+    }
+
+    #[test]
+    fn test_rotation_rsa4096() {
+        assert_eq!(
+            next_rotation_step(&[(RSA4096, Current)]).unwrap(),
+            RotationStep::Create(RSA2048)
+        );
+        assert_eq!(
+            next_rotation_step(&[(RSA2048, New), (RSA4096, Current)]).unwrap(),
+            RotationStep::Migrate(RSA4096)
+        );
+        // temporary RSA-2048 keypair
+        assert_eq!(
+            next_rotation_step(&[(RSA2048, New)]).unwrap(),
+            RotationStep::Create(RSA4096)
+        );
+        assert_eq!(
+            next_rotation_step(&[(RSA2048, New), (RSA4096, New)]).unwrap(),
+            RotationStep::Migrate(RSA2048)
+        );
+    }
+
+    #[test]
+    fn test_rotation_invalid_secret() {
+        assert!(next_rotation_step(&[(RSA2048, Unknown)]).is_err());
+        assert!(next_rotation_step(&[(RSA2048, Unknown), (RSA4096, New)]).is_err());
+        assert!(next_rotation_step(&[]).is_err());
+    }
+
+    #[test]
+    fn test_version_name() {
+        assert_eq!(
+            serde_json::to_value(RSA2048).unwrap(),
+            version_name(&RSA2048)
+        );
+        assert_eq!(
+            serde_json::to_value(RSA4096).unwrap(),
+            version_name(&RSA4096)
+        );
+    }
+}
@@ -2,13 +2,14 @@ use std::fs::OpenOptions;
 
 use console::Term;
 use tracing::{error, level_filters::LevelFilter};
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
 use crate::cmd::{handle_error, models::DcCmdError};
 
-use super::get_or_create_config_dir;
+use super::{get_or_create_config_dir, record::RecordLayer};
 
-pub fn init_logging(err_term: &Term, debug: bool) {
+/// initializes the log file (and the recording of API requests if passed)
+pub fn init_logging(err_term: &Term, debug: bool, record: Option<RecordLayer>) {
     let log_format = tracing_subscriber::fmt::format()
         .with_level(true)
         .with_thread_names(false)
@@ -43,10 +44,17 @@ pub fn init_logging(err_term: &Term, debug: bool) {
 
     let log_file = log_file.unwrap();
 
-    // initialize logging
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
+    let log_layer = tracing_subscriber::fmt::layer()
         .event_format(log_format)
         .with_writer(std::sync::Mutex::new(log_file))
+        .with_filter(env_filter);
+
+    // recording uses its own filter (client events are recorded without --debug)
+    let record_layer = record.map(|record| record.with_filter(RecordLayer::targets()));
+
+    // initialize logging
+    tracing_subscriber::registry()
+        .with(log_layer)
+        .with(record_layer)
         .init();
 }
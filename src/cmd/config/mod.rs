@@ -1,34 +1,59 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use console::Term;
-use dco3::{auth::Connected, AuthenticationMethods, Dracoon, OAuth2Flow, User};
+use dco3::{auth::Connected, Dracoon, OAuth2Flow};
 use dialoguer::Confirm;
-use keyring::Entry;
+use tracing::{error, warn};
 
 use self::{
-    credentials::HandleCredentials,
-    models::{ConfigAuthCommand, ConfigCryptoCommand},
+    auth::revoke_refresh_token,
+    credentials::{open_credentials, Credentials, HandleCredentials},
+    models::{
+        ConfigAuthCommand, ConfigCryptoCommand, ConfigKeypairCommand, ConfigOAuthCommand,
+        ConfigPoliciesCommand, ConfigSettingsCommand, ExportedToken, EXPORT_VERSION,
+    },
+    keypair::rotate_keypair,
+    secrets::{decrypt_secret, encrypt_secret},
 };
 
 use super::{
+    init_dracoon,
     models::{ConfigCommand, DcCmdError},
-    utils::strings::{format_error_message, to_readable_size},
-    SERVICE_NAME,
+    read_encryption_password_file,
+    utils::strings::{format_error_message, format_success_message},
+    ENCRYPTION_SECRET_ENV,
 };
 
+pub mod auth;
 pub mod credentials;
+mod device;
+mod keypair;
 pub mod logs;
 pub mod models;
+mod oauth;
+mod policies;
+pub mod record;
+pub mod secrets;
+mod settings;
+mod system;
 
 pub const MAX_CONCURRENT_REQUESTS: usize = 10;
 pub const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024 * 32; // 32 MB (standard S3 chunk)
 pub const DEFAULT_CONCURRENT_MULTIPLIER: u8 = 10;
 pub const MAX_VELOCITY: u8 = 10;
 pub const MIN_VELOCITY: u8 = 1;
+pub const BULK_CONCURRENT_MULTIPLIER: usize = 5;
 pub const CLIENT_ID: &str = env!("DCCMD_CLIENT_ID");
 pub const CLIENT_SECRET: &str = env!("DCCMD_CLIENT_SECRET");
 pub const APPLICATION_NAME: &str = "dccmd";
 
+/// concurrent requests of bulk admin operations (velocity 1-10 equals 5-50 requests)
+pub fn bulk_concurrency(velocity: Option<u8>, default: usize) -> usize {
+    velocity.map_or(default, |velocity| {
+        usize::from(velocity.clamp(MIN_VELOCITY, MAX_VELOCITY)) * BULK_CONCURRENT_MULTIPLIER
+    })
+}
+
 pub struct ConfigCommandHandler {
     entry: Box<dyn HandleCredentials>,
     term: Term,
@@ -91,100 +116,117 @@ impl ConfigCommandHandler {
         Ok(())
     }
 
-    pub async fn get_system_info(&self, target: &str) -> Result<(), DcCmdError> {
-        let dracoon = self.get_dracoon_client(target).await?;
-
-        let oidc_info = dracoon
-            .system()
-            .auth
-            .get_openid_idp_configurations()
-            .await?;
-        let ad_info = dracoon
-            .system()
-            .auth
-            .get_active_directory_configurations()
-            .await?;
-        let customer_info = dracoon.user().get_customer_info().await?;
-
-        self.term
-            .write_line(&format!("► System info for: {target}"))
-            .map_err(|_| DcCmdError::IoError)?;
-
-        // Customer info
-        self.term
-            .write_line(&format!("► Customer: {}", customer_info.name))
-            .map_err(|_| DcCmdError::IoError)?;
-
-        let percent_space_used =
-            (customer_info.space_used as f64 / customer_info.space_limit as f64) * 100.0;
-        let percent_users_used =
-            (customer_info.accounts_used as f64 / customer_info.accounts_limit as f64) * 100.0;
-        let space_used = to_readable_size(customer_info.space_used);
-        let space_limit = to_readable_size(customer_info.space_limit);
-
-        self.term
-            .write_line(&format!(
-                "► Space used: {space_used} / {space_limit} ({percent_space_used:.2}%)"
+    pub async fn remove_refresh_token(
+        &self,
+        target: &str,
+        keep_remote: bool,
+    ) -> Result<(), DcCmdError> {
+        let confirmed = Confirm::new()
+            .with_prompt(format!(
+                "Are you sure you want to remove the token for {target}?"
             ))
-            .map_err(|_| DcCmdError::IoError)?;
+            .interact_opt();
 
-        self.term
-            .write_line(&format!(
-                "► Users used: {} / {} ({percent_users_used:.2}%)",
-                customer_info.accounts_used, customer_info.accounts_limit
-            ))
-            .map_err(|_| DcCmdError::IoError)?;
+        if let Ok(Some(true)) = confirmed {
+            if !keep_remote {
+                if let Ok(refresh_token) = self.entry.get_dracoon_env() {
+                    // an already invalid token is removed anyway
+                    if let Err(e) = revoke_refresh_token(target, refresh_token).await {
+                        warn!("Failed to revoke tokens for {}: {}", target, e);
+                    }
+                }
+            }
 
-        // Authentication methods
-        if !oidc_info.is_empty() {
+            self.entry.delete_dracoon_env()?;
             self.term
-                .write_line("\n► OpenID Connect IDP configurations:")
+                .write_line(&format!("► Token removed for {target}"))
                 .map_err(|_| DcCmdError::IoError)?;
+        }
 
-            for info in oidc_info {
-                self.term
-                    .write_line(&format!(
-                        "► {} ({})",
-                        info.name.unwrap_or_default(),
-                        info.id
-                    ))
-                    .map_err(|_| DcCmdError::IoError)?;
-            }
-        } else {
+        Ok(())
+    }
+
+    pub fn export_refresh_token(
+        &self,
+        target: &str,
+        out: Option<PathBuf>,
+        passphrase: Option<String>,
+    ) -> Result<(), DcCmdError> {
+        let Ok(refresh_token) = self.entry.get_dracoon_env() else {
+            let msg = format_error_message(
+                format!("No token found for this DRACOON url: {target}.").as_str(),
+            );
             self.term
-                .write_line("\n► No OpenID Connect IDP configurations found.")
+                .write_line(&msg)
                 .map_err(|_| DcCmdError::IoError)?;
+            return Err(DcCmdError::InvalidAccount);
+        };
+
+        let passphrase = match passphrase {
+            Some(passphrase) => passphrase,
+            None => dialoguer::Password::new()
+                .with_prompt("Please enter a passphrase to encrypt the token")
+                .with_confirmation("Please confirm the passphrase", "Passphrases do not match.")
+                .interact()
+                .or(Err(DcCmdError::IoError))?,
+        };
+
+        if passphrase.is_empty() {
+            return Err(DcCmdError::InvalidArgument(
+                "Passphrase must not be empty.".to_string(),
+            ));
         }
 
-        if !ad_info.items.is_empty() {
-            self.term
-                .write_line("\n► Active Directory configurations:")
-                .map_err(|_| DcCmdError::IoError)?;
+        let export = ExportedToken {
+            version: EXPORT_VERSION,
+            target: target.to_string(),
+            token: encrypt_secret(&refresh_token, &passphrase)?,
+        };
+
+        let export = serde_json::to_string_pretty(&export).map_err(|_| DcCmdError::IoError)?;
 
-            for info in ad_info.items {
+        match out {
+            Some(out) => {
+                std::fs::write(&out, export).map_err(|_| DcCmdError::IoError)?;
                 self.term
-                    .write_line(&format!("► {} ({})", info.alias, info.id))
+                    .write_line(&format_success_message(&format!(
+                        "Token for {target} exported to {}.",
+                        out.display()
+                    )))
                     .map_err(|_| DcCmdError::IoError)?;
             }
-        } else {
-            self.term
-                .write_line("\n► No Active Directory configurations found.")
-                .map_err(|_| DcCmdError::IoError)?;
+            None => self
+                .term
+                .write_line(&export)
+                .map_err(|_| DcCmdError::IoError)?,
         }
+
         Ok(())
     }
 
-    pub fn remove_refresh_token(&self, target: &str) -> Result<(), DcCmdError> {
-        let confirmed = Confirm::new()
-            .with_prompt("Are you sure you want to remove the token?")
-            .interact_opt();
+    pub fn import_refresh_token(
+        &self,
+        export: &ExportedToken,
+        passphrase: Option<String>,
+    ) -> Result<(), DcCmdError> {
+        let passphrase = match passphrase {
+            Some(passphrase) => passphrase,
+            None => dialoguer::Password::new()
+                .with_prompt("Please enter the passphrase to decrypt the token")
+                .interact()
+                .or(Err(DcCmdError::IoError))?,
+        };
 
-        if let Ok(Some(true)) = confirmed {
-            self.entry.delete_dracoon_env()?;
-            self.term
-                .write_line(&format!("► Token removed for {target}"))
-                .map_err(|_| DcCmdError::IoError)?;
-        }
+        let refresh_token = decrypt_secret(&export.token, &passphrase)?;
+
+        self.entry.set_dracoon_env(&refresh_token)?;
+
+        self.term
+            .write_line(&format_success_message(&format!(
+                "Token imported for {}.",
+                export.target
+            )))
+            .map_err(|_| DcCmdError::IoError)?;
 
         Ok(())
     }
@@ -211,6 +253,118 @@ impl ConfigCommandHandler {
         Ok(())
     }
 
+    pub async fn set_encryption_secret(
+        &self,
+        target: &str,
+        secret: Option<String>,
+    ) -> Result<(), DcCmdError> {
+        let secret = match secret {
+            Some(secret) => secret,
+            None => dialoguer::Password::new()
+                .with_prompt("Please enter your encryption secret")
+                .interact()
+                .or(Err(DcCmdError::IoError))?,
+        };
+
+        // verify the secret before storing it
+        let base_url = format!("{}/", target.trim_end_matches("/-crypto"));
+        let dracoon = init_dracoon(&base_url, None, false).await?;
+
+        dracoon
+            .get_keypair(Some(secret.clone()))
+            .await
+            .map_err(|e| {
+                error!("Error getting keypair: {}", e);
+                DcCmdError::InvalidArgument("Invalid encryption secret.".to_string())
+            })?;
+
+        self.entry.set_dracoon_env(&secret)?;
+
+        self.term
+            .write_line(
+                format!(
+                    "► Encryption secret stored for {}.",
+                    target.trim_end_matches("/-crypto")
+                )
+                .as_str(),
+            )
+            .map_err(|_| DcCmdError::IoError)?;
+
+        Ok(())
+    }
+
+    pub async fn rotate_keypair(
+        &self,
+        target: &str,
+        secret: Option<String>,
+        new_secret: Option<String>,
+    ) -> Result<(), DcCmdError> {
+        let base_url = format!("{}/", target.trim_end_matches("/-crypto"));
+
+        let stored_secret = self.entry.get_dracoon_env().ok();
+
+        let secret = match secret.or_else(|| stored_secret.clone()) {
+            Some(secret) => secret,
+            None => dialoguer::Password::new()
+                .with_prompt("Please enter your current encryption secret")
+                .interact()
+                .or(Err(DcCmdError::IoError))?,
+        };
+
+        let new_secret = match new_secret {
+            Some(new_secret) => new_secret,
+            None => dialoguer::Password::new()
+                .with_prompt("Please enter the encryption secret for the new keypair")
+                .with_confirmation("Please confirm the new secret", "Secrets do not match.")
+                .interact()
+                .or(Err(DcCmdError::IoError))?,
+        };
+
+        if new_secret == secret {
+            return Err(DcCmdError::InvalidArgument(
+                "New encryption secret must differ from the current secret.".to_string(),
+            ));
+        }
+
+        let dracoon = init_dracoon(&base_url, None, false).await?;
+        let rotation = rotate_keypair(&dracoon, &secret, &new_secret).await?;
+
+        if rotation.failed > 0 {
+            let msg = format_error_message(
+                format!(
+                    "{} file key(s) could not be re-encrypted - old keypair kept (re-run to retry).",
+                    rotation.failed
+                )
+                .as_str(),
+            );
+            self.term
+                .write_line(&msg)
+                .map_err(|_| DcCmdError::IoError)?;
+            return Err(DcCmdError::InvalidArgument(
+                "Keypair rotation incomplete.".to_string(),
+            ));
+        }
+
+        // replace a stored secret - it no longer matches the keypair
+        if stored_secret.is_some() {
+            self.entry.set_dracoon_env(&new_secret)?;
+        }
+
+        let msg = format_success_message(
+            format!(
+                "Keypair rotated for {} ({} file key(s) re-encrypted).",
+                target.trim_end_matches("/-crypto"),
+                rotation.reencrypted
+            )
+            .as_str(),
+        );
+        self.term
+            .write_line(&msg)
+            .map_err(|_| DcCmdError::IoError)?;
+
+        Ok(())
+    }
+
     pub fn remove_encryption_secret(&self, target: &str) -> Result<(), DcCmdError> {
         self.entry.delete_dracoon_env()?;
         self.term
@@ -224,18 +378,44 @@ impl ConfigCommandHandler {
 pub async fn handle_config_cmd(cmd: ConfigCommand, term: Term) -> Result<(), DcCmdError> {
     match cmd {
         ConfigCommand::Auth { cmd } => match cmd {
-            ConfigAuthCommand::Ls { target } => {
+            ConfigAuthCommand::Ls { target: None, .. } => auth::list_stored_accounts(&term).await,
+            ConfigAuthCommand::Ls {
+                target: Some(target),
+                ..
+            } => {
                 let (target, entry) = prepare_config_cmd(&target, &term, false)?;
 
                 let handler = ConfigCommandHandler::new(entry, term);
                 handler.get_refresh_token_info(target).await?;
                 Ok(())
             }
-            ConfigAuthCommand::Rm { target } => {
+            ConfigAuthCommand::Rm {
+                target,
+                keep_remote,
+            } => {
                 let (target, entry) = prepare_config_cmd(&target, &term, false)?;
 
                 let handler = ConfigCommandHandler::new(entry, term);
-                handler.remove_refresh_token(&target)?;
+                handler.remove_refresh_token(&target, keep_remote).await?;
+                Ok(())
+            }
+            ConfigAuthCommand::Export {
+                target,
+                out,
+                passphrase,
+            } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.export_refresh_token(&target, out, passphrase)?;
+                Ok(())
+            }
+            ConfigAuthCommand::Import { source, passphrase } => {
+                let export = read_exported_token(&source)?;
+                let (_, entry) = prepare_config_cmd(&export.target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.import_refresh_token(&export, passphrase)?;
                 Ok(())
             }
         },
@@ -254,13 +434,100 @@ pub async fn handle_config_cmd(cmd: ConfigCommand, term: Term) -> Result<(), DcC
                 handler.remove_encryption_secret(&target)?;
                 Ok(())
             }
+            ConfigCryptoCommand::Set {
+                target,
+                secret_file,
+            } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, true)?;
+
+                let secret = match secret_file {
+                    Some(secret_file) => Some(read_encryption_password_file(&secret_file)?),
+                    None => std::env::var(ENCRYPTION_SECRET_ENV).ok(),
+                };
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.set_encryption_secret(&target, secret).await?;
+                Ok(())
+            }
+            ConfigCryptoCommand::Keypair { cmd } => match cmd {
+                ConfigKeypairCommand::Rotate {
+                    target,
+                    secret_file,
+                    new_secret_file,
+                } => {
+                    let (target, entry) = prepare_config_cmd(&target, &term, true)?;
+
+                    let secret = match secret_file {
+                        Some(secret_file) => Some(read_encryption_password_file(&secret_file)?),
+                        None => std::env::var(ENCRYPTION_SECRET_ENV).ok(),
+                    };
+
+                    let new_secret = new_secret_file
+                        .map(|new_secret_file| read_encryption_password_file(&new_secret_file))
+                        .transpose()?;
+
+                    let handler = ConfigCommandHandler::new(entry, term);
+                    handler
+                        .rotate_keypair(&target, secret, new_secret)
+                        .await?;
+                    Ok(())
+                }
+            },
+        },
+        ConfigCommand::Settings { cmd } => match cmd {
+            ConfigSettingsCommand::Ls { target } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.get_system_settings(&target).await?;
+                Ok(())
+            }
+        },
+        ConfigCommand::Policies { cmd } => match cmd {
+            ConfigPoliciesCommand::Ls { target, json } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.get_policies(&target, json).await?;
+                Ok(())
+            }
         },
-        ConfigCommand::SystemInfo { target } => {
+        ConfigCommand::OAuth { cmd } => match cmd {
+            ConfigOAuthCommand::Ls { target } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.list_oauth_clients(&target).await?;
+                Ok(())
+            }
+            ConfigOAuthCommand::Create {
+                target,
+                name,
+                grant_types,
+                redirect_uris,
+            } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler
+                    .create_oauth_client(&target, name, grant_types, redirect_uris)
+                    .await?;
+                Ok(())
+            }
+            ConfigOAuthCommand::Rm { target, client_id } => {
+                let (target, entry) = prepare_config_cmd(&target, &term, false)?;
+
+                let handler = ConfigCommandHandler::new(entry, term);
+                handler.delete_oauth_client(&target, &client_id).await?;
+                Ok(())
+            }
+        },
+        ConfigCommand::SystemInfo { target, json } => {
             let (target, entry) = prepare_config_cmd(&target, &term, false)?;
 
             let handler = ConfigCommandHandler::new(entry, term);
 
-            handler.get_system_info(&target).await?;
+            handler.get_system_info(&target, json).await?;
 
             Ok(())
         }
@@ -271,7 +538,7 @@ fn prepare_config_cmd(
     target: &str,
     term: &Term,
     is_crypto: bool,
-) -> Result<(String, impl HandleCredentials), DcCmdError> {
+) -> Result<(String, Credentials), DcCmdError> {
     let base_url = format!(
         "https://{}",
         target
@@ -286,9 +553,9 @@ fn prepare_config_cmd(
         base_url
     };
 
-    let Ok(entry) = Entry::new(SERVICE_NAME, &base_url) else {
+    let Ok(entry) = open_credentials(&base_url) else {
         let msg =
-            format_error_message("Secure storage for credentials not available on this platform.");
+            format_error_message("Secure storage for credentials not available on this platform (use --credential-store file).");
         term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
         return Err(DcCmdError::CredentialStorageFailed);
     };
@@ -296,7 +563,24 @@ fn prepare_config_cmd(
     Ok((base_url, entry))
 }
 
-fn get_or_create_config_dir() -> PathBuf {
+fn read_exported_token(source: &Path) -> Result<ExportedToken, DcCmdError> {
+    let content = std::fs::read_to_string(source)
+        .map_err(|_| DcCmdError::InvalidPath(source.display().to_string()))?;
+
+    let export: ExportedToken = serde_json::from_str(&content)
+        .map_err(|e| DcCmdError::InvalidArgument(format!("Invalid token export ({e}).")))?;
+
+    if export.version != EXPORT_VERSION {
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Unsupported token export version: {}.",
+            export.version
+        )));
+    }
+
+    Ok(export)
+}
+
+pub(crate) fn get_or_create_config_dir() -> PathBuf {
     if let Some(config_dir) = dirs::config_dir() {
         let config_dir = config_dir.join(APPLICATION_NAME);
 
@@ -314,3 +598,17 @@ fn get_or_create_config_dir() -> PathBuf {
         panic!("Unsupported platform (no config dir found). Only Linux, MacOS and Windows are supported.");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_concurrency() {
+        assert_eq!(10, bulk_concurrency(None, 10));
+        assert_eq!(5, bulk_concurrency(Some(1), 10));
+        assert_eq!(50, bulk_concurrency(Some(10), 10));
+        assert_eq!(50, bulk_concurrency(Some(20), 10));
+        assert_eq!(5, bulk_concurrency(Some(0), 10));
+    }
+}
@@ -1,18 +1,65 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+/// current version of the export format
+pub const EXPORT_VERSION: u8 = 1;
 
 #[derive(Parser)]
 pub enum ConfigAuthCommand {
     /// List DRACOON refresh token
     Ls {
         /// DRACOON url
-        target: String,
+        #[clap(required_unless_present = "all")]
+        target: Option<String>,
+
+        /// list all stored accounts (instances, users and token ages)
+        #[clap(long, conflicts_with = "target")]
+        all: bool,
     },
 
-    /// Remove a DRACOON refresh token
+    /// Remove a DRACOON refresh token (revokes the token)
     Rm {
         /// DRACOON url
         target: String,
+
+        /// only remove the local token (token stays valid in DRACOON)
+        #[clap(long)]
+        keep_remote: bool,
     },
+
+    /// Export a DRACOON refresh token (encrypted with a passphrase)
+    Export {
+        /// DRACOON url
+        target: String,
+
+        /// file to write the export to (default: stdout)
+        #[clap(long, short)]
+        out: Option<PathBuf>,
+
+        /// passphrase to encrypt the token with (prompted if not provided)
+        #[clap(long)]
+        passphrase: Option<String>,
+    },
+
+    /// Import an exported DRACOON refresh token
+    Import {
+        /// exported token file
+        source: PathBuf,
+
+        /// passphrase to decrypt the token with (prompted if not provided)
+        #[clap(long)]
+        passphrase: Option<String>,
+    },
+}
+
+/// exported refresh token (encrypted with a passphrase)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedToken {
+    pub version: u8,
+    pub target: String,
+    pub token: String,
 }
 
 #[derive(Parser)]
@@ -28,4 +75,106 @@ pub enum ConfigCryptoCommand {
         /// DRACOON url
         target: String,
     },
+
+    /// Store or update a DRACOON encryption secret (verified against the keypair)
+    Set {
+        /// DRACOON url
+        target: String,
+
+        /// file containing the encryption secret (default: DCCMD_ENCRYPTION_SECRET or prompt)
+        #[clap(long)]
+        secret_file: Option<PathBuf>,
+    },
+
+    /// Manage the DRACOON user keypair
+    Keypair {
+        #[clap(subcommand)]
+        cmd: ConfigKeypairCommand,
+    },
+}
+
+#[derive(Parser)]
+pub enum ConfigKeypairCommand {
+    /// Create a new keypair, re-encrypt all file keys with it and remove the old keypair
+    Rotate {
+        /// DRACOON url
+        target: String,
+
+        /// file containing the current encryption secret (default: stored secret, DCCMD_ENCRYPTION_SECRET or prompt)
+        #[clap(long)]
+        secret_file: Option<PathBuf>,
+
+        /// file containing the encryption secret for the new keypair (default: prompt)
+        #[clap(long)]
+        new_secret_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Parser)]
+pub enum ConfigPoliciesCommand {
+    /// List DRACOON share password and classification policies
+    Ls {
+        /// DRACOON url
+        target: String,
+
+        /// print policies as JSON (for scripted password validation)
+        #[clap(long)]
+        json: bool,
+    },
+}
+
+#[derive(Parser)]
+pub enum ConfigSettingsCommand {
+    /// List DRACOON system settings (password policies, defaults, encryption)
+    Ls {
+        /// DRACOON url
+        target: String,
+    },
+}
+
+#[derive(Parser)]
+pub enum ConfigOAuthCommand {
+    /// List DRACOON OAuth clients
+    Ls {
+        /// DRACOON url
+        target: String,
+    },
+
+    /// Create a DRACOON OAuth client (prints client id and secret)
+    Create {
+        /// DRACOON url
+        target: String,
+
+        /// name of the OAuth client
+        #[clap(long, short)]
+        name: String,
+
+        /// allowed grant types (default: authorization-code, refresh-token)
+        #[clap(long = "grant-type", value_enum)]
+        grant_types: Vec<OAuthGrantType>,
+
+        /// allowed redirect URIs (default: <DRACOON url>/oauth/callback)
+        #[clap(long = "redirect-uri")]
+        redirect_uris: Vec<String>,
+    },
+
+    /// Remove a DRACOON OAuth client
+    Rm {
+        /// DRACOON url
+        target: String,
+
+        /// id of the OAuth client
+        client_id: String,
+    },
+}
+
+/// OAuth grant types of an OAuth client
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthGrantType {
+    AuthorizationCode,
+    RefreshToken,
+    Password,
+    ClientCredentials,
+    Implicit,
 }
@@ -0,0 +1,250 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tabled::{
+    settings::{object::Segment, Modify, Panel, Style, Width},
+    Table, Tabled,
+};
+
+use crate::cmd::{
+    models::DcCmdError,
+    utils::{
+        api::{api_request, send_api_json, send_api_request},
+        strings::format_success_message,
+    },
+};
+
+use super::{models::OAuthGrantType, ConfigCommandHandler, CLIENT_ID};
+
+/// OAuth client management is not provided by dco3 and requested directly
+const OAUTH_CLIENTS_PATH: &str = "settings/oauth/clients";
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct OAuthClient {
+    client_id: String,
+    client_name: String,
+    client_secret: Option<String>,
+    client_type: Option<String>,
+    #[serde(default)]
+    grant_types: Vec<String>,
+    #[serde(default)]
+    redirect_uris: Vec<String>,
+    is_standard: Option<bool>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateOAuthClientRequest {
+    client_name: String,
+    client_type: &'static str,
+    grant_types: Vec<OAuthGrantType>,
+    redirect_uris: Vec<String>,
+}
+
+impl CreateOAuthClientRequest {
+    fn new(
+        target: &str,
+        name: String,
+        grant_types: Vec<OAuthGrantType>,
+        redirect_uris: Vec<String>,
+    ) -> Self {
+        let grant_types = if grant_types.is_empty() {
+            vec![
+                OAuthGrantType::AuthorizationCode,
+                OAuthGrantType::RefreshToken,
+            ]
+        } else {
+            grant_types
+        };
+
+        let redirect_uris = if redirect_uris.is_empty() {
+            vec![format!("{}/oauth/callback", target.trim_end_matches('/'))]
+        } else {
+            redirect_uris
+        };
+
+        Self {
+            client_name: name,
+            client_type: "confidential",
+            grant_types,
+            redirect_uris,
+        }
+    }
+}
+
+#[derive(Tabled)]
+struct OAuthClientInfo {
+    client_id: String,
+    name: String,
+    #[tabled(display_with = "crate::cmd::users::display_option")]
+    client_type: Option<String>,
+    grant_types: String,
+    redirect_uris: String,
+    #[tabled(display_with = "crate::cmd::users::display_option")]
+    standard: Option<bool>,
+}
+
+impl From<OAuthClient> for OAuthClientInfo {
+    fn from(client: OAuthClient) -> Self {
+        Self {
+            client_id: client.client_id,
+            name: client.client_name,
+            client_type: client.client_type,
+            grant_types: client.grant_types.join(", "),
+            redirect_uris: client.redirect_uris.join(", "),
+            standard: client.is_standard,
+        }
+    }
+}
+
+impl ConfigCommandHandler {
+    pub async fn list_oauth_clients(&self, target: &str) -> Result<(), DcCmdError> {
+        let dracoon = self.get_dracoon_client(target).await?;
+
+        let request = api_request(&dracoon, Method::GET, OAUTH_CLIENTS_PATH).await?;
+        let clients = send_api_json::<Vec<OAuthClient>>(request).await?;
+
+        let clients = clients
+            .into_iter()
+            .map(OAuthClientInfo::from)
+            .collect::<Vec<_>>();
+        let count = clients.len();
+
+        let mut table = Table::new(clients);
+        table
+            .with(Panel::footer(format!("{count} OAuth clients")))
+            .with(Style::modern())
+            .with(Modify::new(Segment::all()).with(Width::wrap(32)));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub async fn create_oauth_client(
+        &self,
+        target: &str,
+        name: String,
+        grant_types: Vec<OAuthGrantType>,
+        redirect_uris: Vec<String>,
+    ) -> Result<(), DcCmdError> {
+        let dracoon = self.get_dracoon_client(target).await?;
+
+        let req = CreateOAuthClientRequest::new(target, name, grant_types, redirect_uris);
+        let request = api_request(&dracoon, Method::POST, OAUTH_CLIENTS_PATH)
+            .await?
+            .json(&req);
+        let client = send_api_json::<OAuthClient>(request).await?;
+
+        let msg = format!("OAuth client {} created", client.client_name);
+        self.term
+            .write_line(format_success_message(&msg).as_str())
+            .map_err(|_| DcCmdError::IoError)?;
+
+        self.write_setting("Client id", &client.client_id)?;
+        self.write_setting(
+            "Client secret",
+            client.client_secret.as_deref().unwrap_or("N/A"),
+        )?;
+        self.write_setting("Redirect URIs", &client.redirect_uris.join(", "))
+    }
+
+    pub async fn delete_oauth_client(
+        &self,
+        target: &str,
+        client_id: &str,
+    ) -> Result<(), DcCmdError> {
+        // removing the client dccmd-rs is built with would lock out every user of this instance
+        if client_id == CLIENT_ID {
+            return Err(DcCmdError::InvalidArgument(
+                "Cannot remove the OAuth client used by dccmd-rs.".to_string(),
+            ));
+        }
+
+        let dracoon = self.get_dracoon_client(target).await?;
+
+        let request = api_request(
+            &dracoon,
+            Method::DELETE,
+            &format!("{OAUTH_CLIENTS_PATH}/{client_id}"),
+        )
+        .await?;
+        send_api_request(request).await?;
+
+        let msg = format!("OAuth client {client_id} removed");
+        self.term
+            .write_line(format_success_message(&msg).as_str())
+            .map_err(|_| DcCmdError::IoError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_oauth_client() {
+        let json = r#"{
+            "clientId": "dccmd",
+            "clientName": "dccmd-rs",
+            "clientSecret": "secret",
+            "clientType": "confidential",
+            "grantTypes": ["authorization_code", "refresh_token"],
+            "redirectUris": ["https://dracoon.team/oauth/callback"],
+            "isStandard": false,
+            "accessTokenValidity": 28800
+        }"#;
+
+        let client = serde_json::from_str::<OAuthClient>(json).unwrap();
+        let info = OAuthClientInfo::from(client);
+
+        assert_eq!(info.client_id, "dccmd");
+        assert_eq!(info.grant_types, "authorization_code, refresh_token");
+        assert_eq!(info.redirect_uris, "https://dracoon.team/oauth/callback");
+        assert_eq!(info.standard, Some(false));
+    }
+
+    #[test]
+    fn test_create_oauth_client_request_defaults() {
+        let req = CreateOAuthClientRequest::new(
+            "https://dracoon.team/",
+            "dccmd-rs".to_string(),
+            vec![],
+            vec![],
+        );
+
+        let json = serde_json::to_value(&req).unwrap();
+
+        assert_eq!(json["clientName"], "dccmd-rs");
+        assert_eq!(json["clientType"], "confidential");
+        assert_eq!(
+            json["grantTypes"],
+            serde_json::json!(["authorization_code", "refresh_token"])
+        );
+        assert_eq!(
+            json["redirectUris"],
+            serde_json::json!(["https://dracoon.team/oauth/callback"])
+        );
+    }
+
+    #[test]
+    fn test_create_oauth_client_request() {
+        let req = CreateOAuthClientRequest::new(
+            "https://dracoon.team",
+            "dccmd-rs".to_string(),
+            vec![OAuthGrantType::ClientCredentials],
+            vec!["http://localhost:8080".to_string()],
+        );
+
+        let json = serde_json::to_value(&req).unwrap();
+
+        assert_eq!(
+            json["grantTypes"],
+            serde_json::json!(["client_credentials"])
+        );
+        assert_eq!(
+            json["redirectUris"],
+            serde_json::json!(["http://localhost:8080"])
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use dco3::{config::MinimumClassification, Config};
+use serde::Serialize;
+use tracing::error;
+
+use crate::cmd::models::DcCmdError;
+
+use super::{
+    settings::{format_optional, NOT_AVAILABLE},
+    ConfigCommandHandler,
+};
+
+/// share policies of a DRACOON instance (used to validate share passwords client-side)
+#[derive(Debug, Default, Serialize)]
+struct SharePolicies {
+    password_min_length: Option<u32>,
+    password_must_contain: Vec<String>,
+    password_characteristics_to_enforce: Option<u32>,
+    password_reject_dictionary_words: Option<bool>,
+    password_reject_user_info: Option<bool>,
+    password_reject_keyboard_patterns: Option<bool>,
+    password_required_from_classification: Option<String>,
+}
+
+impl ConfigCommandHandler {
+    pub async fn get_policies(&self, target: &str, json: bool) -> Result<(), DcCmdError> {
+        let dracoon = self.get_dracoon_client(target).await?;
+
+        let password_policies = dracoon.config().get_password_policies().await?;
+        let classification_policies = dracoon.config().get_classification_policies().await?;
+
+        let mut policies = SharePolicies::default();
+
+        if let Some(shares_policies) = password_policies.shares_password_policies {
+            policies.password_min_length = shares_policies
+                .min_length
+                .and_then(|min_length| u32::try_from(min_length).ok());
+
+            if let Some(rules) = shares_policies.character_rules {
+                policies.password_must_contain = rules
+                    .must_contain_characters
+                    .iter()
+                    .map(|rule| format!("{rule:?}").to_lowercase())
+                    .collect();
+                policies.password_characteristics_to_enforce =
+                    u32::try_from(rules.number_of_characteristics_to_enforce).ok();
+            }
+
+            policies.password_reject_dictionary_words = shares_policies.reject_dictionary_words;
+            policies.password_reject_user_info = shares_policies.reject_user_info;
+            policies.password_reject_keyboard_patterns = shares_policies.reject_keyboard_patterns;
+        }
+
+        if let Some(share_policies) = classification_policies.share_classification_policies {
+            policies.password_required_from_classification =
+                classification_name(&share_policies.classification_requires_share_password)
+                    .map(ToString::to_string);
+        }
+
+        if json {
+            let json = serde_json::to_string_pretty(&policies).map_err(|e| {
+                error!("Error serializing policies: {}", e);
+                DcCmdError::IoError
+            })?;
+
+            return self.term.write_line(&json).map_err(|_| DcCmdError::IoError);
+        }
+
+        self.print_policies(target, &policies)
+    }
+
+    fn print_policies(&self, target: &str, policies: &SharePolicies) -> Result<(), DcCmdError> {
+        self.write_setting("Policies for", target)?;
+
+        self.term
+            .write_line("\n► Share password policies:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting(
+            "Min. length",
+            &format_optional(policies.password_min_length),
+        )?;
+        self.write_setting(
+            "Must contain",
+            &if policies.password_must_contain.is_empty() {
+                NOT_AVAILABLE.to_string()
+            } else {
+                policies.password_must_contain.join(", ")
+            },
+        )?;
+        self.write_setting(
+            "Characteristics to enforce",
+            &format_optional(policies.password_characteristics_to_enforce),
+        )?;
+        self.write_setting(
+            "Reject dictionary words",
+            &format_optional(policies.password_reject_dictionary_words),
+        )?;
+        self.write_setting(
+            "Reject user info",
+            &format_optional(policies.password_reject_user_info),
+        )?;
+        self.write_setting(
+            "Reject keyboard patterns",
+            &format_optional(policies.password_reject_keyboard_patterns),
+        )?;
+
+        self.term
+            .write_line("\n► Classification policies:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting(
+            "Share password required from",
+            policies
+                .password_required_from_classification
+                .as_deref()
+                .unwrap_or("never"),
+        )
+    }
+}
+
+/// maps the minimum classification requiring a share password (None if never required)
+fn classification_name(level: &MinimumClassification) -> Option<&'static str> {
+    match level {
+        MinimumClassification::Public => Some("public"),
+        MinimumClassification::Internal => Some("internal"),
+        MinimumClassification::Confidential => Some("confidential"),
+        MinimumClassification::StrictlyConfidential => Some("strictly-confidential"),
+        MinimumClassification::NoPassword => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classification_name() {
+        assert_eq!(Some("public"), classification_name(&1.into()));
+        assert_eq!(Some("confidential"), classification_name(&3.into()));
+        assert_eq!(Some("strictly-confidential"), classification_name(&4.into()));
+    }
+
+    #[test]
+    fn test_classification_name_none() {
+        assert_eq!(None, classification_name(&0.into()));
+        assert_eq!(None, classification_name(&5.into()));
+    }
+}
@@ -0,0 +1,220 @@
+use std::{fs::File, io::Write, path::Path, sync::Mutex};
+
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{filter::Targets, layer::Context, Layer};
+
+use crate::cmd::models::DcCmdError;
+
+/// placeholder of sensitive values
+const REDACTED: &str = "[redacted]";
+
+/// placeholder of request / response bodies (without --record-bodies)
+const OMITTED: &str = "[omitted]";
+
+/// fields holding credentials (case insensitive)
+const SENSITIVE_FIELDS: [&str; 5] = ["token", "authorization", "password", "secret", "cookie"];
+
+/// markers followed by credentials in values (case insensitive)
+const SENSITIVE_MARKERS: [&str; 6] = [
+    "bearer",
+    "x-sds-auth-token",
+    "access_token",
+    "refresh_token",
+    "password",
+    "secret",
+];
+
+/// fields holding request / response bodies
+const BODY_KEYS: [&str; 3] = ["body", "payload", "response_body"];
+
+/// records sanitized request / response events of the DRACOON client as JSON lines
+/// (attach to bug reports - tokens are redacted, bodies only with --record-bodies)
+pub struct RecordLayer {
+    file: Mutex<File>,
+    bodies: bool,
+}
+
+impl RecordLayer {
+    pub fn new(path: &Path, bodies: bool) -> Result<Self, DcCmdError> {
+        let mut file = File::create(path)
+            .map_err(|_| DcCmdError::InvalidPath(path.display().to_string()))?;
+
+        // session header (no command line - it may contain credentials)
+        let header = json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "bodies": bodies,
+        });
+
+        writeln!(file, "{header}").map_err(|_| DcCmdError::IoError)?;
+
+        Ok(Self {
+            file: Mutex::new(file),
+            bodies,
+        })
+    }
+
+    /// HTTP client events (and errors / warnings of dccmd-rs for context)
+    pub fn targets() -> Targets {
+        Targets::new()
+            .with_target("dco3", Level::DEBUG)
+            .with_target("reqwest", Level::DEBUG)
+            .with_target("reqwest_retry", Level::DEBUG)
+            .with_target(env!("CARGO_CRATE_NAME"), Level::WARN)
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecordLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let record = json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "fields": sanitize_fields(visitor.0, self.bodies),
+        });
+
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{record}");
+        }
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor(Map<String, Value>);
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0
+            .insert(field.name().to_string(), Value::from(value.to_string()));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), Value::from(format!("{value:?}")));
+    }
+}
+
+/// redacts credentials and omits bodies (unless recorded explicitly)
+fn sanitize_fields(fields: Map<String, Value>, bodies: bool) -> Map<String, Value> {
+    fields
+        .into_iter()
+        .map(|(key, value)| {
+            let lower_key = key.to_ascii_lowercase();
+
+            let value = if SENSITIVE_FIELDS.iter().any(|k| lower_key.contains(k)) {
+                Value::from(REDACTED)
+            } else if !bodies && BODY_KEYS.contains(&lower_key.as_str()) {
+                Value::from(OMITTED)
+            } else {
+                match value {
+                    Value::String(value) => Value::from(redact(&value)),
+                    value => value,
+                }
+            };
+
+            (key, value)
+        })
+        .collect()
+}
+
+/// replaces values following sensitive markers (e.g. `Bearer <token>`, `"password":"<secret>"`)
+fn redact(value: &str) -> String {
+    let mut redacted = value.to_string();
+
+    for marker in SENSITIVE_MARKERS {
+        let mut search_from = 0;
+
+        while let Some(pos) = redacted[search_from..].to_ascii_lowercase().find(marker) {
+            let marker_end = search_from + pos + marker.len();
+
+            // skip separators between marker and value (e.g. `": "`, `=`)
+            let value_start = redacted[marker_end..]
+                .find(|c: char| !matches!(c, '"' | '\'' | ':' | '=' | ' '))
+                .map_or(redacted.len(), |i| marker_end + i);
+
+            let value_end = redacted[value_start..]
+                .find(['"', '\'', '&', ',', '}', ' ', '\n'])
+                .map_or(redacted.len(), |i| value_start + i);
+
+            if value_end > value_start && &redacted[value_start..value_end] != REDACTED {
+                redacted.replace_range(value_start..value_end, REDACTED);
+                search_from = value_start + REDACTED.len();
+            } else {
+                search_from = marker_end;
+            }
+        }
+    }
+
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_bearer() {
+        assert_eq!(
+            "Authorization: Bearer [redacted]",
+            redact("Authorization: Bearer abc.def-123")
+        );
+    }
+
+    #[test]
+    fn test_redact_json() {
+        assert_eq!(
+            r#"{"refresh_token":"[redacted]","expires_in":3600}"#,
+            redact(r#"{"refresh_token":"abc123","expires_in":3600}"#)
+        );
+    }
+
+    #[test]
+    fn test_redact_query() {
+        assert_eq!(
+            "grant_type=password&password=[redacted]&username=user",
+            redact("grant_type=password&password=secret&username=user")
+        );
+    }
+
+    #[test]
+    fn test_redact_plain() {
+        assert_eq!(
+            "GET /api/v4/nodes?parent_id=1 200",
+            redact("GET /api/v4/nodes?parent_id=1 200")
+        );
+    }
+
+    #[test]
+    fn test_sanitize_fields() {
+        let mut fields = Map::new();
+        fields.insert("x_sds_auth_token".into(), Value::from("abc"));
+        fields.insert("body".into(), Value::from("{}"));
+        fields.insert("status".into(), Value::from("200"));
+
+        let fields = sanitize_fields(fields, false);
+
+        assert_eq!(fields["x_sds_auth_token"], REDACTED);
+        assert_eq!(fields["body"], OMITTED);
+        assert_eq!(fields["status"], "200");
+    }
+
+    #[test]
+    fn test_sanitize_fields_bodies() {
+        let mut fields = Map::new();
+        fields.insert("body".into(), Value::from(r#"{"name":"file.txt"}"#));
+
+        let fields = sanitize_fields(fields, true);
+
+        assert_eq!(fields["body"], r#"{"name":"file.txt"}"#);
+    }
+}
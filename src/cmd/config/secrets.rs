@@ -0,0 +1,127 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use openssl::{
+    hash::MessageDigest,
+    pkcs5::pbkdf2_hmac,
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use tracing::error;
+
+use crate::cmd::models::DcCmdError;
+
+const PBKDF2_ITERATIONS: usize = 600_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// encrypts a secret (e.g. refresh token) with a passphrase (AES-256-GCM, key derived via PBKDF2)
+///
+/// returns base64 encoded salt, nonce, tag and ciphertext
+pub fn encrypt_secret(secret: &str, passphrase: &str) -> Result<String, DcCmdError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    let mut tag = [0u8; TAG_LEN];
+
+    rand_bytes(&mut salt).map_err(crypto_error)?;
+    rand_bytes(&mut nonce).map_err(crypto_error)?;
+
+    let key = derive_key(passphrase, &salt)?;
+
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(&nonce),
+        &[],
+        secret.as_bytes(),
+        &mut tag,
+    )
+    .map_err(crypto_error)?;
+
+    let mut encrypted = Vec::with_capacity(SALT_LEN + NONCE_LEN + TAG_LEN + ciphertext.len());
+    encrypted.extend_from_slice(&salt);
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&tag);
+    encrypted.extend_from_slice(&ciphertext);
+
+    Ok(STANDARD.encode(encrypted))
+}
+
+/// decrypts a secret encrypted via `encrypt_secret`
+pub fn decrypt_secret(encrypted: &str, passphrase: &str) -> Result<String, DcCmdError> {
+    let invalid = || DcCmdError::InvalidArgument("Invalid passphrase or corrupted secret.".into());
+
+    let encrypted = STANDARD.decode(encrypted.trim()).map_err(|_| invalid())?;
+
+    if encrypted.len() < SALT_LEN + NONCE_LEN + TAG_LEN {
+        return Err(invalid());
+    }
+
+    let (salt, rest) = encrypted.split_at(SALT_LEN);
+    let (nonce, rest) = rest.split_at(NONCE_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+
+    let key = derive_key(passphrase, salt)?;
+
+    let secret = decrypt_aead(
+        Cipher::aes_256_gcm(),
+        &key,
+        Some(nonce),
+        &[],
+        ciphertext,
+        tag,
+    )
+    .map_err(|_| invalid())?;
+
+    String::from_utf8(secret).map_err(|_| invalid())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; KEY_LEN], DcCmdError> {
+    let mut key = [0u8; KEY_LEN];
+
+    pbkdf2_hmac(
+        passphrase.as_bytes(),
+        salt,
+        PBKDF2_ITERATIONS,
+        MessageDigest::sha256(),
+        &mut key,
+    )
+    .map_err(crypto_error)?;
+
+    Ok(key)
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn crypto_error(err: openssl::error::ErrorStack) -> DcCmdError {
+    error!("Crypto error: {}", err);
+    DcCmdError::Unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decrypt_secret, encrypt_secret};
+
+    #[test]
+    fn test_encrypt_decrypt_secret() {
+        let encrypted = encrypt_secret("some-refresh-token", "passphrase").unwrap();
+
+        assert_ne!(encrypted, "some-refresh-token");
+        assert_eq!(
+            decrypt_secret(&encrypted, "passphrase").unwrap(),
+            "some-refresh-token"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_secret_wrong_passphrase() {
+        let encrypted = encrypt_secret("some-refresh-token", "passphrase").unwrap();
+
+        assert!(decrypt_secret(&encrypted, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_secret_invalid_input() {
+        assert!(decrypt_secret("not base64!", "passphrase").is_err());
+        assert!(decrypt_secret("dG9vIHNob3J0", "passphrase").is_err());
+    }
+}
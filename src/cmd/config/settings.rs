@@ -0,0 +1,106 @@
+use dco3::{Config, User};
+
+use crate::cmd::{branding::load_instance_name, models::DcCmdError};
+
+use super::ConfigCommandHandler;
+
+pub(super) const NOT_AVAILABLE: &str = "N/A";
+
+impl ConfigCommandHandler {
+    pub async fn get_system_settings(&self, target: &str) -> Result<(), DcCmdError> {
+        let dracoon = self.get_dracoon_client(target).await?;
+
+        let general_settings = dracoon.config().get_general_settings().await?;
+        let defaults = dracoon.config().get_defaults().await?;
+        let password_policies = dracoon.config().get_password_policies().await?;
+        let customer_info = dracoon.user().get_customer_info().await?;
+        let branding_name = load_instance_name(dracoon.get_base_url().as_str()).await;
+
+        self.write_setting("Settings for", target)?;
+        self.write_setting("Branding name", &format_optional(branding_name))?;
+        self.write_setting("Customer", &customer_info.name)?;
+
+        // crypto
+        self.term
+            .write_line("\n► Encryption:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting(
+            "Encryption enabled",
+            &general_settings.crypto_enabled.to_string(),
+        )?;
+        self.write_setting(
+            "Customer encryption enabled",
+            &customer_info.customer_encryption_enabled.to_string(),
+        )?;
+
+        // share defaults
+        self.term
+            .write_line("\n► Defaults:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting(
+            "Language",
+            defaults
+                .language_default
+                .as_deref()
+                .unwrap_or(NOT_AVAILABLE),
+        )?;
+        self.write_setting(
+            "Download share expiration (days)",
+            &format_optional(defaults.download_share_default_expiration_period),
+        )?;
+        self.write_setting(
+            "Upload share expiration (days)",
+            &format_optional(defaults.upload_share_default_expiration_period),
+        )?;
+        self.write_setting(
+            "File expiration (days)",
+            &format_optional(defaults.file_default_expiration_period),
+        )?;
+        self.write_setting(
+            "Share password via SMS",
+            &general_settings.share_password_sms_enabled.to_string(),
+        )?;
+
+        // password policies
+        self.term
+            .write_line("\n► Password policies:")
+            .map_err(|_| DcCmdError::IoError)?;
+
+        if let Some(login_policies) = password_policies.login_password_policies {
+            self.write_setting(
+                "Login password min. length",
+                &login_policies.min_length.to_string(),
+            )?;
+            self.write_setting(
+                "Login password expiration enabled",
+                &login_policies.password_expiration.enabled.to_string(),
+            )?;
+        }
+
+        if let Some(shares_policies) = password_policies.shares_password_policies {
+            self.write_setting(
+                "Share password min. length",
+                &format_optional(shares_policies.min_length),
+            )?;
+        }
+
+        if let Some(encryption_policies) = password_policies.encryption_password_policies {
+            self.write_setting(
+                "Encryption password min. length",
+                &format_optional(encryption_policies.min_length),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn write_setting(&self, name: &str, value: &str) -> Result<(), DcCmdError> {
+        self.term
+            .write_line(&format!("► {name}: {value}"))
+            .map_err(|_| DcCmdError::IoError)
+    }
+}
+
+pub(super) fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| NOT_AVAILABLE.to_string(), |v| v.to_string())
+}
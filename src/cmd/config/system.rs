@@ -0,0 +1,308 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use dco3::{auth::Connected, AuthenticationMethods, Config, Dracoon, Public, User};
+use futures_util::future::join_all;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{debug, error};
+
+use crate::cmd::{models::DcCmdError, utils::strings::to_readable_size};
+
+use super::{
+    settings::{format_optional, NOT_AVAILABLE},
+    ConfigCommandHandler,
+};
+
+/// S3 endpoints are reported as unreachable if they don't respond in time
+const S3_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// diagnostics of a DRACOON instance (e.g. attached to support requests)
+#[derive(Debug, Default, Serialize)]
+struct SystemDiagnostics {
+    target: String,
+    customer: String,
+    space_used: u64,
+    space_limit: u64,
+    users_used: u64,
+    users_limit: u64,
+    api_version: String,
+    server_version: String,
+    openid_idps: Vec<String>,
+    active_directories: Vec<String>,
+    s3_storage: bool,
+    s3_endpoints: Vec<S3Endpoint>,
+    password_policies: PasswordPolicySummary,
+    features: BTreeMap<&'static str, bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct S3Endpoint {
+    host: String,
+    reachable: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct PasswordPolicySummary {
+    login_min_length: Option<u32>,
+    login_expiration_enabled: Option<bool>,
+    share_min_length: Option<u32>,
+    encryption_min_length: Option<u32>,
+}
+
+impl ConfigCommandHandler {
+    pub async fn get_system_info(&self, target: &str, json: bool) -> Result<(), DcCmdError> {
+        let dracoon = self.get_dracoon_client(target).await?;
+        let info = get_diagnostics(&dracoon, target).await?;
+
+        if json {
+            let json = serde_json::to_string_pretty(&info).map_err(|e| {
+                error!("Error serializing system info: {}", e);
+                DcCmdError::IoError
+            })?;
+
+            return self.term.write_line(&json).map_err(|_| DcCmdError::IoError);
+        }
+
+        self.print_system_info(&info)
+    }
+
+    fn print_system_info(&self, info: &SystemDiagnostics) -> Result<(), DcCmdError> {
+        self.write_setting("System info for", &info.target)?;
+        self.write_setting("Customer", &info.customer)?;
+        self.write_setting(
+            "Space used",
+            &format!(
+                "{} / {} ({:.2}%)",
+                to_readable_size(info.space_used),
+                to_readable_size(info.space_limit),
+                percent(info.space_used, info.space_limit)
+            ),
+        )?;
+        self.write_setting(
+            "Users used",
+            &format!(
+                "{} / {} ({:.2}%)",
+                info.users_used,
+                info.users_limit,
+                percent(info.users_used, info.users_limit)
+            ),
+        )?;
+        self.write_setting("API version", &info.api_version)?;
+        self.write_setting("Server version", &info.server_version)?;
+
+        // authentication
+        self.term
+            .write_line("\n► Authentication:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting("OpenID Connect IDPs", &join_or_na(&info.openid_idps))?;
+        self.write_setting("Active Directories", &join_or_na(&info.active_directories))?;
+
+        // storage
+        self.term
+            .write_line("\n► Storage:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting("S3 storage", &info.s3_storage.to_string())?;
+
+        for endpoint in &info.s3_endpoints {
+            self.write_setting(
+                &endpoint.host,
+                if endpoint.reachable {
+                    "reachable"
+                } else {
+                    "unreachable"
+                },
+            )?;
+        }
+
+        // password policies
+        let policies = &info.password_policies;
+        self.term
+            .write_line("\n► Password policies:")
+            .map_err(|_| DcCmdError::IoError)?;
+        self.write_setting(
+            "Login password min. length",
+            &format_optional(policies.login_min_length),
+        )?;
+        self.write_setting(
+            "Login password expiration enabled",
+            &format_optional(policies.login_expiration_enabled),
+        )?;
+        self.write_setting(
+            "Share password min. length",
+            &format_optional(policies.share_min_length),
+        )?;
+        self.write_setting(
+            "Encryption password min. length",
+            &format_optional(policies.encryption_min_length),
+        )?;
+
+        // features
+        self.term
+            .write_line("\n► Features:")
+            .map_err(|_| DcCmdError::IoError)?;
+
+        for (feature, enabled) in &info.features {
+            self.write_setting(feature, &enabled.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn get_diagnostics(
+    dracoon: &Dracoon<Connected>,
+    target: &str,
+) -> Result<SystemDiagnostics, DcCmdError> {
+    let customer_info = dracoon.user().get_customer_info().await?;
+    let software_version = dracoon.public().get_software_version().await?;
+    let public_info = dracoon.public().get_system_info().await?;
+    let general_settings = dracoon.config().get_general_settings().await?;
+    let infrastructure = dracoon.config().get_infrastructure_properties().await?;
+    let password_policies = dracoon.config().get_password_policies().await?;
+    let oidc_info = dracoon
+        .system()
+        .auth
+        .get_openid_idp_configurations()
+        .await?;
+    let ad_info = dracoon
+        .system()
+        .auth
+        .get_active_directory_configurations()
+        .await?;
+
+    let s3_endpoints = if public_info.use_s3_storage {
+        join_all(public_info.s3_hosts.iter().map(|host| async move {
+            S3Endpoint {
+                host: host.clone(),
+                reachable: is_reachable(host).await,
+            }
+        }))
+        .await
+    } else {
+        Vec::new()
+    };
+
+    let password_policies = PasswordPolicySummary {
+        login_min_length: password_policies
+            .login_password_policies
+            .as_ref()
+            .map(|policies| u32::from(policies.min_length)),
+        login_expiration_enabled: password_policies
+            .login_password_policies
+            .as_ref()
+            .map(|policies| policies.password_expiration.enabled),
+        share_min_length: password_policies
+            .shares_password_policies
+            .as_ref()
+            .and_then(|policies| policies.min_length)
+            .and_then(|min_length| u32::try_from(min_length).ok()),
+        encryption_min_length: password_policies
+            .encryption_password_policies
+            .as_ref()
+            .and_then(|policies| policies.min_length)
+            .map(u32::from),
+    };
+
+    let features = BTreeMap::from([
+        ("crypto", general_settings.crypto_enabled),
+        ("customer_encryption", customer_info.customer_encryption_enabled),
+        ("share_password_sms", general_settings.share_password_sms_enabled),
+        (
+            "email_notification_button",
+            general_settings.email_notification_button_enabled,
+        ),
+        ("eula", general_settings.eula_enabled),
+        ("s3_tags", general_settings.s3_tags_enabled),
+        ("home_rooms", general_settings.home_rooms_active),
+        (
+            "media_server",
+            infrastructure.media_server_config_enabled.unwrap_or(false),
+        ),
+        ("s3_direct_upload_enforced", public_info.s3_enforce_direct_upload),
+        (
+            "dracoon_cloud",
+            infrastructure.is_dracoon_cloud.unwrap_or(false),
+        ),
+    ]);
+
+    Ok(SystemDiagnostics {
+        target: target.to_string(),
+        customer: customer_info.name,
+        space_used: customer_info.space_used,
+        space_limit: customer_info.space_limit,
+        users_used: customer_info.accounts_used,
+        users_limit: customer_info.accounts_limit,
+        api_version: software_version.rest_api_version,
+        server_version: software_version.sds_server_version,
+        openid_idps: oidc_info
+            .into_iter()
+            .map(|info| format!("{} ({})", info.name.unwrap_or_default(), info.id))
+            .collect(),
+        active_directories: ad_info
+            .items
+            .into_iter()
+            .map(|info| format!("{} ({})", info.alias, info.id))
+            .collect(),
+        s3_storage: public_info.use_s3_storage,
+        s3_endpoints,
+        password_policies,
+        features,
+    })
+}
+
+/// true if the S3 endpoint responds (any HTTP status - S3 rejects anonymous requests)
+async fn is_reachable(host: &str) -> bool {
+    let Ok(client) = Client::builder().timeout(S3_CHECK_TIMEOUT).build() else {
+        return false;
+    };
+
+    match client.head(to_s3_url(host)).send().await {
+        Ok(_) => true,
+        Err(e) => {
+            debug!("S3 endpoint {} not reachable: {}", host, e);
+            false
+        }
+    }
+}
+
+/// S3 hosts are provided without scheme
+fn to_s3_url(host: &str) -> String {
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.to_string()
+    } else {
+        format!("https://{host}")
+    }
+}
+
+fn percent(used: u64, limit: u64) -> f64 {
+    if limit == 0 {
+        return 0.0;
+    }
+
+    used as f64 / limit as f64 * 100.0
+}
+
+fn join_or_na(values: &[String]) -> String {
+    if values.is_empty() {
+        NOT_AVAILABLE.to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_s3_url() {
+        assert_eq!("https://s3.example.com", to_s3_url("s3.example.com"));
+        assert_eq!("http://minio:9000", to_s3_url("http://minio:9000"));
+    }
+
+    #[test]
+    fn test_percent() {
+        assert!((percent(50, 200) - 25.0).abs() < f64::EPSILON);
+        assert!(percent(50, 0).abs() < f64::EPSILON);
+    }
+}
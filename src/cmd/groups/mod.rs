@@ -1,13 +1,11 @@
-use std::sync::Arc;
-
 use console::Term;
 use dco3::{
     auth::Connected,
     groups::{CreateGroupRequest, Group, GroupsFilter},
-    Dracoon, Groups, ListAllParams,
+    Dracoon, Groups, ListAllParams, RangedItems,
 };
+use futures_util::{stream, StreamExt, TryStreamExt};
 
-use tokio::sync::Semaphore;
 use tracing::error;
 
 mod models;
@@ -17,8 +15,12 @@ mod users;
 use super::{
     config::MAX_CONCURRENT_REQUESTS,
     init_dracoon,
+    journal::{Journal, JournalEntry, JournalOperation},
     models::{build_params, DcCmdError, GroupsCommand, ListOptions},
-    utils::strings::format_success_message,
+    utils::{
+        pagination::fetch_all_paginated, rate_limit::with_rate_limit,
+        strings::format_success_message,
+    },
 };
 
 pub use models::GroupsUsersCommand;
@@ -26,18 +28,43 @@ pub use models::GroupsUsersCommand;
 pub struct GroupCommandHandler {
     client: Dracoon<Connected>,
     term: Term,
+    journal: Journal,
 }
 
 impl GroupCommandHandler {
-    pub async fn try_new(target_domain: String, term: Term) -> Result<Self, DcCmdError> {
+    pub async fn try_new(
+        target_domain: String,
+        term: Term,
+        journal: Journal,
+    ) -> Result<Self, DcCmdError> {
         let client = init_dracoon(&target_domain, None, false).await?;
 
-        Ok(Self { client, term })
+        Ok(Self {
+            client,
+            term,
+            journal,
+        })
+    }
+
+    pub fn new_from_client(client: Dracoon<Connected>, term: Term) -> Self {
+        Self {
+            client,
+            term,
+            journal: Journal::default(),
+        }
     }
 
     async fn create_group(&self, name: String) -> Result<(), DcCmdError> {
         let req = CreateGroupRequest::new(name, None);
-        let group = self.client.groups().create_group(req).await?;
+        let group =
+            with_rate_limit(|| async { Ok(self.client.groups().create_group(req.clone()).await?) })
+                .await?;
+
+        self.journal.record(&[JournalEntry::new(
+            JournalOperation::CreateGroup,
+            group.id,
+            group.name.clone(),
+        )])?;
 
         let msg = format!("Group {} ({}) created", group.name, group.id);
 
@@ -49,17 +76,32 @@ impl GroupCommandHandler {
     }
 
     async fn delete_group(&self, name: Option<String>, id: Option<u64>) -> Result<(), DcCmdError> {
-        let group_id = match (name, id) {
-            (_, Some(id)) => id,
-            (Some(name), _) => self.find_group_by_name(name).await?.id,
+        let group = match (name, id) {
+            (_, Some(id)) => {
+                with_rate_limit(|| async { Ok(self.client.groups().get_group(id).await?) }).await?
+            }
+            (Some(name), _) => self.find_group_by_name(name).await?,
             _ => {
                 return Err(DcCmdError::InvalidArgument(
                     "Either group name or id must be provided".to_string(),
                 ))
             }
         };
+        let group_id = group.id;
 
-        self.client.groups().delete_group(group_id).await?;
+        let entries = [JournalEntry::new(
+            JournalOperation::DeleteGroup,
+            group_id,
+            group.name,
+        )];
+
+        if self.journal.is_dry_run() {
+            return self.journal.print_dry_run(&self.term, &entries);
+        }
+
+        with_rate_limit(|| async { Ok(self.client.groups().delete_group(group_id).await?) })
+            .await?;
+        self.journal.record(&entries)?;
 
         let msg = format!("Group {group_id} deleted");
 
@@ -70,11 +112,14 @@ impl GroupCommandHandler {
         Ok(())
     }
 
-    async fn find_group_by_name(&self, name: String) -> Result<Group, DcCmdError> {
-        let params = ListAllParams::builder()
-            .with_filter(GroupsFilter::name_contains(&name))
-            .build();
-        let groups = self.client.groups().get_groups(Some(params)).await?;
+    pub async fn find_group_by_name(&self, name: String) -> Result<Group, DcCmdError> {
+        let groups = with_rate_limit(|| async {
+            let params = ListAllParams::builder()
+                .with_filter(GroupsFilter::name_contains(&name))
+                .build();
+            Ok(self.client.groups().get_groups(Some(params)).await?)
+        })
+        .await?;
 
         let Some(group) = groups.items.iter().find(|g| g.name == name) else {
             error!("No group found with name: {name}");
@@ -85,67 +130,85 @@ impl GroupCommandHandler {
         Ok(group.clone())
     }
 
-    async fn list_groups(&self, opts: ListOptions) -> Result<(), DcCmdError> {
-        let params = build_params(
-            opts.filter(),
-            opts.offset().unwrap_or(0),
-            opts.limit().unwrap_or(500).into(),
-        )?;
-
-        let mut groups = self.client.groups().get_groups(Some(params)).await?;
-
-        if opts.all() && groups.range.total > 500 {
-            let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-            let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
-
-            let mut handles = Vec::new();
-
-            (500..=groups.range.total).step_by(500).for_each(|offset| {
-                let tx = tx.clone();
-                let dracoon_client = self.client.clone();
-                let filter = opts.filter().clone();
-                let semaphore = semaphore.clone();
-                let handle = tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.map_err(|_| {
-                        error!("Error acquiring semaphore permit");
-                        DcCmdError::IoError
-                    })?;
-
-                    let params = build_params(&filter, offset, 500.into())?;
-                    let users = dracoon_client.groups().get_groups(Some(params)).await?;
-
-                    tx.send(users).await.map_err(|e| {
-                        error!("Error sending users: {}", e);
-                        DcCmdError::IoError
-                    })?;
-
-                    Ok::<(), DcCmdError>(())
-                });
-
-                handles.push(handle);
-            });
-
-            drop(tx);
-
-            while let Some(results) = rx.recv().await {
-                groups.items.extend(results.items);
-            }
-
-            for handle in handles {
-                if let Err(e) = handle.await {
-                    error!("Error fetching users: {}", e);
-                    return Err(DcCmdError::IoError);
+    async fn list_groups(
+        &self,
+        opts: ListOptions,
+        with_member_count: bool,
+    ) -> Result<(), DcCmdError> {
+        let groups = with_rate_limit(|| async {
+            let params = build_params(
+                opts.filter(),
+                opts.offset().unwrap_or(0),
+                opts.limit().unwrap_or(500).into(),
+            )?;
+            Ok(self.client.groups().get_groups(Some(params)).await?)
+        })
+        .await?;
+
+        let groups = if opts.all() {
+            let client = self.client.clone();
+            let filter = opts.filter().clone();
+
+            fetch_all_paginated(groups, move |offset| {
+                let client = client.clone();
+                let params = build_params(&filter, offset, 500.into());
+
+                async move {
+                    let groups = client.groups().get_groups(Some(params?)).await?;
+
+                    Ok::<_, DcCmdError>(groups)
                 }
-            }
-        }
+            })
+            .await?
+        } else {
+            groups
+        };
+
+        let groups = if with_member_count {
+            self.with_member_counts(groups).await?
+        } else {
+            groups
+        };
 
         self.print_groups(groups, opts.csv())?;
 
         Ok(())
     }
+
+    /// sets the user count of each group (fetched concurrently via group users)
+    async fn with_member_counts(
+        &self,
+        mut groups: RangedItems<Group>,
+    ) -> Result<RangedItems<Group>, DcCmdError> {
+        groups.items = stream::iter(groups.items)
+            .map(|mut group| async move {
+                let users = with_rate_limit(|| async {
+                    let params = ListAllParams::builder().with_limit(1).build();
+                    Ok(self
+                        .client
+                        .groups()
+                        .get_group_users(group.id, Some(params))
+                        .await?)
+                })
+                .await?;
+
+                group.cnt_users = Some(users.range.total);
+
+                Ok::<_, DcCmdError>(group)
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await?;
+
+        Ok(groups)
+    }
 }
 
-pub async fn handle_groups_cmd(cmd: GroupsCommand, term: Term) -> Result<(), DcCmdError> {
+pub async fn handle_groups_cmd(
+    cmd: GroupsCommand,
+    term: Term,
+    journal: Journal,
+) -> Result<(), DcCmdError> {
     let target = match &cmd {
         GroupsCommand::Create { target, .. }
         | GroupsCommand::Ls { target, .. }
@@ -156,7 +219,7 @@ pub async fn handle_groups_cmd(cmd: GroupsCommand, term: Term) -> Result<(), DcC
         },
     };
 
-    let handler = GroupCommandHandler::try_new(target.to_string(), term).await?;
+    let handler = GroupCommandHandler::try_new(target.to_string(), term, journal).await?;
     match cmd {
         GroupsCommand::Create { target: _, name } => handler.create_group(name).await,
         GroupsCommand::Ls {
@@ -166,9 +229,13 @@ pub async fn handle_groups_cmd(cmd: GroupsCommand, term: Term) -> Result<(), DcC
             limit,
             all,
             csv,
+            with_member_count,
         } => {
             handler
-                .list_groups(ListOptions::new(filter, offset, limit, all, csv))
+                .list_groups(
+                    ListOptions::new(filter, offset, limit, all, csv),
+                    with_member_count,
+                )
                 .await
         }
         GroupsCommand::Rm {
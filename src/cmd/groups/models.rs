@@ -10,6 +10,8 @@ pub struct GroupInfo {
     pub cnt_users: u64,
     #[tabled(display_with = "crate::cmd::users::display_option")]
     pub updated_at: Option<String>,
+    #[tabled(display_with = "crate::cmd::users::display_option")]
+    pub expire_at: Option<String>,
 }
 
 impl From<Group> for GroupInfo {
@@ -20,6 +22,7 @@ impl From<Group> for GroupInfo {
             cnt_users: group.cnt_users.unwrap_or(0),
             created_at: group.created_at.to_string(),
             updated_at: group.updated_at.map(|dt| dt.to_rfc3339()),
+            expire_at: group.expire_at.map(|dt| dt.to_rfc3339()),
         }
     }
 }
@@ -10,6 +10,7 @@ use tabled::{
 use crate::cmd::{
     groups::models::{GroupInfo, GroupUserInfo},
     models::{DcCmdError, PrintFormat},
+    utils::fields::CsvOutput,
 };
 
 use super::GroupCommandHandler;
@@ -24,26 +25,27 @@ impl GroupCommandHandler {
 
         match print_mode {
             PrintFormat::Csv => {
-                let header = "id,name,cnt_users,created_at,updated_at";
-                self.term
-                    .write_line(header)
-                    .map_err(|_| DcCmdError::IoError)?;
+                let header = "id,name,cnt_users,created_at,updated_at,expire_at";
+                let output = CsvOutput::new(&self.term, header)?;
 
                 for group in groups.items {
                     let updated_at = match group.updated_at {
                         Some(updated_at) => updated_at.to_rfc3339(),
                         None => "N/A".to_string(),
                     };
-                    self.term
-                        .write_line(&format!(
-                            "{},{},{},{},{}",
-                            group.id,
-                            group.name,
-                            group.cnt_users.unwrap_or(0),
-                            group.created_at,
-                            updated_at
-                        ))
-                        .map_err(|_| DcCmdError::IoError)?;
+                    let expire_at = match group.expire_at {
+                        Some(expire_at) => expire_at.to_rfc3339(),
+                        None => "N/A".to_string(),
+                    };
+                    output.write_line(&format!(
+                        "{},{},{},{},{},{}",
+                        group.id,
+                        group.name,
+                        group.cnt_users.unwrap_or(0),
+                        group.created_at.to_rfc3339(),
+                        updated_at,
+                        expire_at
+                    ))?;
                 }
 
                 Ok(())
@@ -84,26 +86,24 @@ impl GroupCommandHandler {
 
         match print_mode {
             PrintFormat::Csv => {
-                if is_first {
-                    let header = "id,user_name,first_name,last_name,email,group_id,group_name";
-                    self.term
-                        .write_line(header)
-                        .map_err(|_| DcCmdError::IoError)?;
-                }
+                let header = "id,user_name,first_name,last_name,email,group_id,group_name";
+                let output = if is_first {
+                    CsvOutput::new(&self.term, header)?
+                } else {
+                    CsvOutput::without_header(&self.term, header)?
+                };
 
                 for user in users.items {
-                    self.term
-                        .write_line(&format!(
-                            "{},{},{},{},{},{},{}",
-                            user.user_info.id,
-                            user.user_info.user_name.unwrap_or("N/A".to_string()),
-                            user.user_info.first_name.unwrap_or("N/A".to_string()),
-                            user.user_info.last_name.unwrap_or("N/A".to_string()),
-                            user.user_info.email.unwrap_or("N/A".to_string()),
-                            group.id,
-                            group.name,
-                        ))
-                        .map_err(|_| DcCmdError::IoError)?;
+                    output.write_line(&format!(
+                        "{},{},{},{},{},{},{}",
+                        user.user_info.id,
+                        user.user_info.user_name.unwrap_or("N/A".to_string()),
+                        user.user_info.first_name.unwrap_or("N/A".to_string()),
+                        user.user_info.last_name.unwrap_or("N/A".to_string()),
+                        user.user_info.email.unwrap_or("N/A".to_string()),
+                        group.id,
+                        group.name,
+                    ))?;
                 }
 
                 Ok(())
@@ -7,7 +7,7 @@ use tracing::error;
 use crate::cmd::{
     models::{build_params, DcCmdError},
     users::UserCommandHandler,
-    utils::strings::format_success_message,
+    utils::{rate_limit::with_rate_limit, strings::format_success_message},
 };
 
 use super::{models::GroupUsersOptions, GroupCommandHandler, GroupsUsersCommand};
@@ -25,7 +25,7 @@ pub async fn handle_group_users_cmd(
             all,
             csv,
         } => {
-            let group_name = target.split('/').last();
+            let group_name = target.split('/').next_back();
             let options = GroupUsersOptions::new(filter, offset, limit, all, csv);
             handler.list_group_users(group_name, options).await
         }
@@ -53,14 +53,19 @@ impl GroupCommandHandler {
         let groups = if let Some(group_name) = group_name.filter(|name| !name.is_empty()) {
             vec![self.get_group_by_name(group_name).await?]
         } else {
-            let mut groups = self.client.groups().get_groups(None).await?;
+            let mut groups =
+                with_rate_limit(|| async { Ok(self.client.groups().get_groups(None).await?) })
+                    .await?;
 
             for offset in (500..=groups.range.total).step_by(500) {
-                let params = ListAllParams::builder()
-                    .with_offset(offset)
-                    .with_limit(500)
-                    .build();
-                let mut new_groups = self.client.groups().get_groups(Some(params)).await?;
+                let mut new_groups = with_rate_limit(|| async {
+                    let params = ListAllParams::builder()
+                        .with_offset(offset)
+                        .with_limit(500)
+                        .build();
+                    Ok(self.client.groups().get_groups(Some(params)).await?)
+                })
+                .await?;
 
                 groups.items.append(&mut new_groups.items);
             }
@@ -69,26 +74,32 @@ impl GroupCommandHandler {
         };
 
         for (idx, group) in groups.iter().enumerate() {
-            let params = build_params(
-                &opts.filter,
-                opts.offset.unwrap_or(0).into(),
-                opts.limit.unwrap_or(500).into(),
-            )?;
-            let mut users = self
-                .client
-                .groups()
-                .get_group_users(group.id, Some(params))
-                .await?;
+            let mut users = with_rate_limit(|| async {
+                let params = build_params(
+                    &opts.filter,
+                    opts.offset.unwrap_or(0).into(),
+                    opts.limit.unwrap_or(500).into(),
+                )?;
+                Ok(self
+                    .client
+                    .groups()
+                    .get_group_users(group.id, Some(params))
+                    .await?)
+            })
+            .await?;
 
             if opts.all {
                 for offset in (500..=users.range.total).step_by(500) {
-                    let params =
-                        build_params(&opts.filter, offset, opts.limit.unwrap_or(500).into())?;
-                    let mut new_users = self
-                        .client
-                        .groups()
-                        .get_group_users(group.id, Some(params))
-                        .await?;
+                    let mut new_users = with_rate_limit(|| async {
+                        let params =
+                            build_params(&opts.filter, offset, opts.limit.unwrap_or(500).into())?;
+                        Ok(self
+                            .client
+                            .groups()
+                            .get_group_users(group.id, Some(params))
+                            .await?)
+                    })
+                    .await?;
 
                     users.items.append(&mut new_users.items);
                 }
@@ -103,9 +114,12 @@ impl GroupCommandHandler {
     }
 
     async fn get_group_by_name(&self, group_name: &str) -> Result<Group, DcCmdError> {
-        let filter = GroupsFilter::name_contains(group_name);
-        let params = ListAllParams::builder().with_filter(filter).build();
-        let group_results = self.client.groups().get_groups(Some(params)).await?;
+        let group_results = with_rate_limit(|| async {
+            let filter = GroupsFilter::name_contains(group_name);
+            let params = ListAllParams::builder().with_filter(filter).build();
+            Ok(self.client.groups().get_groups(Some(params)).await?)
+        })
+        .await?;
 
         let Some(group) = group_results
             .items
@@ -143,7 +157,7 @@ impl GroupCommandHandler {
             let client = self.client.clone();
             let term = self.term.clone();
             let user_cmd_handler = UserCommandHandler::new_from_client(client, term);
-            user_cmd_handler.find_user_by_username(&name).await?.id
+            user_cmd_handler.find_user_id_by_username(&name).await?
         } else {
             return Err(DcCmdError::InvalidArgument(
                 "Either user name or id must be provided".to_string(),
@@ -152,7 +166,14 @@ impl GroupCommandHandler {
 
         let req = ChangeGroupMembersRequest::new(vec![user_id]);
 
-        self.client.groups().add_group_users(group_id, req).await?;
+        with_rate_limit(|| async {
+            Ok(self
+                .client
+                .groups()
+                .add_group_users(group_id, req.clone())
+                .await?)
+        })
+        .await?;
 
         let msg = format!("User {user_id} added to group {group_id}");
 
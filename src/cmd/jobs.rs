@@ -0,0 +1,531 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use console::Term;
+use dco3::{
+    auth::Connected,
+    nodes::{
+        models::NodeType,
+        rooms::models::{RoomGroupsAddBatchRequestItem, RoomUsersAddBatchRequestItem},
+        Node,
+    },
+    Dracoon, Nodes, Rooms,
+};
+use serde::Deserialize;
+use serde_yaml::Value;
+use tracing::{error, info};
+
+use super::{
+    get_error_message,
+    groups::GroupCommandHandler,
+    init_dracoon,
+    journal::{Journal, JournalEntry, JournalOperation},
+    models::{DcCmdError, PasswordAuth},
+    nodes::{
+        create_folder,
+        models::{ClassificationLevel, CmdMkdirOptions, CmdUploadOptions, ShareNotification},
+        share::share_node,
+        upload::upload,
+    },
+    rooms::PermissionPreset,
+    users::UserCommandHandler,
+    utils::{
+        dates::parse_expiration,
+        strings::{build_node_path, format_error_message, format_success_message, parse_path},
+    },
+};
+
+/// a declarative list of operations (see README for the format)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Job {
+    #[serde(default)]
+    continue_on_error: bool,
+    // steps are written as `- mkdir: {...}` instead of yaml tags
+    #[serde(with = "serde_yaml::with::singleton_map_recursive")]
+    steps: Vec<JobStep>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum JobStep {
+    Mkdir {
+        path: String,
+        classification: Option<String>,
+        notes: Option<String>,
+        #[serde(default)]
+        parents: bool,
+    },
+    Upload {
+        source: PathBuf,
+        target: String,
+        #[serde(default)]
+        recursive: bool,
+        #[serde(default)]
+        overwrite: bool,
+        #[serde(default)]
+        share: bool,
+    },
+    Share {
+        path: String,
+        password: Option<String>,
+        expiration: Option<String>,
+        #[serde(default)]
+        notify_emails: Vec<String>,
+        notify_message: Option<String>,
+    },
+    Grant {
+        room: String,
+        user: Option<String>,
+        group: Option<String>,
+        permissions: PermissionPreset,
+    },
+}
+
+impl JobStep {
+    fn describe(&self) -> String {
+        match self {
+            JobStep::Mkdir { path, .. } => format!("mkdir {path}"),
+            JobStep::Upload { source, target, .. } => {
+                format!("upload {} to {target}", source.display())
+            }
+            JobStep::Share { path, .. } => format!("share {path}"),
+            JobStep::Grant {
+                room, user, group, ..
+            } => format!(
+                "grant {} on {room}",
+                user.as_deref().or(group.as_deref()).unwrap_or("-")
+            ),
+        }
+    }
+}
+
+/// runs all steps of a job file - variables (`${name}`) are replaced in all steps
+pub async fn run_job(
+    term: Term,
+    source: PathBuf,
+    vars: Vec<String>,
+    continue_on_error: bool,
+    auth: Option<PasswordAuth>,
+    encryption_password: Option<String>,
+    journal: Journal,
+) -> Result<(), DcCmdError> {
+    let job = read_job(&source, &parse_vars(&vars)?)?;
+    let continue_on_error = continue_on_error || job.continue_on_error;
+    let count_steps = job.steps.len();
+    let mut failed = 0;
+
+    for (idx, step) in job.steps.into_iter().enumerate() {
+        let description = step.describe();
+        info!("Step {}/{}: {}", idx + 1, count_steps, description);
+
+        let res = run_step(
+            &term,
+            step,
+            auth.clone(),
+            encryption_password.clone(),
+            &journal,
+        )
+        .await;
+
+        let Err(e) = res else {
+            continue;
+        };
+
+        let msg = format!(
+            "Step {}/{} ({description}) failed: {}",
+            idx + 1,
+            count_steps,
+            get_error_message(&e)
+        );
+
+        if !continue_on_error {
+            error!("{}", msg);
+            return Err(e);
+        }
+
+        term.write_line(&format_error_message(&msg))
+            .map_err(|_| DcCmdError::IoError)?;
+        failed += 1;
+    }
+
+    if failed > 0 {
+        return Err(DcCmdError::JobFailed(format!(
+            "{failed} of {count_steps} step(s) failed."
+        )));
+    }
+
+    let msg = format!("Job {} complete ({count_steps} steps).", source.display());
+    term.write_line(&format_success_message(&msg))
+        .map_err(|_| DcCmdError::IoError)
+}
+
+async fn run_step(
+    term: &Term,
+    step: JobStep,
+    auth: Option<PasswordAuth>,
+    encryption_password: Option<String>,
+    journal: &Journal,
+) -> Result<(), DcCmdError> {
+    match step {
+        JobStep::Mkdir {
+            path,
+            classification,
+            notes,
+            parents,
+        } => {
+            let classification = classification
+                .as_deref()
+                .map(|c| {
+                    ClassificationLevel::from_str(c, true).map_err(|_| {
+                        DcCmdError::InvalidArgument(format!("Invalid classification: {c}"))
+                    })
+                })
+                .transpose()?;
+
+            let opts = CmdMkdirOptions::new(classification, notes, parents, auth, journal.clone());
+            create_folder(term.clone(), path, opts).await
+        }
+        JobStep::Upload {
+            source,
+            target,
+            recursive,
+            overwrite,
+            share,
+        } => {
+            if journal.is_dry_run() {
+                let line = format!("upload {} to {target}", source.display());
+                return journal.print_dry_run_line(term, &line);
+            }
+
+            let opts = CmdUploadOptions {
+                overwrite,
+                recursive,
+                share,
+                auth,
+                encryption_password,
+                ..CmdUploadOptions::default()
+            };
+            upload(term.clone(), source, target, opts).await
+        }
+        JobStep::Share {
+            path,
+            password,
+            expiration,
+            notify_emails,
+            notify_message,
+        } => {
+            let expiration = expiration.as_deref().map(parse_expiration).transpose()?;
+            let notification = ShareNotification::try_new(notify_emails, notify_message)?;
+            // the node might only be created by a previous step
+            if journal.is_dry_run() {
+                let line = format!("POST /api/v4/shares/downloads ({path})");
+                return journal.print_dry_run_line(term, &line);
+            }
+
+            let dracoon = init_dracoon(&path, auth, false).await?;
+            let node = get_node(&dracoon, &path).await?;
+
+            let link = share_node(
+                &dracoon,
+                &node,
+                password,
+                expiration,
+                notification.as_ref(),
+            )
+            .await?;
+            let msg = format_success_message(&format!("Shared {}.\n▶︎▶︎ {link}", node.name));
+
+            term.write_line(&msg).map_err(|_| DcCmdError::IoError)
+        }
+        JobStep::Grant {
+            room,
+            user,
+            group,
+            permissions,
+        } => {
+            let dracoon = init_dracoon(&room, auth, false).await?;
+            let node = get_node(&dracoon, &room).await?;
+
+            if node.node_type != NodeType::Room {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Permissions can only be granted on rooms: {room}"
+                )));
+            }
+
+            let entry = match (user, group) {
+                (Some(user), None) => {
+                    let handler =
+                        UserCommandHandler::new_from_client(dracoon.clone(), term.clone());
+                    let user_id = handler.find_user_id_by_username(&user).await?;
+
+                    JournalEntry::new(JournalOperation::GrantRoomUser, node.id, room)
+                        .with_target_id(user_id)
+                }
+                (None, Some(group)) => {
+                    let handler =
+                        GroupCommandHandler::new_from_client(dracoon.clone(), term.clone());
+                    let group = handler.find_group_by_name(group).await?;
+
+                    JournalEntry::new(JournalOperation::GrantRoomGroup, node.id, room)
+                        .with_target_id(group.id)
+                }
+                _ => {
+                    return Err(DcCmdError::InvalidArgument(
+                        "Grant requires either a user or a group.".to_string(),
+                    ))
+                }
+            };
+
+            if journal.is_dry_run() {
+                return journal.print_dry_run(term, &[entry]);
+            }
+
+            let principal_id = entry.target_id.unwrap_or_default();
+
+            if entry.operation == JournalOperation::GrantRoomUser {
+                let item = RoomUsersAddBatchRequestItem::new(principal_id, permissions.into());
+                dracoon
+                    .nodes()
+                    .update_room_users(node.id, vec![item].into())
+                    .await?;
+            } else {
+                let item = RoomGroupsAddBatchRequestItem::new(principal_id, permissions.into(), None);
+                dracoon
+                    .nodes()
+                    .update_room_groups(node.id, vec![item].into())
+                    .await?;
+            }
+
+            journal.record(&[entry])
+        }
+    }
+}
+
+async fn get_node(dracoon: &Dracoon<Connected>, path: &str) -> Result<Node, DcCmdError> {
+    let node_path = build_node_path(parse_path(path, dracoon.get_base_url().as_ref())?);
+
+    dracoon
+        .nodes()
+        .get_node_from_path(&node_path)
+        .await?
+        .ok_or(DcCmdError::InvalidPath(path.to_string()))
+}
+
+/// parses variables passed as `name=value`
+fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>, DcCmdError> {
+    vars.iter()
+        .map(|var| {
+            var.split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.to_string()))
+                .ok_or_else(|| {
+                    DcCmdError::InvalidArgument(format!("Invalid variable (use name=value): {var}"))
+                })
+        })
+        .collect()
+}
+
+fn read_job(path: &Path, vars: &BTreeMap<String, String>) -> Result<Job, DcCmdError> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        error!("Error reading job file {}: {}", path.display(), e);
+        DcCmdError::InvalidArgument(format!("Job file not found: {}", path.display()))
+    })?;
+
+    parse_job(&content, vars)
+}
+
+/// parses a job - passed variables override the variables of the job file
+fn parse_job(content: &str, vars: &BTreeMap<String, String>) -> Result<Job, DcCmdError> {
+    let invalid = |e: serde_yaml::Error| {
+        error!("Error parsing job file: {}", e);
+        DcCmdError::InvalidArgument(format!("Invalid job file: {e}"))
+    };
+
+    let mut value = serde_yaml::from_str::<Value>(content).map_err(invalid)?;
+
+    // variables are only used for substitution
+    let mut variables = value
+        .as_mapping_mut()
+        .and_then(|job| job.remove("variables"))
+        .map(serde_yaml::from_value::<BTreeMap<String, String>>)
+        .transpose()
+        .map_err(invalid)?
+        .unwrap_or_default();
+
+    variables.extend(vars.clone());
+
+    if let Some(steps) = value.get_mut("steps") {
+        substitute_value(steps, &variables)?;
+    }
+
+    serde_yaml::from_value(value).map_err(invalid)
+}
+
+fn substitute_value(
+    value: &mut Value,
+    variables: &BTreeMap<String, String>,
+) -> Result<(), DcCmdError> {
+    match value {
+        Value::String(s) => *s = substitute_variables(s, variables)?,
+        Value::Sequence(seq) => {
+            for item in seq {
+                substitute_value(item, variables)?;
+            }
+        }
+        Value::Mapping(map) => {
+            for (_, item) in map.iter_mut() {
+                substitute_value(item, variables)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// replaces all `${name}` occurrences - unknown variables are an error
+fn substitute_variables(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+) -> Result<String, DcCmdError> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find('}') else {
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Unterminated variable in: {text}"
+            )));
+        };
+
+        let name = &rest[start + 2..start + end];
+        let value = variables
+            .get(name.trim())
+            .ok_or_else(|| DcCmdError::InvalidArgument(format!("Unknown variable: {name}")))?;
+
+        result.push_str(value);
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JOB: &str = r#"
+variables:
+  target: dracoon.example.com
+  project: apollo
+continue_on_error: true
+steps:
+  - mkdir:
+      path: "${target}/Projects/${project}"
+      classification: confidential
+      parents: true
+  - upload:
+      source: ./docs
+      target: "${target}/Projects/${project}"
+      recursive: true
+  - share:
+      path: "${target}/Projects/${project}/docs"
+      expiration: +30d
+  - grant:
+      room: "${target}/Projects"
+      group: "${project}-team"
+      permissions: edit
+"#;
+
+    #[test]
+    fn test_parse_job() {
+        let job = parse_job(JOB, &BTreeMap::new()).unwrap();
+
+        assert!(job.continue_on_error);
+        assert_eq!(4, job.steps.len());
+        assert_eq!(
+            JobStep::Mkdir {
+                path: "dracoon.example.com/Projects/apollo".into(),
+                classification: Some("confidential".into()),
+                notes: None,
+                parents: true,
+            },
+            job.steps[0]
+        );
+        assert_eq!(
+            JobStep::Upload {
+                source: "./docs".into(),
+                target: "dracoon.example.com/Projects/apollo".into(),
+                recursive: true,
+                overwrite: false,
+                share: false,
+            },
+            job.steps[1]
+        );
+        assert_eq!(
+            JobStep::Grant {
+                room: "dracoon.example.com/Projects".into(),
+                user: None,
+                group: Some("apollo-team".into()),
+                permissions: PermissionPreset::Edit,
+            },
+            job.steps[3]
+        );
+    }
+
+    #[test]
+    fn test_parse_job_overrides_variables() {
+        let vars = parse_vars(&["project=gemini".to_string()]).unwrap();
+        let job = parse_job(JOB, &vars).unwrap();
+
+        assert_eq!(
+            JobStep::Share {
+                path: "dracoon.example.com/Projects/gemini/docs".into(),
+                password: None,
+                expiration: Some("+30d".into()),
+                notify_emails: vec![],
+                notify_message: None,
+            },
+            job.steps[2]
+        );
+    }
+
+    #[test]
+    fn test_parse_job_unknown_step() {
+        let job = "steps:\n  - rmrf:\n      path: /\n";
+
+        assert!(parse_job(job, &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let vars = BTreeMap::from([("a".to_string(), "1".to_string())]);
+
+        assert_eq!("x1y1", substitute_variables("x${a}y${ a }", &vars).unwrap());
+        assert_eq!("no vars", substitute_variables("no vars", &vars).unwrap());
+    }
+
+    #[test]
+    fn test_substitute_variables_invalid() {
+        let vars = BTreeMap::new();
+
+        assert!(substitute_variables("${missing}", &vars).is_err());
+        assert!(substitute_variables("${open", &vars).is_err());
+    }
+
+    #[test]
+    fn test_parse_vars() {
+        let vars = parse_vars(&["a=1".to_string(), "b=x=y".to_string()]).unwrap();
+
+        assert_eq!(Some(&"1".to_string()), vars.get("a"));
+        assert_eq!(Some(&"x=y".to_string()), vars.get("b"));
+        assert!(parse_vars(&["novalue".to_string()]).is_err());
+    }
+}
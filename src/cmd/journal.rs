@@ -0,0 +1,283 @@
+use std::{
+    fs::OpenOptions,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{SecondsFormat, Utc};
+use console::{style, Term};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use super::models::DcCmdError;
+
+const DRY_RUN_PREFIX: &str = "Dry run: ";
+
+/// operations recorded in the journal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalOperation {
+    DeleteNode,
+    PurgeDeletedNode,
+    RestoreNode,
+    CopyNode,
+    MoveNode,
+    CreateFolder,
+    CreateRoom,
+    UpdateRoom,
+    CreateUser,
+    DeleteUser,
+    CreateGroup,
+    DeleteGroup,
+    GrantRoomUser,
+    GrantRoomGroup,
+    RevokeRoomUser,
+    RevokeRoomGroup,
+}
+
+/// a single journal entry (one line in the journal CSV file)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub timestamp: String,
+    pub operation: JournalOperation,
+    /// id of the affected object (node, user or group)
+    pub id: u64,
+    /// parent node id (nodes only)
+    pub parent_id: Option<u64>,
+    /// target node id (copy / move), deleted node id (delete), user id or group id (room permissions)
+    pub target_id: Option<u64>,
+    /// node path, user name or group name
+    pub name: String,
+}
+
+impl JournalEntry {
+    pub fn new(operation: JournalOperation, id: u64, name: impl Into<String>) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            operation,
+            id,
+            parent_id: None,
+            target_id: None,
+            name: name.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_parent_id(mut self, parent_id: Option<u64>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    #[must_use]
+    pub fn with_target_id(mut self, target_id: u64) -> Self {
+        self.target_id = Some(target_id);
+        self
+    }
+
+    /// describes the API operation of this entry
+    pub fn describe(&self) -> String {
+        let target_id = self.target_id.unwrap_or_default();
+
+        match self.operation {
+            JournalOperation::DeleteNode => {
+                format!("DELETE /api/v4/nodes/{} ({})", self.id, self.name)
+            }
+            JournalOperation::PurgeDeletedNode => format!(
+                "DELETE /api/v4/nodes/deleted_nodes (deleted node {}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::RestoreNode => format!(
+                "POST /api/v4/nodes/deleted_nodes/actions/restore (deleted node {}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::CopyNode => format!(
+                "POST /api/v4/nodes/{target_id}/copy_to (node {}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::MoveNode => format!(
+                "POST /api/v4/nodes/{target_id}/move_to (node {}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::CreateFolder => format!("POST /api/v4/nodes/folders ({})", self.name),
+            JournalOperation::CreateRoom => format!("POST /api/v4/nodes/rooms ({})", self.name),
+            JournalOperation::UpdateRoom => {
+                format!("PUT /api/v4/nodes/rooms/{} ({})", self.id, self.name)
+            }
+            JournalOperation::CreateUser => format!("POST /api/v4/users ({})", self.name),
+            JournalOperation::DeleteUser => {
+                format!("DELETE /api/v4/users/{} ({})", self.id, self.name)
+            }
+            JournalOperation::CreateGroup => format!("POST /api/v4/groups ({})", self.name),
+            JournalOperation::DeleteGroup => {
+                format!("DELETE /api/v4/groups/{} ({})", self.id, self.name)
+            }
+            JournalOperation::GrantRoomUser => format!(
+                "PUT /api/v4/nodes/rooms/{}/users (user {target_id}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::GrantRoomGroup => format!(
+                "PUT /api/v4/nodes/rooms/{}/groups (group {target_id}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::RevokeRoomUser => format!(
+                "DELETE /api/v4/nodes/rooms/{}/users (user {target_id}: {})",
+                self.id, self.name
+            ),
+            JournalOperation::RevokeRoomGroup => format!(
+                "DELETE /api/v4/nodes/rooms/{}/groups (group {target_id}: {})",
+                self.id, self.name
+            ),
+        }
+    }
+}
+
+/// handles dry runs and records performed operations to an optional journal file
+#[derive(Clone, Default)]
+pub struct Journal {
+    path: Option<PathBuf>,
+    dry_run: bool,
+    lock: Arc<Mutex<()>>,
+}
+
+impl Journal {
+    pub fn new(path: Option<PathBuf>, dry_run: bool) -> Self {
+        Self {
+            path,
+            dry_run,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// true if performed operations are written to a journal file
+    pub fn is_recording(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// prints the operations that would be executed
+    pub fn print_dry_run(&self, term: &Term, entries: &[JournalEntry]) -> Result<(), DcCmdError> {
+        for entry in entries {
+            self.print_dry_run_line(term, &entry.describe())?;
+        }
+
+        Ok(())
+    }
+
+    /// prints an operation without journal entry (e.g. uploads)
+    pub fn print_dry_run_line(&self, term: &Term, line: &str) -> Result<(), DcCmdError> {
+        let prefix = style(DRY_RUN_PREFIX).yellow().bold();
+
+        term.write_line(&format!("{prefix} {line}"))
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    /// appends performed operations to the journal file (if any)
+    pub fn record(&self, entries: &[JournalEntry]) -> Result<(), DcCmdError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let _guard = self.lock.lock().map_err(|_| DcCmdError::IoError)?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| {
+                error!("Failed to open journal {}: {}", path.display(), e);
+                DcCmdError::IoError
+            })?;
+
+        let is_empty = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+
+        let mut writer = csv::WriterBuilder::new()
+            .has_headers(is_empty)
+            .from_writer(file);
+
+        for entry in entries {
+            writer.serialize(entry).map_err(|e| {
+                error!("Failed to write journal entry: {}", e);
+                DcCmdError::IoError
+            })?;
+        }
+
+        writer.flush().map_err(|_| DcCmdError::IoError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_delete_node() {
+        let entry = JournalEntry::new(JournalOperation::DeleteNode, 42, "/room/file.pdf");
+
+        assert_eq!("DELETE /api/v4/nodes/42 (/room/file.pdf)", entry.describe());
+    }
+
+    #[test]
+    fn test_describe_restore_node() {
+        let entry = JournalEntry::new(JournalOperation::RestoreNode, 43, "/room/file.pdf");
+
+        assert_eq!(
+            "POST /api/v4/nodes/deleted_nodes/actions/restore (deleted node 43: /room/file.pdf)",
+            entry.describe()
+        );
+    }
+
+    #[test]
+    fn test_describe_purge_deleted_node() {
+        let entry = JournalEntry::new(JournalOperation::PurgeDeletedNode, 42, "/room/file.pdf");
+
+        assert_eq!(
+            "DELETE /api/v4/nodes/deleted_nodes (deleted node 42: /room/file.pdf)",
+            entry.describe()
+        );
+    }
+
+    #[test]
+    fn test_describe_revoke_room_user() {
+        let entry =
+            JournalEntry::new(JournalOperation::RevokeRoomUser, 3, "/room").with_target_id(12);
+
+        assert_eq!(
+            "DELETE /api/v4/nodes/rooms/3/users (user 12: /room)",
+            entry.describe()
+        );
+    }
+
+    #[test]
+    fn test_describe_copy_node() {
+        let entry =
+            JournalEntry::new(JournalOperation::CopyNode, 42, "/room/file.pdf").with_target_id(7);
+
+        assert_eq!(
+            "POST /api/v4/nodes/7/copy_to (node 42: /room/file.pdf)",
+            entry.describe()
+        );
+    }
+
+    #[test]
+    fn test_journal_entry_csv_roundtrip() {
+        let entry = JournalEntry::new(JournalOperation::MoveNode, 1, "/a/b")
+            .with_parent_id(Some(2))
+            .with_target_id(3);
+
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.serialize(&entry).unwrap();
+        let data = writer.into_inner().unwrap();
+
+        let mut reader = csv::Reader::from_reader(data.as_slice());
+        let parsed = reader
+            .deserialize::<JournalEntry>()
+            .next()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(entry, parsed);
+    }
+}
@@ -0,0 +1,543 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tracing::{debug, error};
+
+use super::models::DcCmdError;
+
+/// id of the room provided by the mock server
+pub const MOCK_ROOM_ID: u64 = 1;
+
+/// in-process mock of the DRACOON API (password flow, nodes, proxied uploads and downloads)
+///
+/// nodes are kept in memory - the server is stopped when dropped
+pub struct MockServer {
+    url: String,
+    handle: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// starts the server on a random local port
+    pub async fn start() -> Result<Self, DcCmdError> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.map_err(|err| {
+            error!("Error starting mock server: {}", err);
+            DcCmdError::IoError
+        })?;
+        let addr = listener.local_addr().map_err(|_| DcCmdError::IoError)?;
+        let url = format!("http://{addr}");
+
+        let state = Arc::new(Mutex::new(MockState::new(url.clone())));
+
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, &state).await {
+                        debug!("Mock server connection failed: {}", err);
+                    }
+                });
+            }
+        });
+
+        debug!("Mock server listening on {}.", url);
+
+        Ok(Self { url, handle })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+struct MockNode {
+    id: u64,
+    name: String,
+    node_type: &'static str,
+    parent_id: Option<u64>,
+    parent_path: String,
+    content: Vec<u8>,
+}
+
+impl MockNode {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "type": self.node_type,
+            "name": self.name,
+            "parentId": self.parent_id,
+            "parentPath": self.parent_path,
+            "size": self.content.len(),
+            "isEncrypted": false,
+            "classification": 2,
+        })
+    }
+}
+
+struct MockUpload {
+    parent_id: u64,
+    name: String,
+    content: Vec<u8>,
+}
+
+struct MockState {
+    url: String,
+    nodes: BTreeMap<u64, MockNode>,
+    uploads: HashMap<String, MockUpload>,
+    next_id: u64,
+}
+
+impl MockState {
+    fn new(url: String) -> Self {
+        let room = MockNode {
+            id: MOCK_ROOM_ID,
+            name: "selftest".to_string(),
+            node_type: "room",
+            parent_id: None,
+            parent_path: "/".to_string(),
+            content: Vec::new(),
+        };
+
+        Self {
+            url,
+            nodes: BTreeMap::from([(MOCK_ROOM_ID, room)]),
+            uploads: HashMap::new(),
+            next_id: MOCK_ROOM_ID + 1,
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+}
+
+struct MockRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+struct MockResponse {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    fn json(status: u16, body: &Value) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn empty(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &json!({ "code": status, "message": message }))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateUpload {
+    parent_id: u64,
+    name: String,
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    state: &Mutex<MockState>,
+) -> Result<(), std::io::Error> {
+    let mut stream = BufReader::new(stream);
+
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+
+    debug!("Mock server: {} {}", request.method, request.path);
+
+    let response = match state.lock() {
+        Ok(mut state) => route(&mut state, &request),
+        Err(_) => MockResponse::error(500, "Mock server state poisoned"),
+    };
+
+    let mut head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    let stream = stream.get_mut();
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
+
+async fn read_request(
+    stream: &mut BufReader<TcpStream>,
+) -> Result<Option<MockRequest>, std::io::Error> {
+    let mut line = String::new();
+    if stream.read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let method = method.to_string();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path.to_string();
+    let query = parse_query(query);
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        stream.read_line(&mut line).await?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let mut body = Vec::new();
+    if headers.get("transfer-encoding").map(String::as_str) == Some("chunked") {
+        loop {
+            line.clear();
+            stream.read_line(&mut line).await?;
+            let size = usize::from_str_radix(line.trim(), 16).unwrap_or(0);
+            let mut chunk = vec![0; size + 2];
+            stream.read_exact(&mut chunk).await?;
+            if size == 0 {
+                break;
+            }
+            body.extend_from_slice(&chunk[..size]);
+        }
+    } else if let Some(len) = headers
+        .get("content-length")
+        .and_then(|len| len.parse().ok())
+    {
+        body = vec![0; len];
+        stream.read_exact(&mut body).await?;
+    }
+
+    Ok(Some(MockRequest {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    }))
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn route(state: &mut MockState, request: &MockRequest) -> MockResponse {
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+
+    if segments.first() == Some(&"oauth") {
+        return match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["oauth", "token"]) => MockResponse::json(
+                200,
+                &json!({
+                    "access_token": "mock-access-token",
+                    "refresh_token": "mock-refresh-token",
+                    "token_type": "bearer",
+                    "expires_in": 28800,
+                }),
+            ),
+            _ => MockResponse::error(404, "Not found"),
+        };
+    }
+
+    // downloads and chunk uploads are authorized via url (like S3 / upload tokens)
+    let authorized = request
+        .headers
+        .get("authorization")
+        .is_some_and(|auth| auth.starts_with("Bearer "));
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "v4", "public", "system", "info"]) => MockResponse::json(
+            200,
+            &json!({
+                "languageDefault": "en-US",
+                "s3Hosts": [],
+                "s3EnforceDirectUpload": false,
+                "useS3Storage": false,
+            }),
+        ),
+        ("POST", ["api", "v4", "uploads", token]) => upload_chunk(state, token, request),
+        ("PUT", ["api", "v4", "uploads", token]) => complete_upload(state, token),
+        ("GET", ["downloads", id]) => download(state, id, request),
+        _ if !authorized => MockResponse::error(401, "Unauthorized"),
+        ("GET", ["api", "v4", "nodes"]) => {
+            let parent_id = request
+                .query
+                .get("parent_id")
+                .and_then(|id| id.parse::<u64>().ok());
+            let items = state
+                .nodes
+                .values()
+                .filter(|node| node.parent_id == parent_id)
+                .map(MockNode::to_json)
+                .collect::<Vec<_>>();
+
+            MockResponse::json(
+                200,
+                &json!({
+                    "range": { "offset": 0, "limit": 500, "total": items.len() },
+                    "items": items,
+                }),
+            )
+        }
+        ("GET", ["api", "v4", "nodes", id]) => match find_node(state, id) {
+            Some(node) => MockResponse::json(200, &node.to_json()),
+            None => MockResponse::error(404, "Node not found"),
+        },
+        ("DELETE", ["api", "v4", "nodes", id]) => {
+            match id.parse().ok().and_then(|id| state.nodes.remove(&id)) {
+                Some(_) => MockResponse::empty(204),
+                None => MockResponse::error(404, "Node not found"),
+            }
+        }
+        ("POST", ["api", "v4", "nodes", "files", "uploads"]) => create_upload(state, request),
+        ("POST", ["api", "v4", "nodes", "files", id, "downloads"]) => match find_node(state, id) {
+            Some(node) => MockResponse::json(
+                200,
+                &json!({ "downloadUrl": format!("{}/downloads/{}", state.url, node.id) }),
+            ),
+            None => MockResponse::error(404, "Node not found"),
+        },
+        _ => MockResponse::error(404, "Not found"),
+    }
+}
+
+fn find_node<'a>(state: &'a MockState, id: &str) -> Option<&'a MockNode> {
+    id.parse().ok().and_then(|id| state.nodes.get(&id))
+}
+
+fn create_upload(state: &mut MockState, request: &MockRequest) -> MockResponse {
+    let Ok(upload) = serde_json::from_slice::<CreateUpload>(&request.body) else {
+        return MockResponse::error(400, "Invalid upload request");
+    };
+
+    if !state.nodes.contains_key(&upload.parent_id) {
+        return MockResponse::error(404, "Parent node not found");
+    }
+
+    let id = state.next_id();
+    let token = format!("mock-upload-{id}");
+
+    state.uploads.insert(
+        token.clone(),
+        MockUpload {
+            parent_id: upload.parent_id,
+            name: upload.name,
+            content: Vec::new(),
+        },
+    );
+
+    MockResponse::json(
+        201,
+        &json!({
+            "uploadUrl": format!("{}/api/v4/uploads/{}", state.url, token),
+            "uploadId": token,
+            "token": token,
+        }),
+    )
+}
+
+fn upload_chunk(state: &mut MockState, token: &str, request: &MockRequest) -> MockResponse {
+    let Some(upload) = state.uploads.get_mut(token) else {
+        return MockResponse::error(404, "Upload not found");
+    };
+
+    // chunks are written to the start of their range (bytes <start>-<end>/<size>)
+    let start = request
+        .headers
+        .get("content-range")
+        .and_then(|range| range.strip_prefix("bytes "))
+        .and_then(|range| range.split_once('-'))
+        .and_then(|(start, _)| start.parse::<usize>().ok())
+        .unwrap_or(upload.content.len());
+
+    let end = start + request.body.len();
+    if upload.content.len() < end {
+        upload.content.resize(end, 0);
+    }
+    upload.content[start..end].copy_from_slice(&request.body);
+
+    MockResponse::json(201, &json!({ "size": upload.content.len() }))
+}
+
+fn complete_upload(state: &mut MockState, token: &str) -> MockResponse {
+    let Some(upload) = state.uploads.remove(token) else {
+        return MockResponse::error(404, "Upload not found");
+    };
+
+    let parent_path = state.nodes.get(&upload.parent_id).map_or_else(
+        || "/".to_string(),
+        |parent| format!("{}{}/", parent.parent_path, parent.name),
+    );
+
+    let id = state.next_id();
+    let node = MockNode {
+        id,
+        name: upload.name,
+        node_type: "file",
+        parent_id: Some(upload.parent_id),
+        parent_path,
+        content: upload.content,
+    };
+    let response = MockResponse::json(201, &node.to_json());
+    state.nodes.insert(id, node);
+
+    response
+}
+
+fn download(state: &MockState, id: &str, request: &MockRequest) -> MockResponse {
+    let Some(node) = find_node(state, id) else {
+        return MockResponse::error(404, "Node not found");
+    };
+
+    let size = node.content.len();
+    let (start, end) = request
+        .headers
+        .get("range")
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.split_once('-'))
+        .and_then(|(start, end)| Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?)))
+        .map_or((0, size.saturating_sub(1)), |(start, end)| {
+            (start, end.min(size.saturating_sub(1)))
+        });
+
+    if size == 0 || start > end {
+        return MockResponse::empty(200);
+    }
+
+    MockResponse {
+        status: 206,
+        headers: vec![("Content-Range", format!("bytes {start}-{end}/{size}"))],
+        body: node.content[start..=end].to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("parent_id=1&limit=500");
+
+        assert_eq!(query.get("parent_id").map(String::as_str), Some("1"));
+        assert_eq!(query.get("limit").map(String::as_str), Some("500"));
+    }
+
+    #[test]
+    fn test_upload_and_download() {
+        let mut state = MockState::new("http://localhost".to_string());
+        let request =
+            |method: &str, path: &str, headers: &[(&str, &str)], body: &[u8]| MockRequest {
+                method: method.to_string(),
+                path: path.to_string(),
+                query: HashMap::new(),
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                body: body.to_vec(),
+            };
+        let auth = [("authorization", "Bearer mock-access-token")];
+
+        let res = route(
+            &mut state,
+            &request(
+                "POST",
+                "/api/v4/nodes/files/uploads",
+                &auth,
+                br#"{"parentId": 1, "name": "test.txt"}"#,
+            ),
+        );
+        assert_eq!(res.status, 201);
+        let token = serde_json::from_slice::<Value>(&res.body).unwrap()["token"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let path = format!("/api/v4/uploads/{token}");
+        let range = [("content-range", "bytes 0-4/5")];
+        assert_eq!(
+            route(&mut state, &request("POST", &path, &range, b"hello")).status,
+            201
+        );
+
+        let res = route(&mut state, &request("PUT", &path, &[], b"{}"));
+        let node = serde_json::from_slice::<Value>(&res.body).unwrap();
+        assert_eq!(node["name"], "test.txt");
+        assert_eq!(node["size"], 5);
+
+        let path = format!("/downloads/{}", node["id"]);
+        let range = [("range", "bytes=1-3")];
+        let res = route(&mut state, &request("GET", &path, &range, b""));
+        assert_eq!(res.status, 206);
+        assert_eq!(res.body, b"ell");
+    }
+
+    #[test]
+    fn test_requires_auth() {
+        let mut state = MockState::new("http://localhost".to_string());
+        let request = MockRequest {
+            method: "GET".to_string(),
+            path: "/api/v4/nodes/1".to_string(),
+            query: HashMap::new(),
+            headers: HashMap::new(),
+            body: Vec::new(),
+        };
+
+        assert_eq!(route(&mut state, &request).status, 401);
+    }
+}
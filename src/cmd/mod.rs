@@ -1,29 +1,68 @@
+use std::{path::Path, sync::OnceLock};
+
 use config::{CLIENT_ID, CLIENT_SECRET};
 use console::Term;
-use keyring::Entry;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
 use self::{
-    config::credentials::HandleCredentials,
+    branding::{instance_label, instance_prompt, load_instance_name},
+    config::credentials::{open_credentials, Credentials, HandleCredentials},
     models::{DcCmdError, PasswordAuth},
-    utils::strings::format_error_message,
+    utils::{
+        deadline::EXIT_CODE_DEADLINE,
+        strings::{format_error_message, NODE_ID_SCHEME},
+    },
 };
 use dco3::{
     auth::{Connected, Disconnected, OAuth2Flow},
     Dracoon, DracoonBuilder, DracoonClientError,
 };
 
+pub mod alias;
+pub mod branding;
 pub mod config;
 pub mod groups;
+pub mod jobs;
+pub mod journal;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock;
 pub mod models;
 pub mod nodes;
 pub mod reports;
+pub mod rooms;
+pub mod selftest;
+pub mod undo;
 pub mod users;
 pub mod utils;
+pub mod webhooks;
 
 // service name to store
 const SERVICE_NAME: &str = env!("CARGO_PKG_NAME");
 
+/// environment variable holding the encryption secret (if not passed explicitly)
+pub const ENCRYPTION_SECRET_ENV: &str = "DCCMD_ENCRYPTION_SECRET";
+
+/// API url of all requests (`--base-url`, e.g. a local test server)
+static BASE_URL_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// sends all requests to given url instead of the instance of the path
+/// (can only be set once - HTTP is allowed for local test servers)
+pub fn init_base_url(base_url: &str) -> Result<(), DcCmdError> {
+    if !base_url.starts_with("https://") && !base_url.starts_with("http://") {
+        return Err(DcCmdError::InvalidUrl(base_url.to_string()));
+    }
+
+    warn!("Sending all requests to {}.", base_url);
+    let _ = BASE_URL_OVERRIDE.set(base_url.trim_end_matches('/').to_string());
+
+    Ok(())
+}
+
+/// API url passed via `--base-url`
+pub fn base_url_override() -> Option<&'static str> {
+    BASE_URL_OVERRIDE.get().map(String::as_str)
+}
+
 /// initializes a dracoon client with encryption enabled (plain keypair ready to use)
 async fn init_encryption(
     dracoon: Dracoon<Connected>,
@@ -31,16 +70,23 @@ async fn init_encryption(
 ) -> Result<Dracoon<Connected>, DcCmdError> {
     let account = format!("{}-crypto", dracoon.get_base_url());
 
-    let entry = Entry::new(SERVICE_NAME, &account).map_err(|_| DcCmdError::CredentialStorageFailed);
+    let entry = open_credentials(&account);
 
     // Helper to get password from user
     let ask_for_secret = || {
         dialoguer::Password::new()
-            .with_prompt("Please enter your encryption secret")
+            .with_prompt(instance_prompt(
+                dracoon.get_base_url().as_str(),
+                "Please enter your encryption secret",
+            ))
             .interact()
             .or(Err(DcCmdError::IoError))
     };
 
+    // Password from environment is handled like a provided password
+    let encryption_password =
+        encryption_password.or_else(|| std::env::var(ENCRYPTION_SECRET_ENV).ok());
+
     let (secret, store) = match encryption_password {
         // Provided password, don't store
         Some(password) => (password, false),
@@ -71,14 +117,31 @@ async fn init_encryption(
 
     // If necessary, create a new entry to store the secret
     if store {
-        let entry =
-            Entry::new(SERVICE_NAME, &account).map_err(|_| DcCmdError::CredentialStorageFailed)?;
+        let entry = open_credentials(&account)?;
         entry.set_dracoon_env(&secret)?;
     }
 
     Ok(dracoon)
 }
 
+/// reads an encryption secret from a file (trailing newline is ignored)
+pub fn read_encryption_password_file(path: &Path) -> Result<String, DcCmdError> {
+    let secret = std::fs::read_to_string(path).map_err(|e| {
+        error!("Error reading encryption password file: {}", e);
+        DcCmdError::InvalidPath(path.display().to_string())
+    })?;
+
+    let secret = secret.trim_end_matches(['\r', '\n']);
+
+    if secret.is_empty() {
+        return Err(DcCmdError::InvalidArgument(
+            "Encryption password file is empty.".to_string(),
+        ));
+    }
+
+    Ok(secret.to_string())
+}
+
 async fn init_dracoon(
     url_path: &str,
     password_auth: Option<PasswordAuth>,
@@ -89,22 +152,19 @@ async fn init_dracoon(
     // use multiple access tokens for transfers
     let token_rotation = if is_transfer { 5 } else { 1 };
 
-    let dccmd_user_agent = format!("{}|{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    let dracoon = build_dracoon(&base_url, token_rotation)?;
 
-    let dracoon = DracoonBuilder::new()
-        .with_base_url(base_url.clone())
-        .with_client_id(CLIENT_ID)
-        .with_client_secret(CLIENT_SECRET)
-        .with_token_rotation(token_rotation)
-        .with_user_agent(dccmd_user_agent)
-        .build()?;
+    // instance name is used in prompts and log lines
+    load_instance_name(&base_url).await;
+    let instance = instance_label(&base_url);
 
-    let entry =
-        Entry::new(SERVICE_NAME, &base_url).map_err(|_| DcCmdError::CredentialStorageFailed);
+    let entry = open_credentials(&base_url);
 
     // Always use password auth first if present
     if let Some(password_auth) = password_auth {
-        return authenticate_password_flow(dracoon, password_auth).await;
+        return authenticate_password_flow(dracoon, password_auth)
+            .await
+            .inspect(|_| info!("Connected to {} (password).", instance));
     }
     // Entry not present & no password auth? Game over.
     let Ok(entry) = entry else {
@@ -124,10 +184,11 @@ async fn init_dracoon(
                     debug!("Error storing refresh token: {}", err);
                     error!("Failed to store refresh token.");
                 }
+                info!("Connected to {} (refresh token).", instance);
                 return Ok(dracoon);
             }
             Err(ref e @ DracoonClientError::Http(ref res)) => {
-                error!("Error connecting with refresh token: {}", e);
+                error!("Error connecting to {} with refresh token: {}", instance, e);
                 debug!("Response: {:?}", res);
                 if res.is_bad_request() {
                     // Refresh token didn't work, delete it
@@ -136,20 +197,37 @@ async fn init_dracoon(
                 }
             }
             Err(ref e @ DracoonClientError::Auth(ref res)) => {
-                error!("Error connecting with refresh token: {}", e);
+                error!("Error connecting to {} with refresh token: {}", instance, e);
                 debug!("Response: {:?}", res);
                 // Refresh token didn't work, delete it
                 let _ = entry.delete_dracoon_env();
                 warn!("Removed invalid refresh token.");
             }
             Err(e) => {
-                error!("Error connecting with refresh token: {}", e);
+                error!("Error connecting to {} with refresh token: {}", instance, e);
             }
         }
     }
 
     // Final resort: auth code flow
-    authenticate_auth_code_flow(dracoon, entry).await
+    authenticate_auth_code_flow(dracoon, &base_url, entry)
+        .await
+        .inspect(|_| info!("Connected to {} (authorization code).", instance))
+}
+
+/// builds an unauthenticated client for a DRACOON instance
+fn build_dracoon(base_url: &str, token_rotation: u8) -> Result<Dracoon<Disconnected>, DcCmdError> {
+    let dccmd_user_agent = format!("{}|{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+
+    let dracoon = DracoonBuilder::new()
+        .with_base_url(base_url)
+        .with_client_id(CLIENT_ID)
+        .with_client_secret(CLIENT_SECRET)
+        .with_token_rotation(token_rotation)
+        .with_user_agent(dccmd_user_agent)
+        .build()?;
+
+    Ok(dracoon)
 }
 
 pub async fn init_public_dracoon(url_path: &str) -> Result<Dracoon<Disconnected>, DcCmdError> {
@@ -169,13 +247,17 @@ pub async fn init_public_dracoon(url_path: &str) -> Result<Dracoon<Disconnected>
 
 async fn authenticate_auth_code_flow(
     dracoon: Dracoon<Disconnected>,
-    entry: Entry,
+    base_url: &str,
+    entry: Credentials,
 ) -> Result<Dracoon<Connected>, DcCmdError> {
-    println!("Please log in via browser (open url): ");
+    println!(
+        "Please log in to {} via browser (open url): ",
+        instance_label(base_url)
+    );
     println!("{}", dracoon.get_authorize_url());
 
     let auth_code = dialoguer::Password::new()
-        .with_prompt("Please enter authorization code")
+        .with_prompt(instance_prompt(base_url, "Please enter authorization code"))
         .interact()
         .or(Err(DcCmdError::IoError))?;
 
@@ -201,6 +283,8 @@ async fn authenticate_password_flow(
 }
 
 fn parse_base_url(url_str: String) -> Result<String, DcCmdError> {
+    let url_str = alias::resolve_alias(&url_str).into_owned();
+
     if url_str.starts_with("http://") {
         error!("HTTP is not supported.");
         return Err(DcCmdError::InvalidUrl(url_str));
@@ -208,6 +292,8 @@ fn parse_base_url(url_str: String) -> Result<String, DcCmdError> {
 
     let url_str = if url_str.starts_with("https://") {
         url_str
+    } else if let Some(url_str) = url_str.strip_prefix(NODE_ID_SCHEME) {
+        format!("https://{url_str}")
     } else {
         format!("https://{url_str}")
     };
@@ -215,7 +301,8 @@ fn parse_base_url(url_str: String) -> Result<String, DcCmdError> {
     let uri_fragments: Vec<&str> = url_str[8..].split('/').collect();
 
     match uri_fragments.len() {
-        2.. => Ok(format!("https://{}", uri_fragments[0])),
+        2.. => Ok(base_url_override()
+            .map_or_else(|| format!("https://{}", uri_fragments[0]), str::to_string)),
         _ => Err(DcCmdError::InvalidUrl(url_str)),
     }
 }
@@ -230,7 +317,12 @@ pub fn handle_error(term: &Term, err: &DcCmdError) {
         .expect("Error writing error message to terminal.");
 
     // exit with error code
-    std::process::exit(1);
+    let code = match err {
+        DcCmdError::DeadlineExceeded(_) => EXIT_CODE_DEADLINE,
+        _ => 1,
+    };
+
+    std::process::exit(code);
 }
 
 fn get_error_message(err: &DcCmdError) -> String {
@@ -248,6 +340,9 @@ fn get_error_message(err: &DcCmdError) -> String {
         DcCmdError::DracoonAuthError(e) => format!("{e}"),
         DcCmdError::InvalidArgument(msg) => msg.to_string(),
         DcCmdError::LogFileCreationFailed => "Log file creation failed.".into(),
+        DcCmdError::TransferFailed(msg)
+        | DcCmdError::JobFailed(msg)
+        | DcCmdError::DeadlineExceeded(msg) => msg.to_string(),
     }
 }
 
@@ -279,6 +374,12 @@ mod tests {
         assert_eq!(base_url, "https://bla.dracoon.com");
     }
 
+    #[test]
+    fn test_base_url_parse_node_id() {
+        let base_url = parse_base_url("dracoon://bla.dracoon.com/123".into()).unwrap();
+        assert_eq!(base_url, "https://bla.dracoon.com");
+    }
+
     #[test]
     fn test_base_url_parse_invalid_path() {
         let base_url = parse_base_url("bla.dracoon.com".into());
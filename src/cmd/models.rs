@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use clap::Parser;
 use thiserror::Error;
 
@@ -9,8 +11,17 @@ use dco3::{
 };
 
 use super::{
-    config::models::{ConfigAuthCommand, ConfigCryptoCommand},
+    config::{
+        credentials::CredentialStore,
+        models::{
+            ConfigAuthCommand, ConfigCryptoCommand, ConfigOAuthCommand, ConfigPoliciesCommand,
+            ConfigSettingsCommand,
+        },
+    },
     groups::GroupsUsersCommand,
+    nodes::models::{ClassificationLevel, CmdResolutionStrategy, LsOutput},
+    reports::EventPartition,
+    rooms::PermissionPreset,
 };
 
 // represents password flow
@@ -45,6 +56,12 @@ pub enum DcCmdError {
     InvalidArgument(String),
     #[error("Log file creation failed")]
     LogFileCreationFailed,
+    #[error("Transfer incomplete")]
+    TransferFailed(String),
+    #[error("Job incomplete")]
+    JobFailed(String),
+    #[error("Deadline exceeded")]
+    DeadlineExceeded(String),
 }
 
 impl From<DracoonClientError> for DcCmdError {
@@ -94,6 +111,62 @@ pub struct DcCmd {
     /// optional encryption password
     #[clap(long)]
     pub encryption_password: Option<String>,
+
+    /// optional file containing the encryption password
+    #[clap(long, conflicts_with = "encryption_password")]
+    pub encryption_password_file: Option<PathBuf>,
+
+    /// print operations of destructive commands (rm, cp, mv, users rm, groups rm, undo, run, rooms revoke-user, rooms update, rooms permissions apply) without executing them
+    #[clap(long, global = true)]
+    pub dry_run: bool,
+
+    /// record performed operations to a journal file (CSV)
+    #[clap(long, global = true)]
+    pub journal: Option<PathBuf>,
+
+    /// skip confirmation of bulk deletes and large recursive operations
+    #[clap(long, short = 'y', global = true)]
+    pub yes: bool,
+
+    /// number of files of recursive operations requiring confirmation (default: 1000)
+    #[clap(long, global = true)]
+    pub confirm_files: Option<u64>,
+
+    /// total size of recursive operations requiring confirmation (e.g. 500MB, default: 10GB)
+    #[clap(long, global = true)]
+    pub confirm_size: Option<String>,
+
+    /// columns of CSV output (comma separated, e.g. id,user_name,email)
+    #[clap(long, global = true)]
+    pub fields: Option<String>,
+
+    /// limit API requests of mass operations (requests per second)
+    #[clap(long, global = true)]
+    pub rate_limit: Option<u32>,
+
+    /// stop starting new work after given duration (e.g. 2h) - running transfers are finished
+    #[clap(long, global = true)]
+    pub deadline: Option<String>,
+
+    /// credential store for refresh tokens and encryption secrets
+    #[clap(long, value_enum, global = true, default_value_t)]
+    pub credential_store: CredentialStore,
+
+    /// file containing the passphrase of the file credential store
+    #[clap(long, global = true)]
+    pub credential_key_file: Option<PathBuf>,
+
+    /// record sanitized DRACOON API requests / responses to a file (for bug reports - tokens are redacted)
+    #[clap(long, global = true)]
+    pub record: Option<PathBuf>,
+
+    /// include request / response bodies in the recording
+    #[clap(long, global = true, requires = "record")]
+    pub record_bodies: bool,
+
+    /// send all API requests to given url (e.g. http://localhost:8080 for a local test server)
+    #[clap(long, global = true)]
+    pub base_url: Option<String>,
 }
 
 #[derive(Parser)]
@@ -110,13 +183,17 @@ pub enum DcCmdCommand {
         #[clap(long)]
         overwrite: bool,
 
-        /// Preserve Download Share Links and point them to the new node in DRACOON
-        #[clap(long)]
+        /// Preserve Download Share Links and point them to the new node in DRACOON (requires --overwrite)
+        #[clap(long, requires = "overwrite")]
         keep_share_links: bool,
 
-        /// classification of the node (1-4)
+        /// classification of the node (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
+
+        /// file expiration (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
         #[clap(long)]
-        classification: Option<u8>,
+        expiration: Option<String>,
 
         #[clap(long, short)]
         velocity: Option<u8>,
@@ -135,6 +212,74 @@ pub enum DcCmdCommand {
 
         #[clap(long)]
         share_password: Option<String>,
+
+        /// expiration of the share link (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
+        #[clap(long, requires = "share")]
+        share_expiration: Option<String>,
+
+        /// send the share link via DRACOON notification email (comma separated)
+        #[clap(long, requires = "share", value_delimiter = ',')]
+        notify_emails: Vec<String>,
+
+        /// message of the notification email
+        #[clap(long, requires = "notify_emails")]
+        notify_message: Option<String>,
+
+        /// generate a share password compliant with the share password policies (printed once)
+        #[clap(long, requires = "share", conflicts_with = "share_password")]
+        generate_password: bool,
+
+        /// write the generated share password to a file instead of printing it
+        #[clap(long, requires = "generate_password")]
+        password_file: Option<PathBuf>,
+
+        /// skip files with same size and hash in DRACOON
+        #[clap(long)]
+        skip_unchanged: bool,
+
+        /// exit successfully even if some files failed (see transfer-errors.csv)
+        #[clap(long)]
+        continue_on_error: bool,
+
+        /// verify uploaded files by comparing the MD5 hash in DRACOON with the local file
+        #[clap(long)]
+        verify: bool,
+
+        /// upload identical files (same name and hash) once and copy them to the other folders
+        #[clap(long, requires = "recursive", conflicts_with_all = ["skip_unchanged", "watch"])]
+        dedupe: bool,
+
+        /// name of the file in DRACOON (single file uploads only)
+        #[clap(long, conflicts_with_all = ["recursive", "watch", "prefix", "suffix"])]
+        rename: Option<String>,
+
+        /// prefix added to the names of uploaded files (e.g. "2024_")
+        #[clap(long)]
+        prefix: Option<String>,
+
+        /// suffix added to the names of uploaded files before the extension (e.g. "_v2")
+        #[clap(long)]
+        suffix: Option<String>,
+
+        /// upload via DRACOON instead of directly to the S3 storage (used automatically if S3 is not reachable)
+        #[clap(long)]
+        no_direct_s3: bool,
+
+        /// continue an interrupted recursive upload (see .transfer-checkpoint.jsonl in the working directory)
+        #[clap(long, requires = "recursive", conflicts_with = "watch")]
+        resume: bool,
+
+        /// keep running and upload new or changed files of the source directory
+        #[clap(long, conflicts_with = "skip_root")]
+        watch: bool,
+
+        /// seconds without changes before a file is uploaded (watch mode)
+        #[clap(long, requires = "watch", default_value = "2")]
+        debounce: u64,
+
+        /// ignore files and folders matching a pattern (e.g. "*.tmp") - watch mode, repeatable
+        #[clap(long, requires = "watch")]
+        exclude: Vec<String>,
     },
     /// Download a file or container from DRACOON to target
     Download {
@@ -155,6 +300,50 @@ pub enum DcCmdCommand {
 
         #[clap(long)]
         include_rooms: bool,
+
+        /// rename nodes with names invalid on the local OS (e.g. `:` on Windows)
+        #[clap(long)]
+        rename_invalid: bool,
+
+        /// place all files directly into target (no folders - duplicate names are numbered)
+        #[clap(long)]
+        flat: bool,
+
+        /// free space to keep on target after download (e.g. 500MB, 2GB)
+        #[clap(long)]
+        min_free_space: Option<String>,
+
+        /// skip free space check on target
+        #[clap(long, conflicts_with = "min_free_space")]
+        no_space_check: bool,
+
+        /// only download files matching a pattern (e.g. "*.pdf") - recursive only, repeatable
+        #[clap(long, requires = "recursive")]
+        include: Vec<String>,
+
+        /// skip files matching a pattern (e.g. "*.tmp") - recursive only, repeatable
+        #[clap(long, requires = "recursive")]
+        exclude: Vec<String>,
+
+        /// only download files of given types (e.g. jpg,png - or image, video, audio, document)
+        #[clap(long, value_delimiter = ',')]
+        file_type: Vec<String>,
+
+        /// exit successfully even if some files failed (see transfer-errors.csv)
+        #[clap(long)]
+        continue_on_error: bool,
+
+        /// system rescue key secret - grants missing file keys of encrypted rooms (admin recovery)
+        #[clap(long)]
+        rescue_secret: Option<String>,
+
+        /// continue an interrupted recursive download (see .transfer-checkpoint.jsonl in the working directory)
+        #[clap(long, requires = "recursive")]
+        resume: bool,
+
+        /// address the source node by id (source only selects the DRACOON instance)
+        #[clap(long)]
+        node_id: Option<u64>,
     },
     /// Transfer files across DRACOON instances
     Transfer {
@@ -168,13 +357,21 @@ pub enum DcCmdCommand {
         #[clap(long)]
         overwrite: bool,
 
-        /// Preserve Download Share Links and point them to the new node in DRACOON
-        #[clap(long)]
+        /// Preserve Download Share Links and point them to the new node in DRACOON (requires --overwrite)
+        #[clap(long, requires = "overwrite")]
         keep_share_links: bool,
 
-        /// classification of the node (1-4)
+        /// classification of the node (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
+
+        /// file expiration (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
         #[clap(long)]
-        classification: Option<u8>,
+        expiration: Option<String>,
+
+        /// concurrent file transfers for rooms / folders (1-10)
+        #[clap(long, short)]
+        velocity: Option<u8>,
 
         /// share upload
         #[clap(long)]
@@ -182,6 +379,73 @@ pub enum DcCmdCommand {
 
         #[clap(long)]
         share_password: Option<String>,
+
+        /// expiration of the share link (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
+        #[clap(long, requires = "share")]
+        share_expiration: Option<String>,
+
+        /// send the share link via DRACOON notification email (comma separated)
+        #[clap(long, requires = "share", value_delimiter = ',')]
+        notify_emails: Vec<String>,
+
+        /// message of the notification email
+        #[clap(long, requires = "notify_emails")]
+        notify_message: Option<String>,
+
+        /// generate a share password compliant with the share password policies (printed once)
+        #[clap(long, requires = "share", conflicts_with = "share_password")]
+        generate_password: bool,
+
+        /// write the generated share password to a file instead of printing it
+        #[clap(long, requires = "generate_password")]
+        password_file: Option<PathBuf>,
+    },
+    /// Migrate a room (sub rooms, folders, permissions and files) to another DRACOON instance
+    Migrate {
+        /// Source DRACOON url
+        source: String,
+
+        /// Target DRACOON url (optionally with a parent room path)
+        target: String,
+
+        /// path of the room to migrate in the source DRACOON
+        #[clap(long)]
+        path: String,
+
+        /// concurrent file transfers (1-10)
+        #[clap(long, short)]
+        velocity: Option<u8>,
+
+        /// do not copy user and group permissions
+        #[clap(long)]
+        skip_permissions: bool,
+
+        /// exit successfully even if some nodes failed (see transfer-errors.csv)
+        #[clap(long)]
+        continue_on_error: bool,
+    },
+    /// Synchronize a local directory into DRACOON (uploads new and changed files)
+    Sync {
+        /// Source directory path
+        source: String,
+
+        /// Target path in DRACOON
+        target: String,
+
+        /// classification of uploaded files (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
+
+        #[clap(long, short)]
+        velocity: Option<u8>,
+
+        /// keep running and repeat the synchronization every --interval
+        #[clap(long, requires = "interval")]
+        daemon: bool,
+
+        /// time between synchronizations in daemon mode (e.g. 30s, 15m, 1h)
+        #[clap(long, requires = "daemon")]
+        interval: Option<String>,
     },
     /// List nodes in DRACOON
     Ls {
@@ -215,14 +479,85 @@ pub enum DcCmdCommand {
         /// fetch all nodes (default: 500)
         #[clap(long)]
         all: bool,
+
+        /// print permissions of the node (and assigned users / groups for rooms)
+        #[clap(long)]
+        acl: bool,
+
+        /// list deleted nodes (recycle bin) of the path with deletion date and deleted by
+        #[clap(long, conflicts_with = "acl")]
+        deleted: bool,
+
+        /// show the aggregated size of rooms and folders (sum of all files below)
+        #[clap(long, conflicts_with_all = ["acl", "deleted"])]
+        du: bool,
+
+        /// print node ids in front of the names (use with --node-id)
+        #[clap(long, conflicts_with_all = ["long", "acl", "deleted"])]
+        ids: bool,
+
+        /// omit the header line of the long output
+        #[clap(long)]
+        no_header: bool,
+
+        /// print node names only (no header, no paging - for scripts)
+        #[clap(short, long, conflicts_with_all = ["long", "acl", "deleted"])]
+        quiet: bool,
+
+        /// do not page the output in interactive terminals
+        #[clap(long)]
+        no_pager: bool,
+
+        /// output format (json-lines: one JSON object per node, streamed as pages arrive)
+        #[clap(
+            long,
+            value_enum,
+            default_value_t = LsOutput::Text,
+            conflicts_with_all = ["long", "acl", "deleted", "ids", "quiet"]
+        )]
+        output: LsOutput,
+
+        /// only list files of given types (e.g. pdf,docx - or image, video, audio, document)
+        #[clap(long, value_delimiter = ',', conflicts_with_all = ["acl", "deleted"])]
+        file_type: Vec<String>,
     },
 
+    /// Copy nodes in DRACOON
     Cp {
         /// Source file path in DRACOON
         source: String,
 
         /// Target file path in DRACOON
         target: String,
+
+        /// conflict resolution if a node with the same name exists (default: autorename)
+        #[clap(long, value_enum)]
+        resolution: Option<CmdResolutionStrategy>,
+
+        /// Preserve Download Share Links and point them to the new node in DRACOON (requires --resolution overwrite)
+        #[clap(long)]
+        keep_share_links: bool,
+
+        /// address the source node by id (source only selects the DRACOON instance)
+        #[clap(long)]
+        node_id: Option<u64>,
+    },
+
+    /// Move nodes in DRACOON
+    Mv {
+        /// Source file path in DRACOON
+        source: String,
+
+        /// Target file path in DRACOON
+        target: String,
+
+        /// conflict resolution if a node with the same name exists (default: autorename)
+        #[clap(long, value_enum)]
+        resolution: Option<CmdResolutionStrategy>,
+
+        /// Preserve Download Share Links and point them to the new node in DRACOON (requires --resolution overwrite)
+        #[clap(long)]
+        keep_share_links: bool,
     },
 
     /// Create a folder in DRACOON
@@ -230,13 +565,17 @@ pub enum DcCmdCommand {
         /// Source file path in DRACOON
         source: String,
 
-        /// classification of the node (1-4)
-        #[clap(long)]
-        classification: Option<u8>,
+        /// classification of the node (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
 
         /// Notes
         #[clap(long)]
         notes: Option<String>,
+
+        /// create missing parent folders (rooms are never created)
+        #[clap(short, long)]
+        parents: bool,
     },
 
     /// Create a room in DRACOON (inhherits permissions from parent)
@@ -248,13 +587,21 @@ pub enum DcCmdCommand {
         #[clap(long, short)]
         admin_users: Option<Vec<String>>,
 
-        /// classification of the node (1-4)
-        #[clap(long)]
-        classification: Option<u8>,
+        /// admin group names
+        #[clap(long, value_delimiter = ',')]
+        admin_groups: Option<Vec<String>>,
+
+        /// classification of the node (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
 
         /// inherit permissions from parent room
         #[clap(long)]
         inherit_permissions: bool,
+
+        /// create missing parent rooms (inheriting permissions)
+        #[clap(long, short)]
+        parents: bool,
     },
 
     /// Delete a node in DRACOON
@@ -265,6 +612,42 @@ pub enum DcCmdCommand {
         /// recursive delete (mandatory for rooms / folders)
         #[clap(short, long)]
         recursive: bool,
+
+        /// permanently delete (purge deleted versions from the recycle bin)
+        #[clap(long, conflicts_with_all = ["older_than", "newer_than"])]
+        permanent: bool,
+
+        /// only delete search results last modified before (age e.g. 90d, RFC3339, yyyy-mm-dd, yyyy-Www or yesterday)
+        #[clap(long, requires = "recursive")]
+        older_than: Option<String>,
+
+        /// only delete search results last modified after (age e.g. 7d, RFC3339, yyyy-mm-dd, yyyy-Www or yesterday)
+        #[clap(long, requires = "recursive")]
+        newer_than: Option<String>,
+
+        /// address the source node by id (source only selects the DRACOON instance)
+        #[clap(long)]
+        node_id: Option<u64>,
+    },
+
+    /// Set or remove the expiration of a file in DRACOON
+    Expire {
+        /// Source file path in DRACOON
+        source: String,
+
+        /// expiration date (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
+        #[clap(long, conflicts_with = "clear", required_unless_present = "clear")]
+        at: Option<String>,
+
+        /// remove expiration
+        #[clap(long)]
+        clear: bool,
+    },
+
+    /// Manage folders in DRACOON
+    Folders {
+        #[clap(subcommand)]
+        cmd: FoldersCommand,
     },
 
     /// Manage users in DRACOON
@@ -279,6 +662,30 @@ pub enum DcCmdCommand {
         cmd: GroupsCommand,
     },
 
+    /// Manage rooms in DRACOON
+    Rooms {
+        #[clap(subcommand)]
+        cmd: RoomsCommand,
+    },
+
+    /// Manage webhooks in DRACOON (customer admin)
+    Webhooks {
+        #[clap(subcommand)]
+        cmd: WebhooksCommand,
+    },
+
+    /// Log in to or out of a DRACOON instance
+    Auth {
+        #[clap(subcommand)]
+        cmd: AuthCommand,
+    },
+
+    /// Manage aliases for DRACOON paths (e.g. proj1:/folder)
+    Alias {
+        #[clap(subcommand)]
+        cmd: AliasCommand,
+    },
+
     /// Configure DRACOON Commander
     Config {
         #[clap(subcommand)]
@@ -291,10 +698,77 @@ pub enum DcCmdCommand {
         cmd: ReportsCommand,
     },
 
+    /// Run a job file (declarative list of operations, e.g. mkdir, upload, share, grant)
+    Run {
+        /// job file (YAML)
+        source: PathBuf,
+
+        /// set a job variable (name=value - overrides variables of the job file), repeatable
+        #[clap(long)]
+        var: Vec<String>,
+
+        /// continue with the next step if a step fails
+        #[clap(long)]
+        continue_on_error: bool,
+    },
+
+    /// Undo reversible operations recorded in a journal (see --journal)
+    Undo {
+        /// DRACOON url
+        target: String,
+
+        /// journal file path
+        source: String,
+    },
+
+    /// Run ls, upload and download against a room or folder (e.g. to validate CI environments)
+    Selftest {
+        /// DRACOON url of a room or folder (a test file is uploaded and removed)
+        #[cfg_attr(feature = "mock-server", clap(required_unless_present = "mock"))]
+        #[cfg_attr(not(feature = "mock-server"), clap(required = true))]
+        target: Option<String>,
+
+        /// run against an in-process mock of the DRACOON API instead of an instance
+        #[cfg(feature = "mock-server")]
+        #[clap(long, conflicts_with = "target")]
+        mock: bool,
+    },
+
     /// Print current dccmd-rs version
     Version,
 }
 
+impl DcCmdCommand {
+    /// commands printing their operations instead of executing them with --dry-run
+    pub fn supports_dry_run(&self) -> bool {
+        matches!(
+            self,
+            DcCmdCommand::Rm { .. }
+                | DcCmdCommand::Cp { .. }
+                | DcCmdCommand::Mv { .. }
+                | DcCmdCommand::Users {
+                    cmd: UsersCommand::Rm { .. }
+                }
+                | DcCmdCommand::Groups {
+                    cmd: GroupsCommand::Rm { .. }
+                }
+                | DcCmdCommand::Undo { .. }
+                | DcCmdCommand::Run { .. }
+                | DcCmdCommand::Rooms {
+                    cmd: RoomsCommand::RevokeUser { .. }
+                }
+                | DcCmdCommand::Rooms {
+                    cmd: RoomsCommand::Update { .. }
+                }
+                | DcCmdCommand::Rooms {
+                    cmd: RoomsCommand::Permissions {
+                        cmd: RoomPermissionsCommand::Apply { .. }
+                    }
+                }
+        )
+    }
+}
+
 #[derive(Parser)]
 pub enum UsersCommand {
     /// List users in DRACOON
@@ -399,6 +873,18 @@ pub enum UsersCommand {
         /// OIDC config id
         #[clap(long)]
         oidc_id: Option<u32>,
+
+        /// update existing users (matched by login or email) instead of failing
+        #[clap(long)]
+        update_existing: bool,
+
+        /// map IdP groups (column 'idp_groups') to DRACOON groups (CSV: idp_group,dracoon_group)
+        #[clap(long)]
+        group_mapping: Option<PathBuf>,
+
+        /// scale concurrent requests (1-10, each step equals 5 concurrent requests)
+        #[clap(long)]
+        velocity: Option<u8>,
     },
 
     /// print user information in DRACOON
@@ -412,6 +898,10 @@ pub enum UsersCommand {
 
         #[clap(long)]
         user_id: Option<u64>,
+
+        /// print user info (including groups and roles) as JSON
+        #[clap(long)]
+        json: bool,
     },
 
     /// swith auth method for users in DRACOON
@@ -452,6 +942,10 @@ pub enum UsersCommand {
         /// default: email
         #[clap(long)]
         login: Option<String>,
+
+        /// scale concurrent requests (1-10, each step equals 5 concurrent requests)
+        #[clap(long)]
+        velocity: Option<u8>,
     },
 
     EnforceMfa {
@@ -473,6 +967,32 @@ pub enum UsersCommand {
         /// optional group id
         #[clap(long)]
         group_id: Option<u64>,
+
+        /// scale concurrent requests (1-10, each step equals 5 concurrent requests)
+        #[clap(long)]
+        velocity: Option<u8>,
+    },
+
+    /// set or remove the account expiration of users in DRACOON
+    SetExpiration {
+        /// DRACOON url
+        target: String,
+
+        /// user filter (e.g. userName:cn:contractor)
+        #[clap(long)]
+        filter: String,
+
+        /// expiration date (RFC3339, yyyy-mm-dd, yyyy-Www, tomorrow or relative e.g. +30d)
+        #[clap(long)]
+        at: Option<String>,
+
+        /// remove expiration
+        #[clap(long)]
+        never: bool,
+
+        /// scale concurrent requests (1-10, each step equals 5 concurrent requests)
+        #[clap(long)]
+        velocity: Option<u8>,
     },
 }
 
@@ -502,6 +1022,10 @@ pub enum GroupsCommand {
         /// print user information in CSV format
         #[clap(long)]
         csv: bool,
+
+        /// fetch the member count of each group (one request per group)
+        #[clap(long)]
+        with_member_count: bool,
     },
 
     /// Create a group in DRACOON
@@ -534,6 +1058,205 @@ pub enum GroupsCommand {
     },
 }
 
+#[derive(Parser)]
+pub enum RoomsCommand {
+    /// Print the room hierarchy (optionally with admins and granted groups)
+    Tree {
+        /// DRACOON url and path (default: all rooms)
+        source: String,
+
+        /// print room admins and granted groups per room
+        #[clap(long)]
+        with_permissions: bool,
+    },
+
+    /// Grant a group on multiple rooms (permission template applied to each room)
+    GrantGroup {
+        /// DRACOON url
+        target: String,
+
+        /// group name
+        #[clap(long)]
+        group: String,
+
+        /// file with one room path per line (e.g. /Projects/apollo)
+        #[clap(long)]
+        rooms_from_file: PathBuf,
+
+        /// permission template granted on each room
+        #[clap(long, value_enum)]
+        template: PermissionPreset,
+    },
+
+    /// Remove direct permissions of a user on all rooms (e.g. offboarding)
+    RevokeUser {
+        /// DRACOON url
+        target: String,
+
+        /// user login (username)
+        #[clap(long)]
+        login: String,
+    },
+
+    /// Update name, quota or notes of a room
+    Update {
+        /// DRACOON url and room path
+        source: String,
+
+        /// new room name
+        #[clap(long)]
+        name: Option<String>,
+
+        /// room quota (e.g. 500GB, 0 removes the quota)
+        #[clap(long)]
+        quota: Option<String>,
+
+        /// Notes
+        #[clap(long)]
+        notes: Option<String>,
+    },
+
+    /// Manage room admins (users and groups with manage permissions)
+    Admins {
+        #[clap(subcommand)]
+        cmd: RoomAdminsCommand,
+    },
+
+    /// Export or apply the room permission matrix (room, principal, type, permission)
+    Permissions {
+        #[clap(subcommand)]
+        cmd: RoomPermissionsCommand,
+    },
+}
+
+#[derive(Parser)]
+pub enum WebhooksCommand {
+    /// List webhooks
+    Ls {
+        /// DRACOON url
+        target: String,
+
+        /// print webhooks in CSV format
+        #[clap(long)]
+        csv: bool,
+    },
+
+    /// List event types available for webhooks
+    EventTypes {
+        /// DRACOON url
+        target: String,
+    },
+
+    /// Create a webhook
+    Create {
+        /// DRACOON url
+        target: String,
+
+        /// webhook name
+        #[clap(long, short)]
+        name: String,
+
+        /// target URL receiving the events
+        #[clap(long)]
+        url: String,
+
+        /// event type (e.g. file.created - see `webhooks event-types`), repeatable
+        #[clap(long = "event-type", required = true)]
+        event_types: Vec<String>,
+
+        /// secret to sign the events (X-DRACOON-Signature header)
+        #[clap(long)]
+        secret: Option<String>,
+
+        /// create the webhook disabled
+        #[clap(long)]
+        disabled: bool,
+    },
+
+    /// Delete a webhook
+    Rm {
+        /// DRACOON url
+        target: String,
+
+        /// webhook id
+        webhook_id: u64,
+    },
+}
+
+#[derive(Parser)]
+pub enum FoldersCommand {
+    /// Update name, notes or classification of a folder
+    Update {
+        /// Source folder path in DRACOON
+        source: String,
+
+        /// new folder name
+        #[clap(long)]
+        name: Option<String>,
+
+        /// Notes
+        #[clap(long)]
+        notes: Option<String>,
+
+        /// classification of the node (public, internal, confidential, strictly-confidential or 1-4)
+        #[clap(long, value_enum)]
+        classification: Option<ClassificationLevel>,
+    },
+}
+
+#[derive(Parser)]
+pub enum RoomPermissionsCommand {
+    /// Export direct permissions of all rooms below a room (all rooms for the root)
+    Export {
+        /// DRACOON url and path (default: all rooms)
+        source: String,
+
+        /// print permissions in CSV format (can be applied via `rooms permissions apply`)
+        #[clap(long)]
+        csv: bool,
+    },
+
+    /// Apply a permission matrix (CSV) - only differing permissions are changed
+    Apply {
+        /// DRACOON url
+        target: String,
+
+        /// CSV file with columns room,principal,type,permission
+        source: PathBuf,
+    },
+}
+
+#[derive(Parser)]
+pub enum RoomAdminsCommand {
+    /// Add users and / or groups as room admins
+    Add {
+        /// DRACOON url and room path
+        source: String,
+
+        /// user logins (comma separated)
+        #[clap(long, value_delimiter = ',')]
+        users: Vec<String>,
+
+        /// group names (comma separated)
+        #[clap(long, value_delimiter = ',')]
+        groups: Vec<String>,
+    },
+
+    /// Remove the admin role of users and / or groups (other permissions are kept, the last admin cannot be removed)
+    Rm {
+        /// DRACOON url and room path
+        source: String,
+
+        /// user logins (comma separated)
+        #[clap(long, value_delimiter = ',')]
+        users: Vec<String>,
+
+        /// group names (comma separated)
+        #[clap(long, value_delimiter = ',')]
+        groups: Vec<String>,
+    },
+}
+
 #[derive(Parser)]
 pub enum ReportsCommand {
     Events {
@@ -572,18 +1295,116 @@ pub enum ReportsCommand {
         #[clap(long)]
         status: Option<u8>,
 
-        /// start date (format: yyyy-mm-dd)
+        /// start date (yyyy-mm-dd, yyyy-Www, RFC3339, today, yesterday or relative e.g. -7d)
         #[clap(long)]
         start_date: Option<String>,
 
-        /// end date (format: yyyy-mm-dd)
+        /// end date (yyyy-mm-dd, yyyy-Www, RFC3339, today, yesterday or relative e.g. -1d)
         #[clap(long)]
         end_date: Option<String>,
+
+        /// keep polling for new events (stop with Ctrl+C)
+        #[clap(long, conflicts_with = "end_date")]
+        follow: bool,
+
+        /// polling interval in follow mode (e.g. 30s, 5m, 1h - default: 30s)
+        #[clap(long)]
+        interval: Option<String>,
+
+        /// print aggregated statistics (total, date range, events per operation) as JSON
+        #[clap(long, conflicts_with_all = ["csv", "follow"])]
+        stats_json: bool,
+
+        /// export events as CSV files (one file per period) into given directory
+        #[clap(long, conflicts_with_all = ["follow", "stats_json"])]
+        out_dir: Option<PathBuf>,
+
+        /// period of a single export file (default: day)
+        #[clap(long, value_enum, requires = "out_dir")]
+        partition: Option<EventPartition>,
+
+        /// continue an interrupted export (see checkpoint file in the export directory)
+        #[clap(long, requires = "out_dir")]
+        resume: bool,
     },
     OperationTypes {
         /// DRACOON url
         target: String,
     },
+    /// list files expiring within n days
+    Expiring {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// expiring within n days (default: 30)
+        #[clap(long)]
+        days: Option<u32>,
+
+        /// print expiring files in CSV format
+        #[clap(long)]
+        csv: bool,
+
+        /// print aggregated statistics (total, date range, files per day) as JSON
+        #[clap(long, conflicts_with = "csv")]
+        stats_json: bool,
+    },
+    /// list storage consumption per room and user counts (capacity planning)
+    Usage {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// print usage in CSV format
+        #[clap(long)]
+        csv: bool,
+
+        /// print storage growth (new files per month) instead of room usage
+        #[clap(long)]
+        growth: bool,
+
+        /// number of months for growth (default: 12)
+        #[clap(long, requires = "growth")]
+        months: Option<u32>,
+    },
+    /// list files flagged as malicious by the virus scan (quarantine)
+    Quarantine {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// print malicious files in CSV format
+        #[clap(long)]
+        csv: bool,
+    },
+    /// list nodes created by deleted or locked users (e.g. to reassign ownership when offboarding)
+    Orphaned {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// list all nodes created by given user id (regardless of the user state)
+        #[clap(long)]
+        created_by: Option<u64>,
+
+        /// print nodes in CSV format
+        #[clap(long)]
+        csv: bool,
+    },
+    /// list settings per room (recycle bin, activities log, member acceptance, encryption, inherited permissions)
+    RoomSettings {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// print room settings in CSV format
+        #[clap(long)]
+        csv: bool,
+    },
+    /// list encryption status per room (unencrypted files in encrypted rooms)
+    Crypto {
+        /// DRACOON url and path (default: all rooms)
+        target: String,
+
+        /// print encryption status in CSV format
+        #[clap(long)]
+        csv: bool,
+    },
     Permissions {
         /// DRACOON url
         target: String,
@@ -607,6 +1428,37 @@ pub enum ReportsCommand {
         /// print user information in CSV format
         #[clap(long)]
         csv: bool,
+
+        /// print aggregated statistics (total, users per permission set) as JSON
+        #[clap(long, conflicts_with = "csv")]
+        stats_json: bool,
+
+        /// export permissions as CSV files (one file per room and an index.csv) into given directory
+        #[clap(long, conflicts_with_all = ["csv", "stats_json"])]
+        out_dir: Option<PathBuf>,
+
+        /// only re-fetch rooms updated since the last incremental export into the same directory
+        #[clap(long, requires = "out_dir", conflicts_with_all = ["filter", "offset"])]
+        incremental: bool,
+    },
+}
+
+#[derive(Parser)]
+pub enum AuthCommand {
+    /// Log in (stores the refresh token - uses --username / --password if passed)
+    Login {
+        /// DRACOON url
+        target: String,
+
+        /// log in via device code (enter a code on another device, e.g. in SSH sessions)
+        #[clap(long)]
+        device: bool,
+    },
+
+    /// Log out (revokes the tokens and removes all stored credentials)
+    Logout {
+        /// DRACOON url
+        target: String,
     },
 }
 
@@ -624,10 +1476,55 @@ pub enum ConfigCommand {
         cmd: ConfigCryptoCommand,
     },
 
+    /// Display DRACOON system settings
+    Settings {
+        #[clap(subcommand)]
+        cmd: ConfigSettingsCommand,
+    },
+
+    /// Display DRACOON share policies (share passwords, classification)
+    Policies {
+        #[clap(subcommand)]
+        cmd: ConfigPoliciesCommand,
+    },
+
+    /// Manage DRACOON OAuth clients (e.g. register a dccmd-rs client)
+    #[clap(name = "oauth")]
+    OAuth {
+        #[clap(subcommand)]
+        cmd: ConfigOAuthCommand,
+    },
+
+    /// Display DRACOON system info (customer, auth methods, S3 endpoints, password policies, features)
     SystemInfo {
         /// DRACOON url
         target: String,
+
+        /// print system info as JSON (e.g. for support tooling)
+        #[clap(long)]
+        json: bool,
+    },
+}
+
+#[derive(Parser)]
+pub enum AliasCommand {
+    /// Add (or replace) an alias for a DRACOON path
+    Add {
+        /// alias name (e.g. proj1)
+        name: String,
+
+        /// DRACOON url (e.g. dracoon.team/rooms/projects/p1)
+        target: String,
+    },
+
+    /// Remove an alias
+    Rm {
+        /// alias name
+        name: String,
     },
+
+    /// List all aliases
+    Ls,
 }
 
 #[derive(Clone, Copy)]
@@ -0,0 +1,99 @@
+use console::Term;
+use dco3::{
+    auth::Connected,
+    nodes::{Node, NodeType},
+    Dracoon,
+};
+
+use crate::cmd::{
+    models::DcCmdError,
+    rooms::{get_all_room_groups, get_all_room_users},
+};
+
+use super::to_node_path;
+
+/// prints the effective permissions of a node and the assigned users / groups (rooms only)
+pub async fn print_node_acl(
+    term: &Term,
+    dracoon: &Dracoon<Connected>,
+    node: &Node,
+) -> Result<(), DcCmdError> {
+    write_line(term, &to_node_path(node))?;
+    write_line(term, &format!("permissions: {}", format_permissions(node)))?;
+
+    if node.node_type != NodeType::Room {
+        return Ok(());
+    }
+
+    let users = get_room_user_permissions(dracoon, node.id).await?;
+    let groups = get_room_group_permissions(dracoon, node.id).await?;
+
+    write_line(term, "users:")?;
+    if users.is_empty() {
+        write_line(term, "  none")?;
+    }
+    for user in users {
+        write_line(term, &format!("  {user}"))?;
+    }
+
+    write_line(term, "groups:")?;
+    if groups.is_empty() {
+        write_line(term, "  none")?;
+    }
+    for group in groups {
+        write_line(term, &format!("  {group}"))?;
+    }
+
+    Ok(())
+}
+
+fn format_permissions(node: &Node) -> String {
+    node.permissions
+        .as_ref()
+        .map_or_else(|| "n/a".to_string(), ToString::to_string)
+}
+
+fn write_line(term: &Term, line: &str) -> Result<(), DcCmdError> {
+    term.write_line(line).map_err(|_| DcCmdError::IoError)
+}
+
+/// returns all granted room users with their permissions
+async fn get_room_user_permissions(
+    dracoon: &Dracoon<Connected>,
+    room_id: u64,
+) -> Result<Vec<String>, DcCmdError> {
+    let users = get_all_room_users(dracoon, room_id).await?;
+
+    Ok(users
+        .into_iter()
+        .filter(|user| user.is_granted)
+        .map(|user| {
+            let user_name = user
+                .user_info
+                .user_name
+                .unwrap_or_else(|| user.user_info.id.to_string());
+
+            match user.permissions {
+                Some(permissions) => format!("{user_name} ({permissions})"),
+                None => user_name,
+            }
+        })
+        .collect())
+}
+
+/// returns all granted room groups with their permissions
+async fn get_room_group_permissions(
+    dracoon: &Dracoon<Connected>,
+    room_id: u64,
+) -> Result<Vec<String>, DcCmdError> {
+    let groups = get_all_room_groups(dracoon, room_id).await?;
+
+    Ok(groups
+        .into_iter()
+        .filter(|group| group.is_granted)
+        .map(|group| match group.permissions {
+            Some(permissions) => format!("{} ({permissions})", group.name),
+            None => group.name,
+        })
+        .collect())
+}
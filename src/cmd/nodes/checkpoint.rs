@@ -0,0 +1,258 @@
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+
+use crate::cmd::models::DcCmdError;
+
+use super::transfer_errors::TransferErrors;
+
+/// progress of an incomplete recursive transfer (written to the working directory and
+/// removed once all files are transferred)
+pub const TRANSFER_CHECKPOINT_FILE: &str = ".transfer-checkpoint.jsonl";
+
+/// transfer of a checkpoint (first line - must match on resume)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TransferCheckpointHeader {
+    operation: String,
+    source: String,
+    target: String,
+}
+
+/// completed files of a recursive upload / download (see `--resume`)
+///
+/// each completed file is appended as a line immediately - the checkpoint is therefore
+/// kept if the transfer is cancelled (Ctrl+C) or files fail
+#[derive(Clone)]
+pub struct TransferCheckpoint {
+    path: PathBuf,
+    completed: Arc<HashSet<String>>,
+    file: Arc<Mutex<File>>,
+}
+
+impl TransferCheckpoint {
+    /// opens the checkpoint of a transfer - fails if an interrupted transfer exists
+    /// without `resume` or if there is none to resume
+    pub fn open(
+        operation: &str,
+        source: &str,
+        target: &str,
+        resume: bool,
+    ) -> Result<Self, DcCmdError> {
+        let path = std::env::current_dir()
+            .map(|dir| dir.join(TRANSFER_CHECKPOINT_FILE))
+            .unwrap_or_else(|_| PathBuf::from(TRANSFER_CHECKPOINT_FILE));
+
+        let header = TransferCheckpointHeader {
+            operation: operation.to_string(),
+            source: source.to_string(),
+            target: target.to_string(),
+        };
+
+        Self::open_at(path, &header, resume)
+    }
+
+    fn open_at(
+        path: PathBuf,
+        header: &TransferCheckpointHeader,
+        resume: bool,
+    ) -> Result<Self, DcCmdError> {
+        let completed = match (load_checkpoint(&path)?, resume) {
+            (Some((checkpoint, completed)), true) => {
+                if &checkpoint != header {
+                    return Err(DcCmdError::InvalidArgument(format!(
+                        "Interrupted transfer differs ({} of {} to {}).",
+                        checkpoint.operation, checkpoint.source, checkpoint.target
+                    )));
+                }
+
+                info!(
+                    "Resuming {} of {} ({} files transferred).",
+                    header.operation,
+                    header.source,
+                    completed.len()
+                );
+                completed
+            }
+            (None, true) => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "No interrupted transfer found ({}).",
+                    path.display()
+                )));
+            }
+            (Some(_), false) => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Interrupted transfer found - use --resume to continue (or remove {}).",
+                    path.display()
+                )));
+            }
+            (None, false) => {
+                let header = serde_json::to_string(header).map_err(|_| DcCmdError::IoError)?;
+                std::fs::write(&path, format!("{header}\n")).map_err(|e| {
+                    error!("Error writing {}: {}", path.display(), e);
+                    DcCmdError::IoError
+                })?;
+                HashSet::new()
+            }
+        };
+
+        let file = OpenOptions::new().append(true).open(&path).map_err(|e| {
+            error!("Error opening {}: {}", path.display(), e);
+            DcCmdError::IoError
+        })?;
+
+        Ok(Self {
+            path,
+            completed: Arc::new(completed),
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// checks if a file was transferred before the interruption
+    pub fn is_completed(&self, item: &str) -> bool {
+        self.completed.contains(item)
+    }
+
+    /// appends a transferred file (a failed write only loses the progress of this file)
+    pub fn complete(&self, item: &str) {
+        let Ok(line) = serde_json::to_string(item) else {
+            return;
+        };
+
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        if let Err(e) = file.write_all(format!("{line}\n").as_bytes()) {
+            warn!("Error writing {}: {}", self.path.display(), e);
+        }
+    }
+
+    /// checks if a (local) file is the checkpoint itself (excluded from uploads)
+    pub fn is_checkpoint_file(&self, path: &Path) -> bool {
+        path.file_name() == self.path.file_name()
+            && std::fs::canonicalize(path).ok() == std::fs::canonicalize(&self.path).ok()
+    }
+
+    /// removes the checkpoint if all files were transferred - failed files are
+    /// retried with `--resume`
+    pub fn finish(self, errors: &TransferErrors) {
+        if !errors.is_empty() {
+            info!(
+                "Kept {} - use --resume to retry failed files.",
+                self.path.display()
+            );
+            return;
+        }
+
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            warn!("Error removing {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// loads the checkpoint of an interrupted transfer (None if there is none)
+///
+/// unreadable lines (e.g. a line cut off by a crash) are skipped
+fn load_checkpoint(
+    path: &Path,
+) -> Result<Option<(TransferCheckpointHeader, HashSet<String>)>, DcCmdError> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        debug!("No transfer checkpoint found at {}", path.display());
+        return Ok(None);
+    };
+
+    let mut lines = content.lines();
+
+    let header = lines
+        .next()
+        .and_then(|line| serde_json::from_str(line).ok())
+        .ok_or_else(|| {
+            error!("Invalid transfer checkpoint {}", path.display());
+            DcCmdError::InvalidArgument(format!("Invalid checkpoint file: {}", path.display()))
+        })?;
+
+    let completed = lines
+        .filter_map(|line| {
+            serde_json::from_str::<String>(line)
+                .inspect_err(|_| warn!("Skipping invalid checkpoint line: {}", line))
+                .ok()
+        })
+        .collect();
+
+    Ok(Some((header, completed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let dir = std::env::temp_dir().join("dccmd-rs-test-transfer-checkpoint");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TRANSFER_CHECKPOINT_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        let header = TransferCheckpointHeader {
+            operation: "download".to_string(),
+            source: "/room".to_string(),
+            target: "/tmp/room".to_string(),
+        };
+
+        assert!(TransferCheckpoint::open_at(path.clone(), &header, true).is_err());
+
+        let checkpoint = TransferCheckpoint::open_at(path.clone(), &header, false).unwrap();
+        checkpoint.complete("/room/a.txt");
+        checkpoint.complete("/room/folder/b\nc.txt");
+        drop(checkpoint);
+
+        // interrupted transfer requires --resume
+        assert!(TransferCheckpoint::open_at(path.clone(), &header, false).is_err());
+
+        let other = TransferCheckpointHeader {
+            source: "/other".to_string(),
+            ..header
+        };
+        assert!(TransferCheckpoint::open_at(path.clone(), &other, true).is_err());
+
+        let header = TransferCheckpointHeader {
+            source: "/room".to_string(),
+            ..other
+        };
+        let checkpoint = TransferCheckpoint::open_at(path.clone(), &header, true).unwrap();
+        assert!(checkpoint.is_completed("/room/a.txt"));
+        assert!(checkpoint.is_completed("/room/folder/b\nc.txt"));
+        assert!(!checkpoint.is_completed("/room/d.txt"));
+        assert!(checkpoint.is_checkpoint_file(&path));
+
+        checkpoint.finish(&TransferErrors::default());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_load_checkpoint_skips_invalid_lines() {
+        let dir = std::env::temp_dir().join("dccmd-rs-test-transfer-checkpoint-invalid");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(TRANSFER_CHECKPOINT_FILE);
+
+        std::fs::write(
+            &path,
+            "{\"operation\":\"upload\",\"source\":\"/src\",\"target\":\"/room\"}\n\"/src/a.txt\"\n\"/src/b.t",
+        )
+        .unwrap();
+
+        let (header, completed) = load_checkpoint(&path).unwrap().unwrap();
+        assert_eq!(header.operation, "upload");
+        assert_eq!(completed, HashSet::from(["/src/a.txt".to_string()]));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
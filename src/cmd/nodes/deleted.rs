@@ -0,0 +1,374 @@
+use chrono::DateTime;
+use console::Term;
+use dco3::{auth::Connected, nodes::NodeType, Dracoon, RangedItems};
+use dialoguer::Confirm;
+use futures_util::{stream, StreamExt, TryStreamExt};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::cmd::{
+    branding::instance_prompt,
+    config::MAX_CONCURRENT_REQUESTS,
+    journal::{Journal, JournalEntry, JournalOperation},
+    models::DcCmdError,
+    utils::{
+        api::{api_request, send_api_json, send_api_request},
+        confirm::assume_yes,
+        pagination::{fetch_all_paginated, PAGE_SIZE},
+        strings::{format_success_message, matches_glob, to_readable_size},
+    },
+};
+
+/// deleted nodes (recycle bin) are not provided by dco3 and requested directly
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeletedNodeSummary {
+    name: String,
+    #[serde(rename = "type")]
+    node_type: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeletedNode {
+    id: u64,
+    name: String,
+    size: Option<u64>,
+    deleted_at: Option<String>,
+    deleted_by: Option<DeletedByUser>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeletedByUser {
+    first_name: Option<String>,
+    last_name: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeleteDeletedNodesRequest {
+    deleted_node_ids: Vec<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreDeletedNodesRequest {
+    deleted_node_ids: Vec<u64>,
+    resolution_strategy: &'static str,
+}
+
+/// prints all deleted nodes (recycle bin) of a parent with deletion date and deleted by
+pub async fn print_deleted_nodes(
+    term: &Term,
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    human_readable: bool,
+) -> Result<(), DcCmdError> {
+    let summaries = get_deleted_node_summaries(dracoon, parent_id).await?;
+
+    let deleted_nodes = stream::iter(summaries)
+        .map(|summary| get_deleted_node_versions(dracoon, parent_id, summary))
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    for node in &deleted_nodes {
+        term.write_line(&format_deleted_node(node, human_readable))
+            .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    if deleted_nodes.is_empty() {
+        term.write_line("No deleted nodes found.")
+            .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(())
+}
+
+/// permanently deletes all deleted versions (recycle bin) of a parent matching a name pattern
+pub async fn purge_deleted_nodes(
+    term: &Term,
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    parent_path: &str,
+    pattern: &str,
+    journal: &Journal,
+) -> Result<(), DcCmdError> {
+    let summaries = get_deleted_node_summaries(dracoon, parent_id)
+        .await?
+        .into_iter()
+        .filter(|summary| matches_glob(&summary.name, pattern));
+
+    let deleted_nodes = stream::iter(summaries)
+        .map(|summary| get_deleted_node_versions(dracoon, parent_id, summary))
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    if deleted_nodes.is_empty() {
+        term.write_line("No deleted versions to purge.")
+            .map_err(|_| DcCmdError::IoError)?;
+        return Ok(());
+    }
+
+    let entries = deleted_nodes
+        .iter()
+        .map(|node| {
+            JournalEntry::new(
+                JournalOperation::PurgeDeletedNode,
+                node.id,
+                format!("{parent_path}{}", node.name),
+            )
+            .with_parent_id(Some(parent_id))
+        })
+        .collect::<Vec<_>>();
+
+    if journal.is_dry_run() {
+        return journal.print_dry_run(term, &entries);
+    }
+
+    let size = to_readable_size(
+        deleted_nodes
+            .iter()
+            .map(|node| node.size.unwrap_or(0))
+            .sum(),
+    );
+
+    let confirmed = assume_yes()
+        || Confirm::new()
+            .with_prompt(instance_prompt(
+                dracoon.get_base_url().as_str(),
+                &format!(
+                    "Do you really want to permanently delete {} deleted versions ({size})?",
+                    deleted_nodes.len()
+                ),
+            ))
+            .interact()
+            .map_err(|_| DcCmdError::IoError)?;
+
+    if !confirmed {
+        return Ok(());
+    }
+
+    let node_ids = deleted_nodes.iter().map(|node| node.id).collect::<Vec<_>>();
+    delete_deleted_nodes(dracoon, node_ids).await?;
+    journal.record(&entries)?;
+
+    let msg = format!(
+        "{} deleted versions purged ({size} reclaimed).",
+        deleted_nodes.len()
+    );
+    info!("{}", msg);
+    term.write_line(&format_success_message(&msg))
+        .map_err(|_| DcCmdError::IoError)
+}
+
+/// returns the id of the most recently deleted version of a node in the recycle bin
+pub async fn find_deleted_node_id(
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    name: &str,
+    node_type: &NodeType,
+) -> Result<Option<u64>, DcCmdError> {
+    let summary = DeletedNodeSummary {
+        name: name.to_string(),
+        node_type: deleted_node_type(node_type).to_string(),
+    };
+
+    let versions = get_deleted_node_versions(dracoon, parent_id, summary).await?;
+
+    Ok(latest_deleted_node(&versions).map(|node| node.id))
+}
+
+/// restores deleted nodes (versions) from the recycle bin to their parent
+/// (renamed if a node with the same name exists)
+pub async fn restore_deleted_nodes(
+    dracoon: &Dracoon<Connected>,
+    deleted_node_ids: Vec<u64>,
+) -> Result<(), DcCmdError> {
+    let request = api_request(dracoon, Method::POST, "nodes/deleted_nodes/actions/restore")
+        .await?
+        .json(&RestoreDeletedNodesRequest {
+            deleted_node_ids,
+            resolution_strategy: "autorename",
+        });
+
+    send_api_request(request).await?;
+
+    Ok(())
+}
+
+fn deleted_node_type(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Room => "room",
+        NodeType::Folder => "folder",
+        NodeType::File => "file",
+    }
+}
+
+fn latest_deleted_node(versions: &[DeletedNode]) -> Option<&DeletedNode> {
+    versions.iter().max_by_key(|node| {
+        node.deleted_at
+            .as_deref()
+            .and_then(|deleted_at| DateTime::parse_from_rfc3339(deleted_at).ok())
+    })
+}
+
+fn format_deleted_node(node: &DeletedNode, human_readable: bool) -> String {
+    let deleted_at = node
+        .deleted_at
+        .as_deref()
+        .and_then(|deleted_at| DateTime::parse_from_rfc3339(deleted_at).ok())
+        .map_or_else(
+            || "n/a".to_string(),
+            |deleted_at| deleted_at.format("%Y %b %e %H:%M").to_string(),
+        );
+
+    let deleted_by = node.deleted_by.as_ref().map_or_else(
+        || "n/a".to_string(),
+        |user| {
+            format!(
+                "{} {}",
+                user.first_name.as_deref().unwrap_or("n/a"),
+                user.last_name.as_deref().unwrap_or("n/a")
+            )
+        },
+    );
+
+    let size = if human_readable {
+        to_readable_size(node.size.unwrap_or(0))
+    } else {
+        node.size.unwrap_or(0).to_string()
+    };
+
+    format!(
+        "{:<12} {:<16} {:<31} {:<16} {}",
+        node.id, deleted_at, deleted_by, size, node.name
+    )
+}
+
+/// returns one summary per deleted node name (with number of versions)
+async fn get_deleted_node_summaries(
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+) -> Result<Vec<DeletedNodeSummary>, DcCmdError> {
+    let summaries = get_deleted_nodes_page(dracoon, parent_id, 0).await?;
+    let client = dracoon.clone();
+
+    let summaries = fetch_all_paginated(summaries, move |offset| {
+        let client = client.clone();
+
+        async move { get_deleted_nodes_page(&client, parent_id, offset).await }
+    })
+    .await?;
+
+    Ok(summaries.items)
+}
+
+/// returns all deleted versions of a node (summary)
+async fn get_deleted_node_versions(
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    summary: DeletedNodeSummary,
+) -> Result<Vec<DeletedNode>, DcCmdError> {
+    let versions = get_deleted_node_versions_page(dracoon, parent_id, &summary, 0).await?;
+    let client = dracoon.clone();
+
+    let versions = fetch_all_paginated(versions, move |offset| {
+        let client = client.clone();
+        let summary = summary.clone();
+
+        async move { get_deleted_node_versions_page(&client, parent_id, &summary, offset).await }
+    })
+    .await?;
+
+    Ok(versions.items)
+}
+
+async fn get_deleted_nodes_page(
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    offset: u64,
+) -> Result<RangedItems<DeletedNodeSummary>, DcCmdError> {
+    let request = api_request(
+        dracoon,
+        Method::GET,
+        &format!("nodes/{parent_id}/deleted_nodes"),
+    )
+    .await?
+    .query(&[("offset", offset), ("limit", PAGE_SIZE)]);
+
+    send_api_json(request).await
+}
+
+async fn get_deleted_node_versions_page(
+    dracoon: &Dracoon<Connected>,
+    parent_id: u64,
+    summary: &DeletedNodeSummary,
+    offset: u64,
+) -> Result<RangedItems<DeletedNode>, DcCmdError> {
+    let request = api_request(
+        dracoon,
+        Method::GET,
+        &format!("nodes/{parent_id}/deleted_nodes/versions"),
+    )
+    .await?
+    .query(&[("type", summary.node_type.as_str()), ("name", summary.name.as_str())])
+    .query(&[("offset", offset), ("limit", PAGE_SIZE)]);
+
+    send_api_json(request).await
+}
+
+/// permanently deletes deleted nodes (versions) from the recycle bin
+async fn delete_deleted_nodes(
+    dracoon: &Dracoon<Connected>,
+    deleted_node_ids: Vec<u64>,
+) -> Result<(), DcCmdError> {
+    let request = api_request(dracoon, Method::DELETE, "nodes/deleted_nodes")
+        .await?
+        .json(&DeleteDeletedNodesRequest { deleted_node_ids });
+
+    send_api_request(request).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_deleted_node() {
+        let json = r#"[
+            {"id": 1, "name": "file.pdf", "deletedAt": "2024-01-01T10:00:00Z"},
+            {"id": 3, "name": "file.pdf", "deletedAt": "2024-03-01T10:00:00+01:00"},
+            {"id": 2, "name": "file.pdf"}
+        ]"#;
+
+        let versions = serde_json::from_str::<Vec<DeletedNode>>(json).unwrap();
+
+        assert_eq!(latest_deleted_node(&versions).map(|node| node.id), Some(3));
+        assert!(latest_deleted_node(&[]).is_none());
+    }
+
+    #[test]
+    fn test_restore_deleted_nodes_request() {
+        let req = RestoreDeletedNodesRequest {
+            deleted_node_ids: vec![1, 2],
+            resolution_strategy: "autorename",
+        };
+
+        let json = serde_json::to_value(&req).unwrap();
+
+        assert_eq!(json["deletedNodeIds"], serde_json::json!([1, 2]));
+        assert_eq!(json["resolutionStrategy"], "autorename");
+    }
+}
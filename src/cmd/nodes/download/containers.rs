@@ -1,17 +1,31 @@
-use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::Path, time::Duration};
 
 use dco3::{
     auth::Connected,
     nodes::{Node, NodeType, NodesSearchFilter, NodesSearchSortBy},
     Dracoon, ListAllParams, Nodes, SortOrder,
 };
-use indicatif::ProgressBar;
 use tracing::{debug, error, info};
 
 use crate::cmd::{
-    config::MAX_CONCURRENT_REQUESTS,
     models::DcCmdError,
-    nodes::download::files::{download_files, get_files},
+    nodes::{
+        checkpoint::TransferCheckpoint,
+        download::{
+            check_free_space, check_invalid_names,
+            files::{download_files, get_files},
+            flat_targets, print_renamed_nodes,
+        },
+        models::CmdDownloadOptions,
+        to_node_path,
+        transfer_errors::TransferErrors,
+    },
+    utils::{
+        confirm::confirm_large_operation,
+        pagination::fetch_all_paginated,
+        progress::spinner,
+        strings::{sanitize_file_name, sanitize_path},
+    },
 };
 
 fn create_folders(
@@ -36,8 +50,8 @@ fn create_folders(
             .trim_start_matches(format!("{}/", parent_node.name).as_str())
             .to_string();
         debug!("Folder base path: {}", folder_base_path);
-        let curr_target = curr_target.join(folder_base_path);
-        let curr_target = curr_target.join(folder.name);
+        let curr_target = curr_target.join(sanitize_path(&folder_base_path));
+        let curr_target = curr_target.join(sanitize_file_name(&folder.name));
 
         std::fs::create_dir_all(&curr_target).map_err(|_| {
             error!("Error creating directory: {:?}", curr_target);
@@ -86,7 +100,7 @@ async fn filter_files_in_sub_rooms(
         .collect::<Vec<_>>())
 }
 
-async fn get_containers(
+pub async fn get_containers(
     dracoon: &Dracoon<Connected>,
     parent_node: &Node,
     include_rooms: bool,
@@ -102,68 +116,32 @@ async fn get_containers(
         .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
         .build();
 
-    let mut folders = dracoon
+    let folders = dracoon
         .nodes()
         .search_nodes("*", Some(parent_node.id), Some(-1), Some(params))
         .await?;
 
-    if folders.range.total > 500 {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-        let mut handles = Vec::new();
-
-        (500..=folders.range.total).step_by(500).for_each(|offset| {
-            let tx = tx.clone();
-            let dracoon_client = dracoon.clone();
-            let parent_node = parent_node.clone();
-            let filter = filter.clone();
-            let semaphore = semaphore.clone();
-            let handle = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.map_err(|_| {
-                    error!("Error acquiring semaphore permit");
-                    DcCmdError::IoError
-                })?;
-                let params = ListAllParams::builder()
-                    .with_filter(filter)
-                    .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
-                    .with_offset(offset)
-                    .build();
-
-                match dracoon_client
-                    .nodes()
-                    .search_nodes("*", Some(parent_node.id), Some(-1), Some(params))
-                    .await
-                {
-                    Ok(batch_items) => {
-                        if let Err(e) = tx.send(batch_items.items).await {
-                            error!("Error sending folders: {}", e);
-                            return Err(DcCmdError::IoError);
-                        }
-
-                        Ok(())
-                    }
-                    Err(e) => {
-                        error!("Error getting folders: {}", e);
-                        Err(e.into())
-                    }
-                }
-            });
-            handles.push(handle);
-        });
-
-        drop(tx);
-
-        while let Some(next_folders) = rx.recv().await {
-            folders.items.extend(next_folders);
-        }
+    let dracoon_client = dracoon.clone();
+    let parent_id = parent_node.id;
+
+    let folders = fetch_all_paginated(folders, move |offset| {
+        let dracoon_client = dracoon_client.clone();
+        let params = ListAllParams::builder()
+            .with_filter(filter.clone())
+            .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+            .with_offset(offset)
+            .build();
+
+        async move {
+            let folders = dracoon_client
+                .nodes()
+                .search_nodes("*", Some(parent_id), Some(-1), Some(params))
+                .await?;
 
-        for handle in handles {
-            if let Err(e) = handle.await {
-                error!("Error getting folders: {}", e);
-                return Err(DcCmdError::IoError);
-            }
+            Ok::<_, DcCmdError>(folders)
         }
-    }
+    })
+    .await?;
 
     let node_filter = if include_rooms {
         |node: &Node| node.node_type == NodeType::Folder || node.node_type == NodeType::Room
@@ -179,27 +157,136 @@ async fn get_containers(
         .collect())
 }
 
+/// only keeps files passing the include / exclude filters and the folders containing them
+fn filter_nodes(
+    download_opts: &CmdDownloadOptions,
+    folders: Vec<Node>,
+    files: Vec<Node>,
+) -> (Vec<Node>, Vec<Node>) {
+    let files = files
+        .into_iter()
+        .filter(|file| download_opts.is_included(&file.name))
+        .collect::<Vec<_>>();
+
+    debug!("Files after filtering: {}", files.len());
+
+    let folders = folders
+        .into_iter()
+        .filter(|folder| {
+            let folder_path = format!("{}/", to_node_path(folder));
+            files.iter().any(|file| {
+                file.parent_path
+                    .as_ref()
+                    .is_some_and(|path| path.starts_with(&folder_path))
+            })
+        })
+        .collect();
+
+    (folders, files)
+}
+
 pub async fn download_container(
     dracoon: &Dracoon<Connected>,
     node: &Node,
     target: &str,
-    velocity: Option<u8>,
-    include_rooms: bool,
+    download_opts: &CmdDownloadOptions,
+    min_free_space: Option<u64>,
 ) -> Result<(), DcCmdError> {
+    let include_rooms = download_opts.include_rooms;
     info!("Attempting download of container {}.", node.name);
     info!("Target: {}", target);
 
     // indicate listing files and folders
-    let progress_spinner = ProgressBar::new_spinner();
+    let progress_spinner = spinner();
     progress_spinner.set_message("Listing files and folders...");
     progress_spinner.enable_steady_tick(Duration::from_millis(100));
 
     // first get all folders below parent
     let folders = get_containers(dracoon, node, include_rooms).await?;
 
+    // get all files
+    let files = get_files(dracoon, node).await?;
+
+    // remove files in sub rooms
+    let files = if include_rooms {
+        files
+    } else {
+        filter_files_in_sub_rooms(dracoon, node, files).await?
+    };
+
+    // apply include / exclude filters (empty folders are skipped)
+    let (folders, files) = if download_opts.has_filters() {
+        filter_nodes(download_opts, folders, files)
+    } else {
+        (folders, files)
+    };
+
+    // check names before creating anything to avoid aborting midway (no folders if flat)
+    let containers = if download_opts.flat {
+        Vec::new()
+    } else {
+        std::iter::once(node).chain(&folders).collect()
+    };
+    let nodes = containers.into_iter().chain(&files);
+    let renamed = match check_invalid_names(nodes, download_opts.rename_invalid) {
+        Ok(renamed) => renamed,
+        Err(err) => {
+            progress_spinner.finish_and_clear();
+            return Err(err);
+        }
+    };
+
+    // summary is printed without spinner
+    progress_spinner.finish_and_clear();
+
+    // completed files are stored to resume interrupted downloads
+    let checkpoint = TransferCheckpoint::open(
+        "download",
+        &to_node_path(node),
+        target,
+        download_opts.resume,
+    )?;
+
+    // files downloaded before an interruption are already on target
+    let total_size = files
+        .iter()
+        .filter(|file| !checkpoint.is_completed(&to_node_path(file)))
+        .filter_map(|file| file.size)
+        .sum::<u64>();
+
+    if !confirm_large_operation(
+        dracoon.get_base_url().as_str(),
+        "Download",
+        files.len() as u64,
+        total_size,
+        download_opts.assume_yes,
+    )? {
+        return Err(DcCmdError::InvalidArgument(
+            "Download not confirmed.".to_string(),
+        ));
+    }
+
+    // fail early instead of running out of space mid-transfer
+    if let Some(min_free_space) = min_free_space {
+        check_free_space(Path::new(target), total_size, min_free_space)?;
+    }
+
+    if download_opts.flat {
+        return download_flat(
+            dracoon,
+            node,
+            target,
+            files,
+            download_opts,
+            &renamed,
+            checkpoint,
+        )
+        .await;
+    }
+
     // create root directory on target
     let target = std::path::PathBuf::from(target);
-    let target = target.clone().join(&node.name);
+    let target = target.clone().join(sanitize_file_name(&node.name));
     std::fs::create_dir_all(&target).or(Err(DcCmdError::IoError))?;
 
     let base_path = node
@@ -214,18 +301,6 @@ pub async fn download_container(
     // create all sub folders
     create_folders(&target, node, &base_path, folders)?;
 
-    // get all files
-    let files = get_files(dracoon, node).await?;
-
-    // remove files in sub rooms
-    let files = if include_rooms {
-        files
-    } else {
-        filter_files_in_sub_rooms(dracoon, node, files).await?
-    };
-
-    progress_spinner.finish_and_clear();
-
     // download all files
     let mut targets = HashMap::new();
 
@@ -242,8 +317,8 @@ pub async fn download_container(
         let parent = format!("/{}", node.name.clone());
         let file_base_path = file_base_path.trim_start_matches(&parent);
         let file_base_path = file_base_path.trim_start_matches('/');
-        let file_target = file_target.join(file_base_path);
-        let target = file_target.join(&file.name);
+        let file_target = file_target.join(sanitize_path(file_base_path));
+        let target = file_target.join(sanitize_file_name(&file.name));
 
         targets.insert(
             file.id,
@@ -257,6 +332,8 @@ pub async fn download_container(
         );
     }
 
+    let errors = TransferErrors::default();
+
     download_files(
         dracoon,
         files,
@@ -265,11 +342,50 @@ pub async fn download_container(
             target.display()
         )))?,
         Some(targets),
-        velocity,
+        download_opts.velocity,
+        &errors,
+        Some(&checkpoint),
     )
     .await?;
 
     info!("Download of container {} complete.", node.name);
 
-    Ok(())
+    checkpoint.finish(&errors);
+    print_renamed_nodes(&renamed)?;
+
+    errors.report(download_opts.continue_on_error)
+}
+
+/// downloads all files of a container directly into target (`--flat`)
+async fn download_flat(
+    dracoon: &Dracoon<Connected>,
+    node: &Node,
+    target: &str,
+    files: Vec<Node>,
+    download_opts: &CmdDownloadOptions,
+    renamed: &HashMap<String, String>,
+    checkpoint: TransferCheckpoint,
+) -> Result<(), DcCmdError> {
+    std::fs::create_dir_all(target).or(Err(DcCmdError::IoError))?;
+
+    let targets = flat_targets(&files, Path::new(target))?;
+    let errors = TransferErrors::default();
+
+    download_files(
+        dracoon,
+        files,
+        target,
+        Some(targets),
+        download_opts.velocity,
+        &errors,
+        Some(&checkpoint),
+    )
+    .await?;
+
+    info!("Download of container {} complete (flat).", node.name);
+
+    checkpoint.finish(&errors);
+    print_renamed_nodes(renamed)?;
+
+    errors.report(download_opts.continue_on_error)
 }
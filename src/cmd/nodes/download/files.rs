@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    path::Path,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
@@ -11,14 +12,25 @@ use dco3::{
     nodes::{Node, NodesSearchFilter, NodesSearchSortBy},
     Download, Dracoon, ListAllParams, Nodes, Public, PublicDownload, SortOrder,
 };
-use indicatif::{ProgressBar, ProgressStyle};
-use tracing::{debug, error, info, warn};
+use indicatif::ProgressStyle;
+use tracing::{debug, error, info};
 
+use super::ranged::{download_ranges, MIN_RANGED_DOWNLOAD_SIZE};
 use crate::cmd::{
-    config::{DEFAULT_CONCURRENT_MULTIPLIER, MAX_CONCURRENT_REQUESTS, MAX_VELOCITY, MIN_VELOCITY},
+    config::{DEFAULT_CONCURRENT_MULTIPLIER, MAX_VELOCITY, MIN_VELOCITY},
     init_public_dracoon,
     models::DcCmdError,
-    nodes::models::CmdDownloadOptions,
+    nodes::{
+        checkpoint::TransferCheckpoint, models::CmdDownloadOptions, to_node_path,
+        transfer_errors::TransferErrors,
+    },
+    utils::{
+        cancel::{mark_file_complete, track_incomplete_file},
+        deadline::check_deadline,
+        pagination::fetch_all_paginated,
+        progress::progress_bar,
+        strings::sanitize_file_name,
+    },
 };
 
 pub async fn get_files(
@@ -30,78 +42,32 @@ pub async fn get_files(
         .with_sort(NodesSearchSortBy::size(SortOrder::Desc))
         .build();
 
-    let mut files = dracoon
+    let files = dracoon
         .nodes()
         .search_nodes("*", Some(parent_node.id), Some(-1), Some(params))
         .await?;
 
-    if files.range.total > 500 {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-        let mut handles = Vec::new();
-
-        (500..=files.range.total).step_by(500).for_each(|offset| {
-            let dracoon_client = dracoon.clone();
-            let parent_node = parent_node.clone();
-            let tx = tx.clone();
-            let semaphore = semaphore.clone();
-            let handle = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.map_err(|_| {
-                    error!("Error acquiring semaphore permit");
-                    DcCmdError::IoError
-                })?;
-                let params = ListAllParams::builder()
-                    .with_filter(NodesSearchFilter::is_file())
-                    .with_sort(NodesSearchSortBy::size(SortOrder::Desc))
-                    .with_offset(offset)
-                    .build();
-
-                match dracoon_client
-                    .nodes()
-                    .search_nodes("*", Some(parent_node.id), Some(-1), Some(params))
-                    .await
-                {
-                    Ok(batch_items) => {
-                        if let Err(e) = tx.send(batch_items.items).await {
-                            error!("Error processing files: {}", e);
-                            return Err(DcCmdError::IoError);
-                        }
-                    }
-                    Err(e) => {
-                        error!("Error getting files: {}", e);
-                        return Err(e.into());
-                    }
-                }
-
-                Ok(())
-            });
-
-            handles.push(handle);
-        });
-
-        drop(tx);
-
-        while let Some(next_files) = rx.recv().await {
-            files.items.extend(next_files);
-        }
-
-        for handle in handles {
-            if let Err(e) = handle.await {
-                error!("Error getting files: {}", e);
-                return Err(DcCmdError::IoError);
-            }
+    let dracoon = dracoon.clone();
+    let parent_id = parent_node.id;
+
+    let files = fetch_all_paginated(files, move |offset| {
+        let dracoon = dracoon.clone();
+        let params = ListAllParams::builder()
+            .with_filter(NodesSearchFilter::is_file())
+            .with_sort(NodesSearchSortBy::size(SortOrder::Desc))
+            .with_offset(offset)
+            .build();
+
+        async move {
+            let files = dracoon
+                .nodes()
+                .search_nodes("*", Some(parent_id), Some(-1), Some(params))
+                .await?;
+
+            Ok::<_, DcCmdError>(files)
         }
-    }
-
-    let actual_count = files.items.len() as u64;
-    if files.range.total != actual_count {
-        warn!(
-            "Total file count mismatch - expected: {}, actual: {}, difference: {} (check error logs)", 
-            files.range.total,
-            actual_count,
-            files.range.total - actual_count
-        );
-    }
+    })
+    .await?;
 
     Ok(files.get_files())
 }
@@ -119,7 +85,7 @@ pub async fn download_public_file(
 
     let access_key = source
         .split('/')
-        .last()
+        .next_back()
         .ok_or(DcCmdError::InvalidPath(source.clone()))?;
 
     let dracoon = init_public_dracoon(&source).await?;
@@ -146,11 +112,12 @@ pub async fn download_public_file(
         target.to_string()
     };
 
-    let mut out_file = tokio::fs::File::create(target)
+    let mut out_file = tokio::fs::File::create(&target)
         .await
         .or(Err(DcCmdError::IoError))?;
+    track_incomplete_file(&target);
 
-    let progress_bar = ProgressBar::new(public_download_share.size);
+    let progress_bar = progress_bar(public_download_share.size);
     progress_bar.set_style(
     ProgressStyle::default_bar()
     .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -176,6 +143,7 @@ pub async fn download_public_file(
         )
         .await?;
 
+    mark_file_complete(Path::new(&target));
     progress_bar.finish_with_message(format!("{file_name} complete"));
 
     info!("Download of public file {file_name} complete.");
@@ -196,7 +164,7 @@ pub async fn download_file(
     // if own name provided - use it - otherwise use node name
     let target = if std::path::Path::new(target).is_dir() {
         let path = std::path::Path::new(target);
-        let target = path.join(node.name.clone());
+        let target = path.join(sanitize_file_name(&node.name));
 
         let Some(target) = target.to_str() else {
             return Err(DcCmdError::InvalidPath(original_target));
@@ -207,11 +175,12 @@ pub async fn download_file(
         target.to_string()
     };
 
-    let mut out_file = tokio::fs::File::create(target)
+    let mut out_file = tokio::fs::File::create(&target)
         .await
         .or(Err(DcCmdError::IoError))?;
+    track_incomplete_file(&target);
 
-    let progress_bar = ProgressBar::new(node.size.unwrap_or(0));
+    let progress_bar = progress_bar(node.size.unwrap_or(0));
     progress_bar.set_style(
         ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -222,19 +191,36 @@ pub async fn download_file(
     let node_name = node.name.clone();
     progress_bar.set_message(node_name.clone());
 
-    let progress_bar_mv = progress_bar.clone();
+    // large unencrypted files are downloaded in parallel ranges
+    let is_ranged =
+        if node.is_encrypted != Some(true) && node.size.unwrap_or(0) >= MIN_RANGED_DOWNLOAD_SIZE {
+            let file = out_file
+                .try_clone()
+                .await
+                .map_err(|_| DcCmdError::IoError)?
+                .into_std()
+                .await;
+            download_ranges(dracoon, node, &file, &progress_bar).await?
+        } else {
+            false
+        };
 
-    dracoon
-        .download(
-            node,
-            &mut out_file,
-            Some(Box::new(move |progress, _| {
-                progress_bar_mv.inc(progress);
-            })),
-            None,
-        )
-        .await?;
+    if !is_ranged {
+        let progress_bar_mv = progress_bar.clone();
+
+        dracoon
+            .download(
+                node,
+                &mut out_file,
+                Some(Box::new(move |progress, _| {
+                    progress_bar_mv.inc(progress);
+                })),
+                None,
+            )
+            .await?;
+    }
 
+    mark_file_complete(Path::new(&target));
     progress_bar.finish_with_message(format!("{node_name} complete"));
 
     info!("Download of node {} complete.", node_name.clone());
@@ -248,7 +234,18 @@ pub async fn download_files(
     target: &str,
     targets: Option<HashMap<u64, String>>,
     velocity: Option<u8>,
+    errors: &TransferErrors,
+    checkpoint: Option<&TransferCheckpoint>,
 ) -> Result<(), DcCmdError> {
+    // files downloaded before an interruption are skipped
+    let files = match checkpoint {
+        Some(checkpoint) => files
+            .into_iter()
+            .filter(|file| !checkpoint.is_completed(&to_node_path(file)))
+            .collect::<Vec<_>>(),
+        None => files,
+    };
+
     info!("Attempting download of {} files.", files.len());
     info!("Target: {}", target);
 
@@ -264,7 +261,7 @@ pub async fn download_files(
     let total_size = files.iter().map(|node| node.size.unwrap_or(0)).sum();
     let file_count = files.len();
 
-    let progress_bar = ProgressBar::new(total_size);
+    let progress_bar = progress_bar(total_size);
     progress_bar.set_style(
         ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -278,6 +275,7 @@ pub async fn download_files(
     let mut handles = Vec::new();
 
     for file in files {
+        let node_path = to_node_path(&file);
         let dracoon_client = dracoon.clone();
         let target = target.to_string();
         debug!("Target: {}", target);
@@ -287,23 +285,26 @@ pub async fn download_files(
         let progress_bar_inc = progress_bar.clone();
         let rm_files = remaining_files.clone();
         let semaphore = semaphore.clone();
+        let checkpoint = checkpoint.cloned();
         let download_task = async move {
             let _permit = semaphore.acquire().await.map_err(|_| {
                 error!("Error acquiring semaphore permit");
                 DcCmdError::IoError
             })?;
+            check_deadline()?;
 
             let target = if let Some(targets) = targets {
                 let target = targets.get(&file.id).expect("Target not found").clone();
                 std::path::PathBuf::from(target)
             } else {
                 let target = std::path::PathBuf::from(target);
-                target.join(&file.name)
+                target.join(sanitize_file_name(&file.name))
             };
 
             let mut out_file = tokio::fs::File::create(&target)
                 .await
                 .or(Err(DcCmdError::IoError))?;
+            track_incomplete_file(&target);
 
             let node_name = file.name.clone();
 
@@ -323,19 +324,27 @@ pub async fn download_files(
                     e
                 })?;
 
+            mark_file_complete(&target);
+            if let Some(checkpoint) = checkpoint {
+                checkpoint.complete(&to_node_path(&file));
+            }
             _ = &rm_files.fetch_sub(1, Ordering::Relaxed);
             let message = format!("Downloading {} files", &rm_files.load(Ordering::Relaxed));
             progress_bar_inc.set_message(message);
             Ok::<(), DcCmdError>(())
         };
 
-        handles.push(tokio::spawn(download_task));
+        handles.push((node_path, tokio::spawn(download_task)));
     }
 
-    for handle in handles {
-        if let Err(e) = handle.await {
-            error!("Error uploading file: {}", e);
-            return Err(DcCmdError::IoError);
+    for (node_path, handle) in handles {
+        match handle.await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => errors.push(node_path, &e),
+            Err(e) => {
+                error!("Error downloading file: {}", e);
+                errors.push(node_path, &DcCmdError::Unknown);
+            }
         }
     }
 
@@ -1,21 +1,41 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use console::Term;
 use containers::download_container;
 use files::{download_file, download_files, download_public_file};
+use rescue::grant_rescue_keys;
 
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::cmd::{
     init_dracoon, init_encryption,
     models::{DcCmdError, ListOptions},
     nodes::{is_search_query, search_nodes},
-    utils::strings::parse_path,
+    utils::{
+        confirm::confirm_large_operation,
+        file_types::file_type_filter,
+        strings::{
+            is_valid_file_name, parse_path, parse_size, sanitize_file_name, to_readable_size,
+        },
+    },
 };
 
-use dco3::nodes::{models::NodeType, Nodes};
+use dco3::nodes::{models::NodeType, Node, Nodes};
 
-use super::models::CmdDownloadOptions;
+use super::{
+    models::CmdDownloadOptions, resolve_node, to_node_path, transfer_errors::TransferErrors,
+};
 
 mod containers;
 mod files;
+mod ranged;
+mod rescue;
+
+pub use containers::get_containers;
+pub use files::get_files;
 
 pub async fn download(
     source: String,
@@ -30,7 +50,19 @@ pub async fn download(
         return download_public_file(source, target, download_opts).await;
     }
 
-    let mut dracoon = init_dracoon(&source, download_opts.auth, true).await?;
+    // free space to keep on target (None if check is disabled)
+    let min_free_space = if download_opts.no_space_check {
+        None
+    } else {
+        let min_free_space = download_opts
+            .min_free_space
+            .as_deref()
+            .map(parse_size)
+            .transpose()?;
+        Some(min_free_space.unwrap_or(0))
+    };
+
+    let mut dracoon = init_dracoon(&source, download_opts.auth.clone(), true).await?;
 
     let (parent_path, node_name, _) = parse_path(&source, dracoon.get_base_url().as_ref())
         .or(Err(DcCmdError::InvalidPath(source.clone())))?;
@@ -41,7 +73,7 @@ pub async fn download(
         debug!("Parent path {}", parent_path);
         dracoon.nodes().get_node_from_path(&parent_path).await?
     } else {
-        dracoon.nodes().get_node_from_path(&node_path).await?
+        resolve_node(&dracoon, &source, &node_path).await?
     };
 
     let Some(node) = node else {
@@ -50,36 +82,93 @@ pub async fn download(
     };
 
     if node.is_encrypted == Some(true) {
-        dracoon = init_encryption(dracoon, download_opts.encryption_password).await?;
+        dracoon = init_encryption(dracoon, download_opts.encryption_password.clone()).await?;
+
+        if let Some(rescue_secret) = download_opts.rescue_secret.as_deref() {
+            grant_rescue_keys(&dracoon, &node, rescue_secret).await?;
+        }
     }
 
     if is_search_query(&node_name) {
         info!("Attempting download of search query {}.", node_name);
+
+        // a single file type is filtered server-side
+        let filter = file_type_filter(&download_opts.file_types);
         let files = search_nodes(
             &dracoon,
             &node_name,
             Some(&parent_path),
-            &ListOptions::new(None, None, None, true, false),
+            &ListOptions::new(filter, None, None, true, false),
         )
         .await?;
-        let files = files.get_files();
+        let files = files
+            .get_files()
+            .into_iter()
+            .filter(|file| download_opts.is_included(&file.name))
+            .collect::<Vec<_>>();
 
         info!("Found {} files.", files.len());
 
-        download_files(&dracoon, files, &target, None, download_opts.velocity).await
+        let renamed = check_invalid_names(&files, download_opts.rename_invalid)?;
+
+        let total_size = files.iter().filter_map(|file| file.size).sum::<u64>();
+
+        if !confirm_large_operation(
+            dracoon.get_base_url().as_str(),
+            "Download",
+            files.len() as u64,
+            total_size,
+            download_opts.assume_yes,
+        )? {
+            return Err(DcCmdError::InvalidArgument(
+                "Download not confirmed.".to_string(),
+            ));
+        }
+
+        if let Some(min_free_space) = min_free_space {
+            check_free_space(Path::new(&target), total_size, min_free_space)?;
+        }
+
+        // search results are placed into target - flat only numbers duplicate names
+        let targets = if download_opts.flat {
+            Some(flat_targets(&files, Path::new(&target))?)
+        } else {
+            None
+        };
+
+        let errors = TransferErrors::default();
+        download_files(
+            &dracoon,
+            files,
+            &target,
+            targets,
+            download_opts.velocity,
+            &errors,
+            None,
+        )
+        .await?;
+
+        print_renamed_nodes(&renamed)?;
+
+        errors.report(download_opts.continue_on_error)
     } else {
         match node.node_type {
-            NodeType::File => download_file(&dracoon, &node, &target).await,
+            NodeType::File => {
+                // node name is only used if target is a directory
+                let renamed = if std::path::Path::new(&target).is_dir() {
+                    check_invalid_names([&node], download_opts.rename_invalid)?
+                } else {
+                    HashMap::new()
+                };
+
+                download_file(&dracoon, &node, &target).await?;
+
+                print_renamed_nodes(&renamed)
+            }
             _ => {
                 if download_opts.recursive {
-                    download_container(
-                        &dracoon,
-                        &node,
-                        &target,
-                        download_opts.velocity,
-                        download_opts.include_rooms,
-                    )
-                    .await
+                    download_container(&dracoon, &node, &target, &download_opts, min_free_space)
+                        .await
                 } else {
                     Err(DcCmdError::InvalidArgument(
                         "Container download requires recursive flag".to_string(),
@@ -89,3 +178,157 @@ pub async fn download(
         }
     }
 }
+
+/// checks node names for characters invalid on the local OS
+///
+/// returns a mapping (node path -> local name) of all nodes to rename or fails
+/// if renaming is not enabled
+fn check_invalid_names<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+    rename_invalid: bool,
+) -> Result<HashMap<String, String>, DcCmdError> {
+    let renamed = nodes
+        .into_iter()
+        .filter(|node| !is_valid_file_name(&node.name))
+        .map(|node| (to_node_path(node), sanitize_file_name(&node.name)))
+        .collect::<HashMap<_, _>>();
+
+    if !renamed.is_empty() && !rename_invalid {
+        for path in renamed.keys() {
+            error!("Invalid file name on this OS: {}", path);
+        }
+
+        return Err(DcCmdError::InvalidArgument(format!(
+            "{} node(s) with names invalid on this OS (use --rename-invalid to rename them)",
+            renamed.len()
+        )));
+    }
+
+    Ok(renamed)
+}
+
+/// maps all files to a target directly in the target directory (`--flat`)
+///
+/// duplicate names are numbered (e.g. `report (1).pdf`) - names are compared case
+/// insensitive to avoid overwrites on case insensitive file systems
+fn flat_targets(files: &[Node], target: &Path) -> Result<HashMap<u64, String>, DcCmdError> {
+    let mut used_names = HashSet::new();
+
+    files
+        .iter()
+        .map(|file| {
+            let name = unique_file_name(&sanitize_file_name(&file.name), &mut used_names);
+
+            if name != file.name {
+                info!("Flat download: {} -> {}", to_node_path(file), name);
+            }
+
+            let file_target = target.join(&name);
+            let file_target = file_target
+                .to_str()
+                .ok_or(DcCmdError::InvalidPath(format!(
+                    "Invalid target: {}",
+                    file_target.display()
+                )))?
+                .to_string();
+
+            Ok((file.id, file_target))
+        })
+        .collect()
+}
+
+/// returns the name or the first numbered variant not used yet (and marks it as used)
+fn unique_file_name(name: &str, used_names: &mut HashSet<String>) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (name, String::new()),
+    };
+
+    let mut unique_name = name.to_string();
+    let mut counter = 1;
+
+    while !used_names.insert(unique_name.to_lowercase()) {
+        unique_name = format!("{stem} ({counter}){extension}");
+        counter += 1;
+    }
+
+    unique_name
+}
+
+/// prints the mapping of renamed nodes (node path -> local name)
+fn print_renamed_nodes(renamed: &HashMap<String, String>) -> Result<(), DcCmdError> {
+    if renamed.is_empty() {
+        return Ok(());
+    }
+
+    let term = Term::stdout();
+    term.write_line(&format!(
+        "Renamed {} node(s) with invalid names:",
+        renamed.len()
+    ))
+    .map_err(|_| DcCmdError::IoError)?;
+
+    let mut renamed = renamed.iter().collect::<Vec<_>>();
+    renamed.sort();
+
+    for (path, name) in renamed {
+        warn!("Renamed {} to {}", path, name);
+        term.write_line(&format!("{path} -> {name}"))
+            .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(())
+}
+
+/// checks if the target file system has enough free space for the download
+fn check_free_space(target: &Path, required: u64, min_free_space: u64) -> Result<(), DcCmdError> {
+    // target might not exist yet - use the closest existing parent
+    let existing_target = target
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or_else(|| Path::new("."));
+
+    let available = fs2::available_space(existing_target).map_err(|e| {
+        error!(
+            "Error getting free space for {}: {}",
+            existing_target.display(),
+            e
+        );
+        DcCmdError::IoError
+    })?;
+
+    debug!("Required: {} - available: {}", required, available);
+
+    if available < required.saturating_add(min_free_space) {
+        error!(
+            "Not enough free space on target: {} required, {} available",
+            required, available
+        );
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Not enough free space on target ({} required, {} available, {} reserved). Use --no-space-check to skip this check.",
+            to_readable_size(required),
+            to_readable_size(available),
+            to_readable_size(min_free_space)
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_file_name() {
+        let mut used_names = HashSet::new();
+
+        assert_eq!(unique_file_name("report.pdf", &mut used_names), "report.pdf");
+        assert_eq!(unique_file_name("report.pdf", &mut used_names), "report (1).pdf");
+        assert_eq!(unique_file_name("Report.PDF", &mut used_names), "Report (2).PDF");
+        assert_eq!(unique_file_name("notes", &mut used_names), "notes");
+        assert_eq!(unique_file_name("notes", &mut used_names), "notes (1)");
+        assert_eq!(unique_file_name(".env", &mut used_names), ".env");
+        assert_eq!(unique_file_name(".env", &mut used_names), ".env (1)");
+    }
+}
@@ -0,0 +1,246 @@
+use std::{fs::File, sync::Arc};
+
+use dco3::{auth::Connected, nodes::Node, Dracoon};
+use futures_util::{stream, StreamExt};
+use indicatif::ProgressBar;
+use reqwest::{header, Client, StatusCode};
+use serde::Deserialize;
+use tracing::{debug, error};
+
+use crate::cmd::{
+    config::DEFAULT_CHUNK_SIZE, models::DcCmdError, utils::rate_limit::send_rate_limited,
+};
+
+/// files of at least this size are downloaded in parallel ranges
+pub const MIN_RANGED_DOWNLOAD_SIZE: u64 = 2 * DEFAULT_CHUNK_SIZE as u64;
+const MAX_PARALLEL_RANGES: usize = 4;
+const WRITE_BUFFER_SIZE: usize = 4 * 1024 * 1024;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DownloadUrlResponse {
+    download_url: String,
+}
+
+/// downloads a file in parallel ranges written at their offsets (target is preallocated)
+///
+/// returns `false` if ranges are not supported by the storage (nothing is written)
+pub async fn download_ranges(
+    dracoon: &Dracoon<Connected>,
+    node: &Node,
+    target: &File,
+    progress_bar: &ProgressBar,
+) -> Result<bool, DcCmdError> {
+    let size = node.size.unwrap_or(0);
+    let client = Client::new();
+    let url = get_download_url(dracoon, &client, node.id).await?;
+
+    let mut ranges = split_ranges(size, DEFAULT_CHUNK_SIZE as u64).into_iter();
+
+    // first range is used to check if ranges are supported
+    let Some(first) = ranges.next() else {
+        return Ok(true);
+    };
+
+    let response = request_range(&client, &url, first).await?;
+
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        debug!(
+            "Range requests not supported (status {}).",
+            response.status()
+        );
+        return Ok(false);
+    }
+
+    fs2::FileExt::allocate(target, size).map_err(|e| {
+        error!("Error preallocating {} bytes: {}", size, e);
+        DcCmdError::IoError
+    })?;
+
+    let target = Arc::new(target.try_clone().map_err(|_| DcCmdError::IoError)?);
+
+    write_range(response, target.clone(), first.0, progress_bar).await?;
+
+    let results = stream::iter(ranges)
+        .map(|range| {
+            let target = target.clone();
+            let client = client.clone();
+            let url = url.clone();
+            async move {
+                let response = request_range(&client, &url, range).await?;
+
+                if response.status() != StatusCode::PARTIAL_CONTENT {
+                    error!("Unexpected status for range: {}", response.status());
+                    return Err(DcCmdError::ConnectionFailed);
+                }
+
+                write_range(response, target, range.0, progress_bar).await
+            }
+        })
+        .buffer_unordered(MAX_PARALLEL_RANGES)
+        .collect::<Vec<_>>()
+        .await;
+
+    results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+    Ok(true)
+}
+
+async fn get_download_url(
+    dracoon: &Dracoon<Connected>,
+    client: &Client,
+    node_id: u64,
+) -> Result<String, DcCmdError> {
+    let url = format!(
+        "{}api/v4/nodes/files/{node_id}/downloads",
+        dracoon.get_base_url()
+    );
+
+    let request = client
+        .post(url)
+        .header(header::AUTHORIZATION, dracoon.get_auth_header().await?);
+
+    let response = send_rate_limited(request)
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            error!("Error getting download url: {}", e);
+            DcCmdError::ConnectionFailed
+        })?;
+
+    let response = response.json::<DownloadUrlResponse>().await.map_err(|e| {
+        error!("Error parsing download url: {}", e);
+        DcCmdError::ConnectionFailed
+    })?;
+
+    Ok(response.download_url)
+}
+
+async fn request_range(
+    client: &Client,
+    url: &str,
+    (start, end): (u64, u64),
+) -> Result<reqwest::Response, DcCmdError> {
+    client
+        .get(url)
+        .header(header::RANGE, format!("bytes={start}-{end}"))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| {
+            error!("Error downloading range {}-{}: {}", start, end, e);
+            DcCmdError::ConnectionFailed
+        })
+}
+
+async fn write_range(
+    mut response: reqwest::Response,
+    target: Arc<File>,
+    offset: u64,
+    progress_bar: &ProgressBar,
+) -> Result<(), DcCmdError> {
+    let mut offset = offset;
+    let mut buffer = Vec::with_capacity(WRITE_BUFFER_SIZE);
+
+    loop {
+        let chunk = response.chunk().await.map_err(|e| {
+            error!("Error reading range: {}", e);
+            DcCmdError::ConnectionFailed
+        })?;
+
+        let done = chunk.is_none();
+
+        if let Some(chunk) = chunk {
+            progress_bar.inc(chunk.len() as u64);
+            buffer.extend_from_slice(&chunk);
+        }
+
+        if buffer.len() >= WRITE_BUFFER_SIZE || (done && !buffer.is_empty()) {
+            let data = std::mem::replace(&mut buffer, Vec::with_capacity(WRITE_BUFFER_SIZE));
+            let len = data.len() as u64;
+            let target = target.clone();
+
+            tokio::task::spawn_blocking(move || write_all_at(&target, &data, offset))
+                .await
+                .map_err(|_| DcCmdError::IoError)?
+                .map_err(|e| {
+                    error!("Error writing at offset {}: {}", offset, e);
+                    DcCmdError::IoError
+                })?;
+
+            offset += len;
+        }
+
+        if done {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, data: &[u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, data, offset)
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, data: &[u8], offset: u64) -> std::io::Result<()> {
+    let mut written = 0;
+
+    while written < data.len() {
+        let n = std::os::windows::fs::FileExt::seek_write(
+            file,
+            &data[written..],
+            offset + written as u64,
+        )?;
+
+        if n == 0 {
+            return Err(std::io::ErrorKind::WriteZero.into());
+        }
+
+        written += n;
+    }
+
+    Ok(())
+}
+
+/// splits a size into inclusive byte ranges of given chunk size
+fn split_ranges(size: u64, chunk_size: u64) -> Vec<(u64, u64)> {
+    (0..size)
+        .step_by(chunk_size as usize)
+        .map(|start| (start, (start + chunk_size).min(size) - 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_ranges() {
+        assert_eq!(vec![(0, 9), (10, 19), (20, 24)], split_ranges(25, 10));
+    }
+
+    #[test]
+    fn test_split_ranges_exact() {
+        assert_eq!(vec![(0, 9), (10, 19)], split_ranges(20, 10));
+    }
+
+    #[test]
+    fn test_split_ranges_small() {
+        assert_eq!(vec![(0, 4)], split_ranges(5, 10));
+        assert!(split_ranges(0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_write_all_at() {
+        let path = std::env::temp_dir().join("dccmd-rs-test-write-all-at");
+        let file = File::create(&path).unwrap();
+        file.set_len(6).unwrap();
+
+        write_all_at(&file, b"def", 3).unwrap();
+        write_all_at(&file, b"abc", 0).unwrap();
+
+        assert_eq!("abcdef", std::fs::read_to_string(&path).unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+}
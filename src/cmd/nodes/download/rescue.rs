@@ -0,0 +1,57 @@
+use dco3::{
+    auth::Connected,
+    nodes::{models::NodeType, Node},
+    Dracoon, RescueKeyPair,
+};
+use tracing::{error, info};
+
+use crate::cmd::{
+    models::DcCmdError,
+    utils::{file_keys::distribute_missing_keys_batched, rate_limit::with_rate_limit},
+};
+
+/// grants the current user missing file keys of a node via the system rescue key
+///
+/// used for admin recovery: file keys missing for the user are decrypted with the
+/// system rescue keypair and encrypted with the public key of the user
+pub async fn grant_rescue_keys(
+    dracoon: &Dracoon<Connected>,
+    node: &Node,
+    rescue_secret: &str,
+) -> Result<(), DcCmdError> {
+    let (room_id, file_id) = rescue_scope(node)?;
+    let user_id = dracoon.get_user_info().await?.id;
+
+    let distribution = distribute_missing_keys_batched(|| {
+        with_rate_limit(|| async {
+            dracoon
+                .settings()
+                .distribute_missing_keys(rescue_secret, Some(room_id), file_id, Some(user_id))
+                .await
+                .map_err(DcCmdError::from)
+        })
+    })
+    .await?;
+
+    info!(
+        "Granted {} file key(s) in room {} via rescue key.",
+        distribution.distributed, room_id
+    );
+
+    Ok(())
+}
+
+/// room and (optional) file of a node to fix file keys for
+fn rescue_scope(node: &Node) -> Result<(u64, Option<u64>), DcCmdError> {
+    match node.node_type {
+        NodeType::Room => Ok((node.id, None)),
+        NodeType::Folder => Ok((node.auth_parent_id.unwrap_or(node.id), None)),
+        NodeType::File => {
+            let room_id = node.auth_parent_id.ok_or_else(|| {
+                error!("No parent room found for {}", node.name);
+                DcCmdError::InvalidPath(node.name.clone())
+            })?;
+            Ok((room_id, Some(node.id)))
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use console::Term;
+use dco3::{
+    auth::{models::DracoonErrorResponse, Connected},
+    nodes::models::NodeType,
+    Dracoon, Nodes, ObjectExpiration,
+};
+use reqwest::{header, Client};
+use serde::Serialize;
+use tracing::{error, info};
+
+use crate::cmd::{
+    init_dracoon,
+    models::DcCmdError,
+    utils::{
+        dates::parse_expiration,
+        rate_limit::send_rate_limited,
+        strings::{build_node_path, format_success_message, parse_path},
+    },
+};
+
+use super::models::CmdExpireOptions;
+
+pub async fn set_expiration(
+    term: Term,
+    source: String,
+    opts: CmdExpireOptions,
+) -> Result<(), DcCmdError> {
+    let expiration = opts.at.as_deref().map(parse_expiration).transpose()?;
+
+    let dracoon = init_dracoon(&source, opts.auth, false).await?;
+
+    let (parent_path, node_name, depth) = parse_path(&source, dracoon.get_base_url().as_ref())?;
+    let node_path = build_node_path((parent_path, node_name, depth));
+
+    let node = dracoon
+        .nodes()
+        .get_node_from_path(&node_path)
+        .await?
+        .ok_or(DcCmdError::InvalidPath(source.clone()))?;
+
+    if node.node_type != NodeType::File {
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Expiration can only be set on files: {}",
+            node.name
+        )));
+    }
+
+    update_file_expiration(&dracoon, node.id, expiration).await?;
+
+    let msg = match expiration {
+        Some(date) => format!("Expiration of {} set to {}.", node.name, date.date_naive()),
+        None => format!("Expiration of {} removed.", node.name),
+    };
+
+    info!("{}", msg);
+    term.write_line(&format_success_message(&msg))
+        .map_err(|_| DcCmdError::IoError)?;
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct UpdateFileExpiration {
+    expiration: ObjectExpiration,
+}
+
+/// updates the expiration of a file (dco3 does not provide file updates)
+async fn update_file_expiration(
+    dracoon: &Dracoon<Connected>,
+    node_id: u64,
+    expiration: Option<DateTime<Utc>>,
+) -> Result<(), DcCmdError> {
+    let url = format!("{}api/v4/nodes/files/{node_id}", dracoon.get_base_url());
+
+    let request = Client::new()
+        .put(url)
+        .header(header::AUTHORIZATION, dracoon.get_auth_header().await?)
+        .json(&UpdateFileExpiration {
+            expiration: to_object_expiration(expiration),
+        });
+
+    let response = send_rate_limited(request).await.map_err(|e| {
+        error!("Error updating file expiration: {}", e);
+        DcCmdError::ConnectionFailed
+    })?;
+
+    if response.status().is_success() {
+        return Ok(());
+    }
+
+    let status = response.status();
+    let err = response
+        .json::<DracoonErrorResponse>()
+        .await
+        .unwrap_or_else(|_| {
+            DracoonErrorResponse::new(i32::from(status.as_u16()), "Updating file failed")
+        });
+
+    error!("Error updating file expiration: {}", err);
+    Err(DcCmdError::DracoonError(err))
+}
+
+/// expiration for requests (`None` disables expiration)
+pub fn to_object_expiration(expire_at: Option<DateTime<Utc>>) -> ObjectExpiration {
+    ObjectExpiration {
+        enable_expiration: expire_at.is_some(),
+        expire_at: expire_at.map(|date| date.to_rfc3339()),
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use console::Term;
+use dco3::{
+    auth::Connected,
+    nodes::{
+        models::{CreateFolderRequest, Node, NodeType},
+        rooms::models::{
+            CreateRoomRequest, RoomGroupsAddBatchRequestItem, RoomUsersAddBatchRequestItem,
+        },
+        Folders, Nodes, Rooms,
+    },
+    Dracoon,
+};
+use tracing::{error, info, warn};
+
+use crate::cmd::{
+    groups::GroupCommandHandler,
+    init_dracoon,
+    models::DcCmdError,
+    rooms::{get_all_room_groups, get_all_room_users},
+    users::UserCommandHandler,
+    utils::strings::{build_node_path, format_success_message, parse_path},
+};
+
+use super::{
+    download::{get_containers, get_files},
+    models::{ClassificationLevel, CmdMigrateOptions, CmdTransferOptions},
+    to_node_path,
+    transfer::transfer_files,
+    transfer_errors::TransferErrors,
+};
+
+/// migrates a room (sub rooms, folders, permissions and files) to another instance
+///
+/// the room is created below the target path (or as a root room if the target is the
+/// instance itself) - permissions are only copied for users / groups found by login / name
+pub async fn migrate(
+    term: Term,
+    source: String,
+    target: String,
+    opts: CmdMigrateOptions,
+) -> Result<(), DcCmdError> {
+    let source_dracoon = init_dracoon(&source, None, true).await?;
+    let target_dracoon = init_dracoon(&target, None, true).await?;
+
+    let room_path = format!("/{}/", opts.path.trim_matches('/'));
+
+    let Some(source_room) = source_dracoon
+        .nodes()
+        .get_node_from_path(&room_path)
+        .await?
+        .filter(|node| node.node_type == NodeType::Room)
+    else {
+        error!("Source room not found: {}", room_path);
+        return Err(DcCmdError::InvalidPath(room_path));
+    };
+
+    if source_room.is_encrypted == Some(true) {
+        return Err(DcCmdError::InvalidArgument(
+            "Migrating encrypted rooms is not supported.".to_string(),
+        ));
+    }
+
+    let target_parent = get_target_parent(&target_dracoon, &target).await?;
+    let admin_id = target_dracoon.get_user_info().await?.id;
+
+    let migration = Migration {
+        source: source_dracoon.clone(),
+        target: target_dracoon.clone(),
+        users: UserCommandHandler::new_from_client(target_dracoon.clone(), term.clone()),
+        groups: GroupCommandHandler::new_from_client(target_dracoon.clone(), term.clone()),
+        admin_id,
+        skip_permissions: opts.skip_permissions,
+        errors: TransferErrors::default(),
+    };
+
+    let root_room = migration
+        .create_room(&source_room, target_parent.as_ref())
+        .await?;
+    migration.copy_permissions(&source_room, &root_room).await?;
+
+    // source path -> target container
+    let mut containers = HashMap::from([(format!("{}/", to_node_path(&source_room)), root_room)]);
+
+    let mut rooms = 1;
+    let mut folders = 0;
+
+    // sorted by parent path - parents are always created first
+    for container in get_containers(&source_dracoon, &source_room, true).await? {
+        let source_path = format!("{}/", to_node_path(&container));
+        let parent_path = container.parent_path.as_deref().unwrap_or("/");
+
+        let Some(parent) = containers.get(parent_path) else {
+            migration.errors.push(
+                source_path,
+                &DcCmdError::InvalidPath(parent_path.to_string()),
+            );
+            continue;
+        };
+
+        let created = match container.node_type {
+            NodeType::Room if container.is_encrypted == Some(true) => Err(
+                DcCmdError::InvalidArgument("Encrypted rooms are skipped.".to_string()),
+            ),
+            NodeType::Room => match migration.create_room(&container, Some(parent)).await {
+                Ok(room) => migration
+                    .copy_permissions(&container, &room)
+                    .await
+                    .map(|()| room),
+                Err(e) => Err(e),
+            },
+            _ => migration.create_folder(&container, parent).await,
+        };
+
+        match created {
+            Ok(node) => {
+                if node.node_type == NodeType::Room {
+                    rooms += 1;
+                } else {
+                    folders += 1;
+                }
+                containers.insert(source_path, node);
+            }
+            Err(e) => migration.errors.push(source_path, &e),
+        }
+    }
+
+    info!("Created {} rooms and {} folders.", rooms, folders);
+
+    let mut transfers = Vec::new();
+
+    for file in get_files(&source_dracoon, &source_room).await? {
+        let parent_path = file.parent_path.as_deref().unwrap_or("/");
+
+        match containers.get(parent_path) {
+            Some(parent) => transfers.push((file, parent.clone())),
+            None => migration.errors.push(
+                to_node_path(&file),
+                &DcCmdError::InvalidPath(parent_path.to_string()),
+            ),
+        }
+    }
+
+    let count_files = transfers.len();
+    let transfer_opts = CmdTransferOptions::new(
+        false,
+        false,
+        false,
+        None,
+        None,
+        opts.velocity,
+        None,
+        None,
+        None,
+        false,
+        None,
+    );
+
+    transfer_files(
+        &source_dracoon,
+        &target_dracoon,
+        transfers,
+        &transfer_opts,
+        &migration.errors,
+    )
+    .await;
+
+    let msg =
+        format!("Migrated {room_path}: {rooms} rooms, {folders} folders, {count_files} files.");
+    info!("{}", msg);
+    term.write_line(&format_success_message(&msg))
+        .map_err(|_| DcCmdError::IoError)?;
+
+    migration.errors.report(opts.continue_on_error)
+}
+
+/// returns the target parent room (None if the target is the instance itself)
+async fn get_target_parent(
+    dracoon: &Dracoon<Connected>,
+    target: &str,
+) -> Result<Option<Node>, DcCmdError> {
+    let (parent_path, node_name, depth) = parse_path(target, dracoon.get_base_url().as_ref())?;
+    let node_path = build_node_path((parent_path, node_name, depth));
+
+    if node_path == "//" {
+        return Ok(None);
+    }
+
+    match dracoon.nodes().get_node_from_path(&node_path).await? {
+        Some(node) if node.node_type == NodeType::Room => Ok(Some(node)),
+        _ => {
+            error!("Target room not found: {}", node_path);
+            Err(DcCmdError::InvalidPath(target.to_string()))
+        }
+    }
+}
+
+struct Migration {
+    source: Dracoon<Connected>,
+    target: Dracoon<Connected>,
+    users: UserCommandHandler,
+    groups: GroupCommandHandler,
+    admin_id: u64,
+    skip_permissions: bool,
+    errors: TransferErrors,
+}
+
+impl Migration {
+    /// creates a room (classification, notes and permission inheritance are kept) - an
+    /// existing room with the same name is reused
+    async fn create_room(&self, source: &Node, parent: Option<&Node>) -> Result<Node, DcCmdError> {
+        let inherit_permissions = parent.is_some() && source.inherit_permissions.unwrap_or(false);
+
+        let req = CreateRoomRequest::builder(&source.name)
+            .with_classification(
+                source
+                    .classification
+                    .and_then(|classification| u8::try_from(classification).ok())
+                    .unwrap_or_else(|| u8::from(ClassificationLevel::Internal)),
+            )
+            .with_inherit_permissions(inherit_permissions);
+
+        let req = match parent {
+            Some(parent) => req.with_parent_id(parent.id),
+            None => req,
+        };
+
+        // rooms without inherited permissions require an admin
+        let req = if inherit_permissions {
+            req
+        } else {
+            req.with_admin_ids(vec![self.admin_id])
+        };
+
+        let req = match source.notes.clone().filter(|notes| !notes.is_empty()) {
+            Some(notes) => req.with_notes(notes).build(),
+            None => req.build(),
+        };
+
+        match self.target.nodes().create_room(req).await {
+            Ok(room) => {
+                info!("Created room: {}", to_node_path(&room));
+                Ok(room)
+            }
+            Err(e) if e.is_conflict() => self.get_existing(parent, &source.name).await,
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// creates a folder (classification and notes are kept) - an existing folder with the
+    /// same name is reused
+    async fn create_folder(&self, source: &Node, parent: &Node) -> Result<Node, DcCmdError> {
+        let req = CreateFolderRequest::builder(&source.name, parent.id);
+
+        let req = match source
+            .classification
+            .and_then(|classification| u8::try_from(classification).ok())
+        {
+            Some(classification) => req.with_classification(classification),
+            None => req,
+        };
+
+        let req = match source.notes.clone().filter(|notes| !notes.is_empty()) {
+            Some(notes) => req.with_notes(notes).build(),
+            None => req.build(),
+        };
+
+        match self.target.nodes().create_folder(req).await {
+            Ok(folder) => Ok(folder),
+            Err(e) if e.is_conflict() => self.get_existing(Some(parent), &source.name).await,
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_existing(&self, parent: Option<&Node>, name: &str) -> Result<Node, DcCmdError> {
+        let parent_path =
+            parent.map_or_else(|| "/".to_string(), |p| format!("{}/", to_node_path(p)));
+        let path = format!("{parent_path}{name}/");
+
+        self.target
+            .nodes()
+            .get_node_from_path(&path)
+            .await?
+            .ok_or(DcCmdError::InvalidPath(path))
+    }
+
+    /// grants all users / groups of the source room found in the target (by login / name)
+    ///
+    /// missing users and groups are recorded as errors
+    async fn copy_permissions(&self, source: &Node, target: &Node) -> Result<(), DcCmdError> {
+        if self.skip_permissions {
+            return Ok(());
+        }
+
+        let room_path = to_node_path(target);
+        let room_id = source.id;
+
+        let users = get_all_room_users(&self.source, room_id).await?;
+
+        let mut user_items = Vec::new();
+
+        for user in users.into_iter().filter(|user| user.is_granted) {
+            let (Some(user_name), Some(permissions)) = (user.user_info.user_name, user.permissions)
+            else {
+                continue;
+            };
+
+            // the creating admin is already granted
+            match self.users.find_user_id_by_username(&user_name).await {
+                Ok(user_id) if user_id == self.admin_id => (),
+                Ok(user_id) => {
+                    user_items.push(RoomUsersAddBatchRequestItem::new(user_id, permissions))
+                }
+                Err(e) => {
+                    warn!("User {} not found in target - skipped.", user_name);
+                    self.errors
+                        .push(format!("{room_path} (user {user_name})"), &e);
+                }
+            }
+        }
+
+        if !user_items.is_empty() {
+            self.target
+                .nodes()
+                .update_room_users(target.id, user_items.into())
+                .await?;
+        }
+
+        let groups = get_all_room_groups(&self.source, room_id).await?;
+
+        let mut group_items = Vec::new();
+
+        for group in groups.into_iter().filter(|group| group.is_granted) {
+            let Some(permissions) = group.permissions else {
+                continue;
+            };
+
+            match self.groups.find_group_by_name(group.name.clone()).await {
+                Ok(found) => group_items.push(RoomGroupsAddBatchRequestItem::new(
+                    found.id,
+                    permissions,
+                    None,
+                )),
+                Err(e) => {
+                    warn!("Group {} not found in target - skipped.", group.name);
+                    self.errors
+                        .push(format!("{room_path} (group {})", group.name), &e);
+                }
+            }
+        }
+
+        if !group_items.is_empty() {
+            self.target
+                .nodes()
+                .update_room_groups(target.id, group_items.into())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
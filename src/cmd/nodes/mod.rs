@@ -2,39 +2,61 @@ use std::sync::Arc;
 
 use console::Term;
 use dialoguer::Confirm;
-use models::{CmdCopyOptions, CmdListNodesOptions};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use models::{
+    ClassificationLevel, CmdCopyOptions, CmdListNodesOptions, CmdMkdirOptions, CmdRmOptions,
+};
 
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use crate::cmd::{
+    branding::instance_prompt,
+    groups::GroupCommandHandler,
     init_dracoon,
+    journal::{Journal, JournalEntry, JournalOperation},
     users::UserCommandHandler,
-    utils::strings::{build_node_path, parse_path, print_node},
+    utils::strings::{build_node_path, format_node_header, parse_node_id, parse_path},
 };
 
 use dco3::{
     auth::Connected,
     nodes::{
-        models::{CreateFolderRequest, NodeList, NodeType},
+        models::{CreateFolderRequest, Node, NodeList, NodeType, TransferNodesRequest},
         rooms::models::CreateRoomRequest,
         Folders, Nodes, Rooms,
     },
     Dracoon,
 };
 
-use self::models::CmdMkRoomOptions;
+use self::{models::CmdMkRoomOptions, virus::load_scan_verdicts};
 
 use super::{
     config::MAX_CONCURRENT_REQUESTS,
-    models::{build_params, DcCmdError, ListOptions, PasswordAuth},
-    utils::strings::{format_error_message, format_success_message},
+    models::{build_params, DcCmdError, ListOptions},
+    utils::{
+        confirm::{assume_yes, confirm_large_operation},
+        file_types::file_type_filter,
+        pagination::for_each_page,
+        pager::Pager,
+        rate_limit::with_rate_limit,
+        strings::{format_error_message, format_success_message, to_readable_size},
+    },
 };
 
+mod acl;
+pub mod checkpoint;
+pub mod deleted;
 pub mod download;
+pub mod expiration;
+pub mod migrate;
 pub mod models;
-mod share;
+pub mod share;
+pub mod sync;
 pub mod transfer;
+pub mod transfer_errors;
+pub mod update;
 pub mod upload;
+pub mod virus;
 
 #[allow(clippy::module_name_repetitions)]
 pub async fn list_nodes(
@@ -54,49 +76,200 @@ pub async fn list_nodes(
         Some(node_path.as_str())
     };
 
-    let node_list = if is_search_query(&node_name) {
+    let node_id = parse_node_id(&source);
+
+    if opts.acl() {
+        let node = match (node_id, node_path) {
+            (Some(node_id), _) => dracoon.nodes().get_node(node_id).await?,
+            (None, Some(node_path)) if !is_search_query(&node_name) => dracoon
+                .nodes()
+                .get_node_from_path(node_path)
+                .await?
+                .ok_or(DcCmdError::InvalidPath(source.clone()))?,
+            _ => {
+                return Err(DcCmdError::InvalidArgument(
+                    "ACL requires a node path (no root node or search).".to_string(),
+                ))
+            }
+        };
+
+        return acl::print_node_acl(&term, &dracoon, &node).await;
+    }
+
+    if opts.deleted() {
+        // deleted nodes of the root level are listed via parent id 0
+        let parent_id = match (node_id, node_path) {
+            (Some(node_id), _) => node_id,
+            (None, Some(node_path)) => {
+                dracoon
+                    .nodes()
+                    .get_node_from_path(node_path)
+                    .await?
+                    .ok_or(DcCmdError::InvalidPath(node_path.to_string()))?
+                    .id
+            }
+            (None, None) => 0,
+        };
+
+        return deleted::print_deleted_nodes(&term, &dracoon, parent_id, opts.human_readable())
+            .await;
+    }
+
+    // pages are printed as they arrive (no accumulation of all nodes)
+    // except for --du: container sizes are aggregated before printing
+    let mut range = None;
+    let mut containers = Vec::new();
+    let mut pager = Pager::new(&term, opts.pager());
+
+    if opts.long() && !opts.no_header() {
+        pager.write_line(&format_node_header(opts.human_readable()))?;
+    }
+
+    let print_page = |node_list: NodeList| {
+        range.get_or_insert(node_list.range);
+
+        let nodes = node_list
+            .items
+            .into_iter()
+            .filter(|node| opts.matches_file_type(node));
+
+        if opts.du() {
+            containers.extend(nodes);
+        } else {
+            for node in nodes {
+                if pager.is_quit() {
+                    break;
+                }
+                pager.write_line(&opts.format_node(&node)?)?;
+            }
+        }
+        Ok(())
+    };
+
+    if is_search_query(&node_name) {
         debug!("Searching for nodes with query {}", node_name);
-        search_nodes(&dracoon, &node_name, Some(&parent_path), opts.list_opts()).await?
+
+        // a single file type is filtered server-side (unless a filter is set)
+        let list_opts = opts.list_opts();
+        let search_opts = match (list_opts.filter(), file_type_filter(opts.file_types())) {
+            (None, Some(filter)) => Some(ListOptions::new(
+                Some(filter),
+                list_opts.offset(),
+                list_opts.limit(),
+                list_opts.all(),
+                list_opts.csv(),
+            )),
+            _ => None,
+        };
+
+        for_each_search_page(
+            &dracoon,
+            &node_name,
+            Some(&parent_path),
+            search_opts.as_ref().unwrap_or(list_opts),
+            opts.long(),
+            print_page,
+        )
+        .await?;
     } else {
+        let parent_id = match (node_id, node_path) {
+            (Some(node_id), _) => Some(node_id),
+            (None, Some(node_path)) => {
+                let node = dracoon
+                    .nodes()
+                    .get_node_from_path(node_path)
+                    .await?
+                    .ok_or(DcCmdError::InvalidPath(node_path.to_string()))?;
+                Some(node.id)
+            }
+            (None, None) => None,
+        };
+
         debug!("Fetching node list from path {}", node_path.unwrap_or("/"));
-        get_nodes(&dracoon, node_path, Some(opts.managed()), opts.list_opts()).await?
-    };
+        for_each_nodes_page(
+            &dracoon,
+            parent_id,
+            Some(opts.managed()),
+            opts.list_opts(),
+            opts.long(),
+            print_page,
+        )
+        .await?;
+    }
 
-    node_list
-        .items
-        .iter()
-        .for_each(|node| print_node(&term, node, Some(opts.long()), Some(opts.human_readable())));
+    if opts.du() {
+        for node in with_container_sizes(&dracoon, containers).await? {
+            pager.write_line(&opts.format_node(&node)?)?;
+        }
+    }
 
     info!("Listed nodes in: {}", node_path.unwrap_or("/"));
-    info!("Total nodes: {}", node_list.range.total);
-    info!("Offset: {}", node_list.range.offset);
-    info!("Limit: {}", node_list.range.limit);
+
+    if let Some(range) = range {
+        info!("Total nodes: {}", range.total);
+        info!("Offset: {}", range.offset);
+        info!("Limit: {}", range.limit);
+    }
 
     Ok(())
 }
 
+/// sets the size of rooms and folders without size to the sum of all files below
+async fn with_container_sizes(
+    dracoon: &Dracoon<Connected>,
+    nodes: Vec<Node>,
+) -> Result<Vec<Node>, DcCmdError> {
+    stream::iter(nodes)
+        .map(|mut node| async move {
+            if node.node_type != NodeType::File && node.size.is_none() {
+                debug!("Aggregating size of {} ({})", node.name, node.id);
+                let files = download::get_files(dracoon, &node).await?;
+                node.size = Some(files.iter().filter_map(|file| file.size).sum());
+            }
+
+            Ok::<_, DcCmdError>(node)
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .try_collect()
+        .await
+}
+
 fn is_search_query(query: &str) -> bool {
     query.contains('*')
 }
 
-async fn get_nodes(
+/// resolves a node by id (dracoon://<domain>/<node-id>) or by path
+pub(crate) async fn resolve_node(
     dracoon: &Dracoon<Connected>,
-    node_path: Option<&str>,
-    managed: Option<bool>,
-    opts: &ListOptions,
-) -> Result<NodeList, DcCmdError> {
-    let parent_id = if let Some(node_path) = node_path {
-        let node = dracoon.nodes().get_node_from_path(node_path).await?;
-
-        let Some(node) = node else {
-            return Err(DcCmdError::InvalidPath(node_path.to_string()));
-        };
+    source: &str,
+    node_path: &str,
+) -> Result<Option<Node>, DcCmdError> {
+    with_rate_limit(|| async {
+        match parse_node_id(source) {
+            Some(node_id) => Ok(Some(dracoon.nodes().get_node(node_id).await?)),
+            None => Ok(dracoon.nodes().get_node_from_path(node_path).await?),
+        }
+    })
+    .await
+}
 
-        Some(node.id)
-    } else {
-        None
-    };
+fn append_page(node_list: &mut Option<NodeList>, page: NodeList) {
+    match node_list {
+        Some(node_list) => node_list.items.extend(page.items),
+        None => *node_list = Some(page),
+    }
+}
 
+/// fetches the nodes of a parent and passes each page to `on_page` (all pages if requested)
+/// virus scan verdicts of each page are loaded before it is passed if `scan_status` is set
+async fn for_each_nodes_page(
+    dracoon: &Dracoon<Connected>,
+    parent_id: Option<u64>,
+    managed: Option<bool>,
+    opts: &ListOptions,
+    scan_status: bool,
+    mut on_page: impl FnMut(NodeList) -> Result<(), DcCmdError>,
+) -> Result<(), DcCmdError> {
     let offset = opts.offset().unwrap_or(0);
     let limit = u64::from(opts.limit().unwrap_or(500))
         .try_into()
@@ -106,77 +279,73 @@ async fn get_nodes(
 
     let params = build_params(opts.filter(), offset, Some(limit))?;
 
-    let mut node_list = dracoon
+    let node_list = dracoon
         .nodes()
         .get_nodes(parent_id, managed, Some(params))
         .await?;
 
-    if opts.all() && node_list.range.total > 500 {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-        let mut handles = Vec::new();
-
-        (500..=node_list.range.total)
-            .step_by(500)
-            .for_each(|offset| {
-                let tx = tx.clone();
-                let semaphore = semaphore.clone();
-                let dracoon = dracoon.clone();
-                let opts = opts.clone();
-
-                let handle = tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.map_err({
-                        error!("Failed to acquire semaphore permit.");
-                        |_| DcCmdError::IoError
-                    })?;
-                    let params = build_params(opts.filter(), offset, None)?;
-
-                    match dracoon
-                        .nodes()
-                        .get_nodes(parent_id, managed, Some(params))
-                        .await
-                    {
-                        Ok(node_list) => {
-                            if let Err(e) = tx.send(node_list.items).await {
-                                error!("Failed to send node list: {}", e);
-                                return Err(DcCmdError::IoError);
-                            }
-
-                            Ok::<(), DcCmdError>(())
-                        }
-                        Err(e) => {
-                            error!("Error getting folders: {}", e);
-                            Err(e.into())
-                        }
-                    }
-                });
-                handles.push(handle);
-            });
-
-        drop(tx);
-
-        while let Some(result) = rx.recv().await {
-            node_list.items.extend(result);
-        }
+    if scan_status {
+        load_scan_verdicts(dracoon, &node_list).await;
+    }
 
-        for handle in handles {
-            if let Err(e) = handle.await {
-                error!("Failed to join task: {}", e);
-                return Err(DcCmdError::IoError);
-            }
-        }
+    if !opts.all() {
+        return on_page(node_list);
     }
-    Ok(node_list)
+
+    let filter = opts.filter();
+
+    for_each_page(
+        node_list,
+        |offset| async move {
+            let params = build_params(filter, offset, None)?;
+            let nodes = dracoon
+                .nodes()
+                .get_nodes(parent_id, managed, Some(params))
+                .await?;
+
+            if scan_status {
+                load_scan_verdicts(dracoon, &nodes).await;
+            }
+
+            Ok::<_, DcCmdError>(nodes)
+        },
+        on_page,
+    )
+    .await
 }
 
+/// collects all pages of a search (see `for_each_search_page`)
 async fn search_nodes(
     dracoon: &Dracoon<Connected>,
     search_string: &str,
     node_path: Option<&str>,
     opts: &ListOptions,
 ) -> Result<NodeList, DcCmdError> {
+    let mut node_list: Option<NodeList> = None;
+
+    for_each_search_page(dracoon, search_string, node_path, opts, false, |page| {
+        append_page(&mut node_list, page);
+        Ok(())
+    })
+    .await?;
+
+    node_list.ok_or(DcCmdError::Unknown)
+}
+
+/// searches nodes below a path and passes each page to `on_page` (all pages if requested)
+/// virus scan verdicts of each page are loaded before it is passed if `scan_status` is set
+async fn for_each_search_page(
+    dracoon: &Dracoon<Connected>,
+    search_string: &str,
+    node_path: Option<&str>,
+    opts: &ListOptions,
+    scan_status: bool,
+    mut on_page: impl FnMut(NodeList) -> Result<(), DcCmdError>,
+) -> Result<(), DcCmdError> {
     let parent_id = if let Some(node_path) = node_path {
-        let node = dracoon.nodes().get_node_from_path(node_path).await?;
+        let node =
+            with_rate_limit(|| async { Ok(dracoon.nodes().get_node_from_path(node_path).await?) })
+                .await?;
 
         let Some(node) = node else {
             return Err(DcCmdError::InvalidPath(node_path.to_string()));
@@ -187,96 +356,62 @@ async fn search_nodes(
         None
     };
 
-    let params = build_params(
-        opts.filter(),
-        opts.offset().unwrap_or(0),
-        Some(
-            u64::from(opts.limit().unwrap_or(500))
-                .try_into()
-                .map_err(|_| {
-                    DcCmdError::InvalidArgument("Limit must be a positive integer.".to_string())
-                })?,
-        ),
-    )?;
+    let limit = u64::from(opts.limit().unwrap_or(500))
+        .try_into()
+        .map_err(|_| {
+            DcCmdError::InvalidArgument("Limit must be a positive integer.".to_string())
+        })?;
 
-    let mut node_list = dracoon
-        .nodes()
-        .search_nodes(search_string, parent_id, Some(0), Some(params))
-        .await?;
+    let node_list = with_rate_limit(|| async {
+        let params = build_params(opts.filter(), opts.offset().unwrap_or(0), Some(limit))?;
+        Ok(dracoon
+            .nodes()
+            .search_nodes(search_string, parent_id, Some(0), Some(params))
+            .await?)
+    })
+    .await?;
 
-    if opts.all() && node_list.range.total > 500 {
-        let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-        let mut handles = Vec::new();
-
-        (500..=node_list.range.total)
-            .step_by(500)
-            .for_each(|offset| {
-                let tx = tx.clone();
-                let semaphore = semaphore.clone();
-                let dracoon = dracoon.clone();
-                let opts = opts.clone();
-                let search_string = search_string.to_string();
-
-                let handle = tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.map_err({
-                        error!("Failed to acquire semaphore permit.");
-                        |_| DcCmdError::IoError
-                    })?;
-                    let params = build_params(opts.filter(), offset, None)?;
-
-                    match dracoon
-                        .nodes()
-                        .search_nodes(&search_string, parent_id, Some(0), Some(params))
-                        .await
-                    {
-                        Ok(node_list) => {
-                            if let Err(e) = tx.send(node_list.items).await {
-                                error!("Failed to send node list: {}", e);
-                                return Err(DcCmdError::IoError);
-                            }
-
-                            Ok::<(), DcCmdError>(())
-                        }
-                        Err(e) => {
-                            error!("Error getting folders: {}", e);
-                            Err(e.into())
-                        }
-                    }
-                });
-                handles.push(handle);
-            });
-
-        drop(tx);
-
-        while let Some(result) = rx.recv().await {
-            node_list.items.extend(result);
-        }
+    if scan_status {
+        load_scan_verdicts(dracoon, &node_list).await;
+    }
 
-        for handle in handles {
-            if let Err(e) = handle.await {
-                error!("Failed to join task: {}", e);
-                return Err(DcCmdError::IoError);
-            }
-        }
+    if !opts.all() {
+        return on_page(node_list);
     }
-    Ok(node_list)
+
+    let filter = opts.filter();
+
+    for_each_page(
+        node_list,
+        |offset| async move {
+            let params = build_params(filter, offset, None)?;
+            let nodes = dracoon
+                .nodes()
+                .search_nodes(search_string, parent_id, Some(0), Some(params))
+                .await?;
+
+            if scan_status {
+                load_scan_verdicts(dracoon, &nodes).await;
+            }
+
+            Ok::<_, DcCmdError>(nodes)
+        },
+        on_page,
+    )
+    .await
 }
 
-pub async fn delete_node(
-    term: Term,
-    source: String,
-    recursive: Option<bool>,
-    auth: Option<PasswordAuth>,
-) -> Result<(), DcCmdError> {
-    let dracoon = init_dracoon(&source, auth, false).await?;
+pub async fn delete_node(term: Term, source: String, opts: CmdRmOptions) -> Result<(), DcCmdError> {
+    let dracoon = init_dracoon(&source, opts.auth.clone(), false).await?;
     let (parent_path, node_name, depth) = parse_path(&source, dracoon.get_base_url().as_ref())?;
     let is_search_query = is_search_query(&node_name);
-    // check if recursive flag is set
-    let recursive = recursive.unwrap_or(false);
 
-    match (recursive, is_search_query) {
-        (true, true) => return delete_node_content(&dracoon, &node_name, parent_path).await,
+    // recycle bin (parent id, path) and name pattern of deleted versions to purge
+    let (deleted, purge_target) = match (opts.recursive, is_search_query) {
+        (true, true) => (
+            delete_node_content(&dracoon, &term, &node_name, parent_path.clone(), &opts).await?,
+            (parent_path.clone(), node_name.clone()),
+        ),
         (false, true) => {
             let msg = format_error_message(
                 "Deleting search results not allowed. Use --recursive flag to delete recursively.",
@@ -285,61 +420,143 @@ pub async fn delete_node(
             term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
             return Ok(());
         }
-        _ => (),
+        (_, false) if opts.has_time_filter() => {
+            return Err(DcCmdError::InvalidArgument(
+                "Time filters require a search query (e.g. /logs/*).".to_string(),
+            ));
+        }
+        (_, false) => {
+            let node_path = build_node_path((parent_path.clone(), node_name.clone(), depth));
+
+            match resolve_node(&dracoon, &source, &node_path).await? {
+                Some(node) => {
+                    let purge_target = (
+                        node.parent_path
+                            .clone()
+                            .unwrap_or_else(|| parent_path.clone()),
+                        node.name.clone(),
+                    );
+                    (
+                        delete_single_node(&dracoon, &term, node, &opts).await?,
+                        purge_target,
+                    )
+                }
+                // only deleted versions are left
+                None if opts.permanent => (true, (parent_path.clone(), node_name.clone())),
+                None => return Err(DcCmdError::InvalidPath(source.clone())),
+            }
+        }
+    };
+
+    if !opts.permanent || !deleted {
+        return Ok(());
     }
 
-    let node_path = build_node_path((parent_path.clone(), node_name.clone(), depth));
-    let node = dracoon
-        .nodes()
-        .get_node_from_path(&node_path)
-        .await?
-        .ok_or(DcCmdError::InvalidPath(source.clone()))?;
+    let (purge_path, pattern) = purge_target;
+
+    // deleted nodes of the root level are listed via parent id 0
+    let parent_id = if purge_path == "/" {
+        0
+    } else {
+        with_rate_limit(|| async { Ok(dracoon.nodes().get_node_from_path(&purge_path).await?) })
+            .await?
+            .ok_or(DcCmdError::InvalidPath(purge_path.clone()))?
+            .id
+    };
+
+    deleted::purge_deleted_nodes(
+        &term,
+        &dracoon,
+        parent_id,
+        &purge_path,
+        &pattern,
+        &opts.journal,
+    )
+    .await
+}
+
+/// deletes a single node (rooms require confirmation) - returns false if aborted
+async fn delete_single_node(
+    dracoon: &Dracoon<Connected>,
+    term: &Term,
+    node: Node,
+    opts: &CmdRmOptions,
+) -> Result<bool, DcCmdError> {
+    let journal = &opts.journal;
+    let node_name = node.name.clone();
 
     // if node type is folder or room and not recursive, abort
-    if !recursive && (node.node_type == NodeType::Folder || node.node_type == NodeType::Room) {
+    if !opts.recursive && (node.node_type == NodeType::Folder || node.node_type == NodeType::Room) {
         let msg = format_error_message("Deleting non-empty folder or room not allowed. Use --recursive flag to delete recursively.");
         error!("{}", msg);
         term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
-        return Ok(());
+        return Ok(false);
     }
 
-    // define async block to delete node
-    let delete_node = async {
-        dracoon.nodes().delete_node(node.id).await?;
-        let msg = format!("Node {node_name} deleted.");
-        info!("{}", msg);
-        let msg = format_success_message(&msg);
+    let entries =
+        vec![
+            JournalEntry::new(JournalOperation::DeleteNode, node.id, to_node_path(&node))
+                .with_parent_id(node.parent_id),
+        ];
+
+    if journal.is_dry_run() {
+        journal.print_dry_run(term, &entries)?;
+        return Ok(true);
+    }
+
+    // large folders require confirmation (file count is not known)
+    if node.node_type == NodeType::Folder
+        && !confirm_large_operation(
+            dracoon.get_base_url().as_str(),
+            "Delete",
+            0,
+            node.size.unwrap_or(0),
+            false,
+        )?
+    {
+        let msg = format_error_message("Deleting folder not confirmed.");
+        error!("{}", msg);
         term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
-        Ok(())
-    };
+        return Ok(false);
+    }
 
-    // check if node is a room
-    match node.node_type {
-        NodeType::Room => {
-            // ask for confirmation if node is a room
-            let confirmed = Confirm::new()
-                .with_prompt(format!("Do you really want to delete room {node_name}?"))
-                .interact()
-                .map_err(|_| DcCmdError::IoError)?;
-
-            if confirmed {
-                delete_node.await
-            } else {
-                let msg = format_error_message("Deleting room not confirmed.");
-                error!("{}", msg);
-                term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
-                Ok(())
-            }
+    // ask for confirmation if node is a room
+    if node.node_type == NodeType::Room {
+        let confirmed = Confirm::new()
+            .with_prompt(instance_prompt(
+                dracoon.get_base_url().as_str(),
+                &format!("Do you really want to delete room {node_name}?"),
+            ))
+            .interact()
+            .map_err(|_| DcCmdError::IoError)?;
+
+        if !confirmed {
+            let msg = format_error_message("Deleting room not confirmed.");
+            error!("{}", msg);
+            term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+            return Ok(false);
         }
-        _ => delete_node.await,
     }
+
+    with_rate_limit(|| async { Ok(dracoon.nodes().delete_node(node.id).await?) }).await?;
+    record_deleted_nodes(dracoon, journal, std::slice::from_ref(&node), entries).await?;
+    let msg = format!("Node {node_name} deleted.");
+    info!("{}", msg);
+    let msg = format_success_message(&msg);
+    term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+
+    Ok(true)
 }
 
+/// deletes all search results (no rooms) - returns false if aborted
 async fn delete_node_content(
     dracoon: &Dracoon<Connected>,
+    term: &Term,
     search: &str,
     parent_path: String,
-) -> Result<(), DcCmdError> {
+    opts: &CmdRmOptions,
+) -> Result<bool, DcCmdError> {
+    let journal = &opts.journal;
     let nodes = search_nodes(
         dracoon,
         search,
@@ -347,63 +564,153 @@ async fn delete_node_content(
         &ListOptions::new(None, None, None, true, false),
     )
     .await?;
-    let node_ids = nodes
+    let nodes = nodes
         .items
-        .iter()
+        .into_iter()
         .filter(|node| node.node_type != NodeType::Room)
-        .map(|node| node.id)
-        .collect::<Vec<u64>>();
-
-    // ask for confirmation and provide info about number of items to delete
-    let confirmed = Confirm::new()
-        .with_prompt(format!(
-            "Do you really want to delete {} items?",
-            node_ids.len()
-        ))
-        .interact()
-        .or(Err(DcCmdError::IoError))?;
+        .filter(|node| {
+            // file timestamp if provided on upload, otherwise last change in DRACOON
+            opts.matches_time(node.timestamp_modification.or(node.updated_at))
+        })
+        .collect::<Vec<_>>();
+
+    if nodes.is_empty() {
+        let msg = format_success_message("No matching items to delete.");
+        term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+        // deleted versions may still be purged
+        return Ok(true);
+    }
+
+    let node_ids = nodes.iter().map(|node| node.id).collect::<Vec<u64>>();
+
+    let entries = nodes
+        .iter()
+        .map(|node| {
+            JournalEntry::new(JournalOperation::DeleteNode, node.id, to_node_path(node))
+                .with_parent_id(node.parent_id)
+        })
+        .collect::<Vec<_>>();
+
+    if journal.is_dry_run() {
+        journal.print_dry_run(term, &entries)?;
+        return Ok(true);
+    }
+
+    let total_size = nodes.iter().filter_map(|node| node.size).sum::<u64>();
+
+    // ask for confirmation and provide info about number and size of items to delete
+    let confirmed = assume_yes()
+        || Confirm::new()
+            .with_prompt(instance_prompt(
+                dracoon.get_base_url().as_str(),
+                &format!(
+                    "Do you really want to delete {} items ({})?",
+                    node_ids.len(),
+                    to_readable_size(total_size)
+                ),
+            ))
+            .interact()
+            .or(Err(DcCmdError::IoError))?;
 
     if confirmed {
-        dracoon.nodes().delete_nodes(node_ids.into()).await?;
+        with_rate_limit(|| async {
+            Ok(dracoon
+                .nodes()
+                .delete_nodes(node_ids.clone().into())
+                .await?)
+        })
+        .await?;
+        record_deleted_nodes(dracoon, journal, &nodes, entries).await?;
     }
 
-    Ok(())
+    Ok(confirmed)
+}
+
+/// records deleted nodes with their id in the recycle bin (required to restore them via undo)
+async fn record_deleted_nodes(
+    dracoon: &Dracoon<Connected>,
+    journal: &Journal,
+    nodes: &[Node],
+    entries: Vec<JournalEntry>,
+) -> Result<(), DcCmdError> {
+    if !journal.is_recording() {
+        return Ok(());
+    }
+
+    let entries = stream::iter(nodes.iter().zip(entries))
+        .map(|(node, entry)| async move {
+            let parent_id = node.parent_id.unwrap_or(0);
+
+            match deleted::find_deleted_node_id(dracoon, parent_id, &node.name, &node.node_type)
+                .await
+            {
+                Ok(Some(deleted_node_id)) => entry.with_target_id(deleted_node_id),
+                Ok(None) => entry,
+                Err(e) => {
+                    warn!("Deleted node {} not found in recycle bin: {}", node.name, e);
+                    entry
+                }
+            }
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect::<Vec<_>>()
+        .await;
+
+    journal.record(&entries)
+}
+
+/// full path of a node (parent path and name)
+pub(crate) fn to_node_path(node: &Node) -> String {
+    format!(
+        "{}{}",
+        node.parent_path.as_deref().unwrap_or("/"),
+        node.name
+    )
 }
 
 pub async fn create_folder(
     term: Term,
     source: String,
-    classification: Option<u8>,
-    notes: Option<String>,
-    auth: Option<PasswordAuth>,
+    opts: CmdMkdirOptions,
 ) -> Result<(), DcCmdError> {
-    let dracoon = init_dracoon(&source, auth, false).await?;
+    let dracoon = init_dracoon(&source, opts.auth, false).await?;
     let (parent_path, node_name, _) = parse_path(&source, dracoon.get_base_url().as_ref())?;
 
     debug!("parent_path: {}", parent_path);
     debug!("base_url: {}", dracoon.get_base_url().as_ref());
 
-    let parent_node = dracoon
-        .nodes()
-        .get_node_from_path(&parent_path)
-        .await?
-        .ok_or(DcCmdError::InvalidPath(source.clone()))?;
+    let parent_node = if opts.parents {
+        create_parent_folders(&dracoon, &parent_path, &opts.journal).await?
+    } else {
+        dracoon
+            .nodes()
+            .get_node_from_path(&parent_path)
+            .await?
+            .ok_or(DcCmdError::InvalidPath(source.clone()))?
+    };
 
     let req = CreateFolderRequest::builder(node_name.clone(), parent_node.id);
 
-    let req = match classification {
-        Some(classification) => req.with_classification(classification),
+    let req = match opts.classification {
+        Some(classification) => req.with_classification(classification.into()),
         None => req,
     };
 
-    let req = match notes {
+    let req = match opts.notes {
         Some(notes) => req.with_notes(notes),
         None => req,
     };
 
     let req = req.build();
 
-    let _folder = dracoon.nodes().create_folder(req).await?;
+    let folder = dracoon.nodes().create_folder(req).await?;
+
+    opts.journal.record(&[JournalEntry::new(
+        JournalOperation::CreateFolder,
+        folder.id,
+        to_node_path(&folder),
+    )
+    .with_parent_id(folder.parent_id)])?;
 
     let msg = format!("Folder {node_name} created.");
     info!("{}", msg);
@@ -413,6 +720,111 @@ pub async fn create_folder(
     Ok(())
 }
 
+/// walks the given path and creates all missing folders (like `mkdir -p`)
+/// rooms are never created implicitly - the first path segment must exist
+async fn create_parent_folders(
+    dracoon: &Dracoon<Connected>,
+    parent_path: &str,
+    journal: &Journal,
+) -> Result<Node, DcCmdError> {
+    let mut current_node: Option<Node> = None;
+    let mut current_path = String::from("/");
+    let mut is_missing = false;
+
+    for name in parent_path.split('/').filter(|name| !name.is_empty()) {
+        current_path.push_str(name);
+        current_path.push('/');
+
+        // once a folder is missing, all children are missing as well
+        if !is_missing {
+            if let Some(node) = dracoon.nodes().get_node_from_path(&current_path).await? {
+                current_node = Some(node);
+                continue;
+            }
+            is_missing = true;
+        }
+
+        let Some(parent_node) = current_node else {
+            error!(
+                "Root room not found (rooms are not created): {}",
+                current_path
+            );
+            return Err(DcCmdError::InvalidPath(current_path));
+        };
+
+        let req = CreateFolderRequest::builder(name, parent_node.id).build();
+        let folder = dracoon.nodes().create_folder(req).await?;
+
+        journal.record(&[JournalEntry::new(
+            JournalOperation::CreateFolder,
+            folder.id,
+            current_path.clone(),
+        )
+        .with_parent_id(folder.parent_id)])?;
+
+        info!("Created parent folder: {}", current_path);
+        current_node = Some(folder);
+    }
+
+    current_node.ok_or(DcCmdError::InvalidPath(parent_path.to_string()))
+}
+
+/// walks the given path and creates all missing rooms (inheriting permissions)
+/// the first path segment must exist - all existing segments must be rooms
+async fn create_parent_rooms(
+    dracoon: &Dracoon<Connected>,
+    parent_path: &str,
+    journal: &Journal,
+) -> Result<Node, DcCmdError> {
+    let mut current_node: Option<Node> = None;
+    let mut current_path = String::from("/");
+    let mut is_missing = false;
+
+    for name in parent_path.split('/').filter(|name| !name.is_empty()) {
+        current_path.push_str(name);
+        current_path.push('/');
+
+        // once a room is missing, all children are missing as well
+        if !is_missing {
+            if let Some(node) = dracoon.nodes().get_node_from_path(&current_path).await? {
+                if node.node_type != NodeType::Room {
+                    error!("Parent is not a room: {}", current_path);
+                    return Err(DcCmdError::InvalidPath(current_path));
+                }
+                current_node = Some(node);
+                continue;
+            }
+            is_missing = true;
+        }
+
+        let Some(parent_node) = current_node else {
+            error!(
+                "Root room not found (root rooms are not created): {}",
+                current_path
+            );
+            return Err(DcCmdError::InvalidPath(current_path));
+        };
+
+        let req = CreateRoomRequest::builder(name)
+            .with_parent_id(parent_node.id)
+            .with_inherit_permissions(true)
+            .build();
+        let room = dracoon.nodes().create_room(req).await?;
+
+        journal.record(&[JournalEntry::new(
+            JournalOperation::CreateRoom,
+            room.id,
+            current_path.clone(),
+        )
+        .with_parent_id(room.parent_id)])?;
+
+        info!("Created parent room: {}", current_path);
+        current_node = Some(room);
+    }
+
+    current_node.ok_or(DcCmdError::InvalidPath(parent_path.to_string()))
+}
+
 pub async fn create_room(
     term: Term,
     source: String,
@@ -421,82 +833,66 @@ pub async fn create_room(
     let dracoon = init_dracoon(&source, opts.auth, false).await?;
     let (parent_path, node_name, _) = parse_path(&source, dracoon.get_base_url().as_ref())?;
 
-    let parent_node = dracoon
-        .nodes()
-        .get_node_from_path(&parent_path)
-        .await?
-        .ok_or(DcCmdError::InvalidPath(source.clone()))?;
+    let parent_node = if opts.parents {
+        create_parent_rooms(&dracoon, &parent_path, &opts.journal).await?
+    } else {
+        dracoon
+            .nodes()
+            .get_node_from_path(&parent_path)
+            .await?
+            .ok_or(DcCmdError::InvalidPath(source.clone()))?
+    };
 
     if parent_node.node_type != NodeType::Room {
         return Err(DcCmdError::InvalidPath(source.clone()));
     }
 
-    let classification = opts.classification.unwrap_or(2);
-
-    let req = match opts.admin_users {
-        Some(users) => {
-            let handler = UserCommandHandler::new_from_client(dracoon.clone(), term.clone());
-            let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-            let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-            let mut handles = Vec::new();
-
-            for user in users {
-                let tx = tx.clone();
-                let semaphore = semaphore.clone();
-                let handler = handler.clone();
-                let handle = tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.map_err(|_| {
-                        error!("Error acquiring semaphore permit");
-                        DcCmdError::IoError
-                    })?;
-                    let user = handler.find_user_by_username(&user).await?;
-                    if let Err(e) = tx.send(user).await {
-                        error!("Failed to send user: {}", e);
-                    }
-
-                    Ok::<(), DcCmdError>(())
-                });
-
-                handles.push(handle);
-            }
-
-            drop(tx);
+    let classification = opts
+        .classification
+        .unwrap_or(ClassificationLevel::Internal)
+        .into();
 
-            let mut admin_users = Vec::new();
-            while let Some(result) = rx.recv().await {
-                admin_users.push(result);
-            }
-
-            for handle in handles {
-                if let Err(e) = handle.await {
-                    error!("Error fetching users: {}", e);
-                    return Err(DcCmdError::IoError);
-                }
-            }
-
-            let admin_users: Vec<_> = admin_users.iter().map(|user| user.id).collect();
+    let admin_users = match opts.admin_users {
+        Some(users) => find_admin_users(&dracoon, &term, users).await?,
+        None => Vec::new(),
+    };
 
-            if admin_users.is_empty() {
-                return Err(DcCmdError::InvalidArgument(
-                    "No valid admin users provided.".to_string(),
-                ));
-            }
+    let admin_groups = match opts.admin_groups {
+        Some(groups) => find_admin_groups(&dracoon, &term, groups).await?,
+        None => Vec::new(),
+    };
 
-            CreateRoomRequest::builder(&node_name.clone())
-                .with_parent_id(parent_node.id)
-                .with_classification(classification)
-                .with_inherit_permissions(opts.inherit_permissions)
-                .with_admin_ids(admin_users)
-                .build()
-        }
-        None => CreateRoomRequest::builder(&node_name.clone())
+    let req = if admin_users.is_empty() && admin_groups.is_empty() {
+        CreateRoomRequest::builder(&node_name.clone())
             .with_parent_id(parent_node.id)
             .with_classification(classification)
             .with_inherit_permissions(true)
-            .build(),
+            .build()
+    } else {
+        let mut req = CreateRoomRequest::builder(&node_name.clone())
+            .with_parent_id(parent_node.id)
+            .with_classification(classification)
+            .with_inherit_permissions(opts.inherit_permissions);
+
+        if !admin_users.is_empty() {
+            req = req.with_admin_ids(admin_users);
+        }
+
+        if !admin_groups.is_empty() {
+            req = req.with_admin_group_ids(admin_groups);
+        }
+
+        req.build()
     };
 
-    let _room = dracoon.nodes().create_room(req).await?;
+    let room = dracoon.nodes().create_room(req).await?;
+
+    opts.journal.record(&[JournalEntry::new(
+        JournalOperation::CreateRoom,
+        room.id,
+        to_node_path(&room),
+    )
+    .with_parent_id(room.parent_id)])?;
 
     let msg = format!("Room {node_name} created.");
     info!("{}", msg);
@@ -506,12 +902,116 @@ pub async fn create_room(
     Ok(())
 }
 
+/// resolves admin user names to user ids
+async fn find_admin_users(
+    dracoon: &Dracoon<Connected>,
+    term: &Term,
+    users: Vec<String>,
+) -> Result<Vec<u64>, DcCmdError> {
+    let handler = UserCommandHandler::new_from_client(dracoon.clone(), term.clone());
+    let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let mut handles = Vec::new();
+
+    for user in users {
+        let tx = tx.clone();
+        let semaphore = semaphore.clone();
+        let handler = handler.clone();
+        let handle = tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.map_err(|_| {
+                error!("Error acquiring semaphore permit");
+                DcCmdError::IoError
+            })?;
+            let user_id = handler.find_user_id_by_username(&user).await?;
+            if let Err(e) = tx.send(user_id).await {
+                error!("Failed to send user: {}", e);
+            }
+
+            Ok::<(), DcCmdError>(())
+        });
+
+        handles.push(handle);
+    }
+
+    drop(tx);
+
+    let mut admin_users = Vec::new();
+    while let Some(result) = rx.recv().await {
+        admin_users.push(result);
+    }
+
+    for handle in handles {
+        if let Err(e) = handle.await {
+            error!("Error fetching users: {}", e);
+            return Err(DcCmdError::IoError);
+        }
+    }
+
+    if admin_users.is_empty() {
+        return Err(DcCmdError::InvalidArgument(
+            "No valid admin users provided.".to_string(),
+        ));
+    }
+
+    Ok(admin_users)
+}
+
+/// resolves admin group names to group ids
+async fn find_admin_groups(
+    dracoon: &Dracoon<Connected>,
+    term: &Term,
+    groups: Vec<String>,
+) -> Result<Vec<u64>, DcCmdError> {
+    let handler = GroupCommandHandler::new_from_client(dracoon.clone(), term.clone());
+
+    let admin_groups = stream::iter(groups)
+        .map(|group| {
+            let handler = &handler;
+            async move { handler.find_group_by_name(group).await }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .map(|group| group.map(|group| group.id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if admin_groups.is_empty() {
+        return Err(DcCmdError::InvalidArgument(
+            "No valid admin groups provided.".to_string(),
+        ));
+    }
+
+    Ok(admin_groups)
+}
+
 pub async fn copy_nodes(
     term: Term,
     source: String,
     target: String,
     opts: CmdCopyOptions,
 ) -> Result<(), DcCmdError> {
+    transfer_nodes(term, source, target, opts, false).await
+}
+
+pub async fn move_nodes(
+    term: Term,
+    source: String,
+    target: String,
+    opts: CmdCopyOptions,
+) -> Result<(), DcCmdError> {
+    transfer_nodes(term, source, target, opts, true).await
+}
+
+async fn transfer_nodes(
+    term: Term,
+    source: String,
+    target: String,
+    opts: CmdCopyOptions,
+    is_move: bool,
+) -> Result<(), DcCmdError> {
+    opts.validate()?;
+
     let client = init_dracoon(&source, opts.auth, false).await?;
 
     let (source_parent_path, source_node_name, source_depth) =
@@ -534,9 +1034,7 @@ pub async fn copy_nodes(
 
         nodes.items
     } else {
-        let source_node = client
-            .nodes()
-            .get_node_from_path(&source_path)
+        let source_node = resolve_node(&client, &source, &source_path)
             .await?
             .ok_or(DcCmdError::InvalidPath(source.clone()))?;
 
@@ -552,12 +1050,46 @@ pub async fn copy_nodes(
         .await?
         .ok_or(DcCmdError::InvalidPath(target.clone()))?;
 
-    client
-        .nodes()
-        .copy_nodes(source_node_ids.into(), target_node.id)
-        .await?;
+    let operation = if is_move {
+        JournalOperation::MoveNode
+    } else {
+        JournalOperation::CopyNode
+    };
+
+    let entries = nodes
+        .iter()
+        .map(|node| {
+            JournalEntry::new(operation, node.id, to_node_path(node))
+                .with_parent_id(node.parent_id)
+                .with_target_id(target_node.id)
+        })
+        .collect::<Vec<_>>();
+
+    if opts.journal.is_dry_run() {
+        return opts.journal.print_dry_run(&term, &entries);
+    }
+
+    // builder methods are only implemented on the request itself (dco3)
+    let req = TransferNodesRequest::from(source_node_ids);
+
+    let req = match opts.resolution {
+        Some(resolution) => req.with_resolution_strategy(resolution.into()),
+        None => req,
+    };
+
+    let req = req.with_keep_share_links(opts.keep_share_links);
+
+    let action = if is_move {
+        client.nodes().move_nodes(req, target_node.id).await?;
+        "Moved"
+    } else {
+        client.nodes().copy_nodes(req, target_node.id).await?;
+        "Copied"
+    };
+
+    opts.journal.record(&entries)?;
 
-    let msg = format!("Copied {count_nodes} node(s) from {source_parent_path} to {target}.");
+    let msg = format!("{action} {count_nodes} node(s) from {source_parent_path} to {target}.");
     info!("{}", msg);
     let msg = format_success_message(&msg);
     term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
@@ -1,40 +1,293 @@
 #![allow(clippy::struct_excessive_bools)]
 
-use crate::cmd::models::{ListOptions, PasswordAuth};
+use std::{path::PathBuf, time::Duration};
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use dco3::nodes::{Node, NodeType, ResolutionStrategy};
+use serde::Serialize;
+
+use crate::cmd::{
+    journal::Journal,
+    models::{DcCmdError, ListOptions, PasswordAuth},
+    nodes::to_node_path,
+    utils::{
+        file_types::{detect_mime_type, matches_file_type, parse_file_types},
+        strings::{format_node, matches_glob},
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CmdResolutionStrategy {
+    Overwrite,
+    Autorename,
+    Fail,
+}
+
+impl From<CmdResolutionStrategy> for ResolutionStrategy {
+    fn from(value: CmdResolutionStrategy) -> Self {
+        match value {
+            CmdResolutionStrategy::Overwrite => ResolutionStrategy::Overwrite,
+            CmdResolutionStrategy::Autorename => ResolutionStrategy::AutoRename,
+            CmdResolutionStrategy::Fail => ResolutionStrategy::Fail,
+        }
+    }
+}
+
+/// classification of a node (numeric DRACOON values are accepted as aliases)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ClassificationLevel {
+    #[value(alias = "1")]
+    Public,
+    #[value(alias = "2")]
+    Internal,
+    #[value(alias = "3")]
+    Confidential,
+    #[value(alias = "4")]
+    StrictlyConfidential,
+}
+
+impl From<ClassificationLevel> for u8 {
+    fn from(value: ClassificationLevel) -> Self {
+        match value {
+            ClassificationLevel::Public => 1,
+            ClassificationLevel::Internal => 2,
+            ClassificationLevel::Confidential => 3,
+            ClassificationLevel::StrictlyConfidential => 4,
+        }
+    }
+}
+
+/// output format of ls
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum LsOutput {
+    /// one line per node (default)
+    #[default]
+    Text,
+    /// one JSON object per node (printed as pages arrive - e.g. for jq)
+    JsonLines,
+}
+
+/// node as JSON object (ls --output json-lines)
+#[derive(Debug, Serialize)]
+pub struct NodeJson<'n> {
+    pub id: u64,
+    pub reference_id: Option<u64>,
+    pub name: &'n str,
+    pub node_type: &'static str,
+    pub path: String,
+    pub parent_id: Option<u64>,
+    pub size: Option<u64>,
+    pub is_encrypted: Option<bool>,
+    pub timestamp_modification: Option<String>,
+    /// detected by the file extension (files only)
+    pub mime_type: Option<&'static str>,
+}
+
+impl<'n> From<&'n Node> for NodeJson<'n> {
+    fn from(node: &'n Node) -> Self {
+        let node_type = match node.node_type {
+            NodeType::Room => "room",
+            NodeType::Folder => "folder",
+            NodeType::File => "file",
+        };
+
+        Self {
+            id: node.id,
+            reference_id: node.reference_id,
+            name: &node.name,
+            node_type,
+            path: to_node_path(node),
+            parent_id: node.parent_id,
+            size: node.size,
+            is_encrypted: node.is_encrypted,
+            timestamp_modification: node
+                .timestamp_modification
+                .as_ref()
+                .map(|timestamp| timestamp.to_rfc3339()),
+            mime_type: (node.node_type == NodeType::File)
+                .then(|| detect_mime_type(&node.name))
+                .flatten(),
+        }
+    }
+}
 
 pub struct CmdCopyOptions {
     pub auth: Option<PasswordAuth>,
+    pub resolution: Option<CmdResolutionStrategy>,
+    pub keep_share_links: bool,
+    pub journal: Journal,
 }
 
 impl CmdCopyOptions {
-    pub fn new(auth: Option<PasswordAuth>) -> Self {
-        Self { auth }
+    pub fn new(
+        auth: Option<PasswordAuth>,
+        resolution: Option<CmdResolutionStrategy>,
+        keep_share_links: bool,
+        journal: Journal,
+    ) -> Self {
+        Self {
+            auth,
+            resolution,
+            keep_share_links,
+            journal,
+        }
+    }
+
+    /// share links can only be kept if existing nodes are overwritten
+    pub fn validate(&self) -> Result<(), DcCmdError> {
+        if self.keep_share_links && self.resolution != Some(CmdResolutionStrategy::Overwrite) {
+            return Err(DcCmdError::InvalidArgument(
+                "--keep-share-links requires --resolution overwrite.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CmdExpireOptions {
+    /// expiration date (`None` removes the expiration)
+    pub at: Option<String>,
+    pub auth: Option<PasswordAuth>,
+}
+
+impl CmdExpireOptions {
+    pub fn new(at: Option<String>, auth: Option<PasswordAuth>) -> Self {
+        Self { at, auth }
+    }
+}
+
+pub struct CmdRmOptions {
+    pub recursive: bool,
+    pub permanent: bool,
+    pub older_than: Option<DateTime<Utc>>,
+    pub newer_than: Option<DateTime<Utc>>,
+    pub auth: Option<PasswordAuth>,
+    pub journal: Journal,
+}
+
+impl CmdRmOptions {
+    pub fn new(
+        recursive: bool,
+        permanent: bool,
+        older_than: Option<DateTime<Utc>>,
+        newer_than: Option<DateTime<Utc>>,
+        auth: Option<PasswordAuth>,
+        journal: Journal,
+    ) -> Self {
+        Self {
+            recursive,
+            permanent,
+            older_than,
+            newer_than,
+            auth,
+            journal,
+        }
+    }
+
+    pub fn has_time_filter(&self) -> bool {
+        self.older_than.is_some() || self.newer_than.is_some()
+    }
+
+    /// checks if a modification timestamp is within the window (nodes without a
+    /// timestamp never match a time filter)
+    pub fn matches_time(&self, modified: Option<DateTime<Utc>>) -> bool {
+        if !self.has_time_filter() {
+            return true;
+        }
+
+        let Some(modified) = modified else {
+            return false;
+        };
+
+        self.older_than.is_none_or(|cutoff| modified < cutoff)
+            && self.newer_than.is_none_or(|cutoff| modified > cutoff)
+    }
+}
+
+pub struct CmdMkdirOptions {
+    pub classification: Option<ClassificationLevel>,
+    pub notes: Option<String>,
+    pub parents: bool,
+    pub auth: Option<PasswordAuth>,
+    pub journal: Journal,
+}
+
+impl CmdMkdirOptions {
+    pub fn new(
+        classification: Option<ClassificationLevel>,
+        notes: Option<String>,
+        parents: bool,
+        auth: Option<PasswordAuth>,
+        journal: Journal,
+    ) -> Self {
+        Self {
+            classification,
+            notes,
+            parents,
+            auth,
+            journal,
+        }
+    }
+}
+
+pub struct CmdUpdateFolderOptions {
+    pub name: Option<String>,
+    pub notes: Option<String>,
+    pub classification: Option<ClassificationLevel>,
+    pub auth: Option<PasswordAuth>,
+}
+
+impl CmdUpdateFolderOptions {
+    pub fn new(
+        name: Option<String>,
+        notes: Option<String>,
+        classification: Option<ClassificationLevel>,
+        auth: Option<PasswordAuth>,
+    ) -> Self {
+        Self {
+            name,
+            notes,
+            classification,
+            auth,
+        }
     }
 }
 
 pub struct CmdMkRoomOptions {
     pub inherit_permissions: bool,
-    pub classification: Option<u8>,
+    pub classification: Option<ClassificationLevel>,
     pub auth: Option<PasswordAuth>,
     pub admin_users: Option<Vec<String>>,
+    pub admin_groups: Option<Vec<String>>,
+    pub parents: bool,
+    pub journal: Journal,
 }
 
 impl CmdMkRoomOptions {
     pub fn new(
         inherit_permissions: bool,
-        classification: Option<u8>,
+        classification: Option<ClassificationLevel>,
         auth: Option<PasswordAuth>,
         admin_users: Option<Vec<String>>,
+        admin_groups: Option<Vec<String>>,
+        parents: bool,
+        journal: Journal,
     ) -> Self {
         Self {
             inherit_permissions,
             classification,
             auth,
             admin_users,
+            admin_groups,
+            parents,
+            journal,
         }
     }
 }
 
+#[derive(Default)]
 pub struct CmdDownloadOptions {
     pub recursive: bool,
     pub velocity: Option<u8>,
@@ -42,8 +295,23 @@ pub struct CmdDownloadOptions {
     pub encryption_password: Option<String>,
     pub share_password: Option<String>,
     pub include_rooms: bool,
+    pub rename_invalid: bool,
+    pub flat: bool,
+    pub min_free_space: Option<String>,
+    pub no_space_check: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub file_types: Vec<String>,
+    pub continue_on_error: bool,
+    pub rescue_secret: Option<String>,
+    /// continue an interrupted recursive download (see `TransferCheckpoint`)
+    pub resume: bool,
+    /// confirms large downloads without prompting (library use - the CLI uses `--yes`)
+    pub assume_yes: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::fn_params_excessive_bools)]
 impl CmdDownloadOptions {
     pub fn new(
         recursive: bool,
@@ -52,6 +320,16 @@ impl CmdDownloadOptions {
         encryption_password: Option<String>,
         share_password: Option<String>,
         include_rooms: bool,
+        rename_invalid: bool,
+        flat: bool,
+        min_free_space: Option<String>,
+        no_space_check: bool,
+        include: Vec<String>,
+        exclude: Vec<String>,
+        file_types: &[String],
+        continue_on_error: bool,
+        rescue_secret: Option<String>,
+        resume: bool,
     ) -> Self {
         Self {
             recursive,
@@ -60,23 +338,205 @@ impl CmdDownloadOptions {
             encryption_password,
             share_password,
             include_rooms,
+            rename_invalid,
+            flat,
+            min_free_space,
+            no_space_check,
+            include,
+            exclude,
+            file_types: parse_file_types(file_types),
+            continue_on_error,
+            rescue_secret,
+            resume,
+            assume_yes: false,
+        }
+    }
+
+    /// checks if a file name passes the include / exclude and file type filters
+    ///
+    /// without include patterns, all names are included - exclude patterns always win
+    pub fn is_included(&self, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| matches_glob(name, p)))
+            && !self.exclude.iter().any(|p| matches_glob(name, p))
+            && matches_file_type(name, &self.file_types)
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.include.is_empty() || !self.exclude.is_empty() || !self.file_types.is_empty()
+    }
+
+    /// builds download options for library use (all options default to off)
+    pub fn builder() -> CmdDownloadOptionsBuilder {
+        CmdDownloadOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct CmdDownloadOptionsBuilder {
+    opts: CmdDownloadOptions,
+}
+
+impl CmdDownloadOptionsBuilder {
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.opts.recursive = recursive;
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: u8) -> Self {
+        self.opts.velocity = Some(velocity);
+        self
+    }
+
+    pub fn with_auth(mut self, auth: PasswordAuth) -> Self {
+        self.opts.auth = Some(auth);
+        self
+    }
+
+    pub fn with_encryption_password(mut self, encryption_password: impl Into<String>) -> Self {
+        self.opts.encryption_password = Some(encryption_password.into());
+        self
+    }
+
+    pub fn with_share_password(mut self, share_password: impl Into<String>) -> Self {
+        self.opts.share_password = Some(share_password.into());
+        self
+    }
+
+    pub fn with_include_rooms(mut self, include_rooms: bool) -> Self {
+        self.opts.include_rooms = include_rooms;
+        self
+    }
+
+    pub fn with_rename_invalid(mut self, rename_invalid: bool) -> Self {
+        self.opts.rename_invalid = rename_invalid;
+        self
+    }
+
+    pub fn with_flat(mut self, flat: bool) -> Self {
+        self.opts.flat = flat;
+        self
+    }
+
+    /// minimum free space after the download (e.g. `10GB`)
+    pub fn with_min_free_space(mut self, min_free_space: impl Into<String>) -> Self {
+        self.opts.min_free_space = Some(min_free_space.into());
+        self
+    }
+
+    pub fn with_no_space_check(mut self, no_space_check: bool) -> Self {
+        self.opts.no_space_check = no_space_check;
+        self
+    }
+
+    pub fn with_include(mut self, include: Vec<String>) -> Self {
+        self.opts.include = include;
+        self
+    }
+
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.opts.exclude = exclude;
+        self
+    }
+
+    /// file types (extensions or categories like `images`)
+    pub fn with_file_types(mut self, file_types: &[String]) -> Self {
+        self.opts.file_types = parse_file_types(file_types);
+        self
+    }
+
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.opts.continue_on_error = continue_on_error;
+        self
+    }
+
+    pub fn with_rescue_secret(mut self, rescue_secret: impl Into<String>) -> Self {
+        self.opts.rescue_secret = Some(rescue_secret.into());
+        self
+    }
+
+    /// continues an interrupted recursive download (files already downloaded are skipped)
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.opts.resume = resume;
+        self
+    }
+
+    /// skips the confirmation prompt of large downloads (non-interactive use)
+    pub fn with_assume_yes(mut self, assume_yes: bool) -> Self {
+        self.opts.assume_yes = assume_yes;
+        self
+    }
+
+    pub fn build(self) -> CmdDownloadOptions {
+        self.opts
+    }
+}
+
+/// recipients (and message) of the DRACOON notification email of a share
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareNotification {
+    pub recipients: Vec<String>,
+    pub message: Option<String>,
+}
+
+impl ShareNotification {
+    /// no notification without recipients - recipients must be email addresses
+    pub fn try_new(
+        recipients: Vec<String>,
+        message: Option<String>,
+    ) -> Result<Option<Self>, DcCmdError> {
+        let recipients = recipients
+            .into_iter()
+            .map(|recipient| recipient.trim().to_string())
+            .filter(|recipient| !recipient.is_empty())
+            .collect::<Vec<_>>();
+
+        if recipients.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(invalid) = recipients.iter().find(|r| !r.contains('@')) {
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Invalid notification email: {invalid}"
+            )));
         }
+
+        Ok(Some(Self {
+            recipients,
+            message,
+        }))
     }
 }
 
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct CmdUploadOptions {
     pub overwrite: bool,
     pub keep_share_links: bool,
     pub recursive: bool,
     pub skip_root: bool,
     pub share: bool,
-    pub classification: Option<u8>,
+    pub classification: Option<ClassificationLevel>,
+    pub expiration: Option<DateTime<Utc>>,
     pub velocity: Option<u8>,
     pub auth: Option<PasswordAuth>,
     pub encryption_password: Option<String>,
     pub share_password: Option<String>,
+    pub share_expiration: Option<DateTime<Utc>>,
+    pub share_notification: Option<ShareNotification>,
+    pub generate_password: bool,
+    pub password_file: Option<PathBuf>,
+    pub skip_unchanged: bool,
+    pub continue_on_error: bool,
+    pub verify: bool,
+    pub dedupe: bool,
+    pub rename: Option<String>,
+    pub prefix: Option<String>,
+    pub suffix: Option<String>,
+    pub no_direct_s3: bool,
+    /// continue an interrupted recursive upload (see `TransferCheckpoint`)
+    pub resume: bool,
+    /// confirms large uploads without prompting (library use - the CLI uses `--yes`)
+    pub assume_yes: bool,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -88,11 +548,25 @@ impl CmdUploadOptions {
         recursive: bool,
         skip_root: bool,
         share: bool,
-        classification: Option<u8>,
+        classification: Option<ClassificationLevel>,
+        expiration: Option<DateTime<Utc>>,
         velocity: Option<u8>,
         auth: Option<PasswordAuth>,
         encryption_password: Option<String>,
         share_password: Option<String>,
+        share_expiration: Option<DateTime<Utc>>,
+        share_notification: Option<ShareNotification>,
+        generate_password: bool,
+        password_file: Option<PathBuf>,
+        skip_unchanged: bool,
+        continue_on_error: bool,
+        verify: bool,
+        dedupe: bool,
+        rename: Option<String>,
+        prefix: Option<String>,
+        suffix: Option<String>,
+        no_direct_s3: bool,
+        resume: bool,
     ) -> Self {
         Self {
             overwrite,
@@ -101,10 +575,222 @@ impl CmdUploadOptions {
             skip_root,
             share,
             classification,
+            expiration,
             velocity,
             auth,
             encryption_password,
             share_password,
+            share_expiration,
+            share_notification,
+            generate_password,
+            password_file,
+            skip_unchanged,
+            continue_on_error,
+            verify,
+            dedupe,
+            rename,
+            prefix,
+            suffix,
+            no_direct_s3,
+            resume,
+            assume_yes: false,
+        }
+    }
+
+    /// names in DRACOON must not contain path separators
+    pub fn validate(&self) -> Result<(), DcCmdError> {
+        if self.rename.as_deref() == Some("") {
+            return Err(DcCmdError::InvalidArgument(
+                "--rename requires a file name.".to_string(),
+            ));
+        }
+
+        let names = [&self.rename, &self.prefix, &self.suffix];
+        if names.into_iter().flatten().any(|name| name.contains(['/', '\\'])) {
+            return Err(DcCmdError::InvalidArgument(
+                "--rename, --prefix and --suffix must not contain path separators.".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// returns the name of the file in DRACOON - either the new name (`--rename`) or the
+    /// local name with prefix and suffix (the suffix is added before the extension)
+    pub fn remote_name(&self, name: &str) -> String {
+        if let Some(rename) = &self.rename {
+            return rename.clone();
+        }
+
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        let suffix = self.suffix.as_deref().unwrap_or_default();
+
+        match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => {
+                format!("{prefix}{stem}{suffix}.{extension}")
+            }
+            _ => format!("{prefix}{name}{suffix}"),
+        }
+    }
+
+    /// builds upload options for library use (all options default to off)
+    pub fn builder() -> CmdUploadOptionsBuilder {
+        CmdUploadOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct CmdUploadOptionsBuilder {
+    opts: CmdUploadOptions,
+}
+
+impl CmdUploadOptionsBuilder {
+    pub fn with_overwrite(mut self, overwrite: bool) -> Self {
+        self.opts.overwrite = overwrite;
+        self
+    }
+
+    /// only applies with overwrite
+    pub fn with_keep_share_links(mut self, keep_share_links: bool) -> Self {
+        self.opts.keep_share_links = keep_share_links;
+        self
+    }
+
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.opts.recursive = recursive;
+        self
+    }
+
+    pub fn with_skip_root(mut self, skip_root: bool) -> Self {
+        self.opts.skip_root = skip_root;
+        self
+    }
+
+    pub fn with_classification(mut self, classification: ClassificationLevel) -> Self {
+        self.opts.classification = Some(classification);
+        self
+    }
+
+    pub fn with_expiration(mut self, expiration: DateTime<Utc>) -> Self {
+        self.opts.expiration = Some(expiration);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: u8) -> Self {
+        self.opts.velocity = Some(velocity);
+        self
+    }
+
+    pub fn with_auth(mut self, auth: PasswordAuth) -> Self {
+        self.opts.auth = Some(auth);
+        self
+    }
+
+    pub fn with_encryption_password(mut self, encryption_password: impl Into<String>) -> Self {
+        self.opts.encryption_password = Some(encryption_password.into());
+        self
+    }
+
+    /// shares the uploaded file (share link is written to the terminal)
+    pub fn with_share(mut self, share: bool) -> Self {
+        self.opts.share = share;
+        self
+    }
+
+    pub fn with_share_password(mut self, share_password: impl Into<String>) -> Self {
+        self.opts.share_password = Some(share_password.into());
+        self
+    }
+
+    pub fn with_share_expiration(mut self, share_expiration: DateTime<Utc>) -> Self {
+        self.opts.share_expiration = Some(share_expiration);
+        self
+    }
+
+    pub fn with_share_notification(mut self, share_notification: ShareNotification) -> Self {
+        self.opts.share_notification = Some(share_notification);
+        self
+    }
+
+    pub fn with_generate_password(mut self, generate_password: bool) -> Self {
+        self.opts.generate_password = generate_password;
+        self
+    }
+
+    pub fn with_password_file(mut self, password_file: PathBuf) -> Self {
+        self.opts.password_file = Some(password_file);
+        self
+    }
+
+    pub fn with_skip_unchanged(mut self, skip_unchanged: bool) -> Self {
+        self.opts.skip_unchanged = skip_unchanged;
+        self
+    }
+
+    pub fn with_continue_on_error(mut self, continue_on_error: bool) -> Self {
+        self.opts.continue_on_error = continue_on_error;
+        self
+    }
+
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.opts.verify = verify;
+        self
+    }
+
+    pub fn with_dedupe(mut self, dedupe: bool) -> Self {
+        self.opts.dedupe = dedupe;
+        self
+    }
+
+    pub fn with_rename(mut self, rename: impl Into<String>) -> Self {
+        self.opts.rename = Some(rename.into());
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.opts.prefix = Some(prefix.into());
+        self
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.opts.suffix = Some(suffix.into());
+        self
+    }
+
+    /// uploads via DRACOON instead of directly to the S3 storage
+    pub fn with_no_direct_s3(mut self, no_direct_s3: bool) -> Self {
+        self.opts.no_direct_s3 = no_direct_s3;
+        self
+    }
+
+    /// continues an interrupted recursive upload (files already uploaded are skipped)
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.opts.resume = resume;
+        self
+    }
+
+    /// skips the confirmation prompt of large uploads (non-interactive use)
+    pub fn with_assume_yes(mut self, assume_yes: bool) -> Self {
+        self.opts.assume_yes = assume_yes;
+        self
+    }
+
+    pub fn build(self) -> CmdUploadOptions {
+        self.opts
+    }
+}
+
+#[derive(Clone)]
+pub struct CmdWatchOptions {
+    pub debounce: Duration,
+    pub exclude: Vec<String>,
+}
+
+impl CmdWatchOptions {
+    pub fn new(debounce: u64, exclude: Vec<String>) -> Self {
+        Self {
+            debounce: Duration::from_secs(debounce),
+            exclude,
         }
     }
 }
@@ -114,15 +800,34 @@ pub struct CmdListNodesOptions {
     human_readable: bool,
     long: bool,
     managed: bool,
+    acl: bool,
+    deleted: bool,
+    du: bool,
+    ids: bool,
+    no_header: bool,
+    quiet: bool,
+    no_pager: bool,
+    output: LsOutput,
+    file_types: Vec<String>,
     auth: Option<PasswordAuth>,
 }
 
 impl CmdListNodesOptions {
+    #[allow(clippy::fn_params_excessive_bools, clippy::too_many_arguments)]
     pub fn new(
         list_opts: ListOptions,
         human_readable: bool,
         long: bool,
         managed: bool,
+        acl: bool,
+        deleted: bool,
+        du: bool,
+        ids: bool,
+        no_header: bool,
+        quiet: bool,
+        no_pager: bool,
+        output: LsOutput,
+        file_types: &[String],
         auth: Option<PasswordAuth>,
     ) -> Self {
         Self {
@@ -130,10 +835,29 @@ impl CmdListNodesOptions {
             human_readable,
             long,
             managed,
+            acl,
+            deleted,
+            du,
+            ids,
+            no_header,
+            quiet,
+            no_pager,
+            output,
+            file_types: parse_file_types(file_types),
             auth,
         }
     }
 
+    pub fn file_types(&self) -> &[String] {
+        &self.file_types
+    }
+
+    /// only files of the given file types are listed (all nodes without file types)
+    pub fn matches_file_type(&self, node: &Node) -> bool {
+        self.file_types.is_empty()
+            || (node.node_type == NodeType::File && matches_file_type(&node.name, &self.file_types))
+    }
+
     pub fn list_opts(&self) -> &ListOptions {
         &self.list_opts
     }
@@ -150,6 +874,52 @@ impl CmdListNodesOptions {
         self.managed
     }
 
+    pub fn acl(&self) -> bool {
+        self.acl
+    }
+
+    pub fn deleted(&self) -> bool {
+        self.deleted
+    }
+
+    pub fn du(&self) -> bool {
+        self.du
+    }
+
+    fn is_json_lines(&self) -> bool {
+        self.output == LsOutput::JsonLines
+    }
+
+    /// header line is omitted with --no-header, --quiet and JSON lines
+    pub fn no_header(&self) -> bool {
+        self.no_header || self.quiet || self.is_json_lines()
+    }
+
+    /// script-friendly output is never paged
+    pub fn pager(&self) -> bool {
+        !self.no_pager && !self.quiet && !self.is_json_lines()
+    }
+
+    /// formats a node line (only the name with --quiet, prefixed by the node id with --ids)
+    /// or a JSON object (--output json-lines)
+    pub fn format_node(&self, node: &Node) -> Result<String, DcCmdError> {
+        if self.is_json_lines() {
+            return serde_json::to_string(&NodeJson::from(node)).map_err(|_| DcCmdError::IoError);
+        }
+
+        let line = if self.quiet {
+            node.name.clone()
+        } else {
+            format_node(node, Some(self.long), Some(self.human_readable))
+        };
+
+        if self.ids {
+            Ok(format!("{:<12} {line}", node.id))
+        } else {
+            Ok(line)
+        }
+    }
+
     pub fn auth(&self) -> Option<PasswordAuth> {
         self.auth.clone()
     }
@@ -161,24 +931,322 @@ pub struct CmdTransferOptions {
     pub overwrite: bool,
     pub keep_share_links: bool,
     pub share: bool,
-    pub classification: Option<u8>,
+    pub classification: Option<ClassificationLevel>,
+    pub expiration: Option<DateTime<Utc>>,
+    pub velocity: Option<u8>,
     pub share_password: Option<String>,
+    pub share_expiration: Option<DateTime<Utc>>,
+    pub share_notification: Option<ShareNotification>,
+    pub generate_password: bool,
+    pub password_file: Option<PathBuf>,
 }
 
+#[allow(clippy::too_many_arguments)]
 impl CmdTransferOptions {
     pub fn new(
         overwrite: bool,
         keep_share_links: bool,
         share: bool,
-        classification: Option<u8>,
+        classification: Option<ClassificationLevel>,
+        expiration: Option<DateTime<Utc>>,
+        velocity: Option<u8>,
         share_password: Option<String>,
+        share_expiration: Option<DateTime<Utc>>,
+        share_notification: Option<ShareNotification>,
+        generate_password: bool,
+        password_file: Option<PathBuf>,
     ) -> Self {
         Self {
             overwrite,
             keep_share_links,
             share,
             classification,
+            expiration,
+            velocity,
             share_password,
+            share_expiration,
+            share_notification,
+            generate_password,
+            password_file,
         }
     }
 }
+
+pub struct CmdMigrateOptions {
+    pub path: String,
+    pub velocity: Option<u8>,
+    pub skip_permissions: bool,
+    pub continue_on_error: bool,
+}
+
+impl CmdMigrateOptions {
+    pub fn new(
+        path: String,
+        velocity: Option<u8>,
+        skip_permissions: bool,
+        continue_on_error: bool,
+    ) -> Self {
+        Self {
+            path,
+            velocity,
+            skip_permissions,
+            continue_on_error,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CmdSyncOptions {
+    pub interval: Option<Duration>,
+    pub classification: Option<ClassificationLevel>,
+    pub velocity: Option<u8>,
+    pub auth: Option<PasswordAuth>,
+    pub encryption_password: Option<String>,
+    /// confirms large uploads without prompting (library use - the CLI uses `--yes`)
+    pub assume_yes: bool,
+}
+
+impl CmdSyncOptions {
+    pub fn new(
+        interval: Option<Duration>,
+        classification: Option<ClassificationLevel>,
+        velocity: Option<u8>,
+        auth: Option<PasswordAuth>,
+        encryption_password: Option<String>,
+    ) -> Self {
+        Self {
+            interval,
+            classification,
+            velocity,
+            auth,
+            encryption_password,
+            assume_yes: false,
+        }
+    }
+
+    /// builds sync options for library use (single sync run by default)
+    pub fn builder() -> CmdSyncOptionsBuilder {
+        CmdSyncOptionsBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct CmdSyncOptionsBuilder {
+    opts: CmdSyncOptions,
+}
+
+impl CmdSyncOptionsBuilder {
+    /// repeats the sync in given interval
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.opts.interval = Some(interval);
+        self
+    }
+
+    pub fn with_classification(mut self, classification: ClassificationLevel) -> Self {
+        self.opts.classification = Some(classification);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: u8) -> Self {
+        self.opts.velocity = Some(velocity);
+        self
+    }
+
+    pub fn with_auth(mut self, auth: PasswordAuth) -> Self {
+        self.opts.auth = Some(auth);
+        self
+    }
+
+    pub fn with_encryption_password(mut self, encryption_password: impl Into<String>) -> Self {
+        self.opts.encryption_password = Some(encryption_password.into());
+        self
+    }
+
+    /// skips the confirmation prompt of large uploads (non-interactive use)
+    pub fn with_assume_yes(mut self, assume_yes: bool) -> Self {
+        self.opts.assume_yes = assume_yes;
+        self
+    }
+
+    pub fn build(self) -> CmdSyncOptions {
+        self.opts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{
+        ClassificationLevel, CmdCopyOptions, CmdListNodesOptions, CmdResolutionStrategy,
+        CmdRmOptions, CmdUploadOptions, LsOutput, ShareNotification,
+    };
+    use crate::cmd::{journal::Journal, models::ListOptions};
+
+    #[test]
+    fn test_classification_level_conversion() {
+        assert_eq!(u8::from(ClassificationLevel::Public), 1);
+        assert_eq!(u8::from(ClassificationLevel::Internal), 2);
+        assert_eq!(u8::from(ClassificationLevel::Confidential), 3);
+        assert_eq!(u8::from(ClassificationLevel::StrictlyConfidential), 4);
+    }
+
+    #[test]
+    fn test_classification_level_parse() {
+        use clap::ValueEnum;
+
+        assert_eq!(
+            ClassificationLevel::from_str("strictly-confidential", true).unwrap(),
+            ClassificationLevel::StrictlyConfidential
+        );
+        assert_eq!(
+            ClassificationLevel::from_str("1", true).unwrap(),
+            ClassificationLevel::Public
+        );
+        assert!(ClassificationLevel::from_str("5", true).is_err());
+    }
+
+    #[test]
+    fn test_list_nodes_options_json_lines() {
+        let opts = CmdListNodesOptions::new(
+            ListOptions::new(None, None, None, true, false),
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            LsOutput::JsonLines,
+            &[],
+            None,
+        );
+
+        assert!(opts.no_header());
+        assert!(!opts.pager());
+    }
+
+    #[test]
+    fn test_share_notification() {
+        let notification = ShareNotification::try_new(
+            vec!["a@b.c".to_string(), " d@e.f ".to_string()],
+            Some("Hello".to_string()),
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(notification.recipients, vec!["a@b.c", "d@e.f"]);
+        assert_eq!(notification.message.as_deref(), Some("Hello"));
+    }
+
+    #[test]
+    fn test_share_notification_empty() {
+        assert_eq!(ShareNotification::try_new(vec![], None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_share_notification_invalid_email() {
+        assert!(ShareNotification::try_new(vec!["foo".to_string()], None).is_err());
+    }
+
+    #[test]
+    fn test_rm_options_time_window() {
+        let older_than = Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let newer_than = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let opts = CmdRmOptions::new(
+            true,
+            false,
+            Some(older_than),
+            Some(newer_than),
+            None,
+            Journal::default(),
+        );
+
+        assert!(opts.matches_time(Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())));
+        assert!(!opts.matches_time(Some(Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap())));
+        assert!(!opts.matches_time(Some(Utc.with_ymd_and_hms(2023, 7, 1, 0, 0, 0).unwrap())));
+        assert!(!opts.matches_time(None));
+    }
+
+    #[test]
+    fn test_rm_options_without_time_filter() {
+        let opts = CmdRmOptions::new(true, false, None, None, None, Journal::default());
+
+        assert!(!opts.has_time_filter());
+        assert!(opts.matches_time(None));
+    }
+
+    #[test]
+    fn test_copy_options_keep_share_links() {
+        let opts = |resolution, keep_share_links| {
+            CmdCopyOptions::new(None, resolution, keep_share_links, Journal::default())
+        };
+
+        assert!(opts(Some(CmdResolutionStrategy::Overwrite), true)
+            .validate()
+            .is_ok());
+        assert!(opts(None, false).validate().is_ok());
+        assert!(opts(None, true).validate().is_err());
+        assert!(opts(Some(CmdResolutionStrategy::Autorename), true)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_upload_options_builder() {
+        let opts = CmdUploadOptions::builder()
+            .with_recursive(true)
+            .with_classification(ClassificationLevel::Confidential)
+            .with_suffix("_v2")
+            .build();
+
+        assert!(opts.recursive);
+        assert!(!opts.overwrite);
+        assert_eq!(opts.classification, Some(ClassificationLevel::Confidential));
+        assert_eq!(opts.remote_name("report.pdf"), "report_v2.pdf");
+    }
+
+    #[test]
+    fn test_upload_options_remote_name() {
+        let opts = |rename: Option<&str>, prefix: Option<&str>, suffix: Option<&str>| {
+            CmdUploadOptions::new(
+                false,
+                false,
+                false,
+                false,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+                None,
+                false,
+                false,
+                false,
+                false,
+                rename.map(String::from),
+                prefix.map(String::from),
+                suffix.map(String::from),
+                false,
+                false,
+            )
+        };
+
+        assert_eq!(opts(None, None, None).remote_name("a.pdf"), "a.pdf");
+        assert_eq!(opts(Some("b.pdf"), None, None).remote_name("a.pdf"), "b.pdf");
+        assert_eq!(
+            opts(None, Some("2024_"), Some("_v2")).remote_name("a.tar.gz"),
+            "2024_a.tar_v2.gz"
+        );
+        assert_eq!(opts(None, None, Some("_v2")).remote_name("README"), "README_v2");
+        assert_eq!(opts(None, None, Some("_v2")).remote_name(".env"), ".env_v2");
+    }
+}
@@ -1,27 +1,245 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use console::Term;
 use dco3::{
-    auth::Connected, nodes::Node, shares::CreateDownloadShareRequest, DownloadShares, Dracoon,
+    auth::Connected,
+    nodes::Node,
+    shares::{CreateDownloadShareRequest, DownloadShareLinkEmail, DownloadSharesFilter},
+    Config, DownloadShares, Dracoon, ListAllParams,
 };
+use tracing::{error, info};
 
-use crate::cmd::models::DcCmdError;
+use crate::cmd::{
+    models::DcCmdError,
+    nodes::{expiration::to_object_expiration, models::ShareNotification},
+    utils::{progress::write_line, strings::format_success_message},
+};
 
 const SHARE_URL: &str = "public/download-shares/";
 
+/// length of generated share passwords (unless the policy requires more)
+const GENERATED_PASSWORD_LENGTH: usize = 20;
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const NUMERIC: &[u8] = b"0123456789";
+const SPECIAL: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{}~";
+
 pub async fn share_node(
     client: &Dracoon<Connected>,
     node: &Node,
     share_password: Option<String>,
+    share_expiration: Option<DateTime<Utc>>,
+    notification: Option<&ShareNotification>,
 ) -> Result<String, DcCmdError> {
-    let share_request = if let Some(password) = share_password {
-        CreateDownloadShareRequest::builder(node.id)
-            .with_password(password)
-            .build()
-    } else {
-        CreateDownloadShareRequest::builder(node.id).build()
+    let share_request = CreateDownloadShareRequest::builder(node.id);
+
+    let share_request = match share_password {
+        Some(password) => share_request.with_password(password),
+        None => share_request,
+    };
+
+    let share_request = match share_expiration {
+        Some(expiration) => share_request.with_expiration(to_object_expiration(Some(expiration))),
+        None => share_request,
+    };
+
+    let share = client
+        .shares()
+        .create_download_share(share_request.build())
+        .await?;
+
+    if let Some(notification) = notification {
+        notify_recipients(client, share.id, node, notification).await?;
+    }
+
+    Ok(to_share_link(client, &share.access_key))
+}
+
+/// shares a container - an existing download share is reused unless a password or
+/// expiration is requested
+pub async fn share_container(
+    client: &Dracoon<Connected>,
+    node: &Node,
+    share_password: Option<String>,
+    share_expiration: Option<DateTime<Utc>>,
+    notification: Option<&ShareNotification>,
+) -> Result<String, DcCmdError> {
+    if share_password.is_none() && share_expiration.is_none() {
+        let params = ListAllParams::builder()
+            .with_filter(DownloadSharesFilter::node_id_equals(node.id))
+            .build();
+
+        let shares = client.shares().get_download_shares(Some(params)).await?;
+
+        if let Some(share) = shares.items.first() {
+            info!("Reusing download share {} of {}.", share.id, node.name);
+
+            if let Some(notification) = notification {
+                notify_recipients(client, share.id, node, notification).await?;
+            }
+
+            return Ok(to_share_link(client, &share.access_key));
+        }
+    }
+
+    share_node(client, node, share_password, share_expiration, notification).await
+}
+
+/// sends the DRACOON notification email with the share link to the recipients
+async fn notify_recipients(
+    client: &Dracoon<Connected>,
+    share_id: u64,
+    node: &Node,
+    notification: &ShareNotification,
+) -> Result<(), DcCmdError> {
+    let body = notification
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("{} has been shared with you.", node.name));
+
+    let email = DownloadShareLinkEmail::new(body, notification.recipients.clone(), None);
+
+    client
+        .shares()
+        .send_download_share_email(share_id, email)
+        .await
+        .inspect_err(|e| error!("Error sending share notification for {}: {}", node.name, e))?;
+
+    info!(
+        "Share notification for {} sent to {} recipient(s).",
+        node.name,
+        notification.recipients.len()
+    );
+
+    Ok(())
+}
+
+fn to_share_link(client: &Dracoon<Connected>, access_key: &str) -> String {
+    format!("{}{}{}", client.get_base_url(), SHARE_URL, access_key)
+}
+
+/// returns the passed share password or generates one compliant with the share
+/// password policies (min. length, one character of each class)
+pub async fn get_share_password(
+    client: &Dracoon<Connected>,
+    share_password: Option<String>,
+    generate_password: bool,
+) -> Result<Option<String>, DcCmdError> {
+    if !generate_password {
+        return Ok(share_password);
+    }
+
+    let policies = client.config().get_password_policies().await?;
+    let min_length = policies
+        .shares_password_policies
+        .and_then(|policies| policies.min_length)
+        .and_then(|min_length| usize::try_from(min_length).ok())
+        .unwrap_or(0);
+
+    generate_password_with_length(GENERATED_PASSWORD_LENGTH.max(min_length)).map(Some)
+}
+
+/// prints a generated share password once - or writes it to a file instead
+pub fn output_generated_password(
+    term: &Term,
+    password: &str,
+    password_file: Option<&Path>,
+) -> Result<(), DcCmdError> {
+    let Some(path) = password_file else {
+        return write_line(term, &format!("▶︎▶︎ Share password: {password}"));
     };
 
-    let share = client.shares().create_download_share(share_request).await?;
+    std::fs::write(path, format!("{password}\n")).map_err(|e| {
+        error!("Error writing share password to {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    write_line(
+        term,
+        &format_success_message(&format!("Share password written to {}.", path.display())),
+    )
+}
+
+fn generate_password_with_length(length: usize) -> Result<String, DcCmdError> {
+    let classes = [LOWERCASE, UPPERCASE, NUMERIC, SPECIAL];
+    let all = classes.concat();
+
+    // one character of each class, the rest from all classes
+    let mut password = classes
+        .iter()
+        .map(|class| random_char(class))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    while password.len() < length {
+        password.push(random_char(&all)?);
+    }
+
+    // shuffle (Fisher-Yates) to not leak the class positions
+    for i in (1..password.len()).rev() {
+        let j = random_index(i + 1)?;
+        password.swap(i, j);
+    }
+
+    Ok(password.into_iter().map(char::from).collect())
+}
+
+fn random_char(chars: &[u8]) -> Result<u8, DcCmdError> {
+    Ok(chars[random_index(chars.len())?])
+}
+
+/// uniform random index below `bound` (rejection sampling to avoid modulo bias)
+fn random_index(bound: usize) -> Result<usize, DcCmdError> {
+    let limit = u32::MAX - u32::MAX % bound as u32;
+
+    loop {
+        let mut buf = [0u8; 4];
+        openssl::rand::rand_bytes(&mut buf).map_err(|e| {
+            error!("Error generating random bytes: {}", e);
+            DcCmdError::Unknown
+        })?;
+
+        let value = u32::from_le_bytes(buf);
+
+        if value < limit {
+            return Ok(value as usize % bound);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_password_length() {
+        assert_eq!(20, generate_password_with_length(20).unwrap().len());
+        assert_eq!(32, generate_password_with_length(32).unwrap().len());
+    }
+
+    #[test]
+    fn test_generate_password_contains_all_classes() {
+        for _ in 0..20 {
+            let password = generate_password_with_length(8).unwrap();
+
+            for class in [LOWERCASE, UPPERCASE, NUMERIC, SPECIAL] {
+                assert!(password.bytes().any(|c| class.contains(&c)), "{password}");
+            }
+        }
+    }
 
-    let share_link = format!("{}{}{}", client.get_base_url(), SHARE_URL, share.access_key);
+    #[test]
+    fn test_generate_password_is_random() {
+        assert_ne!(
+            generate_password_with_length(20).unwrap(),
+            generate_password_with_length(20).unwrap()
+        );
+    }
 
-    Ok(share_link)
+    #[test]
+    fn test_random_index_in_bounds() {
+        for bound in [1, 2, 7, 88] {
+            assert!(random_index(bound).unwrap() < bound);
+        }
+    }
 }
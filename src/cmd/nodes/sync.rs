@@ -0,0 +1,199 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use console::Term;
+use fs2::FileExt;
+use tracing::{error, info};
+
+use crate::cmd::{
+    config::get_or_create_config_dir,
+    get_error_message,
+    models::DcCmdError,
+    utils::{
+        deadline::{is_exceeded, wait_for_deadline},
+        progress::write_line,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+use super::{
+    models::{CmdSyncOptions, CmdUploadOptions},
+    upload::upload,
+};
+
+/// synchronizes a local directory into DRACOON (uploads new and changed files)
+///
+/// with an interval, the synchronization is repeated until cancelled (or the deadline is
+/// exceeded) - failed cycles are logged and retried with the next cycle
+pub async fn sync(
+    term: Term,
+    source: PathBuf,
+    target: String,
+    opts: CmdSyncOptions,
+) -> Result<(), DcCmdError> {
+    if !source.is_dir() {
+        error!("Sync requires a directory: {}", source.display());
+        return Err(DcCmdError::InvalidPath(
+            source.to_string_lossy().to_string(),
+        ));
+    }
+
+    // held until the sync finishes (released on drop)
+    let _lock = acquire_lock(&source, &target)?;
+
+    // unchanged files are skipped, changed files are overwritten
+    let mut upload_opts = CmdUploadOptions::builder()
+        .with_overwrite(true)
+        .with_recursive(true)
+        .with_skip_root(true)
+        .with_skip_unchanged(true)
+        .with_assume_yes(opts.assume_yes);
+
+    if let Some(classification) = opts.classification {
+        upload_opts = upload_opts.with_classification(classification);
+    }
+
+    if let Some(velocity) = opts.velocity {
+        upload_opts = upload_opts.with_velocity(velocity);
+    }
+
+    if let Some(auth) = opts.auth {
+        upload_opts = upload_opts.with_auth(auth);
+    }
+
+    if let Some(encryption_password) = opts.encryption_password {
+        upload_opts = upload_opts.with_encryption_password(encryption_password);
+    }
+
+    let upload_opts = upload_opts.build();
+
+    let mut cycle = 1;
+
+    loop {
+        let started = Instant::now();
+        info!(
+            "Starting sync cycle {}: {} -> {}",
+            cycle,
+            source.display(),
+            target
+        );
+
+        let res = upload(
+            term.clone(),
+            source.clone(),
+            target.clone(),
+            upload_opts.clone(),
+        )
+        .await;
+        let elapsed = started.elapsed();
+
+        let Some(interval) = opts.interval else {
+            return res;
+        };
+
+        let msg = match &res {
+            Ok(()) => {
+                let summary = format_cycle_summary(cycle, elapsed, None);
+                info!("{}", summary);
+                format_success_message(&summary)
+            }
+            Err(e) => {
+                let summary = format_cycle_summary(cycle, elapsed, Some(&get_error_message(e)));
+                error!("{}", summary);
+                format_error_message(&summary)
+            }
+        };
+
+        write_line(&term, &msg)?;
+
+        // cycles start every interval (immediately if a cycle took longer)
+        tokio::select! {
+            () = tokio::time::sleep(interval.saturating_sub(elapsed)) => {}
+            () = wait_for_deadline() => {}
+        }
+
+        // no new cycle is started after the deadline (scheduled syncs must not overlap)
+        if is_exceeded() {
+            let msg = format!("Deadline exceeded after {cycle} sync cycle(s).");
+            info!("{}", msg);
+            return Err(DcCmdError::DeadlineExceeded(msg));
+        }
+
+        cycle += 1;
+    }
+}
+
+/// locks the sync of source and target - fails if the same sync is already running
+fn acquire_lock(source: &Path, target: &str) -> Result<File, DcCmdError> {
+    let source = source
+        .canonicalize()
+        .unwrap_or_else(|_| source.to_path_buf());
+    let path = get_or_create_config_dir().join(lock_file_name(&source, target));
+
+    let file = File::create(&path).map_err(|e| {
+        error!("Error creating lock file {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    file.try_lock_exclusive().map_err(|_| {
+        error!("Lock file {} is held by another process.", path.display());
+        DcCmdError::InvalidArgument(format!(
+            "Sync of {} to {target} is already running.",
+            source.display()
+        ))
+    })?;
+
+    Ok(file)
+}
+
+/// lock file name per source and target (e.g. `sync-<md5>.lock`)
+fn lock_file_name(source: &Path, target: &str) -> String {
+    let key = format!("{}|{}", source.display(), target.trim_end_matches('/'));
+
+    format!("sync-{:x}.lock", md5::compute(key))
+}
+
+fn format_cycle_summary(cycle: u64, elapsed: Duration, error: Option<&str>) -> String {
+    let secs = elapsed.as_secs();
+
+    match error {
+        None => format!("Sync cycle {cycle} completed in {secs}s."),
+        Some(error) => format!("Sync cycle {cycle} failed after {secs}s: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_file_name() {
+        let name = lock_file_name(Path::new("/backup"), "some.domain.com/room/");
+
+        assert!(name.starts_with("sync-"));
+        assert!(name.ends_with(".lock"));
+        assert_eq!(
+            name,
+            lock_file_name(Path::new("/backup"), "some.domain.com/room")
+        );
+        assert_ne!(
+            name,
+            lock_file_name(Path::new("/other"), "some.domain.com/room")
+        );
+    }
+
+    #[test]
+    fn test_format_cycle_summary() {
+        assert_eq!(
+            "Sync cycle 1 completed in 12s.",
+            format_cycle_summary(1, Duration::from_millis(12_400), None)
+        );
+        assert_eq!(
+            "Sync cycle 3 failed after 0s: Invalid path: foo",
+            format_cycle_summary(3, Duration::ZERO, Some("Invalid path: foo"))
+        );
+    }
+}
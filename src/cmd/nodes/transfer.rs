@@ -1,17 +1,37 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
 use console::Term;
 use dco3::auth::Connected;
-use dco3::nodes::{FileMeta, Node, ResolutionStrategy, UploadOptions};
+use dco3::nodes::{FileMeta, Node, NodeType, ResolutionStrategy, UploadOptions};
 use dco3::{Download, Dracoon, Nodes, Upload};
 use indicatif::{ProgressBar, ProgressStyle};
 use tokio::io::{duplex, AsyncWriteExt, BufReader, BufWriter};
-use tracing::{debug, error};
+use tracing::{debug, error, info};
 
+use crate::cmd::config::{DEFAULT_CONCURRENT_MULTIPLIER, MAX_VELOCITY, MIN_VELOCITY};
 use crate::cmd::models::DcCmdError;
-use crate::cmd::nodes::share::share_node;
+use crate::cmd::nodes::download::get_files;
+use crate::cmd::nodes::share::{
+    get_share_password, output_generated_password, share_container, share_node,
+};
+use crate::cmd::nodes::upload::{create_root_folder, report_s3_connection_error};
+use crate::cmd::utils::deadline::check_deadline;
+use crate::cmd::utils::progress::{progress_bar, write_line};
 use crate::cmd::utils::strings::{format_success_message, parse_path};
 use crate::cmd::{init_dracoon, init_encryption};
 
-use super::models::CmdTransferOptions;
+use super::{
+    expiration::to_object_expiration,
+    models::{ClassificationLevel, CmdTransferOptions},
+    to_node_path,
+    transfer_errors::TransferErrors,
+};
 
 const MAX_BUFFER_SIZE: usize = 64 * 1024;
 
@@ -33,8 +53,6 @@ pub async fn transfer_node(
         source_dracoon = init_encryption(source_dracoon, None).await?;
     }
 
-    let target_dracoon_mv = target_dracoon.clone();
-
     if parent_node.is_encrypted.unwrap_or(false) && opts.share {
         error!("Parent node is encrypted. Cannot upload to encrypted nodes.");
         return Err(DcCmdError::InvalidArgument(
@@ -42,6 +60,311 @@ pub async fn transfer_node(
         ));
     }
 
+    if source_node.node_type != NodeType::File {
+        return transfer_container(
+            &term,
+            &source_dracoon,
+            &target_dracoon,
+            &source_node,
+            &parent_node,
+            &opts,
+        )
+        .await;
+    }
+
+    let progress_bar = progress_bar(source_node.size.unwrap_or(0));
+    progress_bar.set_style(
+    ProgressStyle::default_bar()
+    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
+    .progress_chars("=>-"),
+);
+
+    let node = transfer_file(
+        &source_dracoon,
+        &target_dracoon,
+        source_node,
+        parent_node,
+        &opts,
+        progress_bar.clone(),
+    )
+    .await?;
+
+    if !node.is_encrypted.unwrap_or(false) && opts.share {
+        let share_password = get_share_password(
+            &target_dracoon,
+            opts.share_password.clone(),
+            opts.generate_password,
+        )
+        .await?;
+        let link = share_node(
+            &target_dracoon,
+            &node,
+            share_password.clone(),
+            opts.share_expiration,
+            opts.share_notification.as_ref(),
+        )
+        .await?;
+        print_share_link(&term, &node, &link, share_password.as_deref(), &opts)?;
+    }
+
+    let msg = format!("Node {} uploaded from {source} to {target}.", node.name);
+    progress_bar.finish_with_message(msg);
+
+    Ok(())
+}
+
+/// transfers all files of a room / folder (folder structure is recreated below a folder
+/// named like the source - empty folders are skipped)
+async fn transfer_container(
+    term: &Term,
+    source_dracoon: &Dracoon<Connected>,
+    target_dracoon: &Dracoon<Connected>,
+    source_node: &Node,
+    parent_node: &Node,
+    opts: &CmdTransferOptions,
+) -> Result<(), DcCmdError> {
+    let source_root = format!("{}/", to_node_path(source_node));
+    info!("Attempting transfer of container: {}.", source_root);
+
+    let files = get_files(source_dracoon, source_node).await?;
+
+    let root_folder = create_root_folder(
+        target_dracoon,
+        &source_node.name,
+        parent_node.id,
+        &format!("{}/", to_node_path(parent_node)),
+        opts.classification,
+    )
+    .await?;
+
+    let relative_parents = files
+        .iter()
+        .map(|file| relative_parent(&source_root, file.parent_path.as_deref().unwrap_or("/")))
+        .collect::<Vec<_>>();
+
+    let folders = create_target_folders(
+        target_dracoon,
+        &root_folder,
+        folder_paths(relative_parents.iter().flatten()),
+        opts,
+    )
+    .await?;
+
+    let errors = TransferErrors::default();
+    let mut transfers = Vec::new();
+
+    for (file, relative_parent) in files.into_iter().zip(relative_parents) {
+        match relative_parent.and_then(|path| folders.get(&path)).cloned() {
+            Some(target_parent) => transfers.push((file, target_parent)),
+            None => errors.push(
+                to_node_path(&file),
+                &DcCmdError::InvalidPath(source_root.clone()),
+            ),
+        }
+    }
+
+    transfer_files(source_dracoon, target_dracoon, transfers, opts, &errors).await;
+    info!("Transfer of {} complete.", source_root);
+
+    if opts.share {
+        let share_password = get_share_password(
+            target_dracoon,
+            opts.share_password.clone(),
+            opts.generate_password,
+        )
+        .await?;
+        let link = share_container(
+            target_dracoon,
+            &root_folder,
+            share_password.clone(),
+            opts.share_expiration,
+            opts.share_notification.as_ref(),
+        )
+        .await?;
+        print_share_link(term, &root_folder, &link, share_password.as_deref(), opts)?;
+    }
+
+    errors.report(false)
+}
+
+/// transfers files (source file and target parent) concurrently with a combined progress bar
+///
+/// failed files are recorded in given errors
+pub async fn transfer_files(
+    source_dracoon: &Dracoon<Connected>,
+    target_dracoon: &Dracoon<Connected>,
+    files: Vec<(Node, Node)>,
+    opts: &CmdTransferOptions,
+    errors: &TransferErrors,
+) {
+    let total_size = files
+        .iter()
+        .map(|(file, _)| file.size.unwrap_or(0))
+        .sum::<u64>();
+
+    let progress_bar = progress_bar(total_size);
+    progress_bar.set_style(
+        ProgressStyle::default_bar()
+        .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
+        .progress_chars("=>-"),
+    );
+
+    let count_files = files.len();
+    progress_bar.set_message(format!("Transferring {count_files} files"));
+
+    // equals min. 10 concurrent, max. 100 concurrent transfers
+    let velocity = opts
+        .velocity
+        .unwrap_or(MIN_VELOCITY)
+        .clamp(MIN_VELOCITY, MAX_VELOCITY);
+
+    let concurrent_reqs = velocity * DEFAULT_CONCURRENT_MULTIPLIER;
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrent_reqs as usize));
+    let remaining_files = Arc::new(AtomicU64::new(count_files as u64));
+
+    let mut handles = Vec::new();
+
+    for (file, target_parent) in files {
+        let file_path = to_node_path(&file);
+
+        let source_dracoon = source_dracoon.clone();
+        let target_dracoon = target_dracoon.clone();
+        let opts = opts.clone();
+        let progress_bar = progress_bar.clone();
+        let remaining_files = remaining_files.clone();
+        let semaphore = semaphore.clone();
+
+        let transfer_task = async move {
+            let _permit = semaphore.acquire().await.map_err(|err| {
+                error!("Error acquiring semaphore: {}", err);
+                DcCmdError::IoError
+            })?;
+            check_deadline()?;
+
+            debug!("Transferring file: {}", file.name);
+
+            transfer_file(
+                &source_dracoon,
+                &target_dracoon,
+                file,
+                target_parent,
+                &opts,
+                progress_bar.clone(),
+            )
+            .await?;
+
+            let remaining = remaining_files.fetch_sub(1, Ordering::Relaxed) - 1;
+            progress_bar.set_message(format!("Transferring {remaining} files"));
+
+            Ok::<(), DcCmdError>(())
+        };
+
+        handles.push((file_path, tokio::spawn(transfer_task)));
+    }
+
+    for (file_path, handle) in handles {
+        match handle.await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => errors.push(file_path, &e),
+            Err(e) => {
+                error!("Error transferring file: {}", e);
+                errors.push(file_path, &DcCmdError::Unknown);
+            }
+        }
+    }
+
+    progress_bar.finish_with_message(format!("Transfer of {count_files} files complete"));
+}
+
+/// creates all folders (sorted - parents first) below the root folder
+///
+/// returns the target folder per relative path (root folder is "")
+async fn create_target_folders(
+    dracoon: &Dracoon<Connected>,
+    root_folder: &Node,
+    paths: BTreeSet<String>,
+    opts: &CmdTransferOptions,
+) -> Result<HashMap<String, Node>, DcCmdError> {
+    let mut folders = HashMap::from([(String::new(), root_folder.clone())]);
+
+    for path in paths {
+        let trimmed = path.trim_end_matches('/');
+        let (parent, name) = match trimmed.rsplit_once('/') {
+            Some((parent, name)) => (format!("{parent}/"), name),
+            None => (String::new(), trimmed),
+        };
+
+        let Some(parent_node) = folders.get(&parent) else {
+            error!("Parent folder of {} not created.", path);
+            continue;
+        };
+
+        let folder = create_root_folder(
+            dracoon,
+            name,
+            parent_node.id,
+            &format!("{}/", to_node_path(parent_node)),
+            opts.classification,
+        )
+        .await?;
+
+        folders.insert(path, folder);
+    }
+
+    Ok(folders)
+}
+
+/// path of a file's parent relative to the source container (e.g. "sub/folder/")
+fn relative_parent(source_root: &str, parent_path: &str) -> Option<String> {
+    parent_path
+        .strip_prefix(source_root)
+        .map(ToString::to_string)
+}
+
+/// all folders (including intermediate folders) of given relative paths
+fn folder_paths<'a>(relative_parents: impl Iterator<Item = &'a String>) -> BTreeSet<String> {
+    let mut paths = BTreeSet::new();
+
+    for parent in relative_parents {
+        let mut path = String::new();
+
+        for segment in parent.split('/').filter(|s| !s.is_empty()) {
+            path.push_str(segment);
+            path.push('/');
+            paths.insert(path.clone());
+        }
+    }
+
+    paths
+}
+
+fn print_share_link(
+    term: &Term,
+    node: &Node,
+    link: &str,
+    share_password: Option<&str>,
+    opts: &CmdTransferOptions,
+) -> Result<(), DcCmdError> {
+    let success_msg = format_success_message(&format!("Shared {}.\n▶︎▶︎ {link}", node.name));
+
+    write_line(term, &format!("\n{success_msg}"))?;
+
+    if let (true, Some(password)) = (opts.generate_password, share_password) {
+        output_generated_password(term, password, opts.password_file.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// streams a file from the source into the target (download and upload run concurrently)
+async fn transfer_file(
+    source_dracoon: &Dracoon<Connected>,
+    target_dracoon: &Dracoon<Connected>,
+    source_node: Node,
+    parent_node: Node,
+    opts: &CmdTransferOptions,
+    progress_bar: ProgressBar,
+) -> Result<Node, DcCmdError> {
     let file_meta = FileMeta::builder(source_node.name.clone(), source_node.size.unwrap_or(0));
 
     let file_meta = if let Some(timestamp_modification) = source_node.timestamp_modification {
@@ -64,27 +387,34 @@ pub async fn transfer_node(
         ResolutionStrategy::AutoRename
     };
 
-    let progress_bar = ProgressBar::new(source_node.size.unwrap_or(0));
-    progress_bar.set_style(
-    ProgressStyle::default_bar()
-    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
-    .progress_chars("=>-"),
-);
-
     let upload_options = UploadOptions::builder(file_meta)
         .with_resolution_strategy(resolution_strategy)
         .with_keep_share_links(opts.keep_share_links)
-        .with_classification(opts.classification.unwrap_or(1))
-        .build();
+        .with_classification(opts.classification.map_or_else(
+            // keep the source classification unless passed
+            || {
+                source_node
+                    .classification
+                    .and_then(|classification| u8::try_from(classification).ok())
+                    .unwrap_or_else(|| u8::from(ClassificationLevel::Public))
+            },
+            u8::from,
+        ));
 
-    let progress_bar_mv = progress_bar.clone();
+    let upload_options = match opts.expiration {
+        Some(expiration) => upload_options
+            .with_expiration(to_object_expiration(Some(expiration)))
+            .build(),
+        None => upload_options.build(),
+    };
 
     let callback = move |read_bytes: u64, _total: u64| {
-        progress_bar_mv.inc(read_bytes);
+        progress_bar.inc(read_bytes);
     };
 
     let (writer, reader) = duplex(MAX_BUFFER_SIZE);
 
+    let source_dracoon = source_dracoon.clone();
     let download_task = tokio::spawn(async move {
         let mut buf_writer = BufWriter::new(writer);
         let res = source_dracoon
@@ -104,6 +434,7 @@ pub async fn transfer_node(
         res
     });
 
+    let target_dracoon_mv = target_dracoon.clone();
     let upload_task = tokio::spawn(async move {
         let buf_reader = BufReader::new(reader);
         target_dracoon_mv
@@ -116,23 +447,12 @@ pub async fn transfer_node(
         .unwrap_or((Err(DcCmdError::Unknown), Err(DcCmdError::Unknown)));
 
     download_res?;
-    let node = upload_res?;
 
-    if !node.is_encrypted.unwrap_or(false) && opts.share {
-        let link = share_node(&target_dracoon, &node, opts.share_password).await?;
-        let file_name = node.name.clone();
-        let success_msg =
-            format_success_message(format!("Shared {file_name}.\n▶︎▶︎ {link}").as_str());
-        let success_msg = format!("\n{success_msg}");
-
-        term.write_line(&success_msg)
-            .expect("Error writing message to terminal.");
+    if let Err(ref e) = upload_res {
+        report_s3_connection_error(target_dracoon, e).await;
     }
 
-    let msg = format!("Node {} uploaded from {source} to {target}.", node.name);
-    progress_bar.finish_with_message(msg);
-
-    Ok(())
+    upload_res
 }
 
 async fn get_node_from_path(path: &str, dracoon: &Dracoon<Connected>) -> Result<Node, DcCmdError> {
@@ -177,3 +497,35 @@ async fn get_transfer_nodes(
 
     Ok((source_node, target_node))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_parent() {
+        assert_eq!(
+            Some("sub/folder/".to_string()),
+            relative_parent("/room/source/", "/room/source/sub/folder/")
+        );
+        assert_eq!(
+            Some(String::new()),
+            relative_parent("/room/source/", "/room/source/")
+        );
+        assert_eq!(None, relative_parent("/room/source/", "/room/other/"));
+    }
+
+    #[test]
+    fn test_folder_paths() {
+        let parents = [
+            "a/b/c/".to_string(),
+            String::new(),
+            "a/d/".to_string(),
+            "e/".to_string(),
+        ];
+
+        let paths = folder_paths(parents.iter()).into_iter().collect::<Vec<_>>();
+
+        assert_eq!(vec!["a/", "a/b/", "a/b/c/", "a/d/", "e/"], paths);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use tracing::{error, warn};
+
+use crate::cmd::{get_error_message, models::DcCmdError};
+
+/// report of all failed files in a bulk transfer (written to the working directory)
+pub const TRANSFER_ERRORS_FILE: &str = "transfer-errors.csv";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct TransferError {
+    path: String,
+    error: String,
+}
+
+/// collects per-file failures of a bulk upload / download (shared across tasks)
+#[derive(Clone, Default)]
+pub struct TransferErrors {
+    errors: Arc<Mutex<Vec<TransferError>>>,
+}
+
+impl TransferErrors {
+    pub fn push(&self, path: impl Into<String>, err: &DcCmdError) {
+        let path = path.into();
+        let error = get_error_message(err);
+        error!("Transfer of {} failed: {}", path, error);
+
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push(TransferError { path, error });
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors
+            .lock()
+            .map(|errors| errors.is_empty())
+            .unwrap_or(true)
+    }
+
+    fn take(&self) -> Vec<TransferError> {
+        self.errors
+            .lock()
+            .map(|mut errors| std::mem::take(&mut *errors))
+            .unwrap_or_default()
+    }
+
+    /// writes all failures to `transfer-errors.csv` and fails if any file failed
+    /// (unless `continue_on_error` is set)
+    pub fn report(&self, continue_on_error: bool) -> Result<(), DcCmdError> {
+        let mut errors = self.take();
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        errors.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let path = Path::new(TRANSFER_ERRORS_FILE);
+        let file = std::fs::File::create(path).map_err(|e| {
+            error!("Error creating {}: {}", path.display(), e);
+            DcCmdError::IoError
+        })?;
+
+        write_errors_csv(&errors, file)?;
+
+        let msg = format!(
+            "{} file(s) failed - see {} for details.",
+            errors.len(),
+            path.display()
+        );
+
+        if continue_on_error {
+            warn!("{}", msg);
+            Ok(())
+        } else {
+            Err(DcCmdError::TransferFailed(msg))
+        }
+    }
+}
+
+fn write_errors_csv(errors: &[TransferError], writer: impl Write) -> Result<(), DcCmdError> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    for err in errors {
+        writer.serialize(err).map_err(|e| {
+            error!("Failed to write transfer error: {}", e);
+            DcCmdError::IoError
+        })?;
+    }
+
+    writer.flush().map_err(|_| DcCmdError::IoError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_errors_csv() {
+        let errors = vec![
+            TransferError {
+                path: "/room/file.pdf".into(),
+                error: "Error reading / writing content.".into(),
+            },
+            TransferError {
+                path: "/room/a, b.txt".into(),
+                error: "Invalid path: /room".into(),
+            },
+        ];
+
+        let mut buf = Vec::new();
+        write_errors_csv(&errors, &mut buf).unwrap();
+
+        assert_eq!(
+            "path,error\n/room/file.pdf,Error reading / writing content.\n\"/room/a, b.txt\",Invalid path: /room\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_take_errors() {
+        let errors = TransferErrors::default();
+        errors.push("/room/file.pdf", &DcCmdError::IoError);
+
+        let taken = errors.take();
+
+        assert_eq!(1, taken.len());
+        assert_eq!("/room/file.pdf", taken[0].path);
+        assert!(errors.take().is_empty());
+    }
+
+    #[test]
+    fn test_report_without_errors() {
+        assert!(TransferErrors::default().report(false).is_ok());
+    }
+}
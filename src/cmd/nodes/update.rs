@@ -0,0 +1,64 @@
+use console::Term;
+use dco3::{
+    nodes::{models::NodeType, Folders, UpdateFolderRequest},
+    Nodes,
+};
+use tracing::info;
+
+use crate::cmd::{
+    init_dracoon,
+    models::DcCmdError,
+    utils::strings::{build_node_path, format_success_message, parse_path},
+};
+
+use super::models::CmdUpdateFolderOptions;
+
+pub async fn update_folder(
+    term: Term,
+    source: String,
+    opts: CmdUpdateFolderOptions,
+) -> Result<(), DcCmdError> {
+    if opts.name.is_none() && opts.notes.is_none() && opts.classification.is_none() {
+        return Err(DcCmdError::InvalidArgument(
+            "At least one of --name, --notes or --classification is required.".to_string(),
+        ));
+    }
+
+    let dracoon = init_dracoon(&source, opts.auth, false).await?;
+
+    let (parent_path, node_name, depth) = parse_path(&source, dracoon.get_base_url().as_ref())?;
+    let node_path = build_node_path((parent_path, node_name, depth));
+
+    let node = dracoon
+        .nodes()
+        .get_node_from_path(&node_path)
+        .await?
+        .ok_or(DcCmdError::InvalidPath(source.clone()))?;
+
+    if node.node_type != NodeType::Folder {
+        return Err(DcCmdError::InvalidArgument(format!("Not a folder: {}", node.name)));
+    }
+
+    let mut req = UpdateFolderRequest::builder();
+
+    if let Some(name) = opts.name {
+        req = req.with_name(name);
+    }
+
+    if let Some(notes) = opts.notes {
+        req = req.with_notes(notes);
+    }
+
+    if let Some(classification) = opts.classification {
+        req = req.with_classification(classification.into());
+    }
+
+    let folder = dracoon.nodes().update_folder(node.id, req.build()).await?;
+
+    let msg = format!("Folder {} updated.", folder.name);
+    info!("{}", msg);
+    term.write_line(&format_success_message(&msg))
+        .map_err(|_| DcCmdError::IoError)?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsStr,
+    path::PathBuf,
+};
+
+use dco3::{
+    auth::Connected,
+    nodes::{ResolutionStrategy, TransferNodesRequest},
+    Dracoon, Nodes,
+};
+use futures_util::{stream, StreamExt};
+use tracing::{debug, info};
+
+use crate::cmd::{config::MAX_CONCURRENT_REQUESTS, models::DcCmdError};
+
+use super::files::hash_file;
+
+/// a file with the same name, size and content as another file of the upload
+/// (server-copied from the uploaded original instead of uploaded)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    pub source: PathBuf,
+    pub original: PathBuf,
+    pub parent_id: u64,
+    pub size: u64,
+}
+
+/// finds duplicates of all files to upload (files are mapped to parent id and size)
+///
+/// only files with same name and size are hashed - the first file (path order) is the original
+pub async fn find_duplicates(
+    files: &BTreeMap<PathBuf, (u64, u64)>,
+) -> Result<Vec<Duplicate>, DcCmdError> {
+    let mut duplicates = Vec::new();
+
+    for candidates in group_candidates(files) {
+        let mut originals: HashMap<String, &PathBuf> = HashMap::new();
+
+        for source in candidates {
+            let hash = hash_file(source).await?;
+            let (parent_id, size) = files[source];
+
+            match originals.get(&hash) {
+                Some(original) => duplicates.push(Duplicate {
+                    source: source.clone(),
+                    original: (*original).clone(),
+                    parent_id,
+                    size,
+                }),
+                None => {
+                    originals.insert(hash, source);
+                }
+            }
+        }
+    }
+
+    info!("Found {} duplicate files.", duplicates.len());
+
+    Ok(duplicates)
+}
+
+/// copies duplicates from their uploaded originals (node ids by source path)
+///
+/// returns the results per duplicate - duplicates of failed uploads fail as well
+pub async fn copy_duplicates(
+    dracoon: &Dracoon<Connected>,
+    duplicates: Vec<Duplicate>,
+    uploaded: &HashMap<PathBuf, u64>,
+    overwrite: bool,
+    keep_share_links: bool,
+) -> Vec<(Duplicate, Result<(), DcCmdError>)> {
+    stream::iter(duplicates)
+        .map(|duplicate| async move {
+            let Some(node_id) = uploaded.get(&duplicate.original).copied() else {
+                let err = DcCmdError::TransferFailed(format!(
+                    "Upload of {} failed.",
+                    duplicate.original.display()
+                ));
+                return (duplicate, Err(err));
+            };
+
+            let resolution_strategy = if overwrite {
+                ResolutionStrategy::Overwrite
+            } else {
+                ResolutionStrategy::AutoRename
+            };
+
+            let req = TransferNodesRequest::from(vec![node_id])
+                .with_resolution_strategy(resolution_strategy)
+                .with_keep_share_links(overwrite && keep_share_links);
+
+            let res = dracoon
+                .nodes()
+                .copy_nodes(req, duplicate.parent_id)
+                .await
+                .map(|_| debug!("Copied duplicate {}", duplicate.source.display()))
+                .map_err(DcCmdError::from);
+
+            (duplicate, res)
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
+}
+
+/// groups of files with same name and size (empty files are not deduplicated)
+fn group_candidates(files: &BTreeMap<PathBuf, (u64, u64)>) -> Vec<Vec<&PathBuf>> {
+    let mut groups: BTreeMap<(&OsStr, u64), Vec<&PathBuf>> = BTreeMap::new();
+
+    for (source, (_, size)) in files {
+        if let (Some(name), true) = (source.file_name(), *size > 0) {
+            groups.entry((name, *size)).or_default().push(source);
+        }
+    }
+
+    groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .collect()
+}
+
+/// removes duplicates from the files to upload
+pub fn remove_duplicates(files: &mut BTreeMap<PathBuf, (u64, u64)>, duplicates: &[Duplicate]) {
+    for duplicate in duplicates {
+        files.remove(&duplicate.source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn files() -> BTreeMap<PathBuf, (u64, u64)> {
+        BTreeMap::from([
+            (PathBuf::from("/a/template.docx"), (1, 100)),
+            (PathBuf::from("/b/template.docx"), (2, 100)),
+            (PathBuf::from("/c/template.docx"), (3, 200)),
+            (PathBuf::from("/c/other.docx"), (3, 100)),
+            (PathBuf::from("/a/empty.txt"), (1, 0)),
+            (PathBuf::from("/b/empty.txt"), (2, 0)),
+        ])
+    }
+
+    #[test]
+    fn test_group_candidates() {
+        let files = files();
+        let groups = group_candidates(&files);
+
+        assert_eq!(
+            groups,
+            vec![vec![
+                &PathBuf::from("/a/template.docx"),
+                &PathBuf::from("/b/template.docx")
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let mut files = files();
+        let duplicates = vec![Duplicate {
+            source: PathBuf::from("/b/template.docx"),
+            original: PathBuf::from("/a/template.docx"),
+            parent_id: 2,
+            size: 100,
+        }];
+
+        remove_duplicates(&mut files, &duplicates);
+
+        assert_eq!(files.len(), 5);
+        assert!(!files.contains_key(Path::new("/b/template.docx")));
+    }
+}
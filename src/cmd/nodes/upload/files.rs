@@ -1,10 +1,11 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     fs::Metadata,
+    io::Read,
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
-        Arc,
+        Arc, Mutex,
     },
     time::SystemTime,
 };
@@ -13,9 +14,9 @@ use console::Term;
 use dco3::{
     auth::Connected,
     nodes::{FileMeta, Node, ResolutionStrategy, UploadOptions},
-    Dracoon, Nodes, Public, PublicUpload, Upload,
+    Dracoon, MissingFileKeys, Nodes, Public, PublicUpload,
 };
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use tracing::{debug, error, info, warn};
 use unicode_normalization::UnicodeNormalization;
 
@@ -23,11 +24,34 @@ use crate::cmd::{
     config::{DEFAULT_CHUNK_SIZE, DEFAULT_CONCURRENT_MULTIPLIER, MAX_VELOCITY, MIN_VELOCITY},
     init_public_dracoon,
     models::DcCmdError,
-    nodes::{models::CmdUploadOptions, share::share_node},
-    utils::{dates::to_datetime_utc, strings::format_success_message},
+    nodes::{
+        checkpoint::TransferCheckpoint,
+        expiration::to_object_expiration,
+        models::{ClassificationLevel, CmdUploadOptions},
+        share::{get_share_password, output_generated_password, share_node},
+        upload::{
+            dedupe::{copy_duplicates, find_duplicates, remove_duplicates},
+            proxied::upload_to_node,
+        },
+        to_node_path,
+        transfer_errors::TransferErrors,
+    },
+    utils::{
+        dates::to_datetime_utc,
+        deadline::check_deadline,
+        file_keys::distribute_missing_keys_batched,
+        rate_limit::with_rate_limit,
+        progress::{progress_bar, write_line},
+        stats::BatchStats,
+        strings::format_success_message,
+    },
 };
 
-pub async fn upload_public_file(source: PathBuf, target: String) -> Result<(), DcCmdError> {
+pub async fn upload_public_file(
+    source: PathBuf,
+    target: String,
+    opts: &CmdUploadOptions,
+) -> Result<(), DcCmdError> {
     let file = tokio::fs::File::open(&source).await.map_err(|err| {
         error!("Error opening file: {}", err);
         DcCmdError::IoError
@@ -48,16 +72,44 @@ pub async fn upload_public_file(source: PathBuf, target: String) -> Result<(), D
 
     let access_key = target
         .split('/')
-        .last()
+        .next_back()
         .ok_or(DcCmdError::InvalidPath(target.clone()))?;
 
     let upload_share = dracoon.public().get_public_upload_share(access_key).await?;
 
-    let file_meta = get_file_meta(&file_meta, &source)?;
+    // encrypted file requests: the file key is encrypted with the public keys of all
+    // recipients (users with a key pair) provided by the share
+    let is_encrypted = upload_share.is_encrypted.unwrap_or(false);
+
+    if is_encrypted {
+        let recipients = upload_share
+            .user_user_public_key_list
+            .as_ref()
+            .map_or(0, |keys| keys.items.len());
+
+        if recipients == 0 {
+            error!(
+                "No public keys provided for encrypted file request {}",
+                access_key
+            );
+            return Err(DcCmdError::InvalidArgument(
+                "Encrypted file request has no recipients with a key pair - upload not possible."
+                    .to_string(),
+            ));
+        }
+
+        info!(
+            "Encrypting {} for {} recipient key(s).",
+            source.display(),
+            recipients
+        );
+    }
+
+    let file_meta = get_file_meta(&file_meta, opts.remote_name(&get_file_name(&source)?));
 
     let file_size = file_meta.size;
 
-    let progress_bar = ProgressBar::new(file_size);
+    let progress_bar = progress_bar(file_size);
     progress_bar.set_style(
     ProgressStyle::default_bar()
     .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -70,7 +122,11 @@ pub async fn upload_public_file(source: PathBuf, target: String) -> Result<(), D
 
     let progress_bar_mv = progress_bar.clone();
 
-    progress_bar_mv.set_message("Uploading");
+    progress_bar_mv.set_message(if is_encrypted {
+        "Encrypting and uploading"
+    } else {
+        "Uploading"
+    });
     progress_bar_mv.set_length(file_size);
 
     dracoon
@@ -115,10 +171,17 @@ pub async fn upload_file(
         ));
     }
 
-    let file_meta = get_file_meta(&file_meta, &source)?;
+    let file_meta = get_file_meta(&file_meta, opts.remote_name(&get_file_name(&source)?));
     let file_name = file_meta.name.clone();
 
-    let progress_bar = ProgressBar::new(file_meta.size);
+    if opts.skip_unchanged && is_unchanged(dracoon, target_node, &source, &file_meta).await? {
+        info!("Skipped unchanged file: {}", source.to_string_lossy());
+        let msg = format_success_message(&format!("Skipped unchanged file {file_name}."));
+        write_line(&term, &msg)?;
+        return Ok(());
+    }
+
+    let progress_bar = progress_bar(file_meta.size);
     progress_bar.set_style(
     ProgressStyle::default_bar()
     .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -130,7 +193,10 @@ pub async fn upload_file(
     progress_bar_mv.set_message("Uploading");
     progress_bar_mv.set_length(file_meta.size);
 
-    let classification = opts.classification.unwrap_or(2);
+    let classification = opts
+        .classification
+        .unwrap_or(ClassificationLevel::Internal)
+        .into();
     let resolution_strategy = if opts.overwrite {
         ResolutionStrategy::Overwrite
     } else {
@@ -146,35 +212,60 @@ pub async fn upload_file(
     let upload_options = UploadOptions::builder(file_meta)
         .with_classification(classification)
         .with_resolution_strategy(resolution_strategy)
-        .with_keep_share_links(keep_share_links)
-        .build();
+        .with_keep_share_links(keep_share_links);
 
-    let reader = tokio::io::BufReader::new(file);
+    let upload_options = match opts.expiration {
+        Some(expiration) => upload_options
+            .with_expiration(to_object_expiration(Some(expiration)))
+            .build(),
+        None => upload_options.build(),
+    };
 
-    let node = dracoon
-        .upload(
-            target_node,
-            upload_options,
-            reader,
-            Some(Box::new(move |progress, _| {
-                progress_bar_mv.inc(progress);
-            })),
-            Some(DEFAULT_CHUNK_SIZE),
-        )
-        .await?;
+    let node = upload_to_node(
+        dracoon,
+        target_node,
+        upload_options,
+        &source,
+        &progress_bar_mv,
+        Some(DEFAULT_CHUNK_SIZE),
+        !opts.no_direct_s3,
+        opts.verify,
+    )
+    .await?;
 
     progress_bar.finish_with_message(format!("Upload of {file_name} complete"));
     info!("Upload of {} complete.", source.to_string_lossy());
 
+    if opts.verify {
+        verify_upload(&node, &source).await?;
+    }
+
     let is_encrypted = node.is_encrypted.unwrap_or(false);
 
+    if is_encrypted {
+        distribute_file_keys(dracoon, &node).await;
+    }
+
     if !is_encrypted && opts.share {
-        let link = share_node(dracoon, &node, opts.share_password).await?;
+        let share_password =
+            get_share_password(dracoon, opts.share_password, opts.generate_password).await?;
+        let link = share_node(
+            dracoon,
+            &node,
+            share_password.clone(),
+            opts.share_expiration,
+            opts.share_notification.as_ref(),
+        )
+        .await?;
         let success_msg =
             format_success_message(format!("Shared {file_name}.\n▶︎▶︎ {link}").as_str());
         let success_msg = format!("\n{success_msg}");
 
-        term.write_line(&success_msg).or(Err(DcCmdError::IoError))?;
+        write_line(&term, &success_msg)?;
+
+        if let (true, Some(password)) = (opts.generate_password, &share_password) {
+            output_generated_password(&term, password, opts.password_file.as_deref())?;
+        }
     }
 
     Ok(())
@@ -183,11 +274,26 @@ pub async fn upload_file(
 pub async fn upload_files(
     dracoon: &Dracoon<Connected>,
     parent_node: &Node,
-    files: BTreeMap<PathBuf, (u64, u64)>,
+    mut files: BTreeMap<PathBuf, (u64, u64)>,
     opts: CmdUploadOptions,
+    errors: &TransferErrors,
+    checkpoint: &TransferCheckpoint,
 ) -> Result<(), DcCmdError> {
+    // files uploaded before an interruption are skipped
+    files.retain(|source, _| !checkpoint.is_completed(&source.to_string_lossy()));
+
     info!("Attempting upload of {} files.", files.len());
 
+    // encrypted files cannot be copied without distributing file keys - uploaded instead
+    let duplicates = if opts.dedupe && parent_node.is_encrypted != Some(true) {
+        find_duplicates(&files).await?
+    } else {
+        if opts.dedupe {
+            warn!("Deduplication is not supported for encrypted rooms.");
+        }
+        Vec::new()
+    };
+
     // equals min. 5 concurrent, max. 50 concurrent requests
     let velocity = opts
         .velocity
@@ -198,8 +304,10 @@ pub async fn upload_files(
     let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrent_reqs as usize));
 
     let total_size = files.values().map(|(_, size)| size).sum::<u64>();
+    let count_files = files.len();
+    remove_duplicates(&mut files, &duplicates);
 
-    let progress_bar = ProgressBar::new(total_size);
+    let progress_bar = progress_bar(total_size);
     progress_bar.set_style(
         ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}) {msg}").unwrap()
@@ -207,17 +315,21 @@ pub async fn upload_files(
     );
 
     progress_bar.set_length(total_size);
-    let count_files = files.len();
     let message = format!("Uploading {count_files} files");
     progress_bar.set_message(message.clone());
+    let stats = BatchStats::new(count_files as u64);
+    let stats_line = stats.attach(&progress_bar);
     let remaining_files = Arc::new(AtomicU64::new(files.len() as u64));
     let uploaded_files = Arc::new(AtomicUsize::new(0));
+    let skipped_files = Arc::new(AtomicUsize::new(0));
+    let uploaded_nodes = Arc::new(Mutex::new(HashMap::new()));
 
     let files_iter: Vec<_> = files.into_iter().collect();
 
     let mut handles = Vec::new();
 
     for (source, (node_id, _)) in files_iter {
+        let source_path = source.to_string_lossy().to_string();
         let dracoon = dracoon.clone();
         let progress_bar = progress_bar.clone();
         let progress_bar_mv = progress_bar.clone();
@@ -225,13 +337,18 @@ pub async fn upload_files(
         let client = dracoon.clone();
         let remaining_files = remaining_files.clone();
         let uploaded_files = uploaded_files.clone();
+        let skipped_files = skipped_files.clone();
+        let uploaded_nodes = uploaded_nodes.clone();
         let semaphore = semaphore.clone();
+        let opts = opts.clone();
+        let checkpoint = checkpoint.clone();
 
         let upload_task = async move {
             let _permit = semaphore.acquire().await.map_err(|err| {
                 error!("Error acquiring semaphore: {}", err);
                 DcCmdError::IoError
             })?;
+            check_deadline()?;
 
             debug!("Uploading file: {}", source.to_string_lossy());
             let file = tokio::fs::File::open(&source).await.map_err(|err| {
@@ -242,11 +359,25 @@ pub async fn upload_files(
             let parent_node = client.nodes().get_node(node_id).await?;
 
             let file_meta = file.metadata().await.or(Err(DcCmdError::IoError))?;
-            let file_meta = get_file_meta(&file_meta, &source)?;
+            let file_meta = get_file_meta(&file_meta, opts.remote_name(&get_file_name(&source)?));
 
             let file_name = file_meta.name.clone();
 
-            let classification = opts.classification.unwrap_or(2);
+            if opts.skip_unchanged
+                && is_unchanged(&client, &parent_node, &source, &file_meta).await?
+            {
+                debug!("Skipped unchanged file: {}", file_name);
+                progress_bar_inc.inc(file_meta.size);
+                _ = &remaining_files.fetch_sub(1, Ordering::Relaxed);
+                _ = &skipped_files.fetch_add(1, Ordering::Relaxed);
+                checkpoint.complete(&source.to_string_lossy());
+                return Ok(());
+            }
+
+            let classification = opts
+                .classification
+                .unwrap_or(ClassificationLevel::Internal)
+                .into();
             let resolution_strategy = if opts.overwrite {
                 ResolutionStrategy::Overwrite
             } else {
@@ -262,26 +393,42 @@ pub async fn upload_files(
             let upload_options = UploadOptions::builder(file_meta)
                 .with_classification(classification)
                 .with_resolution_strategy(resolution_strategy)
-                .with_keep_share_links(keep_share_links)
-                .build();
-
-            let reader = tokio::io::BufReader::new(file);
-
-            match client
-                .upload(
-                    &parent_node,
-                    upload_options,
-                    reader,
-                    Some(Box::new(move |progress: u64, _total: u64| {
-                        progress_bar_mv.inc(progress);
-                    })),
-                    None,
-                )
-                .await
-                .inspect_err(|_e| {
-                    error!("Error uploading file: {}", file_name);
-                }) {
-                Ok(_) => {
+                .with_keep_share_links(keep_share_links);
+
+            let upload_options = match opts.expiration {
+                Some(expiration) => upload_options
+                    .with_expiration(to_object_expiration(Some(expiration)))
+                    .build(),
+                None => upload_options.build(),
+            };
+
+            match upload_to_node(
+                &client,
+                &parent_node,
+                upload_options,
+                &source,
+                &progress_bar_mv,
+                None,
+                !opts.no_direct_s3,
+                opts.verify,
+            )
+            .await
+            {
+                Ok(node) => {
+                    if opts.verify {
+                        verify_upload(&node, &source).await?;
+                    }
+
+                    if node.is_encrypted == Some(true) {
+                        distribute_file_keys(&client, &node).await;
+                    }
+
+                    if let Ok(mut uploaded_nodes) = uploaded_nodes.lock() {
+                        uploaded_nodes.insert(source.clone(), node.id);
+                    }
+
+                    checkpoint.complete(&source.to_string_lossy());
+
                     _ = &remaining_files.fetch_sub(1, Ordering::Relaxed);
                     _ = &uploaded_files.fetch_add(1, Ordering::Relaxed);
                     debug!("Uploaded file: {}", file_name);
@@ -293,49 +440,215 @@ pub async fn upload_files(
                 }
                 Err(e) => {
                     error!("Error uploading file: {file_name} ({e})");
-                    return Err(e.into());
+                    return Err(e);
                 }
             }
 
             Ok::<(), DcCmdError>(())
         };
 
-        handles.push(tokio::spawn(upload_task));
+        let stats = stats.clone();
+        let upload_task = async move {
+            let result = upload_task.await;
+            stats.record(result.is_ok());
+            result
+        };
+
+        handles.push((source_path, tokio::spawn(upload_task)));
+    }
+
+    for (source_path, handle) in handles {
+        match handle.await {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => errors.push(source_path, &e),
+            Err(e) => {
+                error!("Error uploading file: {}", e);
+                errors.push(source_path, &DcCmdError::Unknown);
+            }
+        }
     }
 
-    for handle in handles {
-        if let Err(e) = handle.await {
-            error!("Error uploading file: {}", e);
-            return Err(DcCmdError::IoError);
+    let uploaded_nodes = uploaded_nodes
+        .lock()
+        .map(|uploaded_nodes| uploaded_nodes.clone())
+        .unwrap_or_default();
+
+    for (duplicate, res) in copy_duplicates(
+        dracoon,
+        duplicates,
+        &uploaded_nodes,
+        opts.overwrite,
+        opts.keep_share_links,
+    )
+    .await
+    {
+        progress_bar.inc(duplicate.size);
+        stats.record(res.is_ok());
+
+        match res {
+            Ok(()) => {
+                uploaded_files.fetch_add(1, Ordering::Relaxed);
+                checkpoint.complete(&duplicate.source.to_string_lossy());
+            }
+            Err(e) => errors.push(duplicate.source.to_string_lossy().to_string(), &e),
         }
     }
 
     let target = parent_node.name.clone();
 
     progress_bar.finish_with_message(format!("Upload to {target} complete"));
+    stats_line.finish(&stats);
+    stats.log_summary("upload");
     let uploaded_files = uploaded_files.load(Ordering::Relaxed);
+    let skipped_files = skipped_files.load(Ordering::Relaxed);
 
     info!("Upload of {uploaded_files} files to {target} complete.");
 
-    if uploaded_files != count_files {
+    if skipped_files > 0 {
+        info!("Skipped {skipped_files} unchanged files.");
+    }
+
+    if uploaded_files + skipped_files != count_files {
         warn!(
             "Failed to upload {} files to {target}.",
-            count_files - uploaded_files
+            count_files - uploaded_files - skipped_files
         );
     }
 
     Ok(())
 }
 
-fn get_file_meta(file_meta: &Metadata, file_path: &Path) -> Result<FileMeta, DcCmdError> {
-    let file_name = file_path
+/// distributes the file key of an encrypted upload to all other room members
+///
+/// the upload itself succeeded - failures are logged (keys can be distributed later)
+async fn distribute_file_keys(dracoon: &Dracoon<Connected>, node: &Node) {
+    let res = distribute_missing_keys_batched(|| {
+        with_rate_limit(|| async {
+            dracoon
+                .distribute_missing_keys(None, Some(node.id), None)
+                .await
+                .map_err(DcCmdError::from)
+        })
+    })
+    .await;
+
+    match res {
+        Ok(distribution) => debug!(
+            "Distributed {} file keys for {}.",
+            distribution.distributed, node.name
+        ),
+        Err(e) => warn!("Failed to distribute file keys for {}: {e}", node.name),
+    }
+}
+
+/// checks if a file with same size and hash already exists in given parent
+///
+/// files without a hash in DRACOON are always treated as changed
+async fn is_unchanged(
+    dracoon: &Dracoon<Connected>,
+    parent_node: &Node,
+    source: &Path,
+    file_meta: &FileMeta,
+) -> Result<bool, DcCmdError> {
+    let path = format!("{}/{}/", to_node_path(parent_node), file_meta.name);
+
+    let Some(node) = dracoon.nodes().get_node_from_path(&path).await? else {
+        return Ok(false);
+    };
+
+    if node.size != Some(file_meta.size) {
+        return Ok(false);
+    }
+
+    let Some(remote_hash) = node.hash else {
+        debug!("No hash available for {}", path);
+        return Ok(false);
+    };
+
+    let local_hash = hash_file(source).await?;
+
+    Ok(local_hash.eq_ignore_ascii_case(&remote_hash))
+}
+
+/// compares the hash of an uploaded node with the local file
+///
+/// encrypted nodes and nodes without a hash cannot be verified (skipped with a warning)
+async fn verify_upload(node: &Node, source: &Path) -> Result<(), DcCmdError> {
+    if node.is_encrypted == Some(true) {
+        warn!("Skipped verification of encrypted file {}.", node.name);
+        return Ok(());
+    }
+
+    let Some(remote_hash) = node.hash.as_deref() else {
+        warn!("No hash available - skipped verification of {}.", node.name);
+        return Ok(());
+    };
+
+    let local_hash = hash_file(source).await?;
+
+    check_hash(&node.name, &local_hash, remote_hash)?;
+    debug!("Verified {} (MD5: {})", node.name, local_hash);
+
+    Ok(())
+}
+
+fn check_hash(name: &str, local_hash: &str, remote_hash: &str) -> Result<(), DcCmdError> {
+    if local_hash.eq_ignore_ascii_case(remote_hash) {
+        return Ok(());
+    }
+
+    error!(
+        "Checksum mismatch for {} (local: {}, DRACOON: {})",
+        name, local_hash, remote_hash
+    );
+
+    Err(DcCmdError::TransferFailed(format!(
+        "Checksum mismatch for {name} (local: {local_hash}, DRACOON: {remote_hash})."
+    )))
+}
+
+pub(super) async fn hash_file(source: &Path) -> Result<String, DcCmdError> {
+    let source = source.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&source)?;
+        compute_md5(file)
+    })
+    .await
+    .map_err(|_| DcCmdError::IoError)?
+    .map_err(|err| {
+        error!("Error hashing file: {}", err);
+        DcCmdError::IoError
+    })
+}
+
+fn compute_md5(mut reader: impl Read) -> std::io::Result<String> {
+    let mut context = md5::Context::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.consume(&buffer[..read]);
+    }
+
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// returns the (NFC normalized) local file name
+fn get_file_name(file_path: &Path) -> Result<String, DcCmdError> {
+    file_path
         .file_name()
         .map(|name| name.to_string_lossy().into_owned())
         .map(|n| n.nfc().collect::<String>())
         .ok_or(DcCmdError::InvalidPath(
             file_path.to_string_lossy().to_string(),
-        ))?;
+        ))
+}
 
+fn get_file_meta(file_meta: &Metadata, file_name: String) -> FileMeta {
     let timestamp_modification = file_meta
         .modified()
         .or(Err(DcCmdError::IoError))
@@ -350,10 +663,10 @@ fn get_file_meta(file_meta: &Metadata, file_path: &Path) -> Result<FileMeta, DcC
 
     let timestamp_creation = to_datetime_utc(timestamp_creation);
 
-    Ok(FileMeta::builder(file_name, file_meta.len())
+    FileMeta::builder(file_name, file_meta.len())
         .with_timestamp_modification(timestamp_modification)
         .with_timestamp_creation(timestamp_creation)
-        .build())
+        .build()
 }
 
 fn calculate_buffer_size(file_size: u64) -> usize {
@@ -365,3 +678,28 @@ fn calculate_buffer_size(file_size: u64) -> usize {
         _ => 256 * 1024,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_md5() {
+        let hash = compute_md5(b"hello".as_slice()).unwrap();
+        assert_eq!(hash, "5d41402abc4b2a76b9719d911017c592");
+    }
+
+    #[test]
+    fn test_compute_md5_empty() {
+        let hash = compute_md5(b"".as_slice()).unwrap();
+        assert_eq!(hash, "d41d8cd98f00b204e9800998ecf8427e");
+    }
+
+    #[test]
+    fn test_check_hash() {
+        let hash = "5d41402abc4b2a76b9719d911017c592";
+
+        assert!(check_hash("hello.txt", hash, &hash.to_uppercase()).is_ok());
+        assert!(check_hash("hello.txt", hash, "d41d8cd98f00b204e9800998ecf8427e").is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
 use async_recursion::async_recursion;
+use console::Term;
 use dashmap::DashMap;
 use dco3::{
     auth::Connected,
@@ -13,7 +14,8 @@ use dco3::{
     Dracoon, Folders, Nodes,
 };
 
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressStyle};
+use tokio::{sync::Semaphore, task::JoinSet};
 
 use tracing::{debug, error, info};
 use unicode_normalization::UnicodeNormalization;
@@ -21,11 +23,24 @@ use unicode_normalization::UnicodeNormalization;
 use crate::cmd::{
     config::MAX_CONCURRENT_REQUESTS,
     models::DcCmdError,
-    nodes::{models::CmdUploadOptions, upload::files::upload_files},
+    nodes::{
+        checkpoint::TransferCheckpoint,
+        models::{ClassificationLevel, CmdUploadOptions},
+        share::{get_share_password, output_generated_password, share_container},
+        to_node_path,
+        transfer_errors::TransferErrors,
+        upload::files::upload_files,
+    },
+    utils::{
+        confirm::confirm_large_operation,
+        progress::{progress_bar, spinner, write_line},
+        strings::format_success_message,
+    },
 };
 
 #[allow(clippy::too_many_lines)]
 pub async fn upload_container(
+    term: &Term,
     dracoon: &Dracoon<Connected>,
     source: PathBuf,
     target: &Node,
@@ -44,6 +59,12 @@ pub async fn upload_container(
             source.to_string_lossy().to_string(),
         ))?;
 
+    if opts.share && opts.skip_root {
+        return Err(DcCmdError::InvalidArgument(
+            "Sharing requires the root folder (remove --skip-root).".to_string(),
+        ));
+    }
+
     if source.is_relative() {
         error!("Only absolute paths are supported.");
         return Err(DcCmdError::InvalidPath(
@@ -51,183 +72,313 @@ pub async fn upload_container(
         ));
     }
 
-    let progress = MultiProgress::new();
-    let progress_spinner = ProgressBar::new_spinner();
+    // deep directory trees exceed MAX_PATH (260 chars) on Windows
+    let source = to_long_path(&source);
+
+    // completed files are stored to resume interrupted uploads
+    let checkpoint = TransferCheckpoint::open(
+        "upload",
+        &source.to_string_lossy(),
+        &to_node_path(target),
+        opts.resume,
+    )?;
+
+    // list first to confirm large uploads before creating anything
+    let (mut files, folders) =
+        match tokio::try_join!(list_files(&source), list_directories(&source)) {
+            Ok((files, folders)) => (files, folders),
+            Err(e) => {
+                error!("Error listing files and folders: {}", e);
+                return Err(e);
+            }
+        };
+
+    // the checkpoint is not uploaded if the working directory is uploaded
+    files.retain(|file| !checkpoint.is_checkpoint_file(file));
+
+    info!("Found {} files.", files.len());
+    info!("Found {} folders.", folders.len());
+
+    // files uploaded before an interruption are skipped
+    let total_size = files
+        .iter()
+        .filter(|file| !checkpoint.is_completed(&file.to_string_lossy()))
+        .filter_map(|file| std::fs::metadata(file).ok())
+        .map(|meta| meta.len())
+        .sum::<u64>();
+
+    if !confirm_large_operation(
+        dracoon.get_base_url().as_str(),
+        "Upload",
+        files.len() as u64,
+        total_size,
+        opts.assume_yes,
+    )? {
+        return Err(DcCmdError::InvalidArgument(
+            "Upload not confirmed.".to_string(),
+        ));
+    }
+
+    let progress_spinner = spinner();
     progress_spinner.set_message("Creating folder structure...");
     progress_spinner.enable_steady_tick(Duration::from_millis(100));
-    progress.add(progress_spinner);
-    let parent_id = if opts.skip_root {
+    let root_folder = if opts.skip_root {
         info!("Skipping root folder.");
-        target.id
+        None
     } else {
-        let root_folder = create_root_folder(dracoon, &root_name, target.id, target_parent).await?;
-        root_folder.id
-    };
-
-    let (files, folders) = match tokio::try_join!(list_files(&source), list_directories(&source)) {
-        Ok((files, folders)) => (files, folders),
-        Err(e) => {
-            error!("Error listing files and folders: {}", e);
-            return Err(e);
-        }
+        let root_folder = create_root_folder(
+            dracoon,
+            &root_name,
+            target.id,
+            target_parent,
+            opts.classification,
+        )
+        .await?;
+        Some(root_folder)
     };
+    let parent_id = root_folder.as_ref().map_or(target.id, |folder| folder.id);
 
-    info!("Found {} files.", files.len());
-    info!("Found {} folders.", folders.len());
-
-    let progress_bar = ProgressBar::new(folders.len() as u64);
+    let progress_bar = progress_bar(folders.len() as u64);
     progress_bar.set_style(
         ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{human_len} ({per_sec}) {msg}").unwrap()
         .progress_chars("=>-"),
     );
 
-    progress.add(progress_bar.clone());
-
-    let folders = group_folders_by_depth(folders);
+    let (ready, mut pending) = group_folders_by_parent(folders);
 
+    let errors = TransferErrors::default();
     let created_nodes = Arc::new(DashMap::new());
     let root_folder_path: String = format!("/{}", &root_name).nfc().collect();
 
     created_nodes.insert(root_folder_path.clone(), parent_id);
 
-    let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
-
-    let root_path = source.parent().unwrap_or_else(|| Path::new("/")).to_owned();
-
-    for depth_level in folders {
-        let mut handles = Vec::new();
-
-        for folder in depth_level {
-            let semaphore = semaphore.clone();
-            let dracoon = dracoon.clone();
-            let created_nodes = created_nodes.clone();
-            let target = target.clone();
-            let progress_bar = progress_bar.clone();
-            let root_path = root_path.clone();
-            let source = source.clone();
-
-            debug!("Created nodes: {:?}", created_nodes);
-
-            let handle = tokio::spawn(async move {
-                let _permit = semaphore.acquire().await.map_err(|_| {
-                    error!("Failed to acquire semaphore permit.");
-                    DcCmdError::IoError
-                })?;
-
-                let (path, _) = folder;
-                let parent_path = path.parent().unwrap_or_else(|| Path::new("/"));
-
-                let parent_path = parent_path.to_string_lossy().to_string();
-                let parent_path = parent_path.nfc().collect::<String>();
-                let normalized_path = normalize_path(&path, &root_path);
-                let normalized_parent = normalized_path.parent().unwrap_or_else(|| Path::new("/"));
-                let normalized_parent = normalized_parent.to_string_lossy().to_string();
-                let normalized_parent = normalized_parent.nfc().collect::<String>();
-                debug!("Normalized path: {}", normalized_parent);
-                debug!("Root and path: {:?} {:?}", root_path, path);
-                let parent_id = *created_nodes.get(&normalized_parent).ok_or_else(|| {
-                    error!("Parent folder not found: {normalized_parent}");
-                    DcCmdError::InvalidPath(parent_path.clone())
-                })?;
-                let name = path
-                    .clone()
-                    .file_name()
-                    .map(|n| n.to_string_lossy().into_owned())
-                    .map(|n| n.nfc().collect::<String>())
-                    .ok_or(DcCmdError::InvalidPath(
-                        source.to_string_lossy().to_string(),
-                    ))?;
-                let folder = CreateFolderRequest::builder(&name, parent_id).build();
-
-                match dracoon.nodes().create_folder(folder).await {
-                    Ok(folder) => {
-                        let folder_path = format!("{normalized_parent}/{name}").nfc().collect();
-                        created_nodes.insert(folder_path, folder.id);
-                        progress_bar.inc(1);
-                    }
-                    Err(e) if e.is_conflict() => {
-                        let target_path = format!(
-                            "{}{}",
-                            target.parent_path.unwrap_or("/".into()),
-                            target.name
-                        );
-                        let path: String = format!("{target_path}{normalized_parent}/{name}/")
-                            .nfc()
-                            .collect();
-                        let folder = dracoon
-                            .nodes()
-                            .get_node_from_path(&path)
-                            .await?
-                            .ok_or_else(|| {
-                                error!("Conflict - folder not found: {path}");
-                                e
-                            })?;
-                        let folder_path = format!("{normalized_parent}/{name}").nfc().collect();
-                        created_nodes.insert(folder_path, folder.id);
-                        progress_bar.inc(1);
-                    }
-                    Err(e) => {
-                        error!("Error creating folder: {}", e);
-                        return Err(e.into());
-                    }
-                }
+    let folder_creation = FolderCreation {
+        dracoon: dracoon.clone(),
+        target: target.clone(),
+        root_path: source.parent().unwrap_or_else(|| Path::new("/")).to_owned(),
+        source: source.clone(),
+        created_nodes: created_nodes.clone(),
+        semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+        progress_bar: progress_bar.clone(),
+        classification: opts.classification,
+    };
 
-                Ok::<(), DcCmdError>(())
-            });
-            handles.push(handle);
-        }
+    // create each folder as soon as its parent exists
+    let mut tasks = JoinSet::new();
+
+    for folder in ready {
+        folder_creation.spawn(&mut tasks, folder);
+    }
 
-        for handle in handles {
-            if let Err(e) = handle.await {
+    while let Some(res) = tasks.join_next().await {
+        let (folder, res) = match res {
+            Ok(res) => res,
+            Err(e) => {
                 error!("Error creating folder: {}", e);
+                continue;
             }
+        };
+
+        if let Err(e) = res {
+            error!("Error creating folder {}: {}", folder.to_string_lossy(), e);
+            errors.push(folder.to_string_lossy(), &e);
+            continue;
+        }
+
+        for child in pending.remove(&folder).unwrap_or_default() {
+            folder_creation.spawn(&mut tasks, child);
         }
     }
 
+    if !pending.is_empty() {
+        let skipped = pending.values().map(Vec::len).sum::<usize>();
+        error!("Skipped {} folder(s) with missing parent folder.", skipped);
+
+        for folder in pending.values().flatten() {
+            errors.push(
+                folder.to_string_lossy(),
+                &DcCmdError::InvalidPath("parent folder not created".to_string()),
+            );
+        }
+    }
+
+    progress_spinner.finish_and_clear();
     progress_bar.finish_with_message("Created folder structure.");
     info!("Created folder structure.");
     let root_path = source.parent().unwrap_or_else(|| Path::new("/"));
 
-    let file_map = create_file_map(files, created_nodes.clone(), root_path)?;
+    let file_map = create_file_map(files, created_nodes.clone(), root_path, &errors);
 
     // upload files
-    upload_files(dracoon, target, file_map, opts.clone()).await?;
+    upload_files(
+        dracoon,
+        target,
+        file_map,
+        opts.clone(),
+        &errors,
+        &checkpoint,
+    )
+    .await?;
 
     info!("Upload of {} complete.", source.to_string_lossy());
+    checkpoint.finish(&errors);
+
+    if let (true, Some(root_folder)) = (opts.share, &root_folder) {
+        let share_password =
+            get_share_password(dracoon, opts.share_password.clone(), opts.generate_password)
+                .await?;
+        let link = share_container(
+            dracoon,
+            root_folder,
+            share_password.clone(),
+            opts.share_expiration,
+            opts.share_notification.as_ref(),
+        )
+        .await?;
+        let success_msg =
+            format_success_message(&format!("Shared {}.\n▶︎▶︎ {link}", root_folder.name));
+
+        write_line(term, &format!("\n{success_msg}"))?;
+
+        if let (true, Some(password)) = (opts.generate_password, &share_password) {
+            output_generated_password(term, password, opts.password_file.as_deref())?;
+        }
+    }
+
+    errors.report(opts.continue_on_error)
+}
 
-    Ok(())
+/// shared state to create folders concurrently
+#[derive(Clone)]
+struct FolderCreation {
+    dracoon: Dracoon<Connected>,
+    target: Node,
+    root_path: PathBuf,
+    source: PathBuf,
+    created_nodes: Arc<DashMap<String, u64>>,
+    semaphore: Arc<Semaphore>,
+    progress_bar: ProgressBar,
+    classification: Option<ClassificationLevel>,
 }
 
+type FolderTask = (PathBuf, Result<(), DcCmdError>);
+
+impl FolderCreation {
+    fn spawn(&self, tasks: &mut JoinSet<FolderTask>, folder: PathBuf) {
+        let folder_creation = self.clone();
+        tasks.spawn(async move {
+            let res = folder_creation.create_folder(&folder).await;
+            (folder, res)
+        });
+    }
+
+    async fn create_folder(&self, path: &Path) -> Result<(), DcCmdError> {
+        let _permit = self.semaphore.acquire().await.map_err(|_| {
+            error!("Failed to acquire semaphore permit.");
+            DcCmdError::IoError
+        })?;
+
+        let parent_path = path.parent().unwrap_or_else(|| Path::new("/"));
+
+        let parent_path = parent_path.to_string_lossy().to_string();
+        let parent_path = parent_path.nfc().collect::<String>();
+        let normalized_path = normalize_path(path, &self.root_path);
+        let normalized_parent = normalized_path.parent().unwrap_or_else(|| Path::new("/"));
+        let normalized_parent = normalized_parent.to_string_lossy().to_string();
+        let normalized_parent = normalized_parent.nfc().collect::<String>();
+        debug!("Normalized path: {}", normalized_parent);
+        debug!("Root and path: {:?} {:?}", self.root_path, path);
+        let parent_id = *self.created_nodes.get(&normalized_parent).ok_or_else(|| {
+            error!("Parent folder not found: {normalized_parent}");
+            DcCmdError::InvalidPath(parent_path.clone())
+        })?;
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .map(|n| n.nfc().collect::<String>())
+            .ok_or(DcCmdError::InvalidPath(
+                self.source.to_string_lossy().to_string(),
+            ))?;
+        let folder = build_folder_request(&name, parent_id, self.classification);
+
+        let folder_id = match self.dracoon.nodes().create_folder(folder).await {
+            Ok(folder) => folder.id,
+            Err(e) if e.is_conflict() => {
+                let target_path = format!(
+                    "{}{}",
+                    self.target.parent_path.as_deref().unwrap_or("/"),
+                    self.target.name
+                );
+                let path: String = format!("{target_path}{normalized_parent}/{name}/")
+                    .nfc()
+                    .collect();
+                let folder = self
+                    .dracoon
+                    .nodes()
+                    .get_node_from_path(&path)
+                    .await?
+                    .ok_or_else(|| {
+                        error!("Conflict - folder not found: {path}");
+                        e
+                    })?;
+                folder.id
+            }
+            Err(e) => {
+                error!("Error creating folder: {}", e);
+                return Err(e.into());
+            }
+        };
+
+        let folder_path = format!("{normalized_parent}/{name}").nfc().collect();
+        self.created_nodes.insert(folder_path, folder_id);
+        self.progress_bar.inc(1);
+
+        Ok(())
+    }
+}
+
+/// maps files to their parent node id and size - files without a created parent
+/// folder are skipped and reported
 fn create_file_map(
     files: Vec<PathBuf>,
     created_nodes: Arc<DashMap<String, u64>>,
     root_path: &Path,
-) -> Result<BTreeMap<PathBuf, (u64, u64)>, DcCmdError> {
+    errors: &TransferErrors,
+) -> BTreeMap<PathBuf, (u64, u64)> {
     files
         .into_iter()
-        .map(|file| {
+        .filter_map(|file| {
             let file_rel_path = normalize_path(&file, root_path);
 
             let file_parent = file_rel_path.parent().unwrap_or_else(|| Path::new("/"));
             let file_parent = file_parent.to_string_lossy().nfc().collect::<String>();
 
             // get node id of parent folder
-            let node_id = *created_nodes.get(&file_parent).ok_or_else(|| {
+            let Some(node_id) = created_nodes.get(&file_parent).map(|id| *id) else {
                 error!("Error getting node id for file path: {}", file_parent);
                 debug!("Processed file: {}", file.to_string_lossy());
                 debug!("Created nodes: {:?}", created_nodes);
                 debug!("Root path: {}", root_path.to_string_lossy());
-                DcCmdError::InvalidPath(file_parent)
-            })?;
+                errors.push(
+                    file.to_string_lossy(),
+                    &DcCmdError::InvalidPath(file_parent),
+                );
+                return None;
+            };
 
             // get file size
-            let file_meta = std::fs::metadata(&file).map_err(|_| DcCmdError::IoError)?;
-            let file_size = file_meta.len();
+            let file_size = match std::fs::metadata(&file) {
+                Ok(file_meta) => file_meta.len(),
+                Err(_) => {
+                    errors.push(file.to_string_lossy(), &DcCmdError::IoError);
+                    return None;
+                }
+            };
 
-            Ok((file, (node_id, file_size)))
+            Some((file, (node_id, file_size)))
         })
-        .collect::<Result<BTreeMap<PathBuf, (u64, u64)>, DcCmdError>>()
+        .collect()
 }
 
 #[async_recursion]
@@ -271,13 +422,30 @@ async fn list_files(root_path: &Path) -> Result<Vec<PathBuf>, DcCmdError> {
     Ok(file_paths)
 }
 
-async fn create_root_folder(
+/// builds the request for a created folder - the classification is applied to files
+/// and folders alike (existing folders are not updated)
+fn build_folder_request(
+    name: &str,
+    parent_id: u64,
+    classification: Option<ClassificationLevel>,
+) -> CreateFolderRequest {
+    let req = CreateFolderRequest::builder(name, parent_id);
+
+    match classification {
+        Some(classification) => req.with_classification(classification.into()).build(),
+        None => req.build(),
+    }
+}
+
+/// creates a folder - an existing folder with the same name is reused
+pub async fn create_root_folder(
     dracoon: &Dracoon<Connected>,
     name: &str,
     parent_id: u64,
     node_parent: &str,
+    classification: Option<ClassificationLevel>,
 ) -> Result<Node, DcCmdError> {
-    let root_folder = CreateFolderRequest::builder(name, parent_id).build();
+    let root_folder = build_folder_request(name, parent_id, classification);
 
     let root_folder = match dracoon.nodes().create_folder(root_folder).await {
         Ok(folder) => folder,
@@ -303,33 +471,76 @@ async fn create_root_folder(
     Ok(root_folder)
 }
 
-fn group_folders_by_depth(folders: Vec<PathBuf>) -> Vec<Vec<(PathBuf, usize)>> {
-    let depth_map: BTreeMap<usize, Vec<_>> =
-        folders.iter().fold(BTreeMap::new(), |mut acc, folder| {
-            let depth = folder.components().count() - 1;
-            acc.entry(depth).or_default().push((folder.clone(), depth));
-            acc
-        });
+/// groups folders by parent folder
+///
+/// returns all folders without a listed parent (ready to create) and the children per folder
+fn group_folders_by_parent(
+    folders: Vec<PathBuf>,
+) -> (Vec<PathBuf>, HashMap<PathBuf, Vec<PathBuf>>) {
+    let known_folders = folders.iter().cloned().collect::<HashSet<_>>();
+    let mut ready = Vec::new();
+    let mut children: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+
+    for folder in folders {
+        match folder.parent() {
+            Some(parent) if known_folders.contains(parent) => {
+                children
+                    .entry(parent.to_path_buf())
+                    .or_default()
+                    .push(folder);
+            }
+            _ => ready.push(folder),
+        }
+    }
+
+    (ready, children)
+}
 
-    depth_map.into_values().collect()
+/// converts an absolute path to an extended-length path (`\\?\` prefix) on Windows
+fn to_long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(to_verbatim_path(&path.to_string_lossy()))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// adds the verbatim prefix to an absolute Windows path (separators are converted to `\`)
+fn to_verbatim_path(path: &str) -> String {
+    let path = path.replace('/', "\\");
+
+    if path.starts_with(r"\\?\") {
+        path
+    } else if let Some(unc_path) = path.strip_prefix(r"\\") {
+        format!(r"\\?\UNC\{unc_path}")
+    } else {
+        format!(r"\\?\{path}")
+    }
+}
+
+/// removes the verbatim prefix (`\\?\` or `\\?\UNC\`) from a path using `/` as separator
+fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(unc_path) = path.strip_prefix("//?/UNC/") {
+        format!("//{unc_path}")
+    } else {
+        path.strip_prefix("//?/").unwrap_or(path).to_string()
+    }
 }
 
 fn normalize_path(path: &Path, root_path: &Path) -> PathBuf {
-    // Normalize Windows paths: replace `\` with `/` and strip drive letters (if any)
-    let path_str = path
-        .to_string_lossy()
-        .replace('\\', "/")
+    // Normalize Windows paths: replace `\` with `/`, strip long path prefix and drive letters (if any)
+    let path_str = strip_verbatim_prefix(&path.to_string_lossy().replace('\\', "/"));
+    let path_str = path_str
         .split(':')
-        .last() // Remove drive letters, e.g., "C:"
+        .next_back() // Remove drive letters, e.g., "C:"
         .unwrap_or("")
         .nfc() // Normalize to NFC
         .collect::<String>();
 
-    let root_str = root_path
-        .to_string_lossy()
-        .replace('\\', "/")
+    let root_str = strip_verbatim_prefix(&root_path.to_string_lossy().replace('\\', "/"));
+    let root_str = root_str
         .split(':')
-        .last()
+        .next_back()
         .unwrap_or("")
         .nfc()
         .collect::<String>();
@@ -359,45 +570,61 @@ mod tests {
 
     use super::*;
 
+    const FIXTURE_PATH: &str = "./tests/fixtures/upload";
+
     #[tokio::test]
     async fn test_list_directories() {
-        let root_path = PathBuf::from("./src");
+        let root_path = PathBuf::from(FIXTURE_PATH);
         let folders = list_directories(&root_path).await.unwrap();
-        assert_eq!(folders.len(), 9);
+        assert_eq!(folders.len(), 2);
     }
 
     #[tokio::test]
     async fn test_list_files() {
-        let root_path = PathBuf::from("./src/cmd/config");
+        let root_path = PathBuf::from(FIXTURE_PATH);
         let files = list_files(&root_path).await.unwrap();
-        assert_eq!(files.len(), 4);
+        assert_eq!(files.len(), 3);
     }
 
     #[test]
-    fn test_group_folders_by_depth() {
+    fn test_group_folders_by_parent() {
         let folders = vec![
-            PathBuf::from("/a/b/c"),
-            PathBuf::from("/a/b/d"),
-            PathBuf::from("/a/e"),
-            PathBuf::from("/f"),
+            PathBuf::from("/root/a"),
+            PathBuf::from("/root/a/b"),
+            PathBuf::from("/root/a/b/c"),
+            PathBuf::from("/root/a/b/d"),
+            PathBuf::from("/root/a/e"),
+            PathBuf::from("/root/f"),
         ];
 
-        let grouped = group_folders_by_depth(folders);
+        let (ready, children) = group_folders_by_parent(folders);
 
-        assert_eq!(grouped.len(), 3);
+        assert_eq!(
+            ready,
+            vec![PathBuf::from("/root/a"), PathBuf::from("/root/f")]
+        );
+        assert_eq!(children.len(), 2);
+        assert_eq!(
+            children.get(&PathBuf::from("/root/a")).unwrap(),
+            &vec![PathBuf::from("/root/a/b"), PathBuf::from("/root/a/e")]
+        );
+        assert_eq!(
+            children.get(&PathBuf::from("/root/a/b")).unwrap(),
+            &vec![PathBuf::from("/root/a/b/c"), PathBuf::from("/root/a/b/d")]
+        );
+    }
 
-        let first_depth = grouped.first().unwrap();
-        assert_eq!(first_depth.len(), 1);
-        assert_eq!(*first_depth.first().unwrap(), (PathBuf::from("/f"), 1));
+    #[test]
+    fn test_group_folders_by_parent_missing_parent() {
+        let folders = vec![PathBuf::from("/root/a/b"), PathBuf::from("/root/a/b/c")];
 
-        let second_depth = grouped.get(1).unwrap();
-        assert_eq!(second_depth.len(), 1);
-        assert_eq!(*second_depth.first().unwrap(), (PathBuf::from("/a/e"), 2));
+        let (ready, children) = group_folders_by_parent(folders);
 
-        let third_depth = grouped.last().unwrap();
-        assert_eq!(third_depth.len(), 2);
-        assert_eq!(*third_depth.first().unwrap(), (PathBuf::from("/a/b/c"), 3));
-        assert_eq!(*third_depth.last().unwrap(), (PathBuf::from("/a/b/d"), 3));
+        assert_eq!(ready, vec![PathBuf::from("/root/a/b")]);
+        assert_eq!(
+            children.get(&PathBuf::from("/root/a/b")).unwrap(),
+            &vec![PathBuf::from("/root/a/b/c")]
+        );
     }
 
     #[test]
@@ -420,6 +647,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_windows_long_path() {
+        let root = PathBuf::from(r"C:\root");
+        let path = PathBuf::from(r"\\?\C:\root\folder1\folder2");
+        assert_eq!(
+            normalize_path(&path, &root),
+            PathBuf::from("/folder1/folder2")
+        );
+    }
+
+    #[test]
+    fn test_windows_unc_long_path() {
+        let root = PathBuf::from(r"\\?\UNC\server\share\root");
+        let path = PathBuf::from(r"\\server\share\root\folder1");
+        assert_eq!(normalize_path(&path, &root), PathBuf::from("/folder1"));
+    }
+
+    #[test]
+    fn test_windows_mixed_separators() {
+        let root = PathBuf::from(r"C:\root");
+        let path = PathBuf::from(r"C:/root\folder1/folder2");
+        assert_eq!(
+            normalize_path(&path, &root),
+            PathBuf::from("/folder1/folder2")
+        );
+    }
+
+    #[test]
+    fn test_to_verbatim_path() {
+        assert_eq!(to_verbatim_path(r"C:\root/folder1"), r"\\?\C:\root\folder1");
+        assert_eq!(
+            to_verbatim_path(r"\\server\share\folder1"),
+            r"\\?\UNC\server\share\folder1"
+        );
+        assert_eq!(to_verbatim_path(r"\\?\C:\root"), r"\\?\C:\root");
+    }
+
     #[test]
     fn test_just_root() {
         let root = PathBuf::from("/root");
@@ -4,13 +4,28 @@ use console::Term;
 use files::{upload_file, upload_public_file};
 use folders::upload_container;
 
-use tracing::error;
+use tracing::{debug, error, warn};
 
-use crate::cmd::{init_dracoon, init_encryption, models::DcCmdError, utils::strings::parse_path};
-use dco3::nodes::Nodes;
+use crate::cmd::{
+    init_dracoon, init_encryption,
+    models::DcCmdError,
+    nodes::{resolve_node, to_node_path},
+    utils::{
+        progress::write_line,
+        strings::{format_error_message, parse_node_id, parse_path},
+    },
+};
+use dco3::{auth::Connected, nodes::Node, Dracoon, Public};
 
+mod dedupe;
 mod files;
 mod folders;
+mod proxied;
+mod s3;
+mod watch;
+
+pub use folders::create_root_folder;
+pub use watch::watch_upload;
 
 use super::models::CmdUploadOptions;
 
@@ -20,9 +35,11 @@ pub async fn upload(
     target: String,
     opts: CmdUploadOptions,
 ) -> Result<(), DcCmdError> {
+    opts.validate()?;
+
     // this is a public upload share
     match (target.contains("/public/upload-shares/"), source.is_file()) {
-        (true, true) => return upload_public_file(source, target).await,
+        (true, true) => return upload_public_file(source, target, &opts).await,
         (true, false) => {
             error!("Public upload shares only support file uploads.");
             return Err(DcCmdError::InvalidPath(
@@ -32,29 +49,7 @@ pub async fn upload(
         _ => (),
     }
 
-    let mut dracoon = init_dracoon(&target, opts.auth.clone(), true).await?;
-
-    let (parent_path, node_name, _) = parse_path(&target, dracoon.get_base_url().as_str())
-        .or(Err(DcCmdError::InvalidPath(target.clone())))?;
-    let node_path = format!("{parent_path}{node_name}/");
-
-    let parent_node = dracoon.nodes().get_node_from_path(&node_path).await?;
-
-    let Some(parent_node) = parent_node else {
-        error!("Target path not found: {}", target);
-        return Err(DcCmdError::InvalidPath(target.clone()));
-    };
-
-    if parent_node.is_encrypted == Some(true) {
-        dracoon = init_encryption(dracoon, opts.encryption_password.clone()).await?;
-    }
-
-    if parent_node.is_encrypted.unwrap_or(false) && opts.share {
-        error!("Parent node is encrypted. Cannot upload to encrypted nodes.");
-        return Err(DcCmdError::InvalidArgument(
-            "Sharing encrypted files currently not supported (remove --share flag).".to_string(),
-        ));
-    }
+    let (dracoon, parent_node, node_path) = resolve_upload_target(&target, &opts).await?;
 
     match (source.is_file(), source.is_dir(), opts.recursive) {
         // is a file
@@ -63,7 +58,7 @@ pub async fn upload(
         }
         // is a directory and recursive flag is set
         (_, true, true) => {
-            upload_container(&dracoon, source, &parent_node, &node_path, &opts).await?;
+            upload_container(&term, &dracoon, source, &parent_node, &node_path, &opts).await?;
         }
         // is a directory and recursive flag is not set
         (_, true, false) => {
@@ -81,3 +76,71 @@ pub async fn upload(
 
     Ok(())
 }
+
+/// resolves the target container of an upload - returns the client (with encryption for
+/// encrypted targets), the target node and its path
+async fn resolve_upload_target(
+    target: &str,
+    opts: &CmdUploadOptions,
+) -> Result<(Dracoon<Connected>, Node, String), DcCmdError> {
+    let mut dracoon = init_dracoon(target, opts.auth.clone(), true).await?;
+
+    let (parent_path, node_name, _) = parse_path(target, dracoon.get_base_url().as_str())
+        .or(Err(DcCmdError::InvalidPath(target.to_string())))?;
+    let node_path = format!("{parent_path}{node_name}/");
+
+    let parent_node = resolve_node(&dracoon, target, &node_path).await?;
+
+    let Some(parent_node) = parent_node else {
+        error!("Target path not found: {}", target);
+        return Err(DcCmdError::InvalidPath(target.to_string()));
+    };
+
+    // target addressed by id - path is required to resolve conflicts
+    let node_path = if parse_node_id(target).is_some() {
+        format!("{}/", to_node_path(&parent_node))
+    } else {
+        node_path
+    };
+
+    if parent_node.is_encrypted == Some(true) {
+        dracoon = init_encryption(dracoon, opts.encryption_password.clone()).await?;
+    }
+
+    if parent_node.is_encrypted.unwrap_or(false) && opts.share {
+        error!("Parent node is encrypted. Cannot upload to encrypted nodes.");
+        return Err(DcCmdError::InvalidArgument(
+            "Sharing encrypted files currently not supported (remove --share flag).".to_string(),
+        ));
+    }
+
+    Ok((dracoon, parent_node, node_path))
+}
+
+/// explains failed direct S3 transfers (e.g. storage endpoint blocked by proxy or firewall)
+pub async fn report_s3_connection_error(dracoon: &Dracoon<Connected>, err: &DcCmdError) {
+    if !matches!(
+        err,
+        DcCmdError::ConnectionFailed | DcCmdError::DracoonS3Error(_)
+    ) {
+        return;
+    }
+
+    let Ok(system_info) = dracoon.public().get_system_info().await else {
+        debug!("Failed to fetch system info for S3 check.");
+        return;
+    };
+
+    if !system_info.use_s3_storage {
+        return;
+    }
+
+    let s3_hosts = system_info.s3_hosts.join(", ");
+    warn!("Direct S3 upload failed - S3 hosts: {}", s3_hosts);
+
+    let msg = format_error_message(&format!(
+        "Upload to S3 storage failed. Please make sure the storage endpoint is reachable from your network: {s3_hosts}"
+    ));
+
+    let _ = write_line(&Term::stderr(), &msg);
+}
@@ -0,0 +1,259 @@
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use dco3::{
+    auth::Connected,
+    nodes::{
+        CompleteUploadRequest, CreateFileUploadRequest, CreateFileUploadResponse, Node,
+        UploadOptions,
+    },
+    Dracoon, Upload,
+};
+use dco3_crypto::{DracoonCrypto, DracoonRSACrypto, Encrypt, FileKey};
+use indicatif::ProgressBar;
+use reqwest::{header, Client, Method};
+use tokio::io::{AsyncRead, AsyncReadExt, BufReader};
+use tracing::{debug, error, warn};
+
+use crate::cmd::{
+    config::DEFAULT_CHUNK_SIZE,
+    models::DcCmdError,
+    utils::api::{api_request, send_api_json, send_api_request},
+};
+
+use super::s3::upload_s3_verified;
+
+/// uploads a file to the parent node - direct S3 uploads fall back to the proxied upload
+/// channel if the S3 storage is not reachable (e.g. blocked by proxy or firewall)
+///
+/// with `verify_parts`, direct S3 uploads send the MD5 of each part (see `upload_s3_verified`)
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_to_node(
+    dracoon: &Dracoon<Connected>,
+    parent_node: &Node,
+    upload_options: UploadOptions,
+    source: &Path,
+    progress_bar: &ProgressBar,
+    chunk_size: Option<usize>,
+    direct_s3: bool,
+    verify_parts: bool,
+) -> Result<Node, DcCmdError> {
+    if direct_s3 {
+        let uploaded = Arc::new(AtomicU64::new(0));
+        let uploaded_mv = uploaded.clone();
+        let progress_bar_mv = progress_bar.clone();
+        let on_progress = move |progress| {
+            uploaded_mv.fetch_add(progress, Ordering::Relaxed);
+            progress_bar_mv.inc(progress);
+        };
+
+        let res = if verify_parts && uses_s3_storage(dracoon).await {
+            upload_s3_verified(
+                dracoon,
+                parent_node,
+                &upload_options,
+                open_file(source).await?,
+                on_progress,
+                chunk_size,
+            )
+            .await
+        } else {
+            dracoon
+                .upload(
+                    parent_node,
+                    upload_options.clone(),
+                    BufReader::new(open_file(source).await?),
+                    Some(Box::new(move |progress, _| on_progress(progress))),
+                    chunk_size,
+                )
+                .await
+                .map_err(DcCmdError::from)
+        };
+
+        match res {
+            Err(DcCmdError::ConnectionFailed | DcCmdError::DracoonS3Error(_))
+                if uses_s3_storage(dracoon).await =>
+            {
+                warn!(
+                    "Direct S3 upload of {} failed - falling back to proxied upload.",
+                    source.display()
+                );
+                progress_bar.set_position(
+                    progress_bar
+                        .position()
+                        .saturating_sub(uploaded.load(Ordering::Relaxed)),
+                );
+            }
+            res => return res,
+        }
+    }
+
+    upload_proxied(
+        dracoon,
+        parent_node,
+        &upload_options,
+        open_file(source).await?,
+        progress_bar,
+        chunk_size,
+    )
+    .await
+}
+
+/// uploads via DRACOON instead of directly to the S3 storage (`directS3Upload` disabled)
+///
+/// encrypted files are encrypted in memory before the upload (as done by dco3)
+async fn upload_proxied(
+    dracoon: &Dracoon<Connected>,
+    parent_node: &Node,
+    upload_options: &UploadOptions,
+    reader: impl AsyncRead + Unpin,
+    progress_bar: &ProgressBar,
+    chunk_size: Option<usize>,
+) -> Result<Node, DcCmdError> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    let size = upload_options.file_meta.size;
+
+    let (mut content, file_key) = UploadContent::try_new(dracoon, parent_node, reader).await?;
+
+    let create_req =
+        CreateFileUploadRequest::from_upload_options(parent_node.id, upload_options, Some(false));
+    let request = api_request(dracoon, Method::POST, "nodes/files/uploads")
+        .await?
+        .json(&create_req);
+    let upload_channel = send_api_json::<CreateFileUploadResponse>(request).await?;
+
+    let client = Client::new();
+    let mut offset = 0;
+
+    while offset < size {
+        let chunk = content.next_chunk(chunk_size, offset, size).await?;
+        let read = chunk.len();
+
+        let request = client
+            .post(&upload_channel.upload_url)
+            .header(header::CONTENT_RANGE, content_range(offset, read, size))
+            .body(chunk);
+        send_api_request(request).await?;
+
+        offset += read as u64;
+        progress_bar.inc(read as u64);
+        debug!("Uploaded {} of {} bytes (proxied).", offset, size);
+    }
+
+    let complete_req = CompleteUploadRequest::builder()
+        .with_resolution_strategy(
+            upload_options
+                .resolution_strategy
+                .clone()
+                .unwrap_or_default(),
+        )
+        .with_keep_share_links(upload_options.keep_share_links.unwrap_or(false));
+    let complete_req = match file_key {
+        Some(file_key) => complete_req.with_file_key(file_key).build(),
+        None => complete_req.build(),
+    };
+
+    let request = client.put(&upload_channel.upload_url).json(&complete_req);
+    send_api_json::<Node>(request).await
+}
+
+/// content of an upload read in chunks - encrypted files are encrypted in memory (as done by
+/// dco3), the file key is returned encrypted for the current user
+pub(super) enum UploadContent<R> {
+    Plain(R),
+    Encrypted(std::io::Cursor<Vec<u8>>),
+}
+
+impl<R: AsyncRead + Unpin> UploadContent<R> {
+    pub(super) async fn try_new(
+        dracoon: &Dracoon<Connected>,
+        parent_node: &Node,
+        mut reader: R,
+    ) -> Result<(Self, Option<FileKey>), DcCmdError> {
+        if parent_node.is_encrypted != Some(true) {
+            return Ok((Self::Plain(reader), None));
+        }
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.map_err(|err| {
+            error!("Error reading file: {}", err);
+            DcCmdError::IoError
+        })?;
+
+        let (encrypted, plain_file_key) = DracoonCrypto::encrypt(&data).map_err(|err| {
+            error!("Error encrypting file: {:?}", err);
+            DcCmdError::Unknown
+        })?;
+
+        let keypair = dracoon.get_keypair(None).await?;
+        let file_key = DracoonCrypto::encrypt_file_key(plain_file_key, keypair).map_err(|err| {
+            error!("Error encrypting file key: {:?}", err);
+            DcCmdError::Unknown
+        })?;
+
+        Ok((
+            Self::Encrypted(std::io::Cursor::new(encrypted)),
+            Some(file_key),
+        ))
+    }
+
+    /// reads the next chunk at offset (fails if the content ends before size)
+    pub(super) async fn next_chunk(
+        &mut self,
+        chunk_size: usize,
+        offset: u64,
+        size: u64,
+    ) -> Result<Vec<u8>, DcCmdError> {
+        let mut chunk = Vec::with_capacity(chunk_size);
+        let read = match self {
+            Self::Plain(reader) => reader.take(chunk_size as u64).read_to_end(&mut chunk).await,
+            Self::Encrypted(data) => data.take(chunk_size as u64).read_to_end(&mut chunk).await,
+        }
+        .map_err(|err| {
+            error!("Error reading file: {}", err);
+            DcCmdError::IoError
+        })?;
+
+        if read == 0 && offset < size {
+            error!("File ended after {} of {} bytes.", offset, size);
+            return Err(DcCmdError::IoError);
+        }
+
+        Ok(chunk)
+    }
+}
+
+async fn open_file(source: &Path) -> Result<tokio::fs::File, DcCmdError> {
+    tokio::fs::File::open(source).await.map_err(|err| {
+        error!("Error opening file: {}", err);
+        DcCmdError::IoError
+    })
+}
+
+async fn uses_s3_storage(dracoon: &Dracoon<Connected>) -> bool {
+    dracoon
+        .get_system_info()
+        .await
+        .is_ok_and(|system_info| system_info.use_s3_storage)
+}
+
+/// range of a chunk (last byte inclusive)
+fn content_range(offset: u64, len: usize, size: u64) -> String {
+    format!("bytes {}-{}/{}", offset, offset + len as u64 - 1, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_range() {
+        assert_eq!(content_range(0, 10, 25), "bytes 0-9/25");
+        assert_eq!(content_range(20, 5, 25), "bytes 20-24/25");
+    }
+}
@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dco3::{
+    auth::Connected,
+    nodes::{
+        CompleteS3FileUploadRequest, CreateFileUploadRequest, CreateFileUploadResponse,
+        GeneratePresignedUrlsRequest, Node, PresignedUrlList, S3FileUploadPart, S3FileUploadStatus,
+        S3UploadStatus, UploadOptions,
+    },
+    Dracoon,
+};
+use reqwest::{header, Client, Method};
+use tokio::io::AsyncRead;
+use tracing::{debug, error};
+
+use crate::cmd::{
+    config::DEFAULT_CHUNK_SIZE,
+    models::DcCmdError,
+    utils::api::{api_request, send_api_json, send_api_request},
+};
+
+use super::proxied::UploadContent;
+
+/// first delay between two requests of the upload status (doubled up to the max delay)
+const POLLING_START_DELAY: Duration = Duration::from_millis(300);
+const MAX_POLLING_DELAY: Duration = Duration::from_secs(10);
+
+/// uploads directly to S3 sending the MD5 of each part as `Content-MD5` (S3 rejects corrupted
+/// parts) - the returned ETag of each part is compared with the MD5 of the part
+///
+/// storages returning ETags which are no MD5 (e.g. SSE-KMS) fail the verification
+pub(super) async fn upload_s3_verified(
+    dracoon: &Dracoon<Connected>,
+    parent_node: &Node,
+    upload_options: &UploadOptions,
+    reader: impl AsyncRead + Unpin,
+    on_progress: impl Fn(u64),
+    chunk_size: Option<usize>,
+) -> Result<Node, DcCmdError> {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    let size = upload_options.file_meta.size;
+
+    let (mut content, file_key) = UploadContent::try_new(dracoon, parent_node, reader).await?;
+
+    let create_req =
+        CreateFileUploadRequest::from_upload_options(parent_node.id, upload_options, Some(true));
+    let request = api_request(dracoon, Method::POST, "nodes/files/uploads")
+        .await?
+        .json(&create_req);
+    let upload_channel = send_api_json::<CreateFileUploadResponse>(request).await?;
+    let upload_path = format!("nodes/files/uploads/{}", upload_channel.upload_id);
+
+    let client = Client::new();
+    let mut parts = Vec::new();
+    let mut offset = 0;
+    let mut part_number = 1;
+
+    // empty files are uploaded as a single empty part
+    while offset < size || part_number == 1 {
+        let chunk = content.next_chunk(chunk_size, offset, size).await?;
+        let read = chunk.len() as u64;
+
+        let e_tag = upload_part(dracoon, &client, &upload_path, part_number, chunk).await?;
+        parts.push(S3FileUploadPart::new(part_number, e_tag));
+
+        offset += read;
+        part_number += 1;
+        on_progress(read);
+        debug!("Uploaded {} of {} bytes (S3, verified).", offset, size);
+    }
+
+    let complete_req = CompleteS3FileUploadRequest::builder(parts)
+        .with_resolution_strategy(
+            upload_options
+                .resolution_strategy
+                .clone()
+                .unwrap_or_default(),
+        )
+        .with_keep_share_links(upload_options.keep_share_links.unwrap_or(false));
+    let complete_req = match file_key {
+        Some(file_key) => complete_req.with_file_key(file_key).build(),
+        None => complete_req.build(),
+    };
+
+    let request = api_request(dracoon, Method::PUT, &format!("{upload_path}/s3"))
+        .await?
+        .json(&complete_req);
+    send_api_request(request).await?;
+
+    wait_for_upload(dracoon, &upload_path).await
+}
+
+/// uploads a part to its presigned url - returns the ETag of the part
+async fn upload_part(
+    dracoon: &Dracoon<Connected>,
+    client: &Client,
+    upload_path: &str,
+    part_number: u32,
+    chunk: Vec<u8>,
+) -> Result<String, DcCmdError> {
+    let urls_req = GeneratePresignedUrlsRequest::new(chunk.len() as u64, part_number, part_number);
+    let request = api_request(dracoon, Method::POST, &format!("{upload_path}/s3_urls"))
+        .await?
+        .json(&urls_req);
+    let urls = send_api_json::<PresignedUrlList>(request).await?;
+
+    let Some(url) = urls.urls.into_iter().next() else {
+        error!("No presigned url received for part {}.", part_number);
+        return Err(DcCmdError::Unknown);
+    };
+
+    let md5 = md5::compute(&chunk);
+
+    let response = client
+        .put(&url.url)
+        .header("Content-MD5", STANDARD.encode(md5.0))
+        .header(header::CONTENT_LENGTH, chunk.len())
+        .body(chunk)
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Connection error (S3 upload): {}", e);
+            DcCmdError::ConnectionFailed
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        error!(
+            "Error uploading part {} to S3: {} {}",
+            part_number, status, body
+        );
+        return Err(DcCmdError::TransferFailed(format!(
+            "Upload of part {part_number} to S3 failed ({status})."
+        )));
+    }
+
+    let Some(e_tag) = response
+        .headers()
+        .get(header::ETAG)
+        .and_then(|e_tag| e_tag.to_str().ok())
+    else {
+        error!("ETag missing for part {}.", part_number);
+        return Err(DcCmdError::TransferFailed(format!(
+            "ETag missing for part {part_number}."
+        )));
+    };
+
+    let e_tag = e_tag.trim_matches('"');
+    check_e_tag(part_number, &format!("{md5:x}"), e_tag)?;
+
+    Ok(e_tag.to_string())
+}
+
+fn check_e_tag(part_number: u32, md5: &str, e_tag: &str) -> Result<(), DcCmdError> {
+    if md5.eq_ignore_ascii_case(e_tag) {
+        return Ok(());
+    }
+
+    error!(
+        "ETag mismatch for part {} (MD5: {}, ETag: {})",
+        part_number, md5, e_tag
+    );
+
+    Err(DcCmdError::TransferFailed(format!(
+        "ETag mismatch for part {part_number} (MD5: {md5}, ETag: {e_tag})."
+    )))
+}
+
+/// polls the upload status until the upload is finished by DRACOON
+async fn wait_for_upload(
+    dracoon: &Dracoon<Connected>,
+    upload_path: &str,
+) -> Result<Node, DcCmdError> {
+    let mut delay = POLLING_START_DELAY;
+
+    loop {
+        let request = api_request(dracoon, Method::GET, upload_path).await?;
+        let upload_status = send_api_json::<S3FileUploadStatus>(request).await?;
+
+        match upload_status.status {
+            S3UploadStatus::Done => {
+                return upload_status.node.ok_or_else(|| {
+                    error!("No node received for finished upload.");
+                    DcCmdError::Unknown
+                })
+            }
+            S3UploadStatus::Error => {
+                let Some(err) = upload_status.error_details else {
+                    error!("Upload failed without error details.");
+                    return Err(DcCmdError::Unknown);
+                };
+                error!("Error finishing upload: {}", err);
+                return Err(DcCmdError::DracoonError(err));
+            }
+            S3UploadStatus::Transfer | S3UploadStatus::Finishing => {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_POLLING_DELAY);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_e_tag() {
+        let md5 = format!("{:x}", md5::compute(b"part"));
+
+        assert!(check_e_tag(1, &md5, &md5).is_ok());
+        assert!(check_e_tag(1, &md5, &md5.to_uppercase()).is_ok());
+        assert!(check_e_tag(2, &md5, "d41d8cd98f00b204e9800998ecf8427e").is_err());
+    }
+}
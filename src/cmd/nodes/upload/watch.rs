@@ -0,0 +1,269 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use console::Term;
+use dco3::{auth::Connected, nodes::Node, Dracoon};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use tracing::{error, info, warn};
+
+use crate::cmd::{
+    get_error_message,
+    models::DcCmdError,
+    nodes::models::{CmdUploadOptions, CmdWatchOptions},
+    utils::{
+        progress::write_line,
+        strings::{format_error_message, matches_glob},
+    },
+};
+
+use super::{create_root_folder, files::upload_file, resolve_upload_target};
+
+/// interval for checking pending files (files are uploaded once unchanged for the debounce period)
+const WATCH_TICK: Duration = Duration::from_millis(500);
+
+/// watches a local directory and uploads new or changed files until cancelled
+pub async fn watch_upload(
+    term: Term,
+    source: PathBuf,
+    target: String,
+    opts: CmdUploadOptions,
+    watch_opts: CmdWatchOptions,
+) -> Result<(), DcCmdError> {
+    opts.validate()?;
+
+    if !source.is_dir() {
+        error!("Watch mode requires a directory: {}", source.display());
+        return Err(DcCmdError::InvalidPath(
+            source.to_string_lossy().to_string(),
+        ));
+    }
+
+    // events report canonical paths on some platforms (e.g. MacOS)
+    let source = source.canonicalize().map_err(|e| {
+        error!("Error resolving {}: {}", source.display(), e);
+        DcCmdError::InvalidPath(source.to_string_lossy().to_string())
+    })?;
+
+    let (dracoon, parent_node, node_path) = resolve_upload_target(&target, &opts).await?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let _ = tx.send(res);
+    })
+    .map_err(|e| {
+        error!("Error creating directory watcher: {}", e);
+        DcCmdError::IoError
+    })?;
+
+    let mode = if opts.recursive {
+        RecursiveMode::Recursive
+    } else {
+        RecursiveMode::NonRecursive
+    };
+
+    watcher.watch(&source, mode).map_err(|e| {
+        error!("Error watching {}: {}", source.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    info!("Watching {} (target: {}).", source.display(), node_path);
+    write_line(
+        &term,
+        &format!("Watching {} - press Ctrl+C to stop.", source.display()),
+    )?;
+
+    let mut uploader = WatchUploader {
+        term,
+        dracoon,
+        source,
+        opts,
+        folders: HashMap::from([(PathBuf::new(), (parent_node, node_path))]),
+    };
+
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    let mut ticker = tokio::time::interval(WATCH_TICK);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Some(Ok(event)) => {
+                    for path in changed_files(&event) {
+                        if !uploader.is_excluded(&path, &watch_opts.exclude) {
+                            pending.insert(path, Instant::now());
+                        }
+                    }
+                }
+                Some(Err(e)) => warn!("Directory watcher error: {}", e),
+                None => break,
+            },
+            _ = ticker.tick() => {
+                for path in take_settled(&mut pending, Instant::now(), watch_opts.debounce) {
+                    uploader.upload(&path).await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct WatchUploader {
+    term: Term,
+    dracoon: Dracoon<Connected>,
+    source: PathBuf,
+    opts: CmdUploadOptions,
+    /// created (or existing) folders in DRACOON by relative path (node and node path)
+    folders: HashMap<PathBuf, (Node, String)>,
+}
+
+impl WatchUploader {
+    fn is_excluded(&self, path: &Path, exclude: &[String]) -> bool {
+        path.strip_prefix(&self.source)
+            .map_or(true, |relative| is_excluded(relative, exclude))
+    }
+
+    /// uploads a single file - failed uploads are reported and watching continues
+    async fn upload(&mut self, path: &Path) -> Result<(), DcCmdError> {
+        // removed or renamed before settling
+        if !path.is_file() {
+            return Ok(());
+        }
+
+        let res = match self.get_parent_folder(path).await {
+            Ok(parent) => {
+                upload_file(
+                    self.term.clone(),
+                    &self.dracoon,
+                    path.to_path_buf(),
+                    &parent,
+                    self.opts.clone(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = res {
+            error!("Upload of {} failed: {}", path.display(), e);
+            let msg = format_error_message(&format!(
+                "Upload of {} failed: {}",
+                path.display(),
+                get_error_message(&e)
+            ));
+            write_line(&self.term, &msg)?;
+        }
+
+        Ok(())
+    }
+
+    /// returns the parent folder in DRACOON - missing folders are created
+    async fn get_parent_folder(&mut self, path: &Path) -> Result<Node, DcCmdError> {
+        let relative = path
+            .strip_prefix(&self.source)
+            .ok()
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let mut current = PathBuf::new();
+
+        for component in relative.components() {
+            let name = component.as_os_str().to_string_lossy().to_string();
+            let folder_path = current.join(&name);
+
+            if !self.folders.contains_key(&folder_path) {
+                let (parent, parent_path) = &self.folders[&current];
+                let folder = create_root_folder(
+                    &self.dracoon,
+                    &name,
+                    parent.id,
+                    parent_path,
+                    self.opts.classification,
+                )
+                .await?;
+                let node_path = format!("{parent_path}{name}/");
+
+                self.folders
+                    .insert(folder_path.clone(), (folder, node_path));
+            }
+
+            current = folder_path;
+        }
+
+        Ok(self.folders[&current].0.clone())
+    }
+}
+
+/// returns the files created or modified by a watcher event
+fn changed_files(event: &Event) -> Vec<PathBuf> {
+    match event.kind {
+        EventKind::Create(_) | EventKind::Modify(_) => event.paths.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// checks if a path (relative to the watched directory) or one of its parents matches an exclude pattern
+fn is_excluded(relative: &Path, exclude: &[String]) -> bool {
+    relative.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        exclude.iter().any(|pattern| matches_glob(&name, pattern))
+    })
+}
+
+/// removes and returns all files without changes for the debounce period (sorted by path)
+fn take_settled(
+    pending: &mut HashMap<PathBuf, Instant>,
+    now: Instant,
+    debounce: Duration,
+) -> Vec<PathBuf> {
+    let mut settled = pending
+        .iter()
+        .filter(|(_, changed_at)| now.duration_since(**changed_at) >= debounce)
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+
+    for path in &settled {
+        pending.remove(path);
+    }
+
+    settled.sort();
+    settled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_excluded() {
+        let exclude = vec!["*.tmp".to_string(), ".git".to_string()];
+
+        assert!(is_excluded(Path::new("report.tmp"), &exclude));
+        assert!(is_excluded(Path::new(".git/config"), &exclude));
+        assert!(is_excluded(Path::new("docs/~draft.TMP"), &exclude));
+        assert!(!is_excluded(Path::new("docs/report.pdf"), &exclude));
+        assert!(!is_excluded(Path::new("report.pdf"), &[]));
+    }
+
+    #[test]
+    fn test_take_settled() {
+        let now = Instant::now();
+        let debounce = Duration::from_secs(2);
+        let mut pending = HashMap::from([
+            (PathBuf::from("b.txt"), now - Duration::from_secs(3)),
+            (PathBuf::from("a.txt"), now - Duration::from_secs(2)),
+            (PathBuf::from("c.txt"), now - Duration::from_secs(1)),
+        ]);
+
+        assert_eq!(
+            vec![PathBuf::from("a.txt"), PathBuf::from("b.txt")],
+            take_settled(&mut pending, now, debounce)
+        );
+        assert_eq!(1, pending.len());
+        assert!(pending.contains_key(Path::new("c.txt")));
+    }
+}
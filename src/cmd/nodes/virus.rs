@@ -0,0 +1,145 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use dco3::{
+    auth::Connected,
+    nodes::{models::NodeType, NodeList},
+    Dracoon,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS,
+    models::DcCmdError,
+    utils::api::{api_request, send_api_json},
+};
+
+/// maximum number of files per verdict request
+const VERDICT_BATCH_SIZE: usize = 100;
+
+/// verdicts of listed files until they are printed (see `load_scan_verdicts`)
+static SCAN_VERDICTS: OnceLock<Mutex<HashMap<u64, VirusProtectionVerdict>>> = OnceLock::new();
+
+/// virus protection info is not provided by dco3 and requested directly
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VirusProtectionVerdict {
+    NoScanning,
+    InProgress,
+    Clean,
+    Malicious,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VirusProtectionInfo {
+    pub node_id: u64,
+    pub verdict: VirusProtectionVerdict,
+    pub last_checked_at: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VirusProtectionInfoRequest {
+    node_ids: Vec<u64>,
+}
+
+fn scan_verdicts() -> &'static Mutex<HashMap<u64, VirusProtectionVerdict>> {
+    SCAN_VERDICTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// fetches the virus protection info of files (batches of 100 files)
+pub async fn get_virus_protection_info(
+    dracoon: &Dracoon<Connected>,
+    file_ids: Vec<u64>,
+) -> Result<Vec<VirusProtectionInfo>, DcCmdError> {
+    let batches = file_ids
+        .chunks(VERDICT_BATCH_SIZE)
+        .map(<[u64]>::to_vec)
+        .collect::<Vec<_>>();
+
+    let infos = stream::iter(batches)
+        .map(|node_ids| async move {
+            let request = api_request(dracoon, Method::POST, "nodes/files/generate_verdict_info")
+                .await?
+                .json(&VirusProtectionInfoRequest { node_ids });
+
+            send_api_json::<Vec<VirusProtectionInfo>>(request).await
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(infos.into_iter().flatten().collect())
+}
+
+/// fetches the verdicts of the files of a page for printing (see `take_scan_verdict`)
+/// errors (e.g. virus protection disabled) are ignored - the status is shown as unknown
+pub async fn load_scan_verdicts(dracoon: &Dracoon<Connected>, node_list: &NodeList) {
+    let file_ids = node_list
+        .items
+        .iter()
+        .filter(|node| node.node_type == NodeType::File)
+        .map(|node| node.id)
+        .collect::<Vec<_>>();
+
+    if file_ids.is_empty() {
+        return;
+    }
+
+    match get_virus_protection_info(dracoon, file_ids).await {
+        Ok(infos) => scan_verdicts()
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .extend(infos.into_iter().map(|info| (info.node_id, info.verdict))),
+        Err(e) => debug!("Error fetching virus protection info: {}", e),
+    }
+}
+
+/// returns (and forgets) the loaded verdict of a file - keeps memory flat for huge listings
+pub fn take_scan_verdict(node_id: u64) -> Option<VirusProtectionVerdict> {
+    scan_verdicts()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .remove(&node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_virus_protection_info() {
+        let json = r#"[
+            {"nodeId": 1, "verdict": "MALICIOUS", "lastCheckedAt": "2024-01-01T00:00:00Z", "sha256": "abc"},
+            {"nodeId": 2, "verdict": "NO_SCANNING"}
+        ]"#;
+
+        let infos = serde_json::from_str::<Vec<VirusProtectionInfo>>(json).unwrap();
+
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].verdict, VirusProtectionVerdict::Malicious);
+        assert_eq!(
+            infos[0].last_checked_at.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        assert_eq!(infos[1].verdict, VirusProtectionVerdict::NoScanning);
+        assert!(infos[1].last_checked_at.is_none());
+    }
+
+    #[test]
+    fn test_take_scan_verdict() {
+        scan_verdicts()
+            .lock()
+            .unwrap()
+            .insert(42, VirusProtectionVerdict::Clean);
+
+        assert_eq!(take_scan_verdict(42), Some(VirusProtectionVerdict::Clean));
+        assert_eq!(take_scan_verdict(42), None);
+    }
+}
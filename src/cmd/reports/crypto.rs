@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use dco3::nodes::{Node, NodesSearchFilter};
+
+use crate::cmd::models::DcCmdError;
+
+use super::{models::RoomCrypto, ReportsCommandHandler};
+
+impl ReportsCommandHandler {
+    /// returns encryption status and unencrypted files of all rooms below target
+    /// (sorted by parent path)
+    pub async fn get_room_crypto(&self, target: &str) -> Result<Vec<RoomCrypto>, DcCmdError> {
+        let rooms = self.search_all(target, NodesSearchFilter::is_room).await?;
+        let files = self.search_all(target, NodesSearchFilter::is_file).await?;
+        let unencrypted_files = count_unencrypted_files(&files);
+
+        let rooms = rooms
+            .iter()
+            .map(|room| {
+                let unencrypted_files = unencrypted_files.get(&room.id).copied().unwrap_or(0);
+                RoomCrypto::new(room, unencrypted_files)
+            })
+            .collect();
+
+        Ok(rooms)
+    }
+}
+
+impl RoomCrypto {
+    pub fn new(room: &Node, unencrypted_files: u64) -> Self {
+        let encrypted = room.is_encrypted.unwrap_or(false);
+
+        Self {
+            id: room.id,
+            name: room.name.clone(),
+            parent_path: room.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            encrypted,
+            // unencrypted files only matter in encrypted rooms
+            unencrypted_files: if encrypted { unencrypted_files } else { 0 },
+        }
+    }
+
+    /// encrypted rooms without unencrypted files
+    pub fn is_compliant(&self) -> bool {
+        self.encrypted && self.unencrypted_files == 0
+    }
+}
+
+/// unencrypted files per room (files are assigned to the room controlling their permissions)
+fn count_unencrypted_files(files: &[Node]) -> HashMap<u64, u64> {
+    let mut counts = HashMap::new();
+
+    for file in files {
+        if let (Some(room_id), false) = (file.auth_parent_id, file.is_encrypted == Some(true)) {
+            *counts.entry(room_id).or_default() += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(encrypted: bool, unencrypted_files: u64) -> RoomCrypto {
+        RoomCrypto {
+            id: 1,
+            name: "room".to_string(),
+            parent_path: "/".to_string(),
+            encrypted,
+            unencrypted_files,
+        }
+    }
+
+    #[test]
+    fn test_is_compliant() {
+        assert!(room(true, 0).is_compliant());
+        assert!(!room(true, 3).is_compliant());
+        assert!(!room(false, 0).is_compliant());
+    }
+}
@@ -1,7 +1,41 @@
-use crate::cmd::models::DcCmdError;
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+use crate::cmd::{
+    models::{DcCmdError, ListOptions},
+    utils::{pagination::fetch_page_with_retry, progress::spinner},
+};
+use chrono::{DateTime, SecondsFormat, Utc};
 use dco3::{eventlog::LogEventList, Eventlog};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+
+use super::{
+    models::{EventOptions, EventPartition},
+    print::EVENTS_CSV_HEADER,
+    ReportsCommandHandler,
+};
+
+/// progress of an incomplete export (removed once the export is complete)
+const EVENTS_CHECKPOINT_FILE: &str = ".events-checkpoint.json";
 
-use super::{models::EventOptions, ReportsCommandHandler};
+/// state of an incomplete export - written after each page (see `--resume`)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct EventsCheckpoint {
+    /// offset of the next page to fetch
+    offset: u64,
+    exported: u64,
+    last_event_id: Option<i64>,
+    /// resolved date range (RFC 3339) - relative dates are not re-evaluated on resume
+    start_date: Option<String>,
+    end_date: Option<String>,
+    /// remaining filters of the export (must match on resume)
+    filters: String,
+}
 
 impl ReportsCommandHandler {
     pub async fn get_events(&self, opts: EventOptions) -> Result<LogEventList, DcCmdError> {
@@ -26,4 +60,326 @@ impl ReportsCommandHandler {
 
         Ok(event_list)
     }
+
+    /// streams events page by page into one CSV file per period (flat memory usage)
+    ///
+    /// pages are fetched respecting the global rate limit and retried on server errors -
+    /// the progress is stored in a checkpoint file to resume interrupted exports
+    pub async fn export_events(
+        &self,
+        opts: EventOptions,
+        out_dir: &Path,
+        partition: EventPartition,
+        resume: bool,
+    ) -> Result<(), DcCmdError> {
+        std::fs::create_dir_all(out_dir).map_err(|e| {
+            error!("Error creating directory {}: {}", out_dir.display(), e);
+            DcCmdError::IoError
+        })?;
+
+        let mut opts = opts;
+        let filters = checkpoint_filters(&opts);
+
+        let (mut offset, mut exported) = match (load_checkpoint(out_dir)?, resume) {
+            (Some(checkpoint), true) => {
+                if checkpoint.filters != filters {
+                    return Err(DcCmdError::InvalidArgument(
+                        "Filters differ from the interrupted export.".to_string(),
+                    ));
+                }
+
+                opts.start_date = parse_checkpoint_date(checkpoint.start_date.as_deref())?;
+                opts.end_date = parse_checkpoint_date(checkpoint.end_date.as_deref())?;
+
+                info!(
+                    "Resuming export at offset {} ({} events exported).",
+                    checkpoint.offset, checkpoint.exported
+                );
+                (checkpoint.offset, checkpoint.exported)
+            }
+            (None, true) => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "No interrupted export found in {}.",
+                    out_dir.display()
+                )));
+            }
+            (Some(_), false) => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Interrupted export found in {} - use --resume to continue (or remove {}).",
+                    out_dir.display(),
+                    EVENTS_CHECKPOINT_FILE
+                )));
+            }
+            (None, false) => (opts.list_options.offset().unwrap_or(0), 0),
+        };
+
+        let spinner = spinner().with_message("Exporting events...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let mut writer: Option<(String, BufWriter<File>)> = None;
+
+        loop {
+            let fetch_page = |offset| {
+                let params = opts.new_params_with_offset(offset);
+                async move {
+                    let events = self.client.eventlog().get_events(params).await?;
+                    Ok::<_, DcCmdError>(events)
+                }
+            };
+            let events = fetch_page_with_retry(&fetch_page, offset).await?;
+
+            let count = events.items.len() as u64;
+            let total = events.range.total;
+            let last_event_id = events.items.last().map(|event| event.id);
+
+            for event in events.items {
+                let period = partition.period(&event.time);
+
+                // events are mostly sorted by time - only keep the current file open
+                if writer
+                    .as_ref()
+                    .is_none_or(|(current, _)| *current != period)
+                {
+                    if let Some((_, mut previous)) = writer.take() {
+                        previous.flush().map_err(|_| DcCmdError::IoError)?;
+                    }
+                    let file = open_partition_file(out_dir, &period)?;
+                    writer = Some((period, file));
+                }
+
+                if let Some((_, file)) = writer.as_mut() {
+                    writeln!(file, "{}", Self::format_event_csv(event))
+                        .map_err(|_| DcCmdError::IoError)?;
+                }
+            }
+
+            exported += count;
+            offset += count;
+            spinner.set_message(format!("Exported {exported} events..."));
+
+            // the checkpoint is only written once the page is on disk
+            if let Some((_, file)) = writer.as_mut() {
+                file.flush().map_err(|_| DcCmdError::IoError)?;
+            }
+
+            write_checkpoint(
+                out_dir,
+                &EventsCheckpoint {
+                    offset,
+                    exported,
+                    last_event_id,
+                    start_date: opts.start_date.map(to_checkpoint_date),
+                    end_date: opts.end_date.map(to_checkpoint_date),
+                    filters: filters.clone(),
+                },
+            )?;
+
+            if count == 0 || !opts.list_options.all() || offset >= total {
+                break;
+            }
+        }
+
+        if let Some((_, mut file)) = writer {
+            file.flush().map_err(|_| DcCmdError::IoError)?;
+        }
+
+        remove_checkpoint(out_dir);
+
+        spinner.finish_and_clear();
+        info!("Exported {} events to {}.", exported, out_dir.display());
+
+        self.term
+            .write_line(&format!(
+                "Exported {exported} events to {}",
+                out_dir.display()
+            ))
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub async fn follow_events(
+        &self,
+        opts: EventOptions,
+        interval: Duration,
+        csv: bool,
+    ) -> Result<(), DcCmdError> {
+        let mut opts = opts;
+
+        // always fetch everything since the last seen event
+        opts.list_options =
+            ListOptions::new(opts.list_options.filter().clone(), None, None, true, csv);
+
+        // without a start date, only events from now on are printed
+        if opts.start_date.is_none() {
+            opts.start_date = Some(Utc::now());
+        }
+
+        if csv {
+            self.print_events_csv_header()?;
+        }
+
+        info!("Following events every {} seconds.", interval.as_secs());
+
+        let mut last_event_id = 0;
+
+        loop {
+            match self.get_events(opts.clone()).await {
+                Ok(events) => {
+                    let mut new_events = events
+                        .items
+                        .into_iter()
+                        .filter(|event| event.id > last_event_id)
+                        .collect::<Vec<_>>();
+
+                    new_events.sort_by_key(|event| event.id);
+
+                    if let Some(newest) = new_events.last() {
+                        last_event_id = newest.id;
+                        opts.start_date = Some(newest.time);
+                    }
+
+                    self.print_followed_events(new_events, csv)?;
+                }
+                // keep following on transient errors
+                Err(e) => error!("Error fetching events: {}", e),
+            }
+
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Stopped following events.");
+                    return Ok(());
+                }
+                () = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+}
+
+/// opens (or creates) the export file of a period - header is only written to new files
+fn open_partition_file(out_dir: &Path, period: &str) -> Result<BufWriter<File>, DcCmdError> {
+    let path = out_dir.join(format!("events-{period}.csv"));
+    debug!("Writing events to {}", path.display());
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| {
+            error!("Error opening {}: {}", path.display(), e);
+            DcCmdError::IoError
+        })?;
+
+    let is_empty = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+    let mut writer = BufWriter::new(file);
+
+    if is_empty {
+        writeln!(writer, "{EVENTS_CSV_HEADER}").map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(writer)
+}
+
+/// filters of an export besides the date range (compared on resume)
+fn checkpoint_filters(opts: &EventOptions) -> String {
+    format!(
+        "filter={:?};user_id={:?};operation_type={:?};status={:?}",
+        opts.list_options.filter(),
+        opts.user_id,
+        opts.operation_type,
+        opts.status
+    )
+}
+
+fn to_checkpoint_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_checkpoint_date(date: Option<&str>) -> Result<Option<DateTime<Utc>>, DcCmdError> {
+    date.map(|date| {
+        DateTime::parse_from_rfc3339(date)
+            .map(|date| date.with_timezone(&Utc))
+            .map_err(|_| DcCmdError::InvalidArgument(format!("Invalid checkpoint date: {date}")))
+    })
+    .transpose()
+}
+
+/// loads the checkpoint of an interrupted export (None if there is none)
+fn load_checkpoint(out_dir: &Path) -> Result<Option<EventsCheckpoint>, DcCmdError> {
+    let path = out_dir.join(EVENTS_CHECKPOINT_FILE);
+
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        debug!("No events checkpoint found in {}", out_dir.display());
+        return Ok(None);
+    };
+
+    serde_json::from_str(&content).map(Some).map_err(|e| {
+        error!("Invalid events checkpoint {}: {}", path.display(), e);
+        DcCmdError::InvalidArgument(format!("Invalid checkpoint file: {}", path.display()))
+    })
+}
+
+/// writes the checkpoint atomically (temporary file and rename)
+fn write_checkpoint(out_dir: &Path, checkpoint: &EventsCheckpoint) -> Result<(), DcCmdError> {
+    let path = out_dir.join(EVENTS_CHECKPOINT_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    let content = serde_json::to_string(checkpoint).map_err(|_| DcCmdError::IoError)?;
+
+    std::fs::write(&tmp_path, content)
+        .and_then(|()| std::fs::rename(&tmp_path, &path))
+        .map_err(|e| {
+            error!("Error writing {}: {}", path.display(), e);
+            DcCmdError::IoError
+        })
+}
+
+fn remove_checkpoint(out_dir: &Path) {
+    let path = out_dir.join(EVENTS_CHECKPOINT_FILE);
+
+    if let Err(e) = std::fs::remove_file(&path) {
+        warn!("Error removing {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let dir = std::env::temp_dir().join("dccmd-rs-test-events-checkpoint");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(load_checkpoint(&dir).unwrap(), None);
+
+        let checkpoint = EventsCheckpoint {
+            offset: 1500,
+            exported: 1500,
+            last_event_id: Some(42),
+            start_date: Some("2024-01-01T00:00:00.000Z".to_string()),
+            end_date: None,
+            filters: "filter=None".to_string(),
+        };
+        write_checkpoint(&dir, &checkpoint).unwrap();
+        assert_eq!(load_checkpoint(&dir).unwrap(), Some(checkpoint));
+
+        remove_checkpoint(&dir);
+        assert_eq!(load_checkpoint(&dir).unwrap(), None);
+
+        std::fs::write(dir.join(EVENTS_CHECKPOINT_FILE), "invalid").unwrap();
+        assert!(load_checkpoint(&dir).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_checkpoint_date_roundtrip() {
+        let date = Utc::now();
+        let parsed = parse_checkpoint_date(Some(&to_checkpoint_date(date))).unwrap();
+
+        assert_eq!(
+            parsed.map(|date| date.timestamp_millis()),
+            Some(date.timestamp_millis())
+        );
+        assert_eq!(parse_checkpoint_date(None).unwrap(), None);
+        assert!(parse_checkpoint_date(Some("invalid")).is_err());
+    }
 }
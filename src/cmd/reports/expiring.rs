@@ -0,0 +1,89 @@
+use chrono::{Duration, Utc};
+use dco3::{
+    nodes::{Node, NodesSearchFilter, NodesSearchSortBy},
+    ListAllParams, Nodes, SortOrder,
+};
+
+use crate::cmd::{
+    models::DcCmdError,
+    utils::{
+        pagination::fetch_all_paginated,
+        strings::{build_node_path, parse_path},
+    },
+};
+
+use super::ReportsCommandHandler;
+
+impl ReportsCommandHandler {
+    /// returns all files below target expiring within given days (sorted by expiration)
+    pub async fn get_expiring_files(
+        &self,
+        target: &str,
+        days: u32,
+    ) -> Result<Vec<Node>, DcCmdError> {
+        let (parent_path, node_name, depth) =
+            parse_path(target, self.client.get_base_url().as_ref())?;
+        let node_path = build_node_path((parent_path, node_name, depth));
+
+        // search all rooms if no path is provided
+        let parent_id = if node_path == "//" {
+            None
+        } else {
+            let node = self
+                .client
+                .nodes()
+                .get_node_from_path(&node_path)
+                .await?
+                .ok_or(DcCmdError::InvalidPath(target.to_string()))?;
+
+            Some(node.id)
+        };
+
+        let params = ListAllParams::builder()
+            .with_filter(NodesSearchFilter::is_file())
+            .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+            .build();
+
+        let files = self
+            .client
+            .nodes()
+            .search_nodes("*", parent_id, Some(-1), Some(params))
+            .await?;
+
+        let client = self.client.clone();
+
+        let files = fetch_all_paginated(files, move |offset| {
+            let client = client.clone();
+            let params = ListAllParams::builder()
+                .with_filter(NodesSearchFilter::is_file())
+                .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+                .with_offset(offset)
+                .build();
+
+            async move {
+                let files = client
+                    .nodes()
+                    .search_nodes("*", parent_id, Some(-1), Some(params))
+                    .await?;
+
+                Ok::<_, DcCmdError>(files)
+            }
+        })
+        .await?;
+
+        let deadline = Utc::now() + Duration::days(i64::from(days));
+
+        let mut expiring_files = files
+            .items
+            .into_iter()
+            .filter(|file| {
+                file.expire_at
+                    .is_some_and(|expire_at| expire_at <= deadline)
+            })
+            .collect::<Vec<_>>();
+
+        expiring_files.sort_by_key(|file| file.expire_at);
+
+        Ok(expiring_files)
+    }
+}
@@ -2,19 +2,31 @@ use std::time::Duration;
 
 use console::Term;
 use dco3::{auth::Connected, Dracoon, Eventlog, Public};
-use indicatif::ProgressBar;
-use models::EventOptions;
+use models::{EventOptions, ReportStats};
 use tracing::{error, warn};
 
 use super::{
     init_dracoon,
     models::{DcCmdError, ListOptions, ReportsCommand},
+    utils::{dates::parse_duration, progress::spinner},
 };
 
+mod crypto;
 mod events;
+mod expiring;
 mod models;
+mod orphaned;
 mod permissions;
 mod print;
+mod quarantine;
+mod settings;
+mod usage;
+
+pub use models::EventPartition;
+
+const DEFAULT_FOLLOW_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_EXPIRING_DAYS: u32 = 30;
+const DEFAULT_USAGE_MONTHS: u32 = 12;
 
 pub struct ReportsCommandHandler {
     term: Term,
@@ -57,6 +69,12 @@ pub async fn handle_reports_cmd(cmd: ReportsCommand, term: Term) -> Result<(), D
     let target = match &cmd {
         ReportsCommand::Events { target, .. }
         | ReportsCommand::Permissions { target, .. }
+        | ReportsCommand::Expiring { target, .. }
+        | ReportsCommand::Usage { target, .. }
+        | ReportsCommand::RoomSettings { target, .. }
+        | ReportsCommand::Crypto { target, .. }
+        | ReportsCommand::Quarantine { target, .. }
+        | ReportsCommand::Orphaned { target, .. }
         | ReportsCommand::OperationTypes { target } => target,
     };
 
@@ -76,6 +94,12 @@ pub async fn handle_reports_cmd(cmd: ReportsCommand, term: Term) -> Result<(), D
             status,
             start_date,
             end_date,
+            follow,
+            interval,
+            stats_json,
+            out_dir,
+            partition,
+            resume,
         } => {
             handler.check_dracoon_api_version().await?;
 
@@ -90,11 +114,31 @@ pub async fn handle_reports_cmd(cmd: ReportsCommand, term: Term) -> Result<(), D
                 status,
             )?;
 
-            let spinner = ProgressBar::new_spinner().with_message("Loading events...");
+            if follow {
+                let interval = interval
+                    .as_deref()
+                    .map(parse_duration)
+                    .transpose()?
+                    .unwrap_or(DEFAULT_FOLLOW_INTERVAL);
+
+                return handler.follow_events(opts, interval, csv).await;
+            }
+
+            if let Some(out_dir) = out_dir {
+                return handler
+                    .export_events(opts, &out_dir, partition.unwrap_or_default(), resume)
+                    .await;
+            }
+
+            let spinner = spinner().with_message("Loading events...");
             spinner.enable_steady_tick(Duration::from_millis(100));
             let events = handler.get_events(opts).await?;
             spinner.finish_and_clear();
 
+            if stats_json {
+                return handler.print_stats_json(&ReportStats::from_events(&events.items));
+            }
+
             handler.print_events(events, csv)?;
 
             Ok(())
@@ -106,24 +150,117 @@ pub async fn handle_reports_cmd(cmd: ReportsCommand, term: Term) -> Result<(), D
 
             Ok(())
         }
+        ReportsCommand::Expiring {
+            target,
+            days,
+            csv,
+            stats_json,
+        } => {
+            let days = days.unwrap_or(DEFAULT_EXPIRING_DAYS);
+
+            let spinner = spinner().with_message("Loading files...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            let files = handler.get_expiring_files(&target, days).await?;
+            spinner.finish_and_clear();
+
+            if stats_json {
+                return handler.print_stats_json(&ReportStats::from_expiring_files(&files));
+            }
+
+            handler.print_expiring_files(files, csv)?;
+
+            Ok(())
+        }
+        ReportsCommand::Usage {
+            target,
+            csv,
+            growth,
+            months,
+        } => {
+            let spinner = spinner().with_message("Loading usage...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+
+            if growth {
+                let months = months.unwrap_or(DEFAULT_USAGE_MONTHS);
+                let growth = handler.get_usage_growth(&target, months).await?;
+                spinner.finish_and_clear();
+
+                return handler.print_usage_growth(&growth, csv);
+            }
+
+            let customer = handler.get_customer_usage().await?;
+            let rooms = handler.get_room_usage(&target).await?;
+            spinner.finish_and_clear();
+
+            handler.print_usage(&customer, &rooms, csv)
+        }
+        ReportsCommand::Quarantine { target, csv } => {
+            let spinner = spinner().with_message("Loading files...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            let files = handler.get_malicious_files(&target).await?;
+            spinner.finish_and_clear();
+
+            handler.print_malicious_files(&files, csv)
+        }
+        ReportsCommand::Orphaned {
+            target,
+            created_by,
+            csv,
+        } => {
+            let spinner = spinner().with_message("Loading nodes...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            let nodes = handler.get_orphaned_nodes(&target, created_by).await?;
+            spinner.finish_and_clear();
+
+            handler.print_orphaned_nodes(&nodes, csv)
+        }
+        ReportsCommand::RoomSettings { target, csv } => {
+            let spinner = spinner().with_message("Loading rooms...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            let rooms = handler.get_room_settings(&target).await?;
+            spinner.finish_and_clear();
+
+            handler.print_room_settings(&rooms, csv)
+        }
+        ReportsCommand::Crypto { target, csv } => {
+            let spinner = spinner().with_message("Loading rooms...");
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            let rooms = handler.get_room_crypto(&target).await?;
+            spinner.finish_and_clear();
+
+            handler.print_room_crypto(&rooms, csv)
+        }
         ReportsCommand::Permissions {
-            target: _,
+            target,
             filter,
             offset,
             limit,
             all,
             csv,
+            stats_json,
+            out_dir,
+            incremental,
         } => {
             let list_opts = ListOptions::new(filter, offset, limit, all, csv);
 
             handler.check_dracoon_api_version().await?;
 
-            let spinner = ProgressBar::new_spinner().with_message("Loading permissions...");
+            if let Some(out_dir) = out_dir {
+                return handler
+                    .export_permissions(&target, list_opts, &out_dir, incremental)
+                    .await;
+            }
+
+            let spinner = spinner().with_message("Loading permissions...");
             spinner.enable_steady_tick(Duration::from_millis(100));
             let permissions = handler.get_permissions(list_opts).await?;
 
             spinner.finish_and_clear();
 
+            if stats_json {
+                return handler.print_stats_json(&ReportStats::from_permissions(&permissions));
+            }
+
             handler.print_permissions(permissions, csv)?;
 
             Ok(())
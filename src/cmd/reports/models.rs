@@ -1,11 +1,22 @@
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use clap::ValueEnum;
 use dco3::{
-    eventlog::{EventStatus, EventlogParams, LogOperation},
-    nodes::NodePermissions,
+    eventlog::{AuditNodeList, EventStatus, EventlogParams, LogEvent, LogOperation},
+    nodes::{Node, NodePermissions},
 };
+use serde::Serialize;
 use tabled::Tabled;
 
-use crate::cmd::models::{DcCmdError, ListOptions};
+use crate::cmd::{
+    models::{DcCmdError, ListOptions},
+    nodes::virus::VirusProtectionInfo,
+    utils::{
+        dates::{parse_point_in_time, DateBound},
+        strings::to_readable_size,
+    },
+};
 
 #[derive(Clone)]
 pub struct EventOptions {
@@ -27,27 +38,12 @@ impl EventOptions {
         status: Option<u8>,
     ) -> Result<Self, DcCmdError> {
         let start_date = start_date
-            .map(|s| {
-                NaiveDate::parse_from_str(&s, "%Y-%m-%d")
-                    .map_err(|e| DcCmdError::InvalidArgument(format!("Invalid start date: {}", e)))
-                    .map(|date| {
-                        let time = NaiveTime::from_hms_opt(0, 0, 0).unwrap(); // Midnight
-                        let naive_datetime = date.and_time(time);
-                        DateTime::<Utc>::from_naive_utc_and_offset(naive_datetime, Utc)
-                    })
-            })
+            .map(|s| parse_point_in_time(&s, DateBound::Start))
             .transpose()?;
 
+        // dates (days, weeks) include the whole day of the end date
         let end_date = end_date
-            .map(|s| {
-                NaiveDate::parse_from_str(&s, "%Y-%m-%d")
-                    .map_err(|e| DcCmdError::InvalidArgument(format!("Invalid end date: {}", e)))
-                    .map(|date| {
-                        let time = NaiveTime::from_hms_opt(23, 59, 59).unwrap(); // End of day
-                        let naive_datetime = date.and_time(time);
-                        DateTime::<Utc>::from_naive_utc_and_offset(naive_datetime, Utc)
-                    })
-            })
+            .map(|s| parse_point_in_time(&s, DateBound::End))
             .transpose()?;
 
         let status = status
@@ -124,6 +120,24 @@ impl From<EventOptions> for EventlogParams {
     }
 }
 
+/// period of a single event export file
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum EventPartition {
+    #[default]
+    Day,
+    Month,
+}
+
+impl EventPartition {
+    /// returns the period of given time (e.g. 2024-01-31 or 2024-01)
+    pub fn period(self, time: &DateTime<Utc>) -> String {
+        match self {
+            EventPartition::Day => time.format("%Y-%m-%d").to_string(),
+            EventPartition::Month => time.format("%Y-%m").to_string(),
+        }
+    }
+}
+
 #[derive(Tabled)]
 pub struct LogEventInfo {
     id: i64,
@@ -176,6 +190,231 @@ impl UserPermissionInfo {
     }
 }
 
+#[derive(Tabled)]
+pub struct ExpiringFileInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub expire_at: String,
+}
+
+impl From<&Node> for ExpiringFileInfo {
+    fn from(node: &Node) -> Self {
+        Self {
+            id: node.id,
+            name: node.name.clone(),
+            parent_path: node.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            expire_at: node
+                .expire_at
+                .map(|date| date.to_rfc3339_opts(SecondsFormat::Secs, true))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub struct QuarantineFileInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub size: String,
+    pub last_checked_at: String,
+}
+
+impl From<&(Node, VirusProtectionInfo)> for QuarantineFileInfo {
+    fn from((node, info): &(Node, VirusProtectionInfo)) -> Self {
+        Self {
+            id: node.id,
+            name: node.name.clone(),
+            parent_path: node.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            size: to_readable_size(node.size.unwrap_or(0)),
+            last_checked_at: info.last_checked_at.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// space and user consumption of the customer (`reports usage`)
+pub struct CustomerUsage {
+    pub name: String,
+    pub space_used: u64,
+    pub space_limit: u64,
+    pub accounts_used: u64,
+    pub accounts_limit: u64,
+}
+
+#[derive(Tabled)]
+pub struct RoomUsageInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub size: String,
+    pub quota: String,
+    pub usage: String,
+}
+
+impl From<&Node> for RoomUsageInfo {
+    fn from(room: &Node) -> Self {
+        let size = room.size.unwrap_or(0);
+        let quota = room.quota.filter(|quota| *quota > 0);
+
+        Self {
+            id: room.id,
+            name: room.name.clone(),
+            parent_path: room.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            size: to_readable_size(size),
+            quota: quota.map_or_else(|| "N/A".to_string(), to_readable_size),
+            usage: quota.map_or_else(
+                || "N/A".to_string(),
+                |quota| format!("{:.2}%", size as f64 / quota as f64 * 100.0),
+            ),
+        }
+    }
+}
+
+/// settings of a room (`reports room-settings`)
+pub struct RoomSettings {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    /// recycle bin retention in days (0: deleted nodes are removed immediately)
+    pub recycle_bin_retention: Option<u64>,
+    pub activities_log: Option<bool>,
+    pub member_acceptance: String,
+    pub encrypted: bool,
+    pub inherit_permissions: Option<bool>,
+}
+
+#[derive(Tabled)]
+pub struct RoomSettingsInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub recycle_bin: String,
+    pub activities_log: String,
+    pub member_acceptance: String,
+    pub encrypted: bool,
+    pub inherit_permissions: String,
+}
+
+impl From<&RoomSettings> for RoomSettingsInfo {
+    fn from(settings: &RoomSettings) -> Self {
+        let format_flag =
+            |flag: Option<bool>| flag.map_or_else(|| "N/A".to_string(), |flag| flag.to_string());
+
+        Self {
+            id: settings.id,
+            name: settings.name.clone(),
+            parent_path: settings.parent_path.clone(),
+            recycle_bin: settings
+                .recycle_bin_retention
+                .map_or_else(|| "N/A".to_string(), |days| format!("{days} days")),
+            activities_log: format_flag(settings.activities_log),
+            member_acceptance: settings.member_acceptance.clone(),
+            encrypted: settings.encrypted,
+            inherit_permissions: format_flag(settings.inherit_permissions),
+        }
+    }
+}
+
+/// encryption status of a room (`reports crypto`)
+pub struct RoomCrypto {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub encrypted: bool,
+    /// unencrypted files in an encrypted room (e.g. uploaded before encryption)
+    pub unencrypted_files: u64,
+}
+
+#[derive(Tabled)]
+pub struct RoomCryptoInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    pub encrypted: bool,
+    pub unencrypted_files: u64,
+    pub compliant: bool,
+}
+
+impl From<&RoomCrypto> for RoomCryptoInfo {
+    fn from(room: &RoomCrypto) -> Self {
+        Self {
+            id: room.id,
+            name: room.name.clone(),
+            parent_path: room.parent_path.clone(),
+            encrypted: room.encrypted,
+            unencrypted_files: room.unencrypted_files,
+            compliant: room.is_compliant(),
+        }
+    }
+}
+
+/// state of the user who created a node (`reports orphaned`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreatorState {
+    Active,
+    Locked,
+    Deleted,
+}
+
+impl std::fmt::Display for CreatorState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreatorState::Active => write!(f, "active"),
+            CreatorState::Locked => write!(f, "locked"),
+            CreatorState::Deleted => write!(f, "deleted"),
+        }
+    }
+}
+
+/// node created by a deleted or locked user (`reports orphaned`)
+#[derive(Tabled)]
+pub struct OrphanedNode {
+    pub id: u64,
+    pub name: String,
+    pub parent_path: String,
+    #[tabled(rename = "type")]
+    pub node_type: String,
+    pub created_by_id: u64,
+    pub created_by: String,
+    pub creator_state: CreatorState,
+}
+
+/// new files and bytes within a month (`reports usage --growth`)
+#[derive(Debug, PartialEq)]
+pub struct UsageGrowth {
+    pub month: String,
+    pub files: u64,
+    pub size: u64,
+}
+
+impl UsageGrowth {
+    pub fn new(month: String) -> Self {
+        Self {
+            month,
+            files: 0,
+            size: 0,
+        }
+    }
+}
+
+#[derive(Tabled)]
+pub struct UsageGrowthInfo {
+    month: String,
+    files: u64,
+    size: String,
+}
+
+impl From<&UsageGrowth> for UsageGrowthInfo {
+    fn from(growth: &UsageGrowth) -> Self {
+        Self {
+            month: growth.month.clone(),
+            files: growth.files,
+            size: to_readable_size(growth.size),
+        }
+    }
+}
+
 #[derive(Tabled)]
 pub struct EventOperationInfo {
     id: i64,
@@ -193,11 +432,102 @@ impl From<LogOperation> for EventOperationInfo {
     }
 }
 
+/// aggregated report metadata for dashboards (`--stats-json`)
+#[derive(Debug, Serialize)]
+pub struct ReportStats {
+    pub report: String,
+    pub generated_at: String,
+    pub total: u64,
+    pub date_range: Option<DateRange>,
+    pub counts: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DateRange {
+    pub start: String,
+    pub end: String,
+}
+
+impl ReportStats {
+    /// aggregates report entries (optional timestamp and key to count)
+    pub fn new(
+        report: &str,
+        entries: impl IntoIterator<Item = (Option<DateTime<Utc>>, String)>,
+    ) -> Self {
+        let mut total = 0;
+        let mut counts = BTreeMap::new();
+        let mut range: Option<(DateTime<Utc>, DateTime<Utc>)> = None;
+
+        for (time, key) in entries {
+            total += 1;
+            *counts.entry(key).or_insert(0) += 1;
+
+            if let Some(time) = time {
+                range = Some(match range {
+                    Some((start, end)) => (start.min(time), end.max(time)),
+                    None => (time, time),
+                });
+            }
+        }
+
+        Self {
+            report: report.to_string(),
+            generated_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            total,
+            date_range: range.map(|(start, end)| DateRange {
+                start: start.to_rfc3339_opts(SecondsFormat::Secs, true),
+                end: end.to_rfc3339_opts(SecondsFormat::Secs, true),
+            }),
+            counts,
+        }
+    }
+
+    /// events counted per operation
+    pub fn from_events(events: &[LogEvent]) -> Self {
+        Self::new(
+            "events",
+            events.iter().map(|event| {
+                let operation = event
+                    .operation_name
+                    .clone()
+                    .unwrap_or_else(|| "N/A".to_string());
+                (Some(event.time), operation)
+            }),
+        )
+    }
+
+    /// user permissions counted per permission set
+    pub fn from_permissions(perms: &AuditNodeList) -> Self {
+        Self::new(
+            "permissions",
+            perms.iter().flat_map(|perm| {
+                perm.audit_user_permission_list
+                    .iter()
+                    .map(|user_perm| (None, user_perm.permissions.to_string()))
+            }),
+        )
+    }
+
+    /// expiring files counted per expiration date
+    pub fn from_expiring_files(files: &[Node]) -> Self {
+        Self::new(
+            "expiring",
+            files.iter().map(|file| {
+                let date = file
+                    .expire_at
+                    .map(|date| date.format("%Y-%m-%d").to_string())
+                    .unwrap_or_else(|| "N/A".to_string());
+                (file.expire_at, date)
+            }),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use chrono::Datelike;
+    use chrono::{Datelike, TimeZone, Utc};
 
-    use super::EventOptions;
+    use super::{DateRange, EventOptions, EventPartition, ReportStats};
 
     #[test]
     fn test_create_event_options_with_start_and_end_date() {
@@ -241,4 +571,47 @@ mod tests {
 
         assert_eq!(params.offset, Some(500));
     }
+
+    #[test]
+    fn test_report_stats() {
+        let first = Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap();
+        let last = Utc.with_ymd_and_hms(2024, 1, 31, 18, 0, 0).unwrap();
+
+        let stats = ReportStats::new(
+            "events",
+            vec![
+                (Some(last), "login".to_string()),
+                (Some(first), "login".to_string()),
+                (None, "upload".to_string()),
+            ],
+        );
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.counts.get("login"), Some(&2));
+        assert_eq!(stats.counts.get("upload"), Some(&1));
+        assert_eq!(
+            stats.date_range,
+            Some(DateRange {
+                start: "2024-01-01T08:00:00Z".to_string(),
+                end: "2024-01-31T18:00:00Z".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_report_stats_empty() {
+        let stats = ReportStats::new("events", Vec::new());
+
+        assert_eq!(stats.total, 0);
+        assert!(stats.counts.is_empty());
+        assert!(stats.date_range.is_none());
+    }
+
+    #[test]
+    fn test_event_partition_period() {
+        let time = Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 59).unwrap();
+
+        assert_eq!(EventPartition::Day.period(&time), "2024-01-31");
+        assert_eq!(EventPartition::Month.period(&time), "2024-01");
+    }
 }
@@ -0,0 +1,109 @@
+use std::collections::{BTreeSet, HashMap};
+
+use dco3::{
+    nodes::{Node, NodeType, NodesSearchFilter},
+    Users,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+
+use crate::cmd::{config::MAX_CONCURRENT_REQUESTS, models::DcCmdError};
+
+use super::{
+    models::{CreatorState, OrphanedNode},
+    ReportsCommandHandler,
+};
+
+impl ReportsCommandHandler {
+    /// returns all nodes below target created by deleted or locked users (sorted by parent path)
+    ///
+    /// if `created_by` is set, all nodes created by this user (id) are returned - regardless
+    /// of the user state
+    pub async fn get_orphaned_nodes(
+        &self,
+        target: &str,
+        created_by: Option<u64>,
+    ) -> Result<Vec<OrphanedNode>, DcCmdError> {
+        let mut nodes = self.search_all(target, NodesSearchFilter::is_room).await?;
+        nodes.extend(self.search_all(target, NodesSearchFilter::is_folder).await?);
+        nodes.extend(self.search_all(target, NodesSearchFilter::is_file).await?);
+
+        let nodes = nodes
+            .into_iter()
+            .filter_map(|node| creator_id(&node).map(|id| (id, node)))
+            .filter(|(id, _)| created_by.is_none_or(|created_by| *id == created_by))
+            .collect::<Vec<_>>();
+
+        let creator_ids = nodes.iter().map(|(id, _)| *id).collect::<BTreeSet<_>>();
+        let states = self.get_creator_states(creator_ids).await?;
+
+        let mut orphaned = nodes
+            .into_iter()
+            .filter_map(|(id, node)| {
+                let state = states.get(&id).copied()?;
+                (created_by.is_some() || state != CreatorState::Active)
+                    .then(|| OrphanedNode::new(&node, id, state))
+            })
+            .collect::<Vec<_>>();
+
+        orphaned.sort_by(|a, b| (&a.parent_path, &a.name).cmp(&(&b.parent_path, &b.name)));
+
+        Ok(orphaned)
+    }
+
+    /// looks up the state of all creators concurrently (users not found are deleted)
+    async fn get_creator_states(
+        &self,
+        user_ids: BTreeSet<u64>,
+    ) -> Result<HashMap<u64, CreatorState>, DcCmdError> {
+        stream::iter(user_ids)
+            .map(|user_id| async move {
+                let state = match self.client.users().get_user(user_id, None).await {
+                    Ok(user) if user.is_locked => CreatorState::Locked,
+                    Ok(_) => CreatorState::Active,
+                    Err(e) => match DcCmdError::from(e) {
+                        DcCmdError::DracoonError(ref err) if err.is_not_found() => {
+                            CreatorState::Deleted
+                        }
+                        e => return Err(e),
+                    },
+                };
+
+                Ok((user_id, state))
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+}
+
+impl OrphanedNode {
+    pub fn new(node: &Node, created_by_id: u64, creator_state: CreatorState) -> Self {
+        let node_type = match node.node_type {
+            NodeType::Room => "room",
+            NodeType::Folder => "folder",
+            NodeType::File => "file",
+        };
+
+        let created_by = node
+            .created_by
+            .as_ref()
+            .and_then(|user| user.user_name.clone())
+            .unwrap_or_else(|| "N/A".to_string());
+
+        Self {
+            id: node.id,
+            name: node.name.clone(),
+            parent_path: node.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            node_type: node_type.to_string(),
+            created_by_id,
+            created_by,
+            creator_state,
+        }
+    }
+}
+
+fn creator_id(node: &Node) -> Option<u64> {
+    node.created_by
+        .as_ref()
+        .and_then(|user| u64::try_from(user.id).ok())
+}
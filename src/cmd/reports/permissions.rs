@@ -1,38 +1,234 @@
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
 use dco3::{
-    eventlog::{AuditNodeList, AuditNodesFilter},
+    eventlog::{AuditNodeList, AuditNodeResponse, AuditNodesFilter},
+    nodes::NodesSearchFilter,
     Eventlog, FilterQuery, ListAllParams, Users,
 };
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info, warn};
+
+use crate::cmd::{
+    models::{build_params, DcCmdError, ListOptions},
+    utils::progress::spinner,
+};
+
+use super::{print::PERMISSIONS_CSV_HEADER, ReportsCommandHandler};
+
+/// index of all exported room files (written last)
+const PERMISSIONS_INDEX_FILE: &str = "index.csv";
+
+/// room timestamps and room files of the last export (used by incremental exports)
+const PERMISSIONS_CACHE_FILE: &str = "cache.json";
+
+/// a single room file of a permissions export
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct RoomExport {
+    node_name: String,
+    node_parent_path: String,
+    file_name: String,
+    rows: u64,
+}
 
-use crate::cmd::models::{build_params, DcCmdError, ListOptions};
+/// state of the last export - rooms are only re-fetched if their update timestamp changed
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PermissionsCache {
+    /// update timestamps (RFC 3339) of all rooms at the time of the export
+    rooms: BTreeMap<i64, Option<String>>,
+    /// exported room files (rooms with permissions only)
+    exports: BTreeMap<i64, RoomExport>,
+}
 
-use super::ReportsCommandHandler;
+/// rooms to re-export (new or updated) and rooms removed since the last export
+#[derive(Debug, Default, PartialEq)]
+struct RoomChanges {
+    changed: Vec<i64>,
+    removed: Vec<i64>,
+}
 
 impl ReportsCommandHandler {
     #[allow(deprecated)]
     pub async fn get_permissions(&self, opts: ListOptions) -> Result<AuditNodeList, DcCmdError> {
         let offset = opts.offset().unwrap_or(0);
 
-        if let Some(filter) = opts.filter() {
-            let params = build_params(&Some(filter.to_string()), offset, None)?;
+        let mut perms = Vec::new();
+
+        for filter in self.get_permission_filters(&opts).await? {
+            let params = build_params(&Some(filter), offset, None)?;
+
+            let next_perms = self.client.eventlog().get_node_permissions(params).await?;
 
-            return Ok(self.client.eventlog().get_node_permissions(params).await?);
+            perms.extend(next_perms);
         }
 
-        let user_ids = self.get_all_user_ids().await?;
+        Ok(perms)
+    }
 
-        let mut perms = Vec::new();
+    /// streams permissions (one request per user) into one CSV file per room and
+    /// writes an index of all room files
+    ///
+    /// incremental exports only re-fetch rooms updated since the last (incremental) export
+    #[allow(deprecated)]
+    pub async fn export_permissions(
+        &self,
+        target: &str,
+        opts: ListOptions,
+        out_dir: &Path,
+        incremental: bool,
+    ) -> Result<(), DcCmdError> {
+        std::fs::create_dir_all(out_dir).map_err(|e| {
+            error!("Error creating directory {}: {}", out_dir.display(), e);
+            DcCmdError::IoError
+        })?;
 
-        for user in user_ids {
-            let user_filter = AuditNodesFilter::user_id_equals(user).to_filter_string();
+        let spinner = spinner().with_message("Exporting permissions...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
 
-            let params = build_params(&Some(user_filter), offset, None)?;
+        // timestamps are fetched first - rooms updated during the export are re-fetched next time
+        let (timestamps, mut cache) = if incremental {
+            (
+                Some(self.get_room_timestamps(target).await?),
+                load_cache(out_dir),
+            )
+        } else {
+            (None, PermissionsCache::default())
+        };
 
-            let next_perms = self.client.eventlog().get_node_permissions(params).await?;
+        let exported = match &timestamps {
+            Some(timestamps) if !cache.rooms.is_empty() => {
+                let changes = get_room_changes(&cache.rooms, timestamps);
+                info!(
+                    "Incremental export: {} changed, {} removed rooms.",
+                    changes.changed.len(),
+                    changes.removed.len()
+                );
 
-            perms.extend(next_perms);
+                self.export_changed_rooms(out_dir, &changes, &mut cache.exports, &spinner)
+                    .await?
+            }
+            _ => {
+                let filters = self.get_permission_filters(&opts).await?;
+                let offset = opts.offset().unwrap_or(0);
+
+                self.export_filters(out_dir, &filters, offset, &mut cache.exports, &spinner)
+                    .await?
+            }
+        };
+
+        write_index(out_dir, &cache.exports)?;
+
+        if let Some(timestamps) = timestamps {
+            cache.rooms = timestamps;
+            write_cache(out_dir, &cache)?;
         }
 
-        Ok(perms)
+        spinner.finish_and_clear();
+        info!(
+            "Exported {} permissions of {} rooms to {}.",
+            exported,
+            cache.exports.len(),
+            out_dir.display()
+        );
+
+        self.term
+            .write_line(&format!(
+                "Exported {exported} permissions of {} rooms to {}",
+                cache.exports.len(),
+                out_dir.display()
+            ))
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    /// fetches permissions for each filter and appends them to the room files
+    #[allow(deprecated)]
+    async fn export_filters(
+        &self,
+        out_dir: &Path,
+        filters: &[String],
+        offset: u64,
+        rooms: &mut BTreeMap<i64, RoomExport>,
+        spinner: &ProgressBar,
+    ) -> Result<u64, DcCmdError> {
+        let mut exported = 0u64;
+
+        for (idx, filter) in filters.iter().enumerate() {
+            let params = build_params(&Some(filter.clone()), offset, None)?;
+            let perms = self.client.eventlog().get_node_permissions(params).await?;
+
+            for perm in &perms {
+                exported += append_room_permissions(out_dir, perm, rooms)?;
+            }
+
+            spinner.set_message(format!(
+                "Exported {exported} permissions ({}/{} requests)...",
+                idx + 1,
+                filters.len()
+            ));
+        }
+
+        Ok(exported)
+    }
+
+    /// removes files of changed and removed rooms and re-fetches changed rooms (one request per room)
+    async fn export_changed_rooms(
+        &self,
+        out_dir: &Path,
+        changes: &RoomChanges,
+        rooms: &mut BTreeMap<i64, RoomExport>,
+        spinner: &ProgressBar,
+    ) -> Result<u64, DcCmdError> {
+        for node_id in changes.changed.iter().chain(&changes.removed) {
+            if let Some(room) = rooms.remove(node_id) {
+                remove_room_file(&out_dir.join(room.file_name));
+            }
+        }
+
+        let filters = changes
+            .changed
+            .iter()
+            .map(|node_id| format!("nodeId:eq:{node_id}"))
+            .collect::<Vec<_>>();
+
+        self.export_filters(out_dir, &filters, 0, rooms, spinner)
+            .await
+    }
+
+    /// update timestamps of all rooms below target
+    async fn get_room_timestamps(
+        &self,
+        target: &str,
+    ) -> Result<BTreeMap<i64, Option<String>>, DcCmdError> {
+        let rooms = self.search_all(target, NodesSearchFilter::is_room).await?;
+
+        Ok(rooms
+            .into_iter()
+            .filter_map(|room| {
+                i64::try_from(room.id)
+                    .ok()
+                    .map(|node_id| (node_id, room.updated_at.map(|dt| dt.to_rfc3339())))
+            })
+            .collect())
+    }
+
+    /// a passed filter is used as is - otherwise permissions are fetched per user
+    async fn get_permission_filters(&self, opts: &ListOptions) -> Result<Vec<String>, DcCmdError> {
+        if let Some(filter) = opts.filter() {
+            return Ok(vec![filter.to_string()]);
+        }
+
+        let user_ids = self.get_all_user_ids().await?;
+
+        Ok(user_ids
+            .into_iter()
+            .map(|user| AuditNodesFilter::user_id_equals(user).to_filter_string())
+            .collect())
     }
 
     async fn get_all_user_ids(&self) -> Result<Vec<u64>, DcCmdError> {
@@ -56,3 +252,284 @@ impl ReportsCommandHandler {
         Ok(user_ids)
     }
 }
+
+/// appends all user permissions of a room to its file (rooms show up in multiple
+/// requests - files are only kept open while writing)
+fn append_room_permissions(
+    out_dir: &Path,
+    perm: &AuditNodeResponse,
+    rooms: &mut BTreeMap<i64, RoomExport>,
+) -> Result<u64, DcCmdError> {
+    if perm.audit_user_permission_list.is_empty() {
+        return Ok(0);
+    }
+
+    let is_new = !rooms.contains_key(&perm.node_id);
+
+    let room = rooms.entry(perm.node_id).or_insert_with(|| RoomExport {
+        node_name: perm.node_name.clone(),
+        node_parent_path: perm.node_parent_path.clone(),
+        file_name: room_file_name(perm.node_id),
+        rows: 0,
+    });
+
+    let mut writer = open_room_file(&out_dir.join(&room.file_name), is_new)?;
+
+    for user_perms in &perm.audit_user_permission_list {
+        writeln!(
+            writer,
+            "{}",
+            ReportsCommandHandler::format_permission_csv(perm, user_perms)
+        )
+        .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    writer.flush().map_err(|_| DcCmdError::IoError)?;
+
+    let rows = perm.audit_user_permission_list.len() as u64;
+    room.rows += rows;
+
+    Ok(rows)
+}
+
+/// opens the export file of a room - files of a previous export are replaced
+/// on first use (with header) and appended to afterwards
+fn open_room_file(path: &Path, is_new: bool) -> Result<BufWriter<File>, DcCmdError> {
+    debug!("Writing permissions to {}", path.display());
+
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(is_new)
+        .append(!is_new)
+        .open(path)
+        .map_err(|e| {
+            error!("Error opening {}: {}", path.display(), e);
+            DcCmdError::IoError
+        })?;
+
+    let mut writer = BufWriter::new(file);
+
+    if is_new {
+        writeln!(writer, "{PERMISSIONS_CSV_HEADER}").map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(writer)
+}
+
+fn remove_room_file(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => debug!("Removed {}", path.display()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        Err(e) => warn!("Error removing {}: {}", path.display(), e),
+    }
+}
+
+/// compares the room timestamps of the last export with the current ones
+/// (rooms without timestamp are always re-fetched)
+fn get_room_changes(
+    previous: &BTreeMap<i64, Option<String>>,
+    current: &BTreeMap<i64, Option<String>>,
+) -> RoomChanges {
+    let changed = current
+        .iter()
+        .filter(|(node_id, updated_at)| {
+            updated_at.is_none() || previous.get(node_id) != Some(updated_at)
+        })
+        .map(|(node_id, _)| *node_id)
+        .collect();
+
+    let removed = previous
+        .keys()
+        .filter(|node_id| !current.contains_key(node_id))
+        .copied()
+        .collect();
+
+    RoomChanges { changed, removed }
+}
+
+/// loads the cache of the last export - a missing or invalid cache triggers a full export
+fn load_cache(out_dir: &Path) -> PermissionsCache {
+    let path = out_dir.join(PERMISSIONS_CACHE_FILE);
+
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        debug!("No permissions cache found in {}", out_dir.display());
+        return PermissionsCache::default();
+    };
+
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        warn!(
+            "Invalid permissions cache {} ({}) - full export",
+            path.display(),
+            e
+        );
+        PermissionsCache::default()
+    })
+}
+
+fn write_cache(out_dir: &Path, cache: &PermissionsCache) -> Result<(), DcCmdError> {
+    let path = out_dir.join(PERMISSIONS_CACHE_FILE);
+    let content = serde_json::to_string(cache).map_err(|_| DcCmdError::IoError)?;
+
+    std::fs::write(&path, content).map_err(|e| {
+        error!("Error writing {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })
+}
+
+fn write_index(out_dir: &Path, rooms: &BTreeMap<i64, RoomExport>) -> Result<(), DcCmdError> {
+    let path = out_dir.join(PERMISSIONS_INDEX_FILE);
+
+    let file = File::create(&path).map_err(|e| {
+        error!("Error creating {}: {}", path.display(), e);
+        DcCmdError::IoError
+    })?;
+
+    write_index_csv(rooms, file)
+}
+
+fn write_index_csv(
+    rooms: &BTreeMap<i64, RoomExport>,
+    writer: impl Write,
+) -> Result<(), DcCmdError> {
+    let mut writer = csv::Writer::from_writer(writer);
+
+    writer
+        .write_record(["nodeId", "nodeName", "nodeParentPath", "file", "rows"])
+        .map_err(|_| DcCmdError::IoError)?;
+
+    for (node_id, room) in rooms {
+        writer
+            .write_record([
+                node_id.to_string(),
+                room.node_name.clone(),
+                room.node_parent_path.clone(),
+                room.file_name.clone(),
+                room.rows.to_string(),
+            ])
+            .map_err(|e| {
+                error!("Failed to write permissions index: {}", e);
+                DcCmdError::IoError
+            })?;
+    }
+
+    writer.flush().map_err(|_| DcCmdError::IoError)
+}
+
+fn room_file_name(node_id: i64) -> String {
+    format!("room-{node_id}.csv")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_index_csv() {
+        let mut rooms = BTreeMap::new();
+        rooms.insert(
+            42,
+            RoomExport {
+                node_name: "Finance, Legal".into(),
+                node_parent_path: "/".into(),
+                file_name: room_file_name(42),
+                rows: 3,
+            },
+        );
+        rooms.insert(
+            7,
+            RoomExport {
+                node_name: "HR".into(),
+                node_parent_path: "/Departments/".into(),
+                file_name: room_file_name(7),
+                rows: 1,
+            },
+        );
+
+        let mut buf = Vec::new();
+        write_index_csv(&rooms, &mut buf).unwrap();
+
+        assert_eq!(
+            "nodeId,nodeName,nodeParentPath,file,rows\n7,HR,/Departments/,room-7.csv,1\n42,\"Finance, Legal\",/,room-42.csv,3\n",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_get_room_changes() {
+        let updated_at = Some("2024-01-01T00:00:00+00:00".to_string());
+        let previous =
+            BTreeMap::from([(1, updated_at.clone()), (2, updated_at.clone()), (3, None)]);
+        let current = BTreeMap::from([
+            (1, updated_at.clone()),
+            (2, Some("2024-01-02T00:00:00+00:00".to_string())),
+            (3, None),
+            (4, updated_at.clone()),
+        ]);
+
+        assert_eq!(
+            RoomChanges {
+                changed: vec![2, 3, 4],
+                removed: vec![],
+            },
+            get_room_changes(&previous, &current)
+        );
+
+        let current = BTreeMap::from([(1, updated_at)]);
+        assert_eq!(
+            RoomChanges {
+                changed: vec![],
+                removed: vec![2, 3],
+            },
+            get_room_changes(&previous, &current)
+        );
+    }
+
+    #[test]
+    fn test_load_cache_roundtrip() {
+        let dir = std::env::temp_dir().join("dccmd-rs-test-permissions-cache");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(load_cache(&dir).rooms.is_empty());
+
+        let mut cache = PermissionsCache::default();
+        cache.rooms.insert(42, None);
+        cache.exports.insert(
+            42,
+            RoomExport {
+                node_name: "HR".into(),
+                node_parent_path: "/".into(),
+                file_name: room_file_name(42),
+                rows: 2,
+            },
+        );
+        write_cache(&dir, &cache).unwrap();
+
+        let loaded = load_cache(&dir);
+        assert_eq!(cache.rooms, loaded.rooms);
+        assert_eq!(cache.exports, loaded.exports);
+
+        std::fs::write(dir.join(PERMISSIONS_CACHE_FILE), "invalid").unwrap();
+        assert!(load_cache(&dir).rooms.is_empty());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_open_room_file_replaces_previous_export() {
+        let dir = std::env::temp_dir().join("dccmd-rs-test-permissions-export");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(room_file_name(1));
+        std::fs::write(&path, "previous export\n").unwrap();
+
+        for is_new in [true, false] {
+            let mut writer = open_room_file(&path, is_new).unwrap();
+            writeln!(writer, "row").unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(format!("{PERMISSIONS_CSV_HEADER}\nrow\nrow\n"), content);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,14 +1,33 @@
 use chrono::SecondsFormat;
-use dco3::eventlog::{AuditNodeList, LogEventList, LogOperationList};
+use dco3::{
+    eventlog::{
+        AuditNodeList, AuditNodeResponse, AuditUserPermission, LogEvent, LogEventList,
+        LogOperationList,
+    },
+    nodes::Node,
+};
 use tabled::settings::{Panel, Style};
+use tracing::error;
 
-use crate::cmd::models::DcCmdError;
+use crate::cmd::{
+    models::DcCmdError,
+    nodes::virus::VirusProtectionInfo,
+    utils::{fields::CsvOutput, strings::to_readable_size},
+};
 
 use super::{
-    models::{EventOperationInfo, LogEventInfo, UserPermissionInfo},
+    models::{
+        CreatorState, CustomerUsage, EventOperationInfo, ExpiringFileInfo, LogEventInfo,
+        OrphanedNode, QuarantineFileInfo, ReportStats, RoomCrypto, RoomCryptoInfo, RoomSettings,
+        RoomSettingsInfo, RoomUsageInfo, UsageGrowth, UsageGrowthInfo, UserPermissionInfo,
+    },
     ReportsCommandHandler,
 };
 
+pub const PERMISSIONS_CSV_HEADER: &str = "nodeId,nodeName,nodeParentPath,userId,userLogin,userFirstName,userLastName,manage,read,create,change,delete,manageDownloadShare,manageUploadShare,readRecycleBin,restoreRecycleBin,deleteRecycleBin";
+
+pub const EVENTS_CSV_HEADER: &str = "id,time,user_id,message,operation_id,operation_name,status,user_client,user_name,customer_id,auth_parent_source,auth_parent_target,object_id1,object_id2,object_type1,object_type2,object_name1,object_name2,attribute1,attribute2,attribute3";
+
 impl ReportsCommandHandler {
     pub fn print_events(&self, events: LogEventList, csv: bool) -> Result<(), DcCmdError> {
         if csv {
@@ -26,79 +45,350 @@ impl ReportsCommandHandler {
         }
     }
 
-    fn print_events_csv(&self, events: LogEventList) -> Result<(), DcCmdError> {
-        const NOT_AVAILABLE: &str = "N/A";
-        let header = "id,time,user_id,message,operation_id,operation_name,status,user_client,user_name,customer_id,auth_parent_source,auth_parent_target,object_id1,object_id2,object_type1,object_type2,object_name1,object_name2,attribute1,attribute2,attribute3";
+    pub fn print_expiring_files(&self, files: Vec<Node>, csv: bool) -> Result<(), DcCmdError> {
+        if csv {
+            self.print_expiring_files_csv(files)
+        } else {
+            self.print_expiring_files_table(files)
+        }
+    }
+
+    pub fn print_usage(
+        &self,
+        customer: &CustomerUsage,
+        rooms: &[Node],
+        csv: bool,
+    ) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(&self.term, "id,name,parent_path,size,quota")?;
+
+            for room in rooms {
+                let line = format!(
+                    "{},{},{},{},{}",
+                    room.id,
+                    room.name,
+                    room.parent_path.as_deref().unwrap_or("/"),
+                    room.size.unwrap_or(0),
+                    room.quota.unwrap_or(0)
+                );
+
+                output.write_line(&line)?;
+            }
+
+            return Ok(());
+        }
+
+        let percent_space_used = if customer.space_limit > 0 {
+            customer.space_used as f64 / customer.space_limit as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        self.term
+            .write_line(&format!("► Customer: {}", customer.name))
+            .map_err(|_| DcCmdError::IoError)?;
+        self.term
+            .write_line(&format!(
+                "► Space used: {} / {} ({percent_space_used:.2}%)",
+                to_readable_size(customer.space_used),
+                to_readable_size(customer.space_limit)
+            ))
+            .map_err(|_| DcCmdError::IoError)?;
+        self.term
+            .write_line(&format!(
+                "► Users used: {} / {}",
+                customer.accounts_used, customer.accounts_limit
+            ))
+            .map_err(|_| DcCmdError::IoError)?;
+
+        let room_count = rooms.len();
+        let rooms = rooms.iter().map(RoomUsageInfo::from).collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(rooms);
+        table
+            .with(Style::modern())
+            .with(Panel::footer(format!("{room_count} rooms")));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_malicious_files(
+        &self,
+        files: &[(Node, VirusProtectionInfo)],
+        csv: bool,
+    ) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(&self.term, "id,name,parent_path,size,last_checked_at")?;
+
+            for file in files {
+                let info = QuarantineFileInfo::from(file);
+                let line = format!(
+                    "{},{},{},{},{}",
+                    info.id,
+                    info.name,
+                    info.parent_path,
+                    file.0.size.unwrap_or(0),
+                    info.last_checked_at
+                );
+
+                output.write_line(&line)?;
+            }
+
+            return Ok(());
+        }
+
+        let file_count = files.len();
+        let files = files
+            .iter()
+            .map(QuarantineFileInfo::from)
+            .collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(files);
+        table
+            .with(Style::modern())
+            .with(Panel::footer(format!("{file_count} malicious files")));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_room_settings(&self, rooms: &[RoomSettings], csv: bool) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(
+                &self.term,
+                "id,name,parent_path,recycle_bin_retention,activities_log,member_acceptance,encrypted,inherit_permissions",
+            )?;
+
+            let format_optional = |value: Option<String>| value.unwrap_or_default();
+
+            for room in rooms {
+                let line = format!(
+                    "{},{},{},{},{},{},{},{}",
+                    room.id,
+                    room.name,
+                    room.parent_path,
+                    format_optional(room.recycle_bin_retention.map(|days| days.to_string())),
+                    format_optional(room.activities_log.map(|flag| flag.to_string())),
+                    room.member_acceptance,
+                    room.encrypted,
+                    format_optional(room.inherit_permissions.map(|flag| flag.to_string()))
+                );
+
+                output.write_line(&line)?;
+            }
+
+            return Ok(());
+        }
+
+        let room_count = rooms.len();
+        let rooms = rooms.iter().map(RoomSettingsInfo::from).collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(rooms);
+        table
+            .with(Style::modern())
+            .with(Panel::footer(format!("{room_count} rooms")));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_room_crypto(&self, rooms: &[RoomCrypto], csv: bool) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(
+                &self.term,
+                "id,name,parent_path,encrypted,unencrypted_files,compliant",
+            )?;
+
+            for room in rooms {
+                let line = format!(
+                    "{},{},{},{},{},{}",
+                    room.id,
+                    room.name,
+                    room.parent_path,
+                    room.encrypted,
+                    room.unencrypted_files,
+                    room.is_compliant()
+                );
+
+                output.write_line(&line)?;
+            }
+
+            return Ok(());
+        }
+
+        let room_count = rooms.len();
+        let encrypted_count = rooms.iter().filter(|room| room.encrypted).count();
+        let non_compliant_count = rooms.iter().filter(|room| !room.is_compliant()).count();
+        let rooms = rooms.iter().map(RoomCryptoInfo::from).collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(rooms);
+        table.with(Style::modern()).with(Panel::footer(format!(
+            "{room_count} rooms | {encrypted_count} encrypted | {non_compliant_count} not compliant"
+        )));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_orphaned_nodes(
+        &self,
+        nodes: &[OrphanedNode],
+        csv: bool,
+    ) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(
+                &self.term,
+                "id,name,parent_path,type,created_by_id,created_by,creator_state",
+            )?;
+
+            for node in nodes {
+                let line = format!(
+                    "{},{},{},{},{},{},{}",
+                    node.id,
+                    node.name,
+                    node.parent_path,
+                    node.node_type,
+                    node.created_by_id,
+                    node.created_by,
+                    node.creator_state
+                );
+
+                output.write_line(&line)?;
+            }
+
+            return Ok(());
+        }
+
+        let node_count = nodes.len();
+        let count_state = |state: CreatorState| {
+            nodes
+                .iter()
+                .filter(|node| node.creator_state == state)
+                .count()
+        };
+        let deleted_count = count_state(CreatorState::Deleted);
+        let locked_count = count_state(CreatorState::Locked);
+
+        let mut table = tabled::Table::new(nodes);
+        table.with(Style::modern()).with(Panel::footer(format!(
+            "{node_count} nodes | {deleted_count} by deleted users | {locked_count} by locked users"
+        )));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_usage_growth(&self, growth: &[UsageGrowth], csv: bool) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(&self.term, "month,files,size")?;
+
+            for entry in growth {
+                output.write_line(&format!("{},{},{}", entry.month, entry.files, entry.size))?;
+            }
+
+            return Ok(());
+        }
+
+        let total_size = growth.iter().map(|entry| entry.size).sum();
+        let total_files: u64 = growth.iter().map(|entry| entry.files).sum();
+        let growth = growth.iter().map(UsageGrowthInfo::from).collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(growth);
+        table.with(Style::modern()).with(Panel::footer(format!(
+            "{total_files} new files ({})",
+            to_readable_size(total_size)
+        )));
 
         self.term
-            .write_line(header)
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    pub fn print_stats_json(&self, stats: &ReportStats) -> Result<(), DcCmdError> {
+        let json = serde_json::to_string_pretty(stats).map_err(|e| {
+            error!("Error serializing report stats: {}", e);
+            DcCmdError::IoError
+        })?;
+
+        self.term.write_line(&json).map_err(|_| DcCmdError::IoError)
+    }
+
+    fn print_expiring_files_csv(&self, files: Vec<Node>) -> Result<(), DcCmdError> {
+        let output = CsvOutput::new(&self.term, "id,name,parent_path,expire_at")?;
+
+        for file in &files {
+            let file = ExpiringFileInfo::from(file);
+            let line = format!(
+                "{},{},{},{}",
+                file.id, file.name, file.parent_path, file.expire_at
+            );
+
+            output.write_line(&line)?;
+        }
+
+        Ok(())
+    }
+
+    fn print_expiring_files_table(&self, files: Vec<Node>) -> Result<(), DcCmdError> {
+        let file_count = files.len();
+        let files = files.iter().map(ExpiringFileInfo::from).collect::<Vec<_>>();
+
+        let mut table = tabled::Table::new(files);
+        table
+            .with(Style::modern())
+            .with(Panel::footer(format!("{file_count} expiring files")));
+
+        self.term
+            .write_line(&table.to_string())
             .map_err(|_| DcCmdError::IoError)?;
 
+        Ok(())
+    }
+
+    fn print_events_csv(&self, events: LogEventList) -> Result<(), DcCmdError> {
+        let output = CsvOutput::new(&self.term, EVENTS_CSV_HEADER)?;
+
         for event in events.items {
-            let id = event.id.to_string();
-            let time = event.time.to_rfc3339_opts(SecondsFormat::Secs, true);
-            let user_id = event.user_id.to_string();
-            let message = event.message;
-            let operation_id = event
-                .operation_id
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let operation_name = event
-                .operation_name
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let status = event
-                .status
-                .map(|s| i64::from(s).to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let user_client = event
-                .user_client
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let user_name = event.user_name.unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let customer_id = event
-                .customer_id
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let auth_parent_source = event
-                .auth_parent_source
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let auth_parent_target = event
-                .auth_parent_target
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_id1 = event
-                .object_id1
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_id2 = event
-                .object_id2
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_type1 = event
-                .object_type1
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_type2 = event
-                .object_type2
-                .map(|id| id.to_string())
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_name1 = event
-                .object_name1
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let object_name2 = event
-                .object_name2
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let attribute1 = event
-                .attribute1
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let attribute2 = event
-                .attribute2
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
-            let attribute3 = event
-                .attribute3
-                .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+            output.write_line(&Self::format_event_csv(event))?;
+        }
 
+        Ok(())
+    }
+
+    pub fn print_events_csv_header(&self) -> Result<(), DcCmdError> {
+        CsvOutput::new(&self.term, EVENTS_CSV_HEADER).map(|_| ())
+    }
+
+    /// prints events as they arrive in follow mode (no header / footer)
+    pub fn print_followed_events(
+        &self,
+        events: Vec<LogEvent>,
+        csv: bool,
+    ) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::without_header(&self.term, EVENTS_CSV_HEADER)?;
+
+            for event in events {
+                output.write_line(&Self::format_event_csv(event))?;
+            }
+
+            return Ok(());
+        }
+
+        for event in events {
             let line = format!(
-            "{id},{time},{user_id},{message},{operation_id},{operation_name},{status},{user_client},{user_name},{customer_id},{auth_parent_source},{auth_parent_target},{object_id1},{object_id2},{object_type1},{object_type2},{object_name1},{object_name2},{attribute1},{attribute2},{attribute3}",
-        );
+                "{} | {} | {} | {}",
+                event.id,
+                event.time.to_rfc3339_opts(SecondsFormat::Secs, true),
+                event.user_id,
+                event.message
+            );
 
             self.term
                 .write_line(&line)
@@ -108,6 +398,75 @@ impl ReportsCommandHandler {
         Ok(())
     }
 
+    pub fn format_event_csv(event: LogEvent) -> String {
+        const NOT_AVAILABLE: &str = "N/A";
+
+        let id = event.id.to_string();
+        let time = event.time.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let user_id = event.user_id.to_string();
+        let message = event.message;
+        let operation_id = event
+            .operation_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let operation_name = event
+            .operation_name
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let status = event
+            .status
+            .map(|s| i64::from(s).to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let user_client = event
+            .user_client
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let user_name = event.user_name.unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let customer_id = event
+            .customer_id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let auth_parent_source = event
+            .auth_parent_source
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let auth_parent_target = event
+            .auth_parent_target
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_id1 = event
+            .object_id1
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_id2 = event
+            .object_id2
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_type1 = event
+            .object_type1
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_type2 = event
+            .object_type2
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_name1 = event
+            .object_name1
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let object_name2 = event
+            .object_name2
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let attribute1 = event
+            .attribute1
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let attribute2 = event
+            .attribute2
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+        let attribute3 = event
+            .attribute3
+            .unwrap_or_else(|| NOT_AVAILABLE.to_string());
+
+        format!(
+            "{id},{time},{user_id},{message},{operation_id},{operation_name},{status},{user_client},{user_name},{customer_id},{auth_parent_source},{auth_parent_target},{object_id1},{object_id2},{object_type1},{object_type2},{object_name1},{object_name2},{attribute1},{attribute2},{attribute3}",
+        )
+    }
+
     fn print_events_table(&self, events: LogEventList) -> Result<(), DcCmdError> {
         let event_count = events.items.len();
         let total_events = events.range.total;
@@ -170,48 +529,46 @@ impl ReportsCommandHandler {
     }
 
     fn print_permissions_csv(&self, perms: AuditNodeList) -> Result<(), DcCmdError> {
-        let header = "nodeId,nodeName,nodeParentPath,userId,userLogin,userFirstName,userLastName,manage,read,create,change,delete,manageDownloadShare,manageUploadShare,readRecycleBin,restoreRecycleBin,deleteRecycleBin";
-
-        self.term
-            .write_line(header)
-            .map_err(|_| DcCmdError::IoError)?;
+        let output = CsvOutput::new(&self.term, PERMISSIONS_CSV_HEADER)?;
 
         for perm in perms {
-            let node_id = perm.node_id.to_string();
-            let node_name = perm.node_name;
-            let node_parent_path = perm.node_parent_path;
-            let permission = perm.audit_user_permission_list.first();
-            if permission.is_none() {
+            let Some(user_perms) = perm.audit_user_permission_list.first() else {
                 continue;
-            }
-            let user_perms = permission.unwrap();
-            let user_id = user_perms.user_id.to_string();
-            let user_login = &user_perms.user_login;
-            let user_first_name = &user_perms.user_first_name;
-            let user_last_name = &user_perms.user_last_name;
-            let manage = user_perms.permissions.manage.to_string();
-            let read = user_perms.permissions.read.to_string();
-            let create = user_perms.permissions.create.to_string();
-            let change = user_perms.permissions.change.to_string();
-            let delete = user_perms.permissions.delete.to_string();
-            let manage_download_share = user_perms.permissions.manage_download_share.to_string();
-            let manage_upload_share = user_perms.permissions.manage_upload_share.to_string();
-            let read_recycle_bin = user_perms.permissions.read_recycle_bin.to_string();
-            let restore_recycle_bin = user_perms.permissions.restore_recycle_bin.to_string();
-            let delete_recycle_bin = user_perms.permissions.delete_recycle_bin.to_string();
+            };
 
-            let line = format!(
-                "{node_id},{node_name},{node_parent_path},{user_id},{user_login},{user_first_name},{user_last_name},{manage},{read},{create},{change},{delete},{manage_download_share},{manage_upload_share},{read_recycle_bin},{restore_recycle_bin},{delete_recycle_bin}",
-            );
-
-            self.term
-                .write_line(&line)
-                .map_err(|_| DcCmdError::IoError)?;
+            output.write_line(&Self::format_permission_csv(&perm, user_perms))?;
         }
 
         Ok(())
     }
 
+    pub fn format_permission_csv(
+        perm: &AuditNodeResponse,
+        user_perms: &AuditUserPermission,
+    ) -> String {
+        let node_id = perm.node_id.to_string();
+        let node_name = &perm.node_name;
+        let node_parent_path = &perm.node_parent_path;
+        let user_id = user_perms.user_id.to_string();
+        let user_login = &user_perms.user_login;
+        let user_first_name = &user_perms.user_first_name;
+        let user_last_name = &user_perms.user_last_name;
+        let manage = user_perms.permissions.manage.to_string();
+        let read = user_perms.permissions.read.to_string();
+        let create = user_perms.permissions.create.to_string();
+        let change = user_perms.permissions.change.to_string();
+        let delete = user_perms.permissions.delete.to_string();
+        let manage_download_share = user_perms.permissions.manage_download_share.to_string();
+        let manage_upload_share = user_perms.permissions.manage_upload_share.to_string();
+        let read_recycle_bin = user_perms.permissions.read_recycle_bin.to_string();
+        let restore_recycle_bin = user_perms.permissions.restore_recycle_bin.to_string();
+        let delete_recycle_bin = user_perms.permissions.delete_recycle_bin.to_string();
+
+        format!(
+            "{node_id},{node_name},{node_parent_path},{user_id},{user_login},{user_first_name},{user_last_name},{manage},{read},{create},{change},{delete},{manage_download_share},{manage_upload_share},{read_recycle_bin},{restore_recycle_bin},{delete_recycle_bin}",
+        )
+    }
+
     pub fn print_event_types(&self, operations: LogOperationList) -> Result<(), DcCmdError> {
         let event_types = operations
             .operation_list
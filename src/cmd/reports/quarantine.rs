@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use dco3::nodes::{Node, NodesSearchFilter};
+
+use crate::cmd::{
+    models::DcCmdError,
+    nodes::virus::{get_virus_protection_info, VirusProtectionInfo, VirusProtectionVerdict},
+};
+
+use super::ReportsCommandHandler;
+
+impl ReportsCommandHandler {
+    /// returns all files below target flagged as malicious by the virus scan (sorted by parent path)
+    pub async fn get_malicious_files(
+        &self,
+        target: &str,
+    ) -> Result<Vec<(Node, VirusProtectionInfo)>, DcCmdError> {
+        let files = self.search_all(target, NodesSearchFilter::is_file).await?;
+
+        let file_ids = files.iter().map(|file| file.id).collect::<Vec<_>>();
+        let mut malicious = get_virus_protection_info(&self.client, file_ids)
+            .await?
+            .into_iter()
+            .filter(|info| info.verdict == VirusProtectionVerdict::Malicious)
+            .map(|info| (info.node_id, info))
+            .collect::<HashMap<_, _>>();
+
+        Ok(files
+            .into_iter()
+            .filter_map(|file| malicious.remove(&file.id).map(|info| (file, info)))
+            .collect())
+    }
+}
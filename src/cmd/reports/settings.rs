@@ -0,0 +1,83 @@
+use dco3::nodes::{rooms::models::GroupMemberAcceptance, Node, NodesSearchFilter};
+use futures_util::{stream, StreamExt, TryStreamExt};
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS, models::DcCmdError, rooms::get_all_room_groups,
+};
+
+use super::{models::RoomSettings, ReportsCommandHandler};
+
+impl ReportsCommandHandler {
+    /// returns the settings of all rooms below target (sorted by parent path)
+    pub async fn get_room_settings(&self, target: &str) -> Result<Vec<RoomSettings>, DcCmdError> {
+        let rooms = self.search_all(target, NodesSearchFilter::is_room).await?;
+
+        stream::iter(rooms)
+            .map(|room| async move {
+                let pending_groups = self.get_pending_groups(room.id).await?;
+                Ok::<_, DcCmdError>(RoomSettings::new(&room, pending_groups))
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+
+    /// returns for each group of a room if new members require acceptance
+    async fn get_pending_groups(&self, room_id: u64) -> Result<Vec<bool>, DcCmdError> {
+        let groups = get_all_room_groups(&self.client, room_id).await?;
+
+        Ok(groups
+            .into_iter()
+            .filter(|group| group.is_granted)
+            .map(|group| {
+                matches!(
+                    group.new_group_member_acceptance,
+                    Some(GroupMemberAcceptance::Pending)
+                )
+            })
+            .collect())
+    }
+}
+
+impl RoomSettings {
+    pub fn new(room: &Node, pending_groups: Vec<bool>) -> Self {
+        Self {
+            id: room.id,
+            name: room.name.clone(),
+            parent_path: room.parent_path.clone().unwrap_or_else(|| "/".to_string()),
+            recycle_bin_retention: room.recycle_bin_retention_period,
+            activities_log: room.has_activities_log,
+            member_acceptance: member_acceptance(&pending_groups),
+            encrypted: room.is_encrypted.unwrap_or(false),
+            inherit_permissions: room.inherit_permissions,
+        }
+    }
+}
+
+/// summarizes the member acceptance of all granted groups of a room
+fn member_acceptance(pending_groups: &[bool]) -> String {
+    let pending = pending_groups.iter().filter(|pending| **pending).count();
+
+    match (pending, pending_groups.len()) {
+        (_, 0) => "no groups".to_string(),
+        (0, _) => "autoallow".to_string(),
+        (pending, total) if pending == total => "pending".to_string(),
+        (pending, total) => format!("pending ({pending}/{total} groups)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::member_acceptance;
+
+    #[test]
+    fn test_member_acceptance() {
+        assert_eq!("no groups", member_acceptance(&[]));
+        assert_eq!("autoallow", member_acceptance(&[false, false]));
+        assert_eq!("pending", member_acceptance(&[true]));
+        assert_eq!(
+            "pending (1/3 groups)",
+            member_acceptance(&[false, true, false])
+        );
+    }
+}
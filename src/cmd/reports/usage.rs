@@ -0,0 +1,188 @@
+use chrono::{DateTime, Months, Utc};
+use dco3::{
+    nodes::{Node, NodesSearchFilter, NodesSearchSortBy},
+    ListAllParams, Nodes, SortOrder, User,
+};
+
+use crate::cmd::{
+    models::DcCmdError,
+    utils::{
+        pagination::fetch_all_paginated,
+        strings::{build_node_path, parse_path},
+    },
+};
+
+use super::{
+    models::{CustomerUsage, UsageGrowth},
+    ReportsCommandHandler,
+};
+
+impl ReportsCommandHandler {
+    /// returns space and user consumption of the customer (instance)
+    pub async fn get_customer_usage(&self) -> Result<CustomerUsage, DcCmdError> {
+        let customer_info = self.client.user().get_customer_info().await?;
+
+        Ok(CustomerUsage {
+            name: customer_info.name,
+            space_used: customer_info.space_used,
+            space_limit: customer_info.space_limit,
+            accounts_used: customer_info.accounts_used,
+            accounts_limit: customer_info.accounts_limit,
+        })
+    }
+
+    /// returns all rooms below target (sorted by size, largest first)
+    pub async fn get_room_usage(&self, target: &str) -> Result<Vec<Node>, DcCmdError> {
+        let mut rooms = self.search_all(target, NodesSearchFilter::is_room).await?;
+
+        rooms.sort_by_key(|room| std::cmp::Reverse(room.size.unwrap_or(0)));
+
+        Ok(rooms)
+    }
+
+    /// returns storage growth (new files per month) of the last n months
+    pub async fn get_usage_growth(
+        &self,
+        target: &str,
+        months: u32,
+    ) -> Result<Vec<UsageGrowth>, DcCmdError> {
+        let files = self.search_all(target, NodesSearchFilter::is_file).await?;
+
+        let since = Utc::now()
+            .checked_sub_months(Months::new(months.saturating_sub(1)))
+            .unwrap_or_else(Utc::now);
+
+        Ok(aggregate_growth(
+            files
+                .iter()
+                .filter_map(|file| file.created_at.map(|date| (date, file.size.unwrap_or(0)))),
+            since,
+        ))
+    }
+
+    /// searches all nodes below target matching filter (all rooms if no path is provided)
+    pub(super) async fn search_all(
+        &self,
+        target: &str,
+        filter: fn() -> NodesSearchFilter,
+    ) -> Result<Vec<Node>, DcCmdError> {
+        let (parent_path, node_name, depth) =
+            parse_path(target, self.client.get_base_url().as_ref())?;
+        let node_path = build_node_path((parent_path, node_name, depth));
+
+        let parent_id = if node_path == "//" {
+            None
+        } else {
+            let node = self
+                .client
+                .nodes()
+                .get_node_from_path(&node_path)
+                .await?
+                .ok_or(DcCmdError::InvalidPath(target.to_string()))?;
+
+            Some(node.id)
+        };
+
+        let params = ListAllParams::builder()
+            .with_filter(filter())
+            .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+            .build();
+
+        let nodes = self
+            .client
+            .nodes()
+            .search_nodes("*", parent_id, Some(-1), Some(params))
+            .await?;
+
+        let client = self.client.clone();
+
+        let nodes = fetch_all_paginated(nodes, move |offset| {
+            let client = client.clone();
+            let params = ListAllParams::builder()
+                .with_filter(filter())
+                .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+                .with_offset(offset)
+                .build();
+
+            async move {
+                let nodes = client
+                    .nodes()
+                    .search_nodes("*", parent_id, Some(-1), Some(params))
+                    .await?;
+
+                Ok::<_, DcCmdError>(nodes)
+            }
+        })
+        .await?;
+
+        Ok(nodes.items)
+    }
+}
+
+/// sums up files and bytes per month (oldest first) - every month since given date is included
+fn aggregate_growth(
+    files: impl IntoIterator<Item = (DateTime<Utc>, u64)>,
+    since: DateTime<Utc>,
+) -> Vec<UsageGrowth> {
+    let mut growth = Vec::new();
+    let mut month = since;
+    let now = Utc::now();
+
+    while month.format("%Y-%m").to_string() <= now.format("%Y-%m").to_string() {
+        growth.push(UsageGrowth::new(month.format("%Y-%m").to_string()));
+        let Some(next) = month.checked_add_months(Months::new(1)) else {
+            break;
+        };
+        month = next;
+    }
+
+    for (created_at, size) in files {
+        let key = created_at.format("%Y-%m").to_string();
+        if let Some(entry) = growth.iter_mut().find(|entry| entry.month == key) {
+            entry.files += 1;
+            entry.size += size;
+        }
+    }
+
+    growth
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Months, TimeZone, Utc};
+
+    use super::aggregate_growth;
+
+    #[test]
+    fn test_aggregate_growth() {
+        let since = Utc::now().checked_sub_months(Months::new(2)).unwrap();
+        let last_month = Utc::now().checked_sub_months(Months::new(1)).unwrap();
+        let too_old = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+
+        let growth = aggregate_growth(
+            vec![
+                (Utc::now(), 10),
+                (Utc::now(), 5),
+                (last_month, 100),
+                (too_old, 1000),
+            ],
+            since,
+        );
+
+        assert_eq!(growth.len(), 3);
+        assert_eq!(growth[0].files, 0);
+        assert_eq!(growth[1].files, 1);
+        assert_eq!(growth[1].size, 100);
+        assert_eq!(growth[2].files, 2);
+        assert_eq!(growth[2].size, 15);
+    }
+
+    #[test]
+    fn test_aggregate_growth_empty() {
+        let growth = aggregate_growth(vec![], Utc::now());
+
+        assert_eq!(growth.len(), 1);
+        assert_eq!(growth[0].files, 0);
+        assert_eq!(growth[0].size, 0);
+    }
+}
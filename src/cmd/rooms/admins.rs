@@ -0,0 +1,298 @@
+use std::collections::{HashMap, HashSet};
+
+use dco3::{
+    nodes::{
+        rooms::models::{
+            ConfigRoomRequest, RoomGroupsAddBatchRequest, RoomGroupsAddBatchRequestItem,
+            RoomUsersAddBatchRequest, RoomUsersAddBatchRequestItem,
+        },
+        Node, NodePermissions,
+    },
+    Rooms,
+};
+use tracing::{error, info};
+
+use crate::cmd::{
+    groups::GroupCommandHandler,
+    models::DcCmdError,
+    users::UserCommandHandler,
+    utils::{rate_limit::with_rate_limit, strings::format_success_message},
+};
+
+use super::{get_all_room_groups, get_all_room_users, RoomsCommandHandler};
+
+impl RoomsCommandHandler {
+    /// adds users and groups as room admins (room configuration)
+    pub async fn add_room_admins(
+        &self,
+        source: &str,
+        users: Vec<String>,
+        groups: Vec<String>,
+    ) -> Result<(), DcCmdError> {
+        let room = self.get_admin_room(source, &users, &groups).await?;
+        let (user_ids, group_ids) = self.resolve_admins(&users, &groups).await?;
+
+        let mut req = ConfigRoomRequest::builder();
+
+        if !user_ids.is_empty() {
+            req = req.with_admin_ids(user_ids);
+        }
+
+        if !group_ids.is_empty() {
+            req = req.with_admin_group_ids(group_ids);
+        }
+
+        let req = req.build();
+        with_rate_limit(|| async {
+            Ok(self
+                .client
+                .nodes()
+                .config_room(room.id, req.clone())
+                .await?)
+        })
+        .await?;
+
+        info!(
+            "Added room admins to {}: users {:?}, groups {:?}",
+            room.name, users, groups
+        );
+
+        self.write_line(&format_success_message(&format!(
+            "Added {} admin(s) to {}.",
+            users.len() + groups.len(),
+            room.name
+        )))
+    }
+
+    /// removes the admin role (manage permission) of users and groups - other permissions are
+    /// kept; fails if a user or group is no admin or if no admin would be left
+    pub async fn remove_room_admins(
+        &self,
+        source: &str,
+        users: Vec<String>,
+        groups: Vec<String>,
+    ) -> Result<(), DcCmdError> {
+        let room = self.get_admin_room(source, &users, &groups).await?;
+        let (user_ids, group_ids) = self.resolve_admins(&users, &groups).await?;
+
+        let admin_users = get_all_room_users(&self.client, room.id)
+            .await?
+            .into_iter()
+            .filter(|user| user.is_granted && is_admin(user.permissions.as_ref()))
+            .filter_map(|user| {
+                let id = u64::try_from(user.user_info.id).ok()?;
+                Some((id, user.permissions?))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let admin_groups = get_all_room_groups(&self.client, room.id)
+            .await?
+            .into_iter()
+            .filter(|group| group.is_granted && is_admin(group.permissions.as_ref()))
+            .filter_map(|group| Some((group.id, group.permissions?)))
+            .collect::<HashMap<_, _>>();
+
+        if let Some((user, _)) = users
+            .iter()
+            .zip(&user_ids)
+            .find(|(_, id)| !admin_users.contains_key(id))
+        {
+            error!("User {} is no admin of {}.", user, room.name);
+            return Err(DcCmdError::InvalidArgument(format!(
+                "User {user} is not an admin of {}.",
+                room.name
+            )));
+        }
+
+        if let Some((group, _)) = groups
+            .iter()
+            .zip(&group_ids)
+            .find(|(_, id)| !admin_groups.contains_key(id))
+        {
+            error!("Group {} is no admin of {}.", group, room.name);
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Group {group} is not an admin of {}.",
+                room.name
+            )));
+        }
+
+        let admin_user_ids = admin_users.keys().copied().collect::<HashSet<_>>();
+        let admin_group_ids = admin_groups.keys().copied().collect::<HashSet<_>>();
+
+        if remaining_admins(&admin_user_ids, &admin_group_ids, &user_ids, &group_ids) == 0 {
+            error!("Removing admins would leave {} without admin.", room.name);
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Cannot remove the last admin of {}.",
+                room.name
+            )));
+        }
+
+        if !user_ids.is_empty() {
+            let items = user_ids
+                .iter()
+                .map(|id| RoomUsersAddBatchRequestItem::new(*id, without_manage(&admin_users[id])))
+                .collect::<Vec<_>>();
+
+            let req: RoomUsersAddBatchRequest = items.into();
+            with_rate_limit(|| async {
+                Ok(self
+                    .client
+                    .nodes()
+                    .update_room_users(room.id, req.clone())
+                    .await?)
+            })
+            .await?;
+        }
+
+        if !group_ids.is_empty() {
+            let items = group_ids
+                .iter()
+                .map(|id| {
+                    RoomGroupsAddBatchRequestItem::new(*id, without_manage(&admin_groups[id]), None)
+                })
+                .collect::<Vec<_>>();
+
+            let req: RoomGroupsAddBatchRequest = items.into();
+            with_rate_limit(|| async {
+                Ok(self
+                    .client
+                    .nodes()
+                    .update_room_groups(room.id, req.clone())
+                    .await?)
+            })
+            .await?;
+        }
+
+        info!(
+            "Removed room admins from {}: users {:?}, groups {:?}",
+            room.name, users, groups
+        );
+
+        self.write_line(&format_success_message(&format!(
+            "Removed {} admin(s) from {}.",
+            users.len() + groups.len(),
+            room.name
+        )))
+    }
+
+    /// returns the room (no root node) - at least one user or group is required
+    async fn get_admin_room(
+        &self,
+        source: &str,
+        users: &[String],
+        groups: &[String],
+    ) -> Result<Node, DcCmdError> {
+        if users.is_empty() && groups.is_empty() {
+            return Err(DcCmdError::InvalidArgument(
+                "Either --users or --groups must be provided.".to_string(),
+            ));
+        }
+
+        self.get_room_from_path(source)
+            .await?
+            .ok_or(DcCmdError::InvalidPath(source.to_string()))
+    }
+
+    /// resolves user logins and group names to ids
+    async fn resolve_admins(
+        &self,
+        users: &[String],
+        groups: &[String],
+    ) -> Result<(Vec<u64>, Vec<u64>), DcCmdError> {
+        let user_handler =
+            UserCommandHandler::new_from_client(self.client.clone(), self.term.clone());
+        let group_handler =
+            GroupCommandHandler::new_from_client(self.client.clone(), self.term.clone());
+
+        let mut user_ids = Vec::new();
+        for user in users {
+            user_ids.push(user_handler.find_user_id_by_username(user).await?);
+        }
+
+        let mut group_ids = Vec::new();
+        for group in groups {
+            group_ids.push(group_handler.find_group_by_name(group.clone()).await?.id);
+        }
+
+        Ok((user_ids, group_ids))
+    }
+}
+
+fn is_admin(permissions: Option<&NodePermissions>) -> bool {
+    permissions.is_some_and(|permissions| permissions.manage)
+}
+
+/// permissions of a former admin (read / write permissions are kept)
+fn without_manage(permissions: &NodePermissions) -> NodePermissions {
+    NodePermissions {
+        manage: false,
+        ..permissions.clone()
+    }
+}
+
+/// number of admins (users and groups) left after removing the given ids
+fn remaining_admins(
+    admin_user_ids: &HashSet<u64>,
+    admin_group_ids: &HashSet<u64>,
+    remove_user_ids: &[u64],
+    remove_group_ids: &[u64],
+) -> usize {
+    let users = admin_user_ids
+        .iter()
+        .filter(|id| !remove_user_ids.contains(id))
+        .count();
+    let groups = admin_group_ids
+        .iter()
+        .filter(|id| !remove_group_ids.contains(id))
+        .count();
+
+    users + groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining_admins() {
+        let users = HashSet::from([1, 2]);
+        let groups = HashSet::from([10]);
+
+        assert_eq!(remaining_admins(&users, &groups, &[1], &[]), 2);
+        assert_eq!(remaining_admins(&users, &groups, &[1, 2], &[]), 1);
+        assert_eq!(remaining_admins(&users, &groups, &[3], &[10]), 2);
+    }
+
+    #[test]
+    fn test_without_manage() {
+        let permissions = NodePermissions {
+            manage: true,
+            read: true,
+            create: true,
+            change: true,
+            delete: true,
+            manage_download_share: true,
+            manage_upload_share: true,
+            read_recycle_bin: true,
+            restore_recycle_bin: true,
+            delete_recycle_bin: false,
+        };
+
+        let permissions = without_manage(&permissions);
+
+        assert!(!permissions.manage);
+        assert!(permissions.read);
+        assert!(permissions.change);
+        assert!(permissions.manage_download_share);
+        assert!(!permissions.delete_recycle_bin);
+    }
+
+    #[test]
+    fn test_remaining_admins_last_admin() {
+        let users = HashSet::from([1]);
+        let groups = HashSet::new();
+
+        assert_eq!(remaining_admins(&users, &groups, &[1], &[]), 0);
+        assert_eq!(remaining_admins(&users, &HashSet::from([10]), &[1], &[10]), 0);
+    }
+}
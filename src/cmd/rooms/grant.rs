@@ -0,0 +1,162 @@
+use std::path::Path;
+
+use dco3::{
+    nodes::{
+        models::NodeType,
+        rooms::models::{RoomGroupsAddBatchRequest, RoomGroupsAddBatchRequestItem},
+    },
+    Nodes, Rooms,
+};
+use futures_util::{stream, StreamExt};
+use tracing::{error, info};
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS,
+    get_error_message,
+    groups::GroupCommandHandler,
+    models::DcCmdError,
+    utils::{
+        rate_limit::with_rate_limit,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+use super::{models::PermissionPreset, RoomsCommandHandler};
+
+impl RoomsCommandHandler {
+    /// grants a group on all rooms listed in a file (one room path per line)
+    pub async fn grant_group(
+        &self,
+        group_name: String,
+        rooms_file: &Path,
+        template: PermissionPreset,
+    ) -> Result<(), DcCmdError> {
+        let content = std::fs::read_to_string(rooms_file)
+            .map_err(|_| DcCmdError::InvalidPath(rooms_file.display().to_string()))?;
+        let room_paths = parse_room_paths(&content);
+
+        if room_paths.is_empty() {
+            return Err(DcCmdError::InvalidArgument(format!(
+                "No rooms found in {}.",
+                rooms_file.display()
+            )));
+        }
+
+        let group = GroupCommandHandler::new_from_client(self.client.clone(), self.term.clone())
+            .find_group_by_name(group_name)
+            .await?;
+        let group_id = group.id;
+
+        let results = stream::iter(room_paths)
+            .map(|room_path| async move {
+                let res = self.grant_room_group(&room_path, group_id, template).await;
+                (room_path, res)
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let total = results.len();
+        let mut failed = 0;
+
+        for (room_path, res) in results {
+            let msg = match res {
+                Ok(room_id) => {
+                    info!(
+                        "Granted group {} on {} ({})",
+                        group.name, room_path, room_id
+                    );
+                    format_success_message(&format!("Granted {} on {room_path}.", group.name))
+                }
+                Err(e) => {
+                    error!(
+                        "Granting group {} on {} failed: {}",
+                        group.name, room_path, e
+                    );
+                    failed += 1;
+                    format_error_message(&format!(
+                        "Granting {} on {room_path} failed: {}",
+                        group.name,
+                        get_error_message(&e)
+                    ))
+                }
+            };
+
+            self.term
+                .write_line(&msg)
+                .map_err(|_| DcCmdError::IoError)?;
+        }
+
+        if failed > 0 {
+            return Err(DcCmdError::JobFailed(format!(
+                "{failed} of {total} room(s) failed."
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// adds the group to a single room - returns the room id
+    async fn grant_room_group(
+        &self,
+        room_path: &str,
+        group_id: u64,
+        template: PermissionPreset,
+    ) -> Result<u64, DcCmdError> {
+        let room = with_rate_limit(|| async {
+            Ok(self.client.nodes().get_node_from_path(room_path).await?)
+        })
+        .await?
+        .ok_or(DcCmdError::InvalidPath(room_path.to_string()))?;
+
+        if room.node_type != NodeType::Room {
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Not a room: {room_path}"
+            )));
+        }
+
+        let item = RoomGroupsAddBatchRequestItem::new(group_id, template.into(), None);
+        let req: RoomGroupsAddBatchRequest = vec![item].into();
+
+        with_rate_limit(|| async {
+            Ok(self
+                .client
+                .nodes()
+                .update_room_groups(room.id, req.clone())
+                .await?)
+        })
+        .await?;
+
+        Ok(room.id)
+    }
+}
+
+/// parses room paths (one per line) - empty lines and comments (#) are skipped
+fn parse_room_paths(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| format!("/{}/", line.trim_matches('/')))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_room_paths;
+
+    #[test]
+    fn test_parse_room_paths() {
+        let content = "# rooms of project apollo\n/Projects/apollo\n\n  Projects/apollo/docs/  \n";
+
+        assert_eq!(
+            vec!["/Projects/apollo/", "/Projects/apollo/docs/"],
+            parse_room_paths(content)
+        );
+    }
+
+    #[test]
+    fn test_parse_room_paths_empty() {
+        assert!(parse_room_paths("\n# no rooms\n").is_empty());
+    }
+}
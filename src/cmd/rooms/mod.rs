@@ -0,0 +1,195 @@
+use console::Term;
+use dco3::{
+    auth::Connected,
+    nodes::{
+        rooms::models::{RoomGroup, RoomUser},
+        Node,
+    },
+    Dracoon, ListAllParams, Nodes, Rooms,
+};
+
+mod admins;
+mod grant;
+mod models;
+mod permissions;
+mod revoke;
+mod tree;
+mod update;
+
+pub use models::PermissionPreset;
+
+use super::{
+    init_dracoon,
+    journal::Journal,
+    models::{DcCmdError, RoomAdminsCommand, RoomPermissionsCommand, RoomsCommand},
+    utils::{
+        pagination::fetch_all_paginated,
+        rate_limit::with_rate_limit,
+        strings::{build_node_path, parse_path},
+    },
+};
+
+pub struct RoomsCommandHandler {
+    client: Dracoon<Connected>,
+    term: Term,
+    journal: Journal,
+}
+
+impl RoomsCommandHandler {
+    pub async fn try_new(
+        target_domain: String,
+        term: Term,
+        journal: Journal,
+    ) -> Result<Self, DcCmdError> {
+        let client = init_dracoon(&target_domain, None, false).await?;
+
+        Ok(Self {
+            client,
+            term,
+            journal,
+        })
+    }
+
+    /// returns the room for given path (None for the root node)
+    async fn get_room_from_path(&self, source: &str) -> Result<Option<Node>, DcCmdError> {
+        let (parent_path, node_name, depth) =
+            parse_path(source, self.client.get_base_url().as_ref())?;
+        let node_path = build_node_path((parent_path, node_name, depth));
+
+        if node_path == "//" {
+            return Ok(None);
+        }
+
+        let node = with_rate_limit(|| async {
+            Ok(self.client.nodes().get_node_from_path(&node_path).await?)
+        })
+        .await?
+        .ok_or(DcCmdError::InvalidPath(source.to_string()))?;
+
+        Ok(Some(node))
+    }
+}
+
+/// fetches all users of a room (all pages)
+pub async fn get_all_room_users(
+    dracoon: &Dracoon<Connected>,
+    room_id: u64,
+) -> Result<Vec<RoomUser>, DcCmdError> {
+    let users =
+        with_rate_limit(|| async { Ok(dracoon.nodes().get_room_users(room_id, None).await?) })
+            .await?;
+    let client = dracoon.clone();
+    let users = fetch_all_paginated(users, move |offset| {
+        let client = client.clone();
+        let params = ListAllParams::builder().with_offset(offset).build();
+
+        async move {
+            let users = client.nodes().get_room_users(room_id, Some(params)).await?;
+
+            Ok::<_, DcCmdError>(users)
+        }
+    })
+    .await?;
+
+    Ok(users.items)
+}
+
+/// fetches all groups of a room (all pages)
+pub async fn get_all_room_groups(
+    dracoon: &Dracoon<Connected>,
+    room_id: u64,
+) -> Result<Vec<RoomGroup>, DcCmdError> {
+    let groups =
+        with_rate_limit(|| async { Ok(dracoon.nodes().get_room_groups(room_id, None).await?) })
+            .await?;
+    let client = dracoon.clone();
+    let groups = fetch_all_paginated(groups, move |offset| {
+        let client = client.clone();
+        let params = ListAllParams::builder().with_offset(offset).build();
+
+        async move {
+            let groups = client
+                .nodes()
+                .get_room_groups(room_id, Some(params))
+                .await?;
+
+            Ok::<_, DcCmdError>(groups)
+        }
+    })
+    .await?;
+
+    Ok(groups.items)
+}
+
+/// full path of a room (e.g. /Projects/apollo)
+fn to_room_path(room: &Node) -> String {
+    format!(
+        "{}{}",
+        room.parent_path.as_deref().unwrap_or("/"),
+        room.name
+    )
+}
+
+pub async fn handle_rooms_cmd(
+    cmd: RoomsCommand,
+    term: Term,
+    journal: Journal,
+) -> Result<(), DcCmdError> {
+    let target = match &cmd {
+        RoomsCommand::Tree { source, .. }
+        | RoomsCommand::GrantGroup { target: source, .. }
+        | RoomsCommand::RevokeUser { target: source, .. }
+        | RoomsCommand::Update { source, .. }
+        | RoomsCommand::Admins {
+            cmd:
+                RoomAdminsCommand::Add { source, .. } | RoomAdminsCommand::Rm { source, .. },
+        }
+        | RoomsCommand::Permissions {
+            cmd:
+                RoomPermissionsCommand::Export { source, .. }
+                | RoomPermissionsCommand::Apply { target: source, .. },
+        } => source,
+    };
+
+    let handler = RoomsCommandHandler::try_new(target.to_string(), term, journal).await?;
+
+    match cmd {
+        RoomsCommand::Tree {
+            source,
+            with_permissions,
+        } => handler.print_room_tree(&source, with_permissions).await,
+        RoomsCommand::GrantGroup {
+            target: _,
+            group,
+            rooms_from_file,
+            template,
+        } => handler.grant_group(group, &rooms_from_file, template).await,
+        RoomsCommand::RevokeUser { target: _, login } => handler.revoke_user(&login).await,
+        RoomsCommand::Update {
+            source,
+            name,
+            quota,
+            notes,
+        } => handler.update_room(&source, name, quota, notes).await,
+        RoomsCommand::Admins { cmd } => match cmd {
+            RoomAdminsCommand::Add {
+                source,
+                users,
+                groups,
+            } => handler.add_room_admins(&source, users, groups).await,
+            RoomAdminsCommand::Rm {
+                source,
+                users,
+                groups,
+            } => handler.remove_room_admins(&source, users, groups).await,
+        },
+        RoomsCommand::Permissions { cmd } => match cmd {
+            RoomPermissionsCommand::Export { source, csv } => {
+                handler.export_permissions(&source, csv).await
+            }
+            RoomPermissionsCommand::Apply { target: _, source } => {
+                handler.apply_permissions(&source).await
+            }
+        },
+    }
+}
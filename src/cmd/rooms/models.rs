@@ -0,0 +1,155 @@
+use clap::ValueEnum;
+use dco3::nodes::{Node, NodePermissions};
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+/// minimal room information needed to render a room tree
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoomTreeEntry {
+    pub id: u64,
+    pub parent_id: Option<u64>,
+    pub name: String,
+}
+
+impl From<&Node> for RoomTreeEntry {
+    fn from(node: &Node) -> Self {
+        Self {
+            id: node.id,
+            parent_id: node.parent_id,
+            name: node.name.clone(),
+        }
+    }
+}
+
+/// room admins and granted groups (with permissions) of a room
+#[derive(Clone, Debug, Default)]
+pub struct RoomAccess {
+    pub admins: Vec<String>,
+    pub groups: Vec<String>,
+}
+
+/// permission templates for users and groups granted on rooms
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionPreset {
+    Read,
+    Edit,
+    Manage,
+}
+
+impl From<PermissionPreset> for NodePermissions {
+    fn from(value: PermissionPreset) -> Self {
+        let edit = value != PermissionPreset::Read;
+        let manage = value == PermissionPreset::Manage;
+
+        NodePermissions {
+            manage,
+            read: true,
+            create: edit,
+            change: edit,
+            delete: edit,
+            manage_download_share: edit,
+            manage_upload_share: edit,
+            read_recycle_bin: edit,
+            restore_recycle_bin: edit,
+            delete_recycle_bin: manage,
+        }
+    }
+}
+
+/// principal of a room permission
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum PrincipalType {
+    User,
+    Group,
+}
+
+impl std::fmt::Display for PrincipalType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrincipalType::User => write!(f, "user"),
+            PrincipalType::Group => write!(f, "group"),
+        }
+    }
+}
+
+/// permission of a principal in the permission matrix
+///
+/// `custom` permissions (not matching a template) are exported but not applied,
+/// `none` removes the principal from the room
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixPermission {
+    Read,
+    Edit,
+    Manage,
+    Custom,
+    None,
+}
+
+impl MatrixPermission {
+    pub fn from_permissions(permissions: Option<&NodePermissions>) -> Self {
+        let Some(permissions) = permissions else {
+            return MatrixPermission::None;
+        };
+
+        [
+            PermissionPreset::Read,
+            PermissionPreset::Edit,
+            PermissionPreset::Manage,
+        ]
+        .into_iter()
+        .find(|preset| is_same_permissions(permissions, &NodePermissions::from(*preset)))
+        .map_or(MatrixPermission::Custom, MatrixPermission::from)
+    }
+}
+
+impl From<PermissionPreset> for MatrixPermission {
+    fn from(value: PermissionPreset) -> Self {
+        match value {
+            PermissionPreset::Read => MatrixPermission::Read,
+            PermissionPreset::Edit => MatrixPermission::Edit,
+            PermissionPreset::Manage => MatrixPermission::Manage,
+        }
+    }
+}
+
+impl std::fmt::Display for MatrixPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MatrixPermission::Read => "read",
+            MatrixPermission::Edit => "edit",
+            MatrixPermission::Manage => "manage",
+            MatrixPermission::Custom => "custom",
+            MatrixPermission::None => "none",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+fn is_same_permissions(a: &NodePermissions, b: &NodePermissions) -> bool {
+    a.manage == b.manage
+        && a.read == b.read
+        && a.create == b.create
+        && a.change == b.change
+        && a.delete == b.delete
+        && a.manage_download_share == b.manage_download_share
+        && a.manage_upload_share == b.manage_upload_share
+        && a.read_recycle_bin == b.read_recycle_bin
+        && a.restore_recycle_bin == b.restore_recycle_bin
+        && a.delete_recycle_bin == b.delete_recycle_bin
+}
+
+/// a single row of the room permission matrix (`rooms permissions export / apply`)
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Tabled)]
+pub struct PermissionEntry {
+    pub room: String,
+    /// user login or group name
+    pub principal: String,
+    #[serde(rename = "type")]
+    #[tabled(rename = "type")]
+    pub principal_type: PrincipalType,
+    pub permission: MatrixPermission,
+}
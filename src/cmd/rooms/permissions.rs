@@ -0,0 +1,495 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    time::Duration,
+};
+
+use dco3::{
+    nodes::{
+        models::NodeType,
+        rooms::models::{RoomGroupsAddBatchRequestItem, RoomUsersAddBatchRequestItem},
+        Node,
+    },
+    Nodes, Rooms,
+};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tabled::settings::{Panel, Style};
+use tracing::{error, info, warn};
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS,
+    get_error_message,
+    groups::GroupCommandHandler,
+    journal::{JournalEntry, JournalOperation},
+    models::DcCmdError,
+    users::UserCommandHandler,
+    utils::{
+        progress::spinner,
+        rate_limit::with_rate_limit,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+use super::{
+    get_all_room_groups, get_all_room_users,
+    models::{MatrixPermission, PermissionEntry, PermissionPreset, PrincipalType},
+    to_room_path, RoomsCommandHandler,
+};
+
+/// current permission and id of a principal in a room
+type RoomPrincipals = HashMap<(PrincipalType, String), (u64, MatrixPermission)>;
+
+/// change of a single principal in a room (`None` revokes all permissions)
+#[derive(Debug, Clone, PartialEq)]
+struct PermissionChange {
+    entry: PermissionEntry,
+    preset: Option<PermissionPreset>,
+}
+
+impl RoomsCommandHandler {
+    /// exports the direct permissions of all rooms below source (room, principal, type, permission)
+    pub async fn export_permissions(&self, source: &str, csv: bool) -> Result<(), DcCmdError> {
+        let spinner = spinner().with_message("Loading permissions...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let entries = match self.get_permission_matrix(source).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                spinner.finish_and_clear();
+                return Err(e);
+            }
+        };
+
+        spinner.finish_and_clear();
+
+        if csv {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+
+            for entry in &entries {
+                writer.serialize(entry).map_err(|e| {
+                    error!("Error writing permission matrix: {}", e);
+                    DcCmdError::IoError
+                })?;
+            }
+
+            let csv = writer
+                .into_inner()
+                .map_err(|_| DcCmdError::IoError)
+                .and_then(|csv| String::from_utf8(csv).map_err(|_| DcCmdError::IoError))?;
+
+            return self.term.write_str(&csv).map_err(|_| DcCmdError::IoError);
+        }
+
+        let entry_count = entries.len();
+        let mut table = tabled::Table::new(entries);
+        table
+            .with(Style::modern())
+            .with(Panel::footer(format!("{entry_count} permissions")));
+
+        self.write_line(&table.to_string())
+    }
+
+    /// applies a permission matrix (CSV) - only differing permissions are changed,
+    /// principals missing in the matrix are kept
+    pub async fn apply_permissions(&self, source: &Path) -> Result<(), DcCmdError> {
+        let entries = read_permission_matrix(source)?;
+
+        let mut rooms: BTreeMap<String, Vec<PermissionEntry>> = BTreeMap::new();
+        for entry in entries {
+            rooms.entry(entry.room.clone()).or_default().push(entry);
+        }
+
+        let spinner = spinner().with_message("Loading permissions...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let changes = stream::iter(rooms)
+            .map(|(room_path, entries)| async move {
+                let room = self.get_matrix_room(&room_path).await?;
+                let principals = self.get_room_principals(room.id).await?;
+                let changes = diff_permissions(&entries, &principals);
+
+                Ok::<_, DcCmdError>((room, principals, changes))
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect::<Vec<_>>()
+            .await;
+
+        spinner.finish_and_clear();
+
+        let changes = changes?
+            .into_iter()
+            .filter(|(_, _, changes)| !changes.is_empty())
+            .collect::<Vec<_>>();
+
+        if changes.is_empty() {
+            return self.write_line(&format_success_message("No permission changes."));
+        }
+
+        let mut planned = Vec::new();
+        for (room, principals, changes) in changes {
+            for change in changes {
+                let principal_id = self.resolve_principal(&change.entry, &principals).await?;
+                planned.push((to_journal_entry(&room, &change, principal_id), change.preset));
+            }
+        }
+
+        if self.journal.is_dry_run() {
+            let entries = planned.into_iter().map(|(entry, _)| entry).collect::<Vec<_>>();
+            return self.journal.print_dry_run(&self.term, &entries);
+        }
+
+        let results = stream::iter(planned)
+            .map(|(entry, preset)| async move {
+                let res = with_rate_limit(|| self.apply_permission_change(&entry, preset)).await;
+                (entry, res)
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let total = results.len();
+        let mut applied = Vec::new();
+
+        for (entry, res) in results {
+            let msg = match res {
+                Ok(()) => {
+                    info!("Applied permission change: {}", entry.describe());
+                    let msg = format_success_message(&format!("Updated {}.", entry.name));
+                    applied.push(entry);
+                    msg
+                }
+                Err(e) => {
+                    error!("Permission change {} failed: {}", entry.describe(), e);
+                    format_error_message(&format!(
+                        "Updating {} failed: {}",
+                        entry.name,
+                        get_error_message(&e)
+                    ))
+                }
+            };
+
+            self.write_line(&msg)?;
+        }
+
+        self.journal.record(&applied)?;
+
+        let failed = total - applied.len();
+        if failed > 0 {
+            return Err(DcCmdError::JobFailed(format!(
+                "{failed} of {total} permission change(s) failed."
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// returns the permission matrix of the room (and all sub rooms) - all rooms for root
+    async fn get_permission_matrix(
+        &self,
+        source: &str,
+    ) -> Result<Vec<PermissionEntry>, DcCmdError> {
+        let root = self.get_room_from_path(source).await?;
+        let root_id = root.as_ref().map(|root| root.id);
+
+        let rooms = root
+            .into_iter()
+            .chain(self.get_sub_rooms(root_id).await?)
+            .collect::<Vec<_>>();
+
+        let matrix = stream::iter(rooms)
+            .map(|room| async move {
+                let principals = self.get_room_principals(room.id).await?;
+                Ok::<_, DcCmdError>(to_permission_entries(&to_room_path(&room), principals))
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(matrix.into_iter().flatten().collect())
+    }
+
+    async fn get_matrix_room(&self, room_path: &str) -> Result<Node, DcCmdError> {
+        let node_path = format!("/{}/", room_path.trim_matches('/'));
+
+        let room = with_rate_limit(|| async {
+            Ok(self.client.nodes().get_node_from_path(&node_path).await?)
+        })
+        .await?
+            .ok_or(DcCmdError::InvalidPath(room_path.to_string()))?;
+
+        if room.node_type != NodeType::Room {
+            return Err(DcCmdError::InvalidArgument(format!("Not a room: {room_path}")));
+        }
+
+        Ok(room)
+    }
+
+    /// granted users (by login) and groups (by name) of a room
+    async fn get_room_principals(&self, room_id: u64) -> Result<RoomPrincipals, DcCmdError> {
+        let users = get_all_room_users(&self.client, room_id).await?;
+        let groups = get_all_room_groups(&self.client, room_id).await?;
+
+        let users = users
+            .into_iter()
+            .filter(|user| user.is_granted)
+            .filter_map(|user| {
+                let id = u64::try_from(user.user_info.id).ok()?;
+                let login = user.user_info.user_name?;
+                let permission = MatrixPermission::from_permissions(user.permissions.as_ref());
+
+                Some(((PrincipalType::User, login), (id, permission)))
+            });
+
+        let groups = groups
+            .into_iter()
+            .filter(|group| group.is_granted)
+            .map(|group| {
+                let permission = MatrixPermission::from_permissions(group.permissions.as_ref());
+                ((PrincipalType::Group, group.name), (group.id, permission))
+            });
+
+        Ok(users.chain(groups).collect())
+    }
+
+    /// returns the id of a principal (from the room or looked up by login / name)
+    async fn resolve_principal(
+        &self,
+        entry: &PermissionEntry,
+        principals: &RoomPrincipals,
+    ) -> Result<u64, DcCmdError> {
+        if let Some((id, _)) = principals.get(&(entry.principal_type, entry.principal.clone())) {
+            return Ok(*id);
+        }
+
+        match entry.principal_type {
+            PrincipalType::User => {
+                UserCommandHandler::new_from_client(self.client.clone(), self.term.clone())
+                    .find_user_id_by_username(&entry.principal)
+                    .await
+            }
+            PrincipalType::Group => {
+                let group =
+                    GroupCommandHandler::new_from_client(self.client.clone(), self.term.clone())
+                        .find_group_by_name(entry.principal.clone())
+                        .await?;
+
+                Ok(group.id)
+            }
+        }
+    }
+
+    /// applies a planned change (preset of grants)
+    async fn apply_permission_change(
+        &self,
+        entry: &JournalEntry,
+        preset: Option<PermissionPreset>,
+    ) -> Result<(), DcCmdError> {
+        let principal_id = entry.target_id.unwrap_or_default();
+
+        match (entry.operation, preset) {
+            (JournalOperation::GrantRoomUser, Some(preset)) => {
+                let item = RoomUsersAddBatchRequestItem::new(principal_id, preset.into());
+                self.client
+                    .nodes()
+                    .update_room_users(entry.id, vec![item].into())
+                    .await?;
+            }
+            (JournalOperation::GrantRoomGroup, Some(preset)) => {
+                let item = RoomGroupsAddBatchRequestItem::new(principal_id, preset.into(), None);
+                self.client
+                    .nodes()
+                    .update_room_groups(entry.id, vec![item].into())
+                    .await?;
+            }
+            (JournalOperation::RevokeRoomUser, _) => {
+                self.client
+                    .nodes()
+                    .delete_room_users(entry.id, vec![principal_id].into())
+                    .await?;
+            }
+            (JournalOperation::RevokeRoomGroup, _) => {
+                self.client
+                    .nodes()
+                    .delete_room_groups(entry.id, vec![principal_id].into())
+                    .await?;
+            }
+            _ => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Invalid permission change: {}",
+                    entry.describe()
+                )))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_permission_matrix(source: &Path) -> Result<Vec<PermissionEntry>, DcCmdError> {
+    let mut rdr = csv::Reader::from_path(source).map_err(|e| {
+        error!("Error reading permission matrix: {}", e);
+        DcCmdError::InvalidPath(source.display().to_string())
+    })?;
+
+    rdr.deserialize::<PermissionEntry>()
+        .collect::<Result<Vec<_>, csv::Error>>()
+        .map_err(|e| {
+            error!("Error reading permission matrix entry: {}", e);
+            DcCmdError::InvalidArgument(format!(
+                "Invalid permission matrix (expected room,principal,type,permission): {e}"
+            ))
+        })
+}
+
+/// matrix entries of a room (sorted by type and principal)
+fn to_permission_entries(room_path: &str, principals: RoomPrincipals) -> Vec<PermissionEntry> {
+    let mut entries = principals
+        .into_iter()
+        .map(|((principal_type, principal), (_, permission))| PermissionEntry {
+            room: room_path.to_string(),
+            principal,
+            principal_type,
+            permission,
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort_by(|a, b| (a.principal_type, &a.principal).cmp(&(b.principal_type, &b.principal)));
+
+    entries
+}
+
+/// compares the matrix entries of a room with its current permissions
+///
+/// `custom` entries are skipped - they cannot be expressed as template
+fn diff_permissions(
+    entries: &[PermissionEntry],
+    current: &RoomPrincipals,
+) -> Vec<PermissionChange> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let current = current
+                .get(&(entry.principal_type, entry.principal.clone()))
+                .map_or(MatrixPermission::None, |(_, permission)| *permission);
+
+            let preset = match entry.permission {
+                MatrixPermission::Custom => {
+                    warn!(
+                        "Skipped custom permissions of {} on {}",
+                        entry.principal, entry.room
+                    );
+                    return None;
+                }
+                _ if entry.permission == current => return None,
+                MatrixPermission::None => None,
+                MatrixPermission::Read => Some(PermissionPreset::Read),
+                MatrixPermission::Edit => Some(PermissionPreset::Edit),
+                MatrixPermission::Manage => Some(PermissionPreset::Manage),
+            };
+
+            Some(PermissionChange {
+                entry: entry.clone(),
+                preset,
+            })
+        })
+        .collect()
+}
+
+/// journal entry of a change (name: room path, principal and permission)
+fn to_journal_entry(room: &Node, change: &PermissionChange, principal_id: u64) -> JournalEntry {
+    let operation = match (change.entry.principal_type, change.preset) {
+        (PrincipalType::User, Some(_)) => JournalOperation::GrantRoomUser,
+        (PrincipalType::Group, Some(_)) => JournalOperation::GrantRoomGroup,
+        (PrincipalType::User, None) => JournalOperation::RevokeRoomUser,
+        (PrincipalType::Group, None) => JournalOperation::RevokeRoomGroup,
+    };
+
+    let name = format!(
+        "{} ({}: {})",
+        to_room_path(room),
+        change.entry.principal,
+        change.entry.permission
+    );
+
+    JournalEntry::new(operation, room.id, name).with_target_id(principal_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        principal: &str,
+        principal_type: PrincipalType,
+        permission: MatrixPermission,
+    ) -> PermissionEntry {
+        PermissionEntry {
+            room: "/Projects/apollo".to_string(),
+            principal: principal.to_string(),
+            principal_type,
+            permission,
+        }
+    }
+
+    fn current() -> RoomPrincipals {
+        HashMap::from([
+            (
+                (PrincipalType::User, "jdoe".to_string()),
+                (1, MatrixPermission::Edit),
+            ),
+            (
+                (PrincipalType::Group, "apollo-team".to_string()),
+                (10, MatrixPermission::Read),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_diff_permissions_unchanged() {
+        let entries = vec![
+            entry("jdoe", PrincipalType::User, MatrixPermission::Edit),
+            entry("apollo-team", PrincipalType::Group, MatrixPermission::Read),
+        ];
+
+        assert!(diff_permissions(&entries, &current()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_permissions_changed() {
+        let entries = vec![
+            entry("jdoe", PrincipalType::User, MatrixPermission::None),
+            entry("apollo-team", PrincipalType::Group, MatrixPermission::Manage),
+            entry("asmith", PrincipalType::User, MatrixPermission::Read),
+            entry("other", PrincipalType::User, MatrixPermission::None),
+            entry("custom", PrincipalType::Group, MatrixPermission::Custom),
+        ];
+
+        let changes = diff_permissions(&entries, &current());
+        let presets = changes
+            .iter()
+            .map(|change| (change.entry.principal.as_str(), change.preset))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            presets,
+            vec![
+                ("jdoe", None),
+                ("apollo-team", Some(PermissionPreset::Manage)),
+                ("asmith", Some(PermissionPreset::Read)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_permission_entries_sorted() {
+        let entries = to_permission_entries("/Projects/apollo", current());
+
+        assert_eq!(
+            entries,
+            vec![
+                entry("jdoe", PrincipalType::User, MatrixPermission::Edit),
+                entry("apollo-team", PrincipalType::Group, MatrixPermission::Read),
+            ]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use dco3::{nodes::Node, Rooms};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tracing::{error, info};
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS,
+    get_error_message,
+    journal::{JournalEntry, JournalOperation},
+    models::DcCmdError,
+    users::UserCommandHandler,
+    utils::{
+        progress::spinner,
+        rate_limit::with_rate_limit,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+use super::{get_all_room_users, to_room_path, RoomsCommandHandler};
+
+impl RoomsCommandHandler {
+    /// removes the direct permissions of a user on all rooms (permissions via groups are kept)
+    pub async fn revoke_user(&self, login: &str) -> Result<(), DcCmdError> {
+        let user_id = UserCommandHandler::new_from_client(self.client.clone(), self.term.clone())
+            .find_user_id_by_username(login)
+            .await?;
+
+        let spinner = spinner().with_message("Scanning rooms...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let rooms = match self.get_rooms_with_user(user_id).await {
+            Ok(rooms) => rooms,
+            Err(e) => {
+                spinner.finish_and_clear();
+                return Err(e);
+            }
+        };
+
+        spinner.finish_and_clear();
+
+        if rooms.is_empty() {
+            return self.write_line(&format!("No direct room permissions found for {login}."));
+        }
+
+        let entries = rooms
+            .iter()
+            .map(|room| {
+                JournalEntry::new(
+                    JournalOperation::RevokeRoomUser,
+                    room.id,
+                    to_room_path(room),
+                )
+                .with_target_id(user_id)
+            })
+            .collect::<Vec<_>>();
+
+        if self.journal.is_dry_run() {
+            return self.journal.print_dry_run(&self.term, &entries);
+        }
+
+        let results = stream::iter(entries)
+            .map(|entry| async move {
+                let res = with_rate_limit(|| async {
+                    Ok(self
+                        .client
+                        .nodes()
+                        .delete_room_users(entry.id, vec![user_id].into())
+                        .await?)
+                })
+                .await;
+                (entry, res)
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let total = results.len();
+        let mut revoked = Vec::new();
+
+        for (entry, res) in results {
+            let msg = match res {
+                Ok(()) => {
+                    info!("Revoked permissions of {} on {}", login, entry.name);
+                    let msg =
+                        format_success_message(&format!("Revoked {login} on {}.", entry.name));
+                    revoked.push(entry);
+                    msg
+                }
+                Err(e) => {
+                    error!("Revoking {} on {} failed: {}", login, entry.name, e);
+                    format_error_message(&format!(
+                        "Revoking {login} on {} failed: {}",
+                        entry.name,
+                        get_error_message(&e)
+                    ))
+                }
+            };
+
+            self.write_line(&msg)?;
+        }
+
+        self.journal.record(&revoked)?;
+
+        let failed = total - revoked.len();
+        if failed > 0 {
+            return Err(DcCmdError::JobFailed(format!(
+                "{failed} of {total} room(s) failed."
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// returns all rooms where the user has direct permissions (rooms are scanned concurrently)
+    async fn get_rooms_with_user(&self, user_id: u64) -> Result<Vec<Node>, DcCmdError> {
+        let rooms = self.get_sub_rooms(None).await?;
+
+        let rooms = stream::iter(rooms)
+            .map(|room| async move {
+                let users = get_all_room_users(&self.client, room.id).await?;
+                let is_granted = users
+                    .iter()
+                    .any(|user| user.is_granted && u64::try_from(user.user_info.id) == Ok(user_id));
+
+                Ok::<_, DcCmdError>(is_granted.then_some(room))
+            })
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(rooms.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use dco3::{
+    nodes::{Node, NodeType, NodesSearchFilter, NodesSearchSortBy},
+    ListAllParams, Nodes, SortOrder,
+};
+use futures_util::{stream, StreamExt};
+
+use crate::cmd::{
+    config::MAX_CONCURRENT_REQUESTS,
+    models::DcCmdError,
+    utils::{pagination::fetch_all_paginated, progress::spinner, rate_limit::with_rate_limit},
+};
+
+use super::{
+    get_all_room_groups, get_all_room_users,
+    models::{RoomAccess, RoomTreeEntry},
+    RoomsCommandHandler,
+};
+
+/// a single rendered line of the room tree
+#[derive(Debug, PartialEq)]
+struct RoomTreeLine {
+    id: u64,
+    line: String,
+    /// prefix to use for additional lines (e.g. permissions) below the room
+    detail_prefix: String,
+}
+
+impl RoomsCommandHandler {
+    pub async fn print_room_tree(
+        &self,
+        source: &str,
+        with_permissions: bool,
+    ) -> Result<(), DcCmdError> {
+        let spinner = spinner().with_message("Loading rooms...");
+        spinner.enable_steady_tick(Duration::from_millis(100));
+
+        let root = self.get_room_from_path(source).await?;
+
+        if let Some(root) = &root {
+            if root.node_type != NodeType::Room {
+                spinner.finish_and_clear();
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Not a room: {}",
+                    root.name
+                )));
+            }
+        }
+
+        let root_id = root.as_ref().map(|root| root.id);
+        let rooms = self.get_sub_rooms(root_id).await?;
+
+        let access = if with_permissions {
+            let room_ids = rooms
+                .iter()
+                .map(|room| room.id)
+                .chain(root_id)
+                .collect::<Vec<_>>();
+
+            self.get_rooms_access(room_ids).await?
+        } else {
+            HashMap::new()
+        };
+
+        spinner.finish_and_clear();
+
+        let entries = rooms.iter().map(RoomTreeEntry::from).collect::<Vec<_>>();
+
+        let root_name = root
+            .as_ref()
+            .map_or_else(|| "/".to_string(), |root| root.name.clone());
+        self.write_line(&root_name)?;
+
+        if let Some(root_access) = root_id.and_then(|id| access.get(&id)) {
+            self.print_room_access(root_access, "")?;
+        }
+
+        for line in render_tree(&entries, root_id) {
+            self.write_line(&line.line)?;
+
+            if let Some(room_access) = access.get(&line.id) {
+                self.print_room_access(room_access, &line.detail_prefix)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_room_access(&self, access: &RoomAccess, prefix: &str) -> Result<(), DcCmdError> {
+        let admins = if access.admins.is_empty() {
+            "none (inherited)".to_string()
+        } else {
+            access.admins.join(", ")
+        };
+
+        let groups = if access.groups.is_empty() {
+            "none".to_string()
+        } else {
+            access.groups.join(", ")
+        };
+
+        self.write_line(&format!("{prefix}  admins: {admins}"))?;
+        self.write_line(&format!("{prefix}  groups: {groups}"))
+    }
+
+    pub(super) fn write_line(&self, line: &str) -> Result<(), DcCmdError> {
+        self.term.write_line(line).map_err(|_| DcCmdError::IoError)
+    }
+
+    /// fetches all rooms below given parent (all rooms if no parent is provided)
+    pub(super) async fn get_sub_rooms(
+        &self,
+        parent_id: Option<u64>,
+    ) -> Result<Vec<Node>, DcCmdError> {
+        let rooms = with_rate_limit(|| async {
+            let params = ListAllParams::builder()
+                .with_filter(NodesSearchFilter::is_room())
+                .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+                .build();
+
+            Ok(self
+                .client
+                .nodes()
+                .search_nodes("*", parent_id, Some(-1), Some(params))
+                .await?)
+        })
+        .await?;
+
+        let client = self.client.clone();
+
+        let rooms = fetch_all_paginated(rooms, move |offset| {
+            let client = client.clone();
+            let params = ListAllParams::builder()
+                .with_filter(NodesSearchFilter::is_room())
+                .with_sort(NodesSearchSortBy::parent_path(SortOrder::Asc))
+                .with_offset(offset)
+                .build();
+
+            async move {
+                let rooms = client
+                    .nodes()
+                    .search_nodes("*", parent_id, Some(-1), Some(params))
+                    .await?;
+
+                Ok::<_, DcCmdError>(rooms)
+            }
+        })
+        .await?;
+
+        Ok(rooms.items)
+    }
+
+    async fn get_rooms_access(
+        &self,
+        room_ids: Vec<u64>,
+    ) -> Result<HashMap<u64, RoomAccess>, DcCmdError> {
+        stream::iter(room_ids)
+            .map(|room_id| async move {
+                let access = self.get_room_access(room_id).await?;
+                Ok::<_, DcCmdError>((room_id, access))
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect()
+    }
+
+    async fn get_room_access(&self, room_id: u64) -> Result<RoomAccess, DcCmdError> {
+        let users = get_all_room_users(&self.client, room_id).await?;
+        let groups = get_all_room_groups(&self.client, room_id).await?;
+
+        let admins = users
+            .into_iter()
+            .filter(|user| {
+                user.is_granted
+                    && user
+                        .permissions
+                        .as_ref()
+                        .is_some_and(|permissions| permissions.manage)
+            })
+            .map(|user| {
+                user.user_info
+                    .user_name
+                    .unwrap_or_else(|| user.user_info.id.to_string())
+            })
+            .collect();
+
+        let groups = groups
+            .into_iter()
+            .filter(|group| group.is_granted)
+            .map(|group| match group.permissions {
+                Some(permissions) => format!("{} ({permissions})", group.name),
+                None => group.name,
+            })
+            .collect();
+
+        Ok(RoomAccess { admins, groups })
+    }
+}
+
+/// renders rooms as tree (children sorted by name) starting below given root
+///
+/// rooms with an unknown parent (no access to parent room) are rendered below the root
+fn render_tree(entries: &[RoomTreeEntry], root_id: Option<u64>) -> Vec<RoomTreeLine> {
+    let ids = entries.iter().map(|entry| entry.id).collect::<HashSet<_>>();
+    let mut children: HashMap<Option<u64>, Vec<&RoomTreeEntry>> = HashMap::new();
+
+    for entry in entries {
+        let parent_id = match entry.parent_id {
+            Some(parent_id) if ids.contains(&parent_id) => Some(parent_id),
+            _ => root_id,
+        };
+
+        children.entry(parent_id).or_default().push(entry);
+    }
+
+    for siblings in children.values_mut() {
+        siblings.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    let mut lines = Vec::new();
+    render_children(&children, root_id, "", &mut lines);
+
+    lines
+}
+
+fn render_children(
+    children: &HashMap<Option<u64>, Vec<&RoomTreeEntry>>,
+    parent_id: Option<u64>,
+    prefix: &str,
+    lines: &mut Vec<RoomTreeLine>,
+) {
+    let Some(siblings) = children.get(&parent_id) else {
+        return;
+    };
+
+    for (idx, entry) in siblings.iter().enumerate() {
+        let is_last = idx == siblings.len() - 1;
+        let (connector, indent) = if is_last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        let child_prefix = format!("{prefix}{indent}");
+
+        lines.push(RoomTreeLine {
+            id: entry.id,
+            line: format!("{prefix}{connector}{}", entry.name),
+            detail_prefix: child_prefix.clone(),
+        });
+
+        render_children(children, Some(entry.id), &child_prefix, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: u64, parent_id: Option<u64>, name: &str) -> RoomTreeEntry {
+        RoomTreeEntry {
+            id,
+            parent_id,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let entries = vec![
+            entry(3, Some(1), "b"),
+            entry(1, None, "root"),
+            entry(2, Some(1), "a"),
+            entry(4, Some(2), "c"),
+        ];
+
+        let lines = render_tree(&entries, None)
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec!["└── root", "    ├── a", "    │   └── c", "    └── b"],
+            lines
+        );
+    }
+
+    #[test]
+    fn test_render_tree_with_root() {
+        let entries = vec![entry(2, Some(1), "a"), entry(3, Some(1), "b")];
+
+        let lines = render_tree(&entries, Some(1));
+
+        assert_eq!(2, lines.len());
+        assert_eq!("├── a", lines[0].line);
+        assert_eq!("│   ", lines[0].detail_prefix);
+        assert_eq!("└── b", lines[1].line);
+        assert_eq!("    ", lines[1].detail_prefix);
+    }
+
+    #[test]
+    fn test_render_tree_with_unknown_parent() {
+        let entries = vec![entry(2, Some(99), "orphan"), entry(3, None, "root")];
+
+        let lines = render_tree(&entries, None)
+            .into_iter()
+            .map(|line| line.line)
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["├── orphan", "└── root"], lines);
+    }
+
+    #[test]
+    fn test_render_tree_empty() {
+        assert!(render_tree(&[], None).is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use dco3::{
+    nodes::{models::NodeType, rooms::models::UpdateRoomRequest},
+    Rooms,
+};
+use tracing::info;
+
+use crate::cmd::{
+    journal::{JournalEntry, JournalOperation},
+    models::DcCmdError,
+    utils::{
+        rate_limit::with_rate_limit,
+        strings::{format_success_message, parse_size},
+    },
+};
+
+use super::{to_room_path, RoomsCommandHandler};
+
+impl RoomsCommandHandler {
+    /// updates name, quota (0 removes the quota) and notes of a room
+    pub async fn update_room(
+        &self,
+        source: &str,
+        name: Option<String>,
+        quota: Option<String>,
+        notes: Option<String>,
+    ) -> Result<(), DcCmdError> {
+        if name.is_none() && quota.is_none() && notes.is_none() {
+            return Err(DcCmdError::InvalidArgument(
+                "At least one of --name, --quota or --notes is required.".to_string(),
+            ));
+        }
+
+        let quota = quota.as_deref().map(parse_size).transpose()?;
+
+        let room = self
+            .get_room_from_path(source)
+            .await?
+            .ok_or(DcCmdError::InvalidPath(source.to_string()))?;
+
+        if room.node_type != NodeType::Room {
+            return Err(DcCmdError::InvalidArgument(format!("Not a room: {}", room.name)));
+        }
+
+        // previous values are not recorded (cannot be undone)
+        let entries = [JournalEntry::new(
+            JournalOperation::UpdateRoom,
+            room.id,
+            to_room_path(&room),
+        )];
+
+        if self.journal.is_dry_run() {
+            return self.journal.print_dry_run(&self.term, &entries);
+        }
+
+        let mut req = UpdateRoomRequest::builder();
+
+        if let Some(name) = name {
+            req = req.with_name(name);
+        }
+
+        if let Some(quota) = quota {
+            req = req.with_quota(quota);
+        }
+
+        if let Some(notes) = notes {
+            req = req.with_notes(notes);
+        }
+
+        let req = req.build();
+        let room = with_rate_limit(|| async {
+            Ok(self
+                .client
+                .nodes()
+                .update_room(room.id, req.clone())
+                .await?)
+        })
+        .await?;
+        self.journal.record(&entries)?;
+
+        let msg = format!("Room {} updated.", room.name);
+        info!("{}", msg);
+        self.write_line(&format_success_message(&msg))
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::Cursor;
+
+use chrono::Utc;
+use console::Term;
+use dco3::{
+    auth::Connected,
+    nodes::{FileMeta, Node, NodeType, UploadOptions},
+    Download, Dracoon, Nodes, Upload,
+};
+use tracing::{error, info};
+
+#[cfg(any(test, feature = "mock-server"))]
+use super::{
+    authenticate_password_flow, build_dracoon,
+    mock::{MockServer, MOCK_ROOM_ID},
+    utils::strings::NODE_ID_SCHEME,
+};
+use super::{
+    init_dracoon, init_encryption,
+    models::{DcCmdError, PasswordAuth},
+    nodes::{resolve_node, to_node_path},
+    utils::strings::{build_node_path, format_error_message, format_success_message, parse_path},
+};
+
+/// runs ls, upload and download against a container (e.g. in CI) - the test file is removed afterwards
+pub async fn selftest(
+    term: Term,
+    target: String,
+    password_auth: Option<PasswordAuth>,
+    encryption_password: Option<String>,
+) -> Result<(), DcCmdError> {
+    let dracoon = init_dracoon(&target, password_auth, false).await?;
+
+    run_selftest(&term, dracoon, &target, encryption_password).await
+}
+
+/// runs the selftest against an in-process mock of the DRACOON API (no instance required)
+#[cfg(any(test, feature = "mock-server"))]
+pub async fn selftest_mock(term: Term) -> Result<(), DcCmdError> {
+    let server = MockServer::start().await?;
+
+    let dracoon = build_dracoon(server.url(), 1)?;
+    let dracoon = authenticate_password_flow(
+        dracoon,
+        PasswordAuth("selftest".to_string(), "selftest".to_string()),
+    )
+    .await?;
+
+    let target = format!("{NODE_ID_SCHEME}mock/{MOCK_ROOM_ID}");
+
+    run_selftest(&term, dracoon, &target, None).await
+}
+
+async fn run_selftest(
+    term: &Term,
+    dracoon: Dracoon<Connected>,
+    target: &str,
+    encryption_password: Option<String>,
+) -> Result<(), DcCmdError> {
+    write_step(term, "connect", dracoon.get_base_url().as_str())?;
+
+    let parent = get_container(&dracoon, target).await?;
+    let nodes = dracoon
+        .nodes()
+        .get_nodes(Some(parent.id), None, None)
+        .await?;
+    write_step(
+        term,
+        "ls",
+        &format!("{} ({} nodes)", to_node_path(&parent), nodes.range.total),
+    )?;
+
+    let dracoon = if parent.is_encrypted == Some(true) {
+        init_encryption(dracoon, encryption_password).await?
+    } else {
+        dracoon
+    };
+
+    let content = format!("dccmd-rs selftest {}", Utc::now().to_rfc3339()).into_bytes();
+    let file_name = format!("dccmd-rs-selftest-{}.txt", Utc::now().timestamp_millis());
+
+    let node = upload_test_file(&dracoon, &parent, &file_name, content.clone()).await?;
+    write_step(term, "upload", &to_node_path(&node))?;
+
+    // always remove the test file (also if the download fails)
+    let downloaded = download_test_file(&dracoon, &node).await;
+    dracoon.nodes().delete_node(node.id).await?;
+
+    if downloaded? != content {
+        error!("Content of {} does not match the upload.", file_name);
+        let msg = format_error_message("download: content does not match the upload.");
+        term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+        return Err(DcCmdError::JobFailed("Selftest failed.".to_string()));
+    }
+
+    write_step(term, "download", &format!("{} bytes", content.len()))?;
+    write_step(term, "cleanup", &file_name)?;
+
+    info!("Selftest against {} passed.", dracoon.get_base_url());
+
+    Ok(())
+}
+
+/// resolves the target container by id or by path (no root node - files can't be uploaded there)
+async fn get_container(dracoon: &Dracoon<Connected>, target: &str) -> Result<Node, DcCmdError> {
+    let node_path = build_node_path(parse_path(target, dracoon.get_base_url().as_ref())?);
+
+    match resolve_node(dracoon, target, &node_path).await? {
+        Some(node) if node.node_type != NodeType::File => Ok(node),
+        _ => Err(DcCmdError::InvalidPath(target.to_string())),
+    }
+}
+
+async fn upload_test_file(
+    dracoon: &Dracoon<Connected>,
+    parent: &Node,
+    file_name: &str,
+    content: Vec<u8>,
+) -> Result<Node, DcCmdError> {
+    let file_meta = FileMeta::builder(file_name.to_string(), content.len() as u64).build();
+    let upload_options = UploadOptions::builder(file_meta).build();
+    let reader = tokio::io::BufReader::new(Cursor::new(content));
+
+    let node = dracoon
+        .upload(parent, upload_options, reader, None, None)
+        .await?;
+
+    Ok(node)
+}
+
+async fn download_test_file(
+    dracoon: &Dracoon<Connected>,
+    node: &Node,
+) -> Result<Vec<u8>, DcCmdError> {
+    let mut content = Vec::new();
+    dracoon.download(node, &mut content, None, None).await?;
+
+    Ok(content)
+}
+
+fn write_step(term: &Term, step: &str, detail: &str) -> Result<(), DcCmdError> {
+    term.write_line(&format_success_message(&format!("{step}: {detail}")))
+        .map_err(|_| DcCmdError::IoError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_selftest_mock() {
+        selftest_mock(Term::stderr()).await.unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+use console::Term;
+use dco3::{nodes::TransferNodesRequest, Groups, Nodes, Users};
+use dialoguer::Confirm;
+use tracing::{error, info, warn};
+
+use super::{
+    branding::instance_prompt,
+    init_dracoon,
+    journal::{Journal, JournalEntry, JournalOperation},
+    models::DcCmdError,
+    nodes::deleted::restore_deleted_nodes,
+    utils::strings::{format_error_message, format_success_message},
+};
+
+/// reverses all reversible operations of a journal (newest first)
+pub async fn undo_journal(
+    term: Term,
+    target: String,
+    source: String,
+    journal: Journal,
+) -> Result<(), DcCmdError> {
+    let mut rdr = csv::Reader::from_path(&source).map_err(|e| {
+        error!("Error reading journal: {}", e);
+        DcCmdError::InvalidArgument(format!("Journal not found: {source}"))
+    })?;
+
+    let entries = rdr
+        .deserialize::<JournalEntry>()
+        .collect::<Result<Vec<_>, csv::Error>>()
+        .map_err(|e| {
+            error!("Error reading journal entry: {}", e);
+            DcCmdError::InvalidArgument(format!("Invalid journal format: {source}"))
+        })?;
+
+    let dracoon = init_dracoon(&target, None, false).await?;
+
+    let mut undone = 0;
+
+    for entry in entries.iter().rev() {
+        let Some(undo_entry) = to_undo_entry(entry) else {
+            let confirmed = Confirm::new()
+                .with_prompt(instance_prompt(
+                    dracoon.get_base_url().as_str(),
+                    &format!("Cannot undo {}. Skip and continue?", entry.describe()),
+                ))
+                .interact()
+                .map_err(|_| DcCmdError::IoError)?;
+
+            if confirmed {
+                warn!("Skipped irreversible operation: {}", entry.describe());
+                continue;
+            }
+
+            let msg = format_error_message("Undo aborted.");
+            term.write_line(&msg).map_err(|_| DcCmdError::IoError)?;
+            return Ok(());
+        };
+
+        if journal.is_dry_run() {
+            journal.print_dry_run(&term, &[undo_entry])?;
+            continue;
+        }
+
+        match undo_entry.operation {
+            JournalOperation::DeleteNode => dracoon.nodes().delete_node(undo_entry.id).await?,
+            JournalOperation::MoveNode => {
+                let req = TransferNodesRequest::from(vec![undo_entry.id]);
+                dracoon
+                    .nodes()
+                    .move_nodes(req, undo_entry.target_id.unwrap_or_default())
+                    .await?;
+            }
+            JournalOperation::RestoreNode => {
+                restore_deleted_nodes(&dracoon, vec![undo_entry.id]).await?;
+            }
+            JournalOperation::DeleteUser => dracoon.users().delete_user(undo_entry.id).await?,
+            JournalOperation::DeleteGroup => dracoon.groups().delete_group(undo_entry.id).await?,
+            _ => {
+                return Err(DcCmdError::InvalidArgument(format!(
+                    "Cannot undo {}",
+                    entry.describe()
+                )))
+            }
+        }
+
+        journal.record(std::slice::from_ref(&undo_entry))?;
+        info!("Undone: {}", entry.describe());
+        undone += 1;
+    }
+
+    if !journal.is_dry_run() {
+        let msg = format!("{undone} operation(s) undone.");
+        term.write_line(&format_success_message(&msg))
+            .map_err(|_| DcCmdError::IoError)?;
+    }
+
+    Ok(())
+}
+
+/// returns the operation reversing the given entry (None if irreversible)
+///
+/// deleted nodes are restored from the recycle bin (if their deleted node id was recorded) -
+/// purged nodes, users and groups cannot be restored, copies are not tracked by id and previous
+/// room permissions are not recorded
+fn to_undo_entry(entry: &JournalEntry) -> Option<JournalEntry> {
+    match entry.operation {
+        JournalOperation::CreateFolder | JournalOperation::CreateRoom => Some(
+            JournalEntry::new(JournalOperation::DeleteNode, entry.id, entry.name.clone())
+                .with_parent_id(entry.parent_id),
+        ),
+        JournalOperation::MoveNode => entry.parent_id.map(|parent_id| {
+            JournalEntry::new(JournalOperation::MoveNode, entry.id, entry.name.clone())
+                .with_parent_id(entry.target_id)
+                .with_target_id(parent_id)
+        }),
+        JournalOperation::CreateUser => Some(JournalEntry::new(
+            JournalOperation::DeleteUser,
+            entry.id,
+            entry.name.clone(),
+        )),
+        JournalOperation::CreateGroup => Some(JournalEntry::new(
+            JournalOperation::DeleteGroup,
+            entry.id,
+            entry.name.clone(),
+        )),
+        JournalOperation::DeleteNode => entry.target_id.map(|deleted_node_id| {
+            JournalEntry::new(
+                JournalOperation::RestoreNode,
+                deleted_node_id,
+                entry.name.clone(),
+            )
+            .with_parent_id(entry.parent_id)
+        }),
+        JournalOperation::PurgeDeletedNode
+        | JournalOperation::RestoreNode
+        | JournalOperation::CopyNode
+        | JournalOperation::UpdateRoom
+        | JournalOperation::DeleteUser
+        | JournalOperation::DeleteGroup
+        | JournalOperation::GrantRoomUser
+        | JournalOperation::GrantRoomGroup
+        | JournalOperation::RevokeRoomUser
+        | JournalOperation::RevokeRoomGroup => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_create_folder() {
+        let entry = JournalEntry::new(JournalOperation::CreateFolder, 5, "/room/folder")
+            .with_parent_id(Some(1));
+
+        let undo_entry = to_undo_entry(&entry).unwrap();
+
+        assert_eq!(JournalOperation::DeleteNode, undo_entry.operation);
+        assert_eq!(5, undo_entry.id);
+    }
+
+    #[test]
+    fn test_undo_move_node() {
+        let entry = JournalEntry::new(JournalOperation::MoveNode, 5, "/room/file.pdf")
+            .with_parent_id(Some(1))
+            .with_target_id(2);
+
+        let undo_entry = to_undo_entry(&entry).unwrap();
+
+        assert_eq!(JournalOperation::MoveNode, undo_entry.operation);
+        assert_eq!(Some(1), undo_entry.target_id);
+        assert_eq!(Some(2), undo_entry.parent_id);
+    }
+
+    #[test]
+    fn test_undo_delete_node() {
+        let entry = JournalEntry::new(JournalOperation::DeleteNode, 5, "/room/file.pdf")
+            .with_parent_id(Some(1))
+            .with_target_id(7);
+
+        let undo_entry = to_undo_entry(&entry).unwrap();
+
+        assert_eq!(JournalOperation::RestoreNode, undo_entry.operation);
+        assert_eq!(7, undo_entry.id);
+        assert_eq!(Some(1), undo_entry.parent_id);
+
+        // deleted node id not recorded
+        let entry = JournalEntry::new(JournalOperation::DeleteNode, 5, "/room/file.pdf");
+        assert!(to_undo_entry(&entry).is_none());
+    }
+
+    #[test]
+    fn test_undo_irreversible() {
+        let entry = JournalEntry::new(JournalOperation::DeleteUser, 5, "foo");
+        assert!(to_undo_entry(&entry).is_none());
+
+        let entry = JournalEntry::new(JournalOperation::CopyNode, 5, "/a").with_target_id(2);
+        assert!(to_undo_entry(&entry).is_none());
+
+        let entry = JournalEntry::new(JournalOperation::MoveNode, 5, "/a").with_target_id(2);
+        assert!(to_undo_entry(&entry).is_none());
+    }
+}
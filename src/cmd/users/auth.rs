@@ -10,7 +10,7 @@ use crate::cmd::{
     utils::strings::format_success_message,
 };
 
-use super::{models::UsersSwitchAuthOptions, UserCommandHandler};
+use super::{models::UsersSwitchAuthOptions, UserCommandHandler, DEFAULT_USER_REQUESTS};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AuthMethod {
@@ -70,7 +70,7 @@ impl UserCommandHandler {
 
         let current_user_infos = stream::iter(user_ids)
             .map(|id| self.client.users().get_user(id, None))
-            .buffer_unordered(5)
+            .buffer_unordered(self.concurrency(DEFAULT_USER_REQUESTS))
             .collect::<Vec<_>>()
             .await
             .into_iter()
@@ -121,7 +121,7 @@ impl UserCommandHandler {
 
                 self.client.users().update_user(id, user_update_req)
             })
-            .buffer_unordered(5)
+            .buffer_unordered(self.concurrency(DEFAULT_USER_REQUESTS))
             .collect::<Vec<_>>()
             .await
             .into_iter()
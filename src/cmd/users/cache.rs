@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use tracing::debug;
+
+use crate::cmd::models::DcCmdError;
+
+use super::UserCommandHandler;
+
+static USER_CACHE: OnceLock<UserCache> = OnceLock::new();
+
+/// user ids by instance and login (per run - shared by all handlers)
+#[derive(Debug, Default)]
+struct UserCache {
+    ids: Mutex<HashMap<(String, String), u64>>,
+}
+
+impl UserCache {
+    fn get(&self, base_url: &str, login: &str) -> Option<u64> {
+        self.ids
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(&(base_url.to_string(), login.to_string()))
+            .copied()
+    }
+
+    fn insert(&self, base_url: &str, login: &str, id: u64) {
+        self.ids
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert((base_url.to_string(), login.to_string()), id);
+    }
+}
+
+fn user_cache() -> &'static UserCache {
+    USER_CACHE.get_or_init(UserCache::default)
+}
+
+impl UserCommandHandler {
+    /// resolves a login to a user id - repeated lookups (e.g. room admins of many rooms)
+    /// are served from the cache
+    pub async fn find_user_id_by_username(&self, user_name: &str) -> Result<u64, DcCmdError> {
+        let base_url = self.client.get_base_url().to_string();
+
+        if let Some(id) = user_cache().get(&base_url, user_name) {
+            debug!("User {} resolved from cache (id: {})", user_name, id);
+            return Ok(id);
+        }
+
+        let user = self.find_user_by_username(user_name).await?;
+        user_cache().insert(&base_url, user_name, user.id);
+
+        Ok(user.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_cache() {
+        let cache = UserCache::default();
+        cache.insert("https://dracoon.example.com/", "user", 42);
+
+        assert_eq!(cache.get("https://dracoon.example.com/", "user"), Some(42));
+        assert_eq!(cache.get("https://dracoon.example.com/", "other"), None);
+    }
+
+    #[test]
+    fn test_user_cache_per_instance() {
+        let cache = UserCache::default();
+        cache.insert("https://a.example.com/", "user", 1);
+        cache.insert("https://b.example.com/", "user", 2);
+
+        assert_eq!(cache.get("https://a.example.com/", "user"), Some(1));
+        assert_eq!(cache.get("https://b.example.com/", "user"), Some(2));
+    }
+}
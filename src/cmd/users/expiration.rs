@@ -0,0 +1,117 @@
+use dco3::{users::UpdateUserRequest, Users};
+use dialoguer::Confirm;
+use futures_util::{stream, StreamExt};
+use tracing::{error, info};
+
+use crate::cmd::{
+    branding::instance_prompt,
+    config::MAX_CONCURRENT_REQUESTS,
+    models::{DcCmdError, ListOptions},
+    nodes::expiration::to_object_expiration,
+    utils::{
+        confirm::assume_yes,
+        dates::parse_expiration,
+        strings::{format_error_message, format_success_message},
+    },
+};
+
+use super::UserCommandHandler;
+
+impl UserCommandHandler {
+    /// sets (or removes with `never`) the account expiration of all users matching the filter
+    pub async fn set_users_expiration(
+        &self,
+        filter: String,
+        at: Option<String>,
+        never: bool,
+    ) -> Result<(), DcCmdError> {
+        let expiration = match (at, never) {
+            (Some(_), true) => {
+                return Err(DcCmdError::InvalidArgument(
+                    "Use either --at or --never.".to_string(),
+                ))
+            }
+            (None, false) => {
+                return Err(DcCmdError::InvalidArgument(
+                    "Either --at or --never is required.".to_string(),
+                ))
+            }
+            (Some(at), false) => Some(parse_expiration(&at)?),
+            (None, true) => None,
+        };
+
+        let user_ids = self
+            .list_users(ListOptions::new(Some(filter), None, None, true, false), false)
+            .await?
+            .items
+            .iter()
+            .map(|u| u.id)
+            .collect::<Vec<_>>();
+
+        if user_ids.is_empty() {
+            let msg = format_error_message("No users match the filter.");
+            self.term
+                .write_line(&msg)
+                .map_err(|_| DcCmdError::IoError)?;
+            return Ok(());
+        }
+
+        let change = match expiration {
+            Some(date) => format!("set the expiration to {}", date.date_naive()),
+            None => "remove the expiration".to_string(),
+        };
+
+        let confirmed = assume_yes()
+            || Confirm::new()
+                .with_prompt(instance_prompt(
+                    self.client.get_base_url().as_str(),
+                    &format!("Do you really want to {change} of {} users?", user_ids.len()),
+                ))
+                .interact()
+                .map_err(|_| DcCmdError::IoError)?;
+
+        if !confirmed {
+            return Ok(());
+        }
+
+        info!("Setting expiration for {} users", user_ids.len());
+
+        let update_results = stream::iter(user_ids)
+            .map(|id| {
+                let update_user_req = UpdateUserRequest::builder()
+                    .with_expiration(to_object_expiration(expiration))
+                    .build();
+                self.client.users().update_user(id, update_user_req)
+            })
+            .buffer_unordered(self.concurrency(MAX_CONCURRENT_REQUESTS))
+            .collect::<Vec<_>>()
+            .await;
+
+        let success_count = update_results.iter().filter(|r| r.is_ok()).count();
+        let failed_count = update_results.len() - success_count;
+
+        for e in update_results.iter().filter_map(|r| r.as_ref().err()) {
+            error!("Failed to set expiration for user. Error: {}", e);
+        }
+
+        info!(
+            "Set expiration for {} users successfully. Failed for {} users",
+            success_count, failed_count
+        );
+
+        let summary = format!(
+            "Expiration updated for {success_count} users ({failed_count} failed)."
+        );
+        let msg = if failed_count > 0 {
+            format_error_message(&summary)
+        } else {
+            format_success_message(&summary)
+        };
+
+        self.term
+            .write_line(&msg)
+            .map_err(|_| DcCmdError::IoError)?;
+
+        Ok(())
+    }
+}
@@ -8,7 +8,7 @@ use crate::cmd::{
     utils::strings::format_success_message,
 };
 
-use super::UserCommandHandler;
+use super::{UserCommandHandler, DEFAULT_USER_REQUESTS};
 
 impl UserCommandHandler {
     pub async fn enforce_mfa(
@@ -73,7 +73,7 @@ impl UserCommandHandler {
         let user_ids = if auth_method.is_some() {
             stream::iter(user_ids)
                 .map(|id| self.client.users().get_user(id, None))
-                .buffer_unordered(5)
+                .buffer_unordered(self.concurrency(DEFAULT_USER_REQUESTS))
                 .collect::<Vec<_>>()
                 .await
                 .into_iter()
@@ -111,7 +111,7 @@ impl UserCommandHandler {
                 let update_user_req = UpdateUserRequest::builder().with_mfa_enforced(true).build();
                 self.client.users().update_user(id, update_user_req)
             })
-            .buffer_unordered(5)
+            .buffer_unordered(self.concurrency(DEFAULT_USER_REQUESTS))
             .collect::<Vec<_>>()
             .await
             .into_iter()
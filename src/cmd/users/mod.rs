@@ -1,4 +1,7 @@
-use std::sync::{atomic::AtomicU32, Arc};
+use std::{
+    path::Path,
+    sync::{atomic::AtomicU32, Arc},
+};
 
 use console::Term;
 use dco3::{
@@ -9,33 +12,50 @@ use dco3::{
     Dracoon, Groups, ListAllParams, Nodes, RangedItems, Rooms, Users,
 };
 
-use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::Method;
+use indicatif::ProgressStyle;
 use models::{CreateUserOptions, UsersSwitchAuthOptions};
-use tokio::sync::Semaphore;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 mod auth;
+mod cache;
+mod expiration;
 mod mfa;
-mod models;
+pub mod models;
 mod print;
+mod upsert;
 
 use super::{
-    config::MAX_CONCURRENT_REQUESTS,
+    config::{bulk_concurrency, MAX_CONCURRENT_REQUESTS},
     init_dracoon,
+    journal::{Journal, JournalEntry, JournalOperation},
     models::{build_params, DcCmdError, ListOptions, UsersCommand},
-    utils::strings::{build_node_path, format_success_message, parse_path},
+    utils::{
+        api::{api_request, send_api_json},
+        deadline::check_deadline,
+        pagination::{fetch_all_paginated, PAGE_SIZE},
+        progress::progress_bar,
+        rate_limit::with_rate_limit,
+        stats::BatchStats,
+        strings::{build_node_path, format_success_message, parse_path},
+    },
 };
 
-pub use models::display_option;
+pub use models::{display_option, CmdImportUsersOptions};
 
 use crate::cmd::users::models::UserImport;
 
-use self::models::UserInfo;
+use self::models::{ExistingUsers, GroupMapping, UserDetails, UserGroupInfo, UserInfo};
+
+/// concurrent requests of user updates (switch auth, enforce MFA) without velocity
+const DEFAULT_USER_REQUESTS: usize = 5;
 
 #[derive(Clone)]
 pub struct UserCommandHandler {
     client: Dracoon<Connected>,
     term: Term,
+    journal: Journal,
+    velocity: Option<u8>,
 }
 
 impl UserCommandHandler {
@@ -50,14 +70,49 @@ impl UserCommandHandler {
             init_dracoon(target_domain, None, false).await?
         };
 
-        Ok(Self { client, term })
+        Ok(Self {
+            client,
+            term,
+            journal: Journal::default(),
+            velocity: None,
+        })
     }
 
     pub fn new_from_client(client: Dracoon<Connected>, term: Term) -> Self {
-        Self { client, term }
+        Self {
+            client,
+            term,
+            journal: Journal::default(),
+            velocity: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = journal;
+        self
+    }
+
+    #[must_use]
+    pub fn with_velocity(mut self, velocity: Option<u8>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// concurrent requests of bulk operations (scaled via velocity)
+    fn concurrency(&self, default: usize) -> usize {
+        bulk_concurrency(self.velocity, default)
     }
 
-    async fn import_users(&self, source: String, oidc_id: Option<u32>) -> Result<(), DcCmdError> {
+    async fn import_users(&self, opts: CmdImportUsersOptions) -> Result<(), DcCmdError> {
+        let CmdImportUsersOptions {
+            source,
+            oidc_id,
+            update_existing,
+            group_mapping,
+            ..
+        } = opts;
+
         let mut rdr = csv::ReaderBuilder::new()
             .flexible(true)
             .trim(csv::Trim::All)
@@ -67,17 +122,23 @@ impl UserCommandHandler {
                 DcCmdError::InvalidArgument(format!("File not found: {source}"))
             })?;
 
-        let imports = rdr
+        let mut imports = rdr
             .deserialize::<UserImport>()
             .collect::<Result<Vec<_>, csv::Error>>()
             .map_err(|e| {
                 error!("Error reading record: {e}");
-                DcCmdError::InvalidArgument(format!("Invalid CSV format. Expected fields: first_name, last_name, email, login (optional), mfa_enabled (optional).\n{e})"))
+                DcCmdError::InvalidArgument(format!("Invalid CSV format. Expected fields: first_name, last_name, email, login (optional), mfa_enabled (optional), groups (optional), idp_groups (optional).\n{e})"))
             })?;
 
+        // IdP groups are mapped to DRACOON groups (assigned like the groups column)
+        if let Some(group_mapping) = group_mapping {
+            let mapping = load_group_mapping(&group_mapping)?;
+            imports.iter_mut().for_each(|import| mapping.apply(import));
+        }
+
         let user_count = imports.len();
 
-        let progress_bar = ProgressBar::new(user_count as u64);
+        let progress_bar = progress_bar(user_count as u64);
         progress_bar.set_style(
             ProgressStyle::default_bar()
                 .template(
@@ -86,15 +147,32 @@ impl UserCommandHandler {
                 .unwrap()
                 .progress_chars("=>-"),
         );
+        let stats = BatchStats::new(user_count as u64);
+        let stats_line = stats.attach(&progress_bar);
+
+        // existing users are matched by login or email and updated instead of failing
+        let existing = if update_existing {
+            Arc::new(self.get_existing_users().await?)
+        } else {
+            Arc::new(ExistingUsers::default())
+        };
+        let groups = Arc::new(self.resolve_import_groups(&imports).await?);
 
         let errors = Arc::new(AtomicU32::new(0));
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_REQUESTS));
+        let updated = Arc::new(AtomicU32::new(0));
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(
+            self.concurrency(MAX_CONCURRENT_REQUESTS),
+        ));
         let mut handles = Vec::new();
 
         for import in imports {
             let handler = self.clone();
             let semaphore = semaphore.clone();
             let errors = errors.clone();
+            let updated = updated.clone();
+            let existing = existing.clone();
+            let groups = groups.clone();
+            let stats = stats.clone();
             let progress_bar = progress_bar.clone();
 
             let handle = tokio::spawn(async move {
@@ -103,20 +181,44 @@ impl UserCommandHandler {
                     DcCmdError::IoError
                 })?;
 
-                match handler
-                    .create_user(CreateUserOptions::new(
-                        &import.first_name,
-                        &import.last_name,
-                        &import.email,
-                        import.login.as_deref(),
-                        oidc_id,
-                        import.mfa_enabled.unwrap_or(false),
-                        true,
-                        None,
-                    ))
+                if let Err(e) = check_deadline() {
+                    warn!("Skipped import of {}: {}", import.email, e);
+                    stats.record(false);
+                    progress_bar.inc(1);
+                    errors.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    return Ok(());
+                }
+
+                let result = if let Some(user_id) = existing.find(&import) {
+                    with_rate_limit(|| handler.update_imported_user(user_id, &import, &groups))
+                        .await
+                        .map(|()| {
+                            updated.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        })
+                } else {
+                    match with_rate_limit(|| {
+                        handler.create_user(CreateUserOptions::new(
+                            &import.first_name,
+                            &import.last_name,
+                            &import.email,
+                            import.login.as_deref(),
+                            oidc_id,
+                            import.mfa_enabled.unwrap_or(false),
+                            true,
+                            None,
+                        ))
+                    })
                     .await
-                {
-                    Ok(_) => {
+                    {
+                        Ok(user_id) => handler.set_imported_groups(user_id, &import, &groups).await,
+                        Err(e) => Err(e),
+                    }
+                };
+
+                stats.record(result.is_ok());
+
+                match result {
+                    Ok(()) => {
                         progress_bar.inc(1);
                     }
                     Err(e) => {
@@ -143,11 +245,19 @@ impl UserCommandHandler {
             }
         }
 
-        let imported = user_count - errors.load(std::sync::atomic::Ordering::Relaxed) as usize;
+        let updated = updated.load(std::sync::atomic::Ordering::Relaxed) as usize;
+        let imported =
+            user_count - errors.load(std::sync::atomic::Ordering::Relaxed) as usize - updated;
 
-        let msg = format!("{imported} users imported");
+        let msg = if update_existing {
+            format!("{imported} users imported, {updated} users updated")
+        } else {
+            format!("{imported} users imported")
+        };
 
         progress_bar.finish_with_message(msg.clone());
+        stats_line.finish(&stats);
+        stats.log_summary("users import");
 
         self.term
             .write_line(format_success_message(&msg).as_str())
@@ -156,7 +266,8 @@ impl UserCommandHandler {
         Ok(())
     }
 
-    async fn create_user<'o>(&self, opts: CreateUserOptions<'o>) -> Result<(), DcCmdError> {
+    /// creates a user and returns its id
+    async fn create_user<'o>(&self, opts: CreateUserOptions<'o>) -> Result<u64, DcCmdError> {
         let payload = if let (Some(login), Some(oidc_id)) = (opts.login, opts.oidc_id) {
             let user_auth_data = UserAuthData::new_oidc(login, oidc_id.into());
             CreateUserRequest::builder(opts.first_name, opts.last_name)
@@ -199,6 +310,12 @@ impl UserCommandHandler {
             }
         }
 
+        self.journal.record(&[JournalEntry::new(
+            JournalOperation::CreateUser,
+            user.id,
+            user.user_name.clone(),
+        )])?;
+
         info!(
             "User {} created (id: {} | auth: {})",
             user.user_name, user.id, user.auth_data.method
@@ -225,7 +342,7 @@ impl UserCommandHandler {
                 .map_err(|_| DcCmdError::IoError)?;
         }
 
-        Ok(())
+        Ok(user.id)
     }
 
     async fn invite_user(
@@ -263,59 +380,30 @@ impl UserCommandHandler {
             opts.limit().unwrap_or(500).into(),
         )?;
 
-        let mut results = self
+        let results = self
             .client
             .users()
             .get_users(Some(params), None, None)
             .await?;
 
-        if opts.all() && results.range.total > 500 {
-            let (tx, mut rx) = tokio::sync::mpsc::channel(MAX_CONCURRENT_REQUESTS);
-            let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
-
-            let mut handles = Vec::new();
-
-            (500..=results.range.total).step_by(500).for_each(|offset| {
-                let tx = tx.clone();
-                let dracoon_client = self.client.clone();
-                let filter = opts.filter().clone();
-                let semaphore = semaphore.clone();
-                let handle = tokio::spawn(async move {
-                    let _permit = semaphore.acquire().await.map_err(|_| {
-                        error!("Error acquiring semaphore permit");
-                        DcCmdError::IoError
-                    })?;
-
-                    let params = build_params(&filter, offset, 500.into())?;
-                    let users = dracoon_client
-                        .users()
-                        .get_users(Some(params), None, None)
-                        .await?;
-
-                    tx.send(users).await.map_err(|e| {
-                        error!("Error sending users: {}", e);
-                        DcCmdError::IoError
-                    })?;
-
-                    Ok::<(), DcCmdError>(())
-                });
-
-                handles.push(handle);
-            });
+        let results = if opts.all() {
+            let client = self.client.clone();
+            let filter = opts.filter().clone();
 
-            drop(tx);
+            fetch_all_paginated(results, move |offset| {
+                let client = client.clone();
+                let params = build_params(&filter, offset, 500.into());
 
-            while let Some(users) = rx.recv().await {
-                results.items.extend(users.items);
-            }
+                async move {
+                    let users = client.users().get_users(Some(params?), None, None).await?;
 
-            for handle in handles {
-                if let Err(e) = handle.await {
-                    error!("Error fetching users: {}", e);
-                    return Err(DcCmdError::IoError);
+                    Ok::<_, DcCmdError>(users)
                 }
-            }
-        }
+            })
+            .await?
+        } else {
+            results
+        };
 
         if print {
             self.print_users(&results, opts.csv())?;
@@ -329,13 +417,16 @@ impl UserCommandHandler {
         user_name: Option<String>,
         user_id: Option<u64>,
     ) -> Result<(), DcCmdError> {
-        let confirm_msg = if let Some(user_name) = user_name {
+        let (user_id, confirm_msg, user_name) = if let Some(user_name) = user_name {
             let user = self.find_user_by_username(&user_name).await?;
-            self.client.users().delete_user(user.id).await?;
-            format!("User {user_name} deleted",)
+            (user.id, format!("User {user_name} deleted",), user_name)
         } else if let Some(user_id) = user_id {
-            self.client.users().delete_user(user_id).await?;
-            format!("User {user_id} (id) deleted",)
+            let user = self.client.users().get_user(user_id, None).await?;
+            (
+                user_id,
+                format!("User {user_id} (id) deleted",),
+                user.user_name,
+            )
         } else {
             error!("User name or user id must be provided");
             return Err(DcCmdError::InvalidArgument(
@@ -343,6 +434,19 @@ impl UserCommandHandler {
             ));
         };
 
+        let entries = [JournalEntry::new(
+            JournalOperation::DeleteUser,
+            user_id,
+            user_name,
+        )];
+
+        if self.journal.is_dry_run() {
+            return self.journal.print_dry_run(&self.term, &entries);
+        }
+
+        self.client.users().delete_user(user_id).await?;
+        self.journal.record(&entries)?;
+
         self.term
             .write_line(format_success_message(confirm_msg.as_str()).as_str())
             .map_err(|_| DcCmdError::IoError)?;
@@ -354,15 +458,12 @@ impl UserCommandHandler {
         &self,
         user_name: Option<String>,
         user_id: Option<u64>,
+        json: bool,
     ) -> Result<(), DcCmdError> {
-        let user: UserInfo = if let Some(user_name) = user_name {
-            self.find_user_by_username(&user_name).await?.try_into()?
+        let user_id = if let Some(user_name) = user_name {
+            self.find_user_by_username(&user_name).await?.id
         } else if let Some(user_id) = user_id {
-            self.client
-                .users()
-                .get_user(user_id, None)
-                .await?
-                .try_into()?
+            user_id
         } else {
             error!("User name or user id must be provided");
             return Err(DcCmdError::InvalidArgument(
@@ -370,11 +471,39 @@ impl UserCommandHandler {
             ));
         };
 
-        self.print_user_info(user)?;
+        // effective roles (including roles granted via groups)
+        let user = self.client.users().get_user(user_id, Some(true)).await?;
+
+        let roles = user
+            .user_roles
+            .as_ref()
+            .map(|roles| roles.items.iter().map(|role| role.name.clone()).collect())
+            .unwrap_or_default();
+
+        let groups = self.get_user_groups(user_id).await?;
+
+        let user: UserInfo = user.try_into()?;
+
+        self.print_user_info(UserDetails::new(user, groups, roles), json)?;
 
         Ok(())
     }
 
+    /// returns all groups the user is a member of (dco3 does not provide the user groups API)
+    async fn get_user_groups(&self, user_id: u64) -> Result<Vec<UserGroupInfo>, DcCmdError> {
+        let groups = get_user_groups_page(&self.client, user_id, 0).await?;
+        let client = self.client.clone();
+
+        let groups = fetch_all_paginated(groups, move |offset| {
+            let client = client.clone();
+
+            async move { get_user_groups_page(&client, user_id, offset).await }
+        })
+        .await?;
+
+        Ok(groups.items)
+    }
+
     pub async fn find_user_by_username(&self, user_name: &str) -> Result<UserItem, DcCmdError> {
         let user_filter = UsersFilter::username_contains(user_name);
         let params = ListAllParams::builder().with_filter(user_filter).build();
@@ -395,7 +524,46 @@ impl UserCommandHandler {
     }
 }
 
-pub async fn handle_users_cmd(cmd: UsersCommand, term: Term) -> Result<(), DcCmdError> {
+async fn get_user_groups_page(
+    dracoon: &Dracoon<Connected>,
+    user_id: u64,
+    offset: u64,
+) -> Result<RangedItems<UserGroupInfo>, DcCmdError> {
+    let request = api_request(dracoon, Method::GET, &format!("users/{user_id}/groups"))
+        .await?
+        .query(&[("filter", "isMember:eq:true")])
+        .query(&[("offset", offset), ("limit", PAGE_SIZE)]);
+
+    send_api_json(request).await
+}
+
+/// imports users from a CSV file into DRACOON (target is the DRACOON url)
+pub async fn import_users(
+    term: Term,
+    target: &str,
+    opts: CmdImportUsersOptions,
+) -> Result<(), DcCmdError> {
+    UserCommandHandler::try_new(target, term, true)
+        .await?
+        .with_velocity(opts.velocity)
+        .import_users(opts)
+        .await
+}
+
+fn load_group_mapping(path: &Path) -> Result<GroupMapping, DcCmdError> {
+    let file = std::fs::File::open(path).map_err(|e| {
+        error!("Error opening group mapping: {}", e);
+        DcCmdError::InvalidArgument(format!("File not found: {}", path.display()))
+    })?;
+
+    GroupMapping::from_reader(file)
+}
+
+pub async fn handle_users_cmd(
+    cmd: UsersCommand,
+    term: Term,
+    journal: Journal,
+) -> Result<(), DcCmdError> {
     let target = match &cmd {
         UsersCommand::Create { target, .. }
         | UsersCommand::Ls { target, .. }
@@ -404,13 +572,24 @@ pub async fn handle_users_cmd(cmd: UsersCommand, term: Term) -> Result<(), DcCmd
         | UsersCommand::Info { target, .. }
         | UsersCommand::SwitchAuth { target, .. }
         | UsersCommand::EnforceMfa { target, .. }
+        | UsersCommand::SetExpiration { target, .. }
         | UsersCommand::Invite { target, .. } => target,
     };
 
+    let velocity = match &cmd {
+        UsersCommand::Import { velocity, .. }
+        | UsersCommand::SwitchAuth { velocity, .. }
+        | UsersCommand::EnforceMfa { velocity, .. }
+        | UsersCommand::SetExpiration { velocity, .. } => *velocity,
+        _ => None,
+    };
+
     let handler = match &cmd {
         UsersCommand::Import { .. } => UserCommandHandler::try_new(target, term, true).await?,
         _ => UserCommandHandler::try_new(target, term, false).await?,
-    };
+    }
+    .with_journal(journal)
+    .with_velocity(velocity);
 
     match cmd {
         UsersCommand::Create {
@@ -489,15 +668,27 @@ pub async fn handle_users_cmd(cmd: UsersCommand, term: Term) -> Result<(), DcCmd
             target: _,
             source,
             oidc_id,
+            update_existing,
+            group_mapping,
+            velocity,
         } => {
-            handler.import_users(source, oidc_id).await?;
+            handler
+                .import_users(CmdImportUsersOptions::new(
+                    source,
+                    oidc_id,
+                    update_existing,
+                    group_mapping,
+                    velocity,
+                ))
+                .await?;
         }
         UsersCommand::Info {
             target: _,
             user_name,
             user_id,
+            json,
         } => {
-            handler.get_user_info(user_name, user_id).await?;
+            handler.get_user_info(user_name, user_id, json).await?;
         }
         UsersCommand::SwitchAuth {
             target: _,
@@ -509,6 +700,7 @@ pub async fn handle_users_cmd(cmd: UsersCommand, term: Term) -> Result<(), DcCmd
             new_ad_id,
             filter,
             login,
+            velocity: _,
         } => {
             let opts = UsersSwitchAuthOptions::try_new(
                 current_method,
@@ -528,11 +720,21 @@ pub async fn handle_users_cmd(cmd: UsersCommand, term: Term) -> Result<(), DcCmd
             filter,
             auth_method_id,
             group_id,
+            velocity: _,
         } => {
             handler
                 .enforce_mfa(auth_method, filter, auth_method_id, group_id)
                 .await?;
         }
+        UsersCommand::SetExpiration {
+            target: _,
+            filter,
+            at,
+            never,
+            velocity: _,
+        } => {
+            handler.set_users_expiration(filter, at, never).await?;
+        }
     }
     Ok(())
 }
@@ -1,8 +1,8 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use dco3::users::{UserData, UserItem};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tabled::Tabled;
 use tracing::debug;
 
@@ -10,6 +10,71 @@ use crate::cmd::models::DcCmdError;
 
 use super::auth::AuthMethod;
 
+/// options of a user import from a CSV file (see README for the expected fields)
+#[derive(Clone, Debug)]
+pub struct CmdImportUsersOptions {
+    pub source: String,
+    pub oidc_id: Option<u32>,
+    pub update_existing: bool,
+    pub group_mapping: Option<PathBuf>,
+    pub velocity: Option<u8>,
+}
+
+impl CmdImportUsersOptions {
+    pub fn new(
+        source: String,
+        oidc_id: Option<u32>,
+        update_existing: bool,
+        group_mapping: Option<PathBuf>,
+        velocity: Option<u8>,
+    ) -> Self {
+        Self {
+            source,
+            oidc_id,
+            update_existing,
+            group_mapping,
+            velocity,
+        }
+    }
+
+    /// builds import options for library use (source is a CSV file)
+    pub fn builder(source: impl Into<String>) -> CmdImportUsersOptionsBuilder {
+        CmdImportUsersOptionsBuilder {
+            opts: Self::new(source.into(), None, false, None, None),
+        }
+    }
+}
+
+pub struct CmdImportUsersOptionsBuilder {
+    opts: CmdImportUsersOptions,
+}
+
+impl CmdImportUsersOptionsBuilder {
+    pub fn with_oidc_id(mut self, oidc_id: u32) -> Self {
+        self.opts.oidc_id = Some(oidc_id);
+        self
+    }
+
+    pub fn with_update_existing(mut self, update_existing: bool) -> Self {
+        self.opts.update_existing = update_existing;
+        self
+    }
+
+    pub fn with_group_mapping(mut self, group_mapping: PathBuf) -> Self {
+        self.opts.group_mapping = Some(group_mapping);
+        self
+    }
+
+    pub fn with_velocity(mut self, velocity: u8) -> Self {
+        self.opts.velocity = Some(velocity);
+        self
+    }
+
+    pub fn build(self) -> CmdImportUsersOptions {
+        self.opts
+    }
+}
+
 pub struct CreateUserOptions<'o> {
     pub first_name: &'o str,
     pub last_name: &'o str,
@@ -61,6 +126,44 @@ pub struct UserInfo {
     pub last_login_at: Option<DateTime<Utc>>,
 }
 
+/// user info including group memberships and roles (`users info`)
+#[derive(Serialize)]
+pub struct UserDetails {
+    pub id: u64,
+    pub first_name: String,
+    pub last_name: String,
+    pub user_name: String,
+    pub email: Option<String>,
+    pub is_locked: bool,
+    pub last_login_at: Option<String>,
+    pub expire_at: Option<String>,
+    pub groups: Vec<UserGroupInfo>,
+    pub roles: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UserGroupInfo {
+    pub id: u64,
+    pub name: String,
+}
+
+impl UserDetails {
+    pub fn new(user: UserInfo, groups: Vec<UserGroupInfo>, roles: Vec<String>) -> Self {
+        Self {
+            id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            user_name: user.username,
+            email: user.email,
+            is_locked: user.is_locked,
+            last_login_at: user.last_login_at.map(|date| date.to_rfc3339()),
+            expire_at: user.expire_at.map(|date| date.to_rfc3339()),
+            groups,
+            roles,
+        }
+    }
+}
+
 pub fn display_option<T: Display>(o: &Option<T>) -> String {
     match o {
         Some(v) => v.to_string(),
@@ -145,6 +248,147 @@ pub struct UserImport {
     pub email: String,
     pub login: Option<String>,
     pub mfa_enabled: Option<bool>,
+    /// group names separated by `;` (optional column)
+    #[serde(default)]
+    pub groups: Option<String>,
+    /// AD / OIDC (IdP) group names separated by `;` - mapped via `--group-mapping` (optional column)
+    #[serde(default)]
+    pub idp_groups: Option<String>,
+}
+
+impl UserImport {
+    /// login of the user (email if no login is given)
+    pub fn login(&self) -> &str {
+        self.login.as_deref().unwrap_or(&self.email)
+    }
+
+    /// group names of the user (None if not set - memberships are not changed)
+    pub fn group_names(&self) -> Option<Vec<&str>> {
+        self.groups.as_deref().map(split_names)
+    }
+
+    /// IdP group names of the user (None if not set)
+    pub fn idp_group_names(&self) -> Option<Vec<&str>> {
+        self.idp_groups.as_deref().map(split_names)
+    }
+}
+
+fn split_names(names: &str) -> Vec<&str> {
+    names
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct GroupMappingRow {
+    idp_group: String,
+    dracoon_group: String,
+}
+
+/// IdP group (case insensitive) to DRACOON groups (`users import --group-mapping`)
+#[derive(Debug, Default)]
+pub struct GroupMapping {
+    groups: HashMap<String, Vec<String>>,
+}
+
+impl GroupMapping {
+    /// reads a mapping CSV with header 'idp_group,dracoon_group' (one row per assignment)
+    pub fn from_reader(reader: impl std::io::Read) -> Result<Self, DcCmdError> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let mut mapping = Self::default();
+
+        for row in rdr.deserialize::<GroupMappingRow>() {
+            let row = row.map_err(|e| {
+                DcCmdError::InvalidArgument(format!(
+                    "Invalid group mapping. Expected fields: idp_group, dracoon_group.\n{e}"
+                ))
+            })?;
+
+            let groups = mapping
+                .groups
+                .entry(row.idp_group.to_lowercase())
+                .or_default();
+
+            if !groups.contains(&row.dracoon_group) {
+                groups.push(row.dracoon_group);
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    /// adds the DRACOON groups mapped to the IdP groups of an import to its groups
+    /// (IdP groups without mapping are ignored)
+    pub fn apply(&self, import: &mut UserImport) {
+        let Some(idp_groups) = import.idp_group_names() else {
+            return;
+        };
+
+        let mut groups = import
+            .group_names()
+            .unwrap_or_default()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        for idp_group in idp_groups {
+            let Some(mapped) = self.groups.get(&idp_group.to_lowercase()) else {
+                debug!("No mapping for IdP group {} ({})", idp_group, import.login());
+                continue;
+            };
+
+            for group in mapped {
+                if !groups.contains(group) {
+                    groups.push(group.clone());
+                }
+            }
+        }
+
+        // memberships are only changed if groups are set or mapped
+        if groups.is_empty() && import.groups.is_none() {
+            return;
+        }
+
+        import.groups = Some(groups.join(";"));
+    }
+}
+
+/// existing users by login and email (case insensitive) to update on import
+#[derive(Default)]
+pub struct ExistingUsers {
+    by_login: HashMap<String, u64>,
+    by_email: HashMap<String, u64>,
+}
+
+impl ExistingUsers {
+    pub fn new(users: &[UserItem]) -> Self {
+        let mut existing = Self::default();
+
+        for user in users {
+            existing
+                .by_login
+                .insert(user.user_name.to_lowercase(), user.id);
+
+            if let Some(email) = &user.email {
+                existing.by_email.insert(email.to_lowercase(), user.id);
+            }
+        }
+
+        existing
+    }
+
+    /// user id of an import (matched by login first, then by email)
+    pub fn find(&self, import: &UserImport) -> Option<u64> {
+        self.by_login
+            .get(&import.login().to_lowercase())
+            .or_else(|| self.by_email.get(&import.email.to_lowercase()))
+            .copied()
+    }
 }
 
 pub struct UsersSwitchAuthOptions {
@@ -287,3 +531,61 @@ impl UsersSwitchAuthOptions {
         &self.login
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_import(groups: Option<&str>, idp_groups: Option<&str>) -> UserImport {
+        UserImport {
+            first_name: "Jane".into(),
+            last_name: "Doe".into(),
+            email: "jane.doe@example.com".into(),
+            login: None,
+            mfa_enabled: None,
+            groups: groups.map(String::from),
+            idp_groups: idp_groups.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_group_mapping_apply() {
+        let csv = "idp_group,dracoon_group\nCN=Sales,Sales\ncn=sales,CRM\nIT,Admins\n";
+        let mapping = GroupMapping::from_reader(csv.as_bytes()).unwrap();
+
+        let mut import = user_import(Some("Everyone"), Some("cn=SALES; Unknown"));
+        mapping.apply(&mut import);
+        assert_eq!(import.group_names(), Some(vec!["Everyone", "Sales", "CRM"]));
+
+        // without (mapped) IdP groups, memberships are not changed
+        let mut import = user_import(None, None);
+        mapping.apply(&mut import);
+        assert_eq!(import.group_names(), None);
+
+        let mut import = user_import(None, Some("Unknown"));
+        mapping.apply(&mut import);
+        assert_eq!(import.group_names(), None);
+    }
+
+    #[test]
+    fn test_group_mapping_invalid() {
+        assert!(GroupMapping::from_reader("group\nSales\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_parse_user_groups() {
+        let json = r#"{
+            "range": {"offset": 0, "limit": 500, "total": 2},
+            "items": [
+                {"id": 1, "name": "Sales", "isMember": true},
+                {"id": 2, "name": "IT", "isMember": true}
+            ]
+        }"#;
+
+        let groups = serde_json::from_str::<dco3::RangedItems<UserGroupInfo>>(json).unwrap();
+
+        assert_eq!(groups.range.total, 2);
+        assert_eq!(groups.items[1].id, 2);
+        assert_eq!(groups.items[1].name, "IT");
+    }
+}
@@ -4,50 +4,74 @@ use tabled::{
     Table,
 };
 
-use crate::cmd::models::{DcCmdError, PrintFormat};
+use crate::cmd::{
+    models::{DcCmdError, PrintFormat},
+    utils::fields::CsvOutput,
+};
 
-use super::{models::UserInfo, UserCommandHandler};
+use super::{
+    models::{UserDetails, UserInfo},
+    UserCommandHandler,
+};
 
 impl UserCommandHandler {
-    pub fn print_user_info(&self, user_info: UserInfo) -> Result<(), DcCmdError> {
-        let last_login = if let Some(last_login) = user_info.last_login_at {
-            last_login.to_string()
-        } else {
+    pub fn print_user_info(&self, user: UserDetails, json: bool) -> Result<(), DcCmdError> {
+        if json {
+            let json = serde_json::to_string_pretty(&user).map_err(|_| DcCmdError::IoError)?;
+            return self.term.write_line(&json).map_err(|_| DcCmdError::IoError);
+        }
+
+        let groups = if user.groups.is_empty() {
             "N/A".to_string()
+        } else {
+            user.groups
+                .iter()
+                .map(|group| format!("{} ({})", group.name, group.id))
+                .collect::<Vec<_>>()
+                .join(", ")
         };
 
-        let expire_at = if let Some(expire_at) = user_info.expire_at {
-            expire_at.to_string()
-        } else {
+        let roles = if user.roles.is_empty() {
             "N/A".to_string()
+        } else {
+            user.roles.join(", ")
         };
 
         self.term
-            .write_line(&format!("► user id: {}", user_info.id))
+            .write_line(&format!("► user id: {}", user.id))
+            .map_err(|_| DcCmdError::IoError)?;
+        self.term
+            .write_line(&format!("► name: {} {}", user.first_name, user.last_name))
+            .map_err(|_| DcCmdError::IoError)?;
+        self.term
+            .write_line(&format!("► username: {}", user.user_name))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
             .write_line(&format!(
-                "► name: {} {}",
-                user_info.first_name, user_info.last_name
+                "► email: {}",
+                user.email.unwrap_or_else(|| "N/A".to_string())
             ))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
-            .write_line(&format!("► username: {}", user_info.username))
+            .write_line(&format!(
+                "► last login at: {}",
+                user.last_login_at.unwrap_or_else(|| "N/A".to_string())
+            ))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
             .write_line(&format!(
-                "► email: {}",
-                user_info.email.unwrap_or_else(|| "N/A".to_string())
+                "► expire at: {}",
+                user.expire_at.unwrap_or_else(|| "N/A".to_string())
             ))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
-            .write_line(&format!("► last login at: {last_login}"))
+            .write_line(&format!("► locked: {}", user.is_locked))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
-            .write_line(&format!("► expire at: {expire_at}"))
+            .write_line(&format!("► groups: {groups}"))
             .map_err(|_| DcCmdError::IoError)?;
         self.term
-            .write_line(&format!("► locked: {}", user_info.is_locked))
+            .write_line(&format!("► roles: {roles}"))
             .map_err(|_| DcCmdError::IoError)?;
 
         Ok(())
@@ -64,9 +88,7 @@ impl UserCommandHandler {
             PrintFormat::Csv => {
                 const NOT_AVAILABLE: &str = "N/A";
                 let header = "id,first_name,last_name,user_name,email,expire_at,is_locked,is_encryption_enabled,has_manageable_rooms,last_login_success_at";
-                self.term
-                    .write_line(header)
-                    .map_err(|_| DcCmdError::IoError)?;
+                let output = CsvOutput::new(&self.term, header)?;
 
                 for user_item in &users.items {
                     let expire_at = if let Some(expire_at) = user_item.expire_at {
@@ -81,21 +103,19 @@ impl UserCommandHandler {
                         .as_deref()
                         .unwrap_or(NOT_AVAILABLE);
 
-                    self.term
-                        .write_line(&format!(
-                            "{},{},{},{},{},{},{},{},{},{}",
-                            user_item.id,
-                            user_item.first_name,
-                            user_item.last_name,
-                            user_item.user_name,
-                            email,
-                            expire_at,
-                            user_item.is_locked,
-                            user_item.is_encryption_enabled.unwrap_or(false),
-                            user_item.has_manageable_rooms.unwrap_or(false),
-                            last_login_success_at
-                        ))
-                        .map_err(|_| DcCmdError::IoError)?;
+                    output.write_line(&format!(
+                        "{},{},{},{},{},{},{},{},{},{}",
+                        user_item.id,
+                        user_item.first_name,
+                        user_item.last_name,
+                        user_item.user_name,
+                        email,
+                        expire_at,
+                        user_item.is_locked,
+                        user_item.is_encryption_enabled.unwrap_or(false),
+                        user_item.has_manageable_rooms.unwrap_or(false),
+                        last_login_success_at
+                    ))?;
                 }
             }
             PrintFormat::Pretty => {
@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use dco3::{groups::ChangeGroupMembersRequest, users::UpdateUserRequest, Groups, Users};
+use tracing::{error, info};
+
+use crate::cmd::{
+    groups::GroupCommandHandler,
+    models::{DcCmdError, ListOptions},
+};
+
+use super::{
+    models::{ExistingUsers, UserImport},
+    UserCommandHandler,
+};
+
+impl UserCommandHandler {
+    /// all users of the instance by login and email (`users import --update-existing`)
+    pub async fn get_existing_users(&self) -> Result<ExistingUsers, DcCmdError> {
+        let users = self
+            .list_users(ListOptions::new(None, None, None, true, false), false)
+            .await?;
+
+        Ok(ExistingUsers::new(&users.items))
+    }
+
+    /// resolves the group names of all imports to ids (unknown groups fail the import)
+    pub async fn resolve_import_groups(
+        &self,
+        imports: &[UserImport],
+    ) -> Result<HashMap<String, u64>, DcCmdError> {
+        let names = imports
+            .iter()
+            .filter_map(UserImport::group_names)
+            .flatten()
+            .collect::<HashSet<_>>();
+
+        let handler = GroupCommandHandler::new_from_client(self.client.clone(), self.term.clone());
+        let mut groups = HashMap::new();
+
+        for name in names {
+            let group = handler.find_group_by_name(name.to_string()).await?;
+            groups.insert(name.to_string(), group.id);
+        }
+
+        Ok(groups)
+    }
+
+    /// updates name, MFA enforcement (if set) and group memberships (if set) of an existing user
+    pub async fn update_imported_user(
+        &self,
+        user_id: u64,
+        import: &UserImport,
+        groups: &HashMap<String, u64>,
+    ) -> Result<(), DcCmdError> {
+        let update_user_req = UpdateUserRequest::builder()
+            .with_first_name(import.first_name.clone())
+            .with_last_name(import.last_name.clone());
+
+        let update_user_req = match import.mfa_enabled {
+            Some(mfa_enforced) => update_user_req.with_mfa_enforced(mfa_enforced).build(),
+            None => update_user_req.build(),
+        };
+
+        self.client
+            .users()
+            .update_user(user_id, update_user_req)
+            .await?;
+
+        self.set_imported_groups(user_id, import, groups).await?;
+
+        info!("User {} updated (id: {})", import.login(), user_id);
+
+        Ok(())
+    }
+
+    /// sets the group memberships of a user to the groups of the import
+    /// (adds missing and removes other memberships - unchanged if no groups are set)
+    pub async fn set_imported_groups(
+        &self,
+        user_id: u64,
+        import: &UserImport,
+        groups: &HashMap<String, u64>,
+    ) -> Result<(), DcCmdError> {
+        let Some(group_names) = import.group_names() else {
+            return Ok(());
+        };
+
+        let target_ids = group_names
+            .iter()
+            .filter_map(|name| groups.get(*name).copied())
+            .collect::<HashSet<_>>();
+
+        let current_ids = self
+            .get_user_groups(user_id)
+            .await?
+            .into_iter()
+            .map(|group| group.id)
+            .collect::<HashSet<_>>();
+
+        for group_id in target_ids.difference(&current_ids) {
+            self.client
+                .groups()
+                .add_group_users(*group_id, ChangeGroupMembersRequest::new(vec![user_id]))
+                .await
+                .inspect_err(|e| error!("Failed to add user {user_id} to group {group_id}: {e}"))?;
+        }
+
+        for group_id in current_ids.difference(&target_ids) {
+            self.client
+                .groups()
+                .remove_group_users(*group_id, ChangeGroupMembersRequest::new(vec![user_id]))
+                .await
+                .inspect_err(|e| {
+                    error!("Failed to remove user {user_id} from group {group_id}: {e}");
+                })?;
+        }
+
+        Ok(())
+    }
+}
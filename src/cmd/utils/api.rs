@@ -0,0 +1,87 @@
+use dco3::{
+    auth::{models::DracoonErrorResponse, Connected},
+    Dracoon,
+};
+use reqwest::{header, Client, Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use tracing::error;
+
+use crate::cmd::models::DcCmdError;
+
+use super::rate_limit::send_rate_limited;
+
+/// builds a request to an API endpoint not provided by dco3 (path relative to /api/v4)
+pub async fn api_request(
+    dracoon: &Dracoon<Connected>,
+    method: Method,
+    path: &str,
+) -> Result<RequestBuilder, DcCmdError> {
+    let url = to_api_url(dracoon.get_base_url().as_str(), path);
+
+    Ok(Client::new()
+        .request(method, url)
+        .header(header::AUTHORIZATION, dracoon.get_auth_header().await?))
+}
+
+/// sends a request built via `api_request` respecting the global rate limit - error responses
+/// are returned as `DracoonError`
+pub async fn send_api_request(request: RequestBuilder) -> Result<Response, DcCmdError> {
+    let response = send_rate_limited(request).await.map_err(|e| {
+        error!("Error sending request: {}", e);
+        DcCmdError::ConnectionFailed
+    })?;
+
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let status = response.status();
+    let err = response
+        .json::<DracoonErrorResponse>()
+        .await
+        .unwrap_or_else(|_| DracoonErrorResponse::new(i32::from(status.as_u16()), "Request failed"));
+
+    error!("Error response: {}", err);
+    Err(DcCmdError::DracoonError(err))
+}
+
+/// sends a request built via `api_request` and parses the JSON response
+pub async fn send_api_json<T: DeserializeOwned>(request: RequestBuilder) -> Result<T, DcCmdError> {
+    send_api_request(request)
+        .await?
+        .json::<T>()
+        .await
+        .map_err(|e| {
+            error!("Error parsing response: {}", e);
+            DcCmdError::Unknown
+        })
+}
+
+fn to_api_url(base_url: &str, path: &str) -> String {
+    format!(
+        "{}/api/v4/{}",
+        base_url.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_api_url() {
+        assert_eq!(
+            "https://dracoon.team/api/v4/nodes/1/deleted_nodes",
+            to_api_url("https://dracoon.team/", "nodes/1/deleted_nodes")
+        );
+    }
+
+    #[test]
+    fn test_to_api_url_without_slashes() {
+        assert_eq!(
+            "https://dracoon.team/api/v4/nodes/deleted_nodes",
+            to_api_url("https://dracoon.team", "/nodes/deleted_nodes")
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use console::Term;
+use tracing::{debug, warn};
+
+use crate::cmd::models::DcCmdError;
+
+use super::{progress::write_line, strings::format_error_message};
+
+/// exit code if a command is cancelled via Ctrl+C (128 + SIGINT)
+pub const EXIT_CODE_CANCELLED: i32 = 130;
+
+static INCOMPLETE_FILES: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+
+fn incomplete_files() -> &'static Mutex<HashSet<PathBuf>> {
+    INCOMPLETE_FILES.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// registers a file being written - it is removed if the command is cancelled
+pub fn track_incomplete_file(path: impl Into<PathBuf>) {
+    incomplete_files()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .insert(path.into());
+}
+
+/// marks a tracked file as complete (kept on cancellation)
+pub fn mark_file_complete(path: &Path) {
+    incomplete_files()
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .remove(path);
+}
+
+/// runs a (transfer) command until completion or Ctrl+C
+///
+/// on cancellation, running tasks are stopped, incomplete files are removed and
+/// the process exits with `EXIT_CODE_CANCELLED`
+/// (journal entries and transfer checkpoints are written immediately and therefore kept)
+pub async fn run_cancellable<F>(term: &Term, command: F) -> Result<(), DcCmdError>
+where
+    F: Future<Output = Result<(), DcCmdError>>,
+{
+    tokio::select! {
+        res = command => return res,
+        _ = tokio::signal::ctrl_c() => {}
+    }
+
+    warn!("Command cancelled.");
+
+    let files = std::mem::take(
+        &mut *incomplete_files()
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner),
+    );
+
+    for file in files {
+        debug!("Removing incomplete file {}", file.display());
+        if let Err(e) = std::fs::remove_file(&file) {
+            warn!("Failed to remove incomplete file {}: {}", file.display(), e);
+        }
+    }
+
+    let _ = write_line(term, &format_error_message("Cancelled."));
+
+    std::process::exit(EXIT_CODE_CANCELLED);
+}
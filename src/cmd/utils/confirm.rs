@@ -0,0 +1,148 @@
+use std::sync::OnceLock;
+
+use console::{style, Term};
+use dialoguer::Confirm;
+use tracing::{info, warn};
+
+use crate::cmd::{
+    branding::instance_prompt,
+    models::DcCmdError,
+    utils::{
+        progress::{suspend, write_line},
+        strings::to_readable_size,
+    },
+};
+
+/// default number of files requiring confirmation of recursive operations
+pub const DEFAULT_CONFIRM_FILES: u64 = 1000;
+
+/// default total size requiring confirmation of recursive operations
+pub const DEFAULT_CONFIRM_SIZE: u64 = 10 * 1024 * 1024 * 1024; // 10 GB
+
+static CONFIRMATION: OnceLock<Confirmation> = OnceLock::new();
+
+/// thresholds of recursive operations requiring confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Confirmation {
+    max_files: u64,
+    max_size: u64,
+    assume_yes: bool,
+}
+
+impl Default for Confirmation {
+    fn default() -> Self {
+        Self {
+            max_files: DEFAULT_CONFIRM_FILES,
+            max_size: DEFAULT_CONFIRM_SIZE,
+            assume_yes: false,
+        }
+    }
+}
+
+impl Confirmation {
+    fn exceeds(&self, files: u64, size: u64) -> bool {
+        files > self.max_files || size > self.max_size
+    }
+}
+
+/// sets the thresholds for confirmation of recursive operations
+/// (can only be set once - without calling this, defaults are used)
+pub fn init_confirmation(max_files: Option<u64>, max_size: Option<u64>, assume_yes: bool) {
+    let confirmation = Confirmation {
+        max_files: max_files.unwrap_or(DEFAULT_CONFIRM_FILES),
+        max_size: max_size.unwrap_or(DEFAULT_CONFIRM_SIZE),
+        assume_yes,
+    };
+
+    let _ = CONFIRMATION.set(confirmation);
+}
+
+fn confirmation() -> Confirmation {
+    CONFIRMATION.get().copied().unwrap_or_default()
+}
+
+/// true if confirmations were answered with yes (`--yes`)
+pub fn assume_yes() -> bool {
+    confirmation().assume_yes
+}
+
+/// asks for confirmation if a recursive operation exceeds the thresholds - `assume_yes`
+/// confirms without prompting (set via the options in library use)
+///
+/// returns false if the operation was not confirmed (summary and prompt are written to stderr)
+pub fn confirm_large_operation(
+    base_url: &str,
+    operation: &str,
+    files: u64,
+    size: u64,
+    assume_yes: bool,
+) -> Result<bool, DcCmdError> {
+    let confirmation = confirmation();
+
+    if !confirmation.exceeds(files, size) {
+        return Ok(true);
+    }
+
+    let summary = format_summary(operation, files, size);
+    warn!("{}", summary);
+
+    if confirmation.assume_yes || assume_yes {
+        info!("Large operation confirmed (--yes).");
+        return Ok(true);
+    }
+
+    write_line(
+        &Term::stderr(),
+        &format!("{} {summary}", style("Warning:").yellow().bold()),
+    )?;
+
+    // active progress bars would overwrite the prompt
+    suspend(|| {
+        Confirm::new()
+            .with_prompt(instance_prompt(base_url, "Do you want to continue?"))
+            .interact()
+    })
+    .map_err(|_| DcCmdError::IoError)
+}
+
+fn format_summary(operation: &str, files: u64, size: u64) -> String {
+    format!(
+        "{operation} affects {files} files ({}).",
+        to_readable_size(size)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exceeds_files() {
+        let confirmation = Confirmation {
+            max_files: 10,
+            ..Confirmation::default()
+        };
+
+        assert!(!confirmation.exceeds(10, 0));
+        assert!(confirmation.exceeds(11, 0));
+    }
+
+    #[test]
+    fn test_exceeds_size() {
+        let confirmation = Confirmation {
+            max_size: 1024,
+            ..Confirmation::default()
+        };
+
+        assert!(!confirmation.exceeds(1, 1024));
+        assert!(confirmation.exceeds(1, 1025));
+    }
+
+    #[test]
+    fn test_format_summary() {
+        assert_eq!(
+            "Download affects 1200 files (0 B).",
+            format_summary("Download", 1200, 0)
+        );
+    }
+}
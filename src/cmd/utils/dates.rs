@@ -1,7 +1,355 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, Utc, Weekday};
+
+use crate::cmd::models::DcCmdError;
 
 pub fn to_datetime_utc(time: SystemTime) -> DateTime<Utc> {
     DateTime::from(time)
 }
+
+/// parses a date (format: yyyy-mm-dd) to midnight UTC
+pub fn parse_date(value: &str) -> Result<DateTime<Utc>, DcCmdError> {
+    let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|e| {
+        DcCmdError::InvalidArgument(format!("Invalid date: {value} (expected yyyy-mm-dd): {e}"))
+    })?;
+
+    let naive_datetime = date.and_time(NaiveTime::MIN);
+
+    Ok(DateTime::<Utc>::from_naive_utc_and_offset(
+        naive_datetime,
+        Utc,
+    ))
+}
+
+/// accepted formats of points in time (used in error messages)
+const DATE_FORMATS: &str =
+    "RFC3339, yyyy-mm-dd, yyyy-Www, today, yesterday, tomorrow or relative e.g. -7d, +30d";
+
+/// resolves dates (days, weeks) to their first or last second (e.g. `--start-date` / `--end-date`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateBound {
+    Start,
+    End,
+}
+
+/// parses a point in time: RFC3339, yyyy-mm-dd, ISO week (`2024-W05`), `today`, `yesterday`,
+/// `tomorrow` or relative to now (`-7d`, `+30d`)
+pub fn parse_point_in_time(value: &str, bound: DateBound) -> Result<DateTime<Utc>, DcCmdError> {
+    parse_point_in_time_from(value, bound, Utc::now())
+}
+
+fn parse_point_in_time_from(
+    value: &str,
+    bound: DateBound,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, DcCmdError> {
+    let value = value.trim();
+    let invalid = || {
+        DcCmdError::InvalidArgument(format!("Invalid date: {value} (expected {DATE_FORMATS})"))
+    };
+
+    if let Some(offset) = value.strip_prefix('+') {
+        return to_chrono_duration(offset)
+            .and_then(|offset| now.checked_add_signed(offset))
+            .ok_or_else(invalid);
+    }
+
+    if let Some(offset) = value.strip_prefix('-') {
+        return to_chrono_duration(offset)
+            .and_then(|offset| now.checked_sub_signed(offset))
+            .ok_or_else(invalid);
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.with_timezone(&Utc));
+    }
+
+    let today = now.date_naive();
+
+    let (first_day, last_day) = match value.to_ascii_lowercase().as_str() {
+        "today" => (today, today),
+        "yesterday" => {
+            let yesterday = today.checked_sub_days(Days::new(1)).ok_or_else(invalid)?;
+            (yesterday, yesterday)
+        }
+        "tomorrow" => {
+            let tomorrow = today.checked_add_days(Days::new(1)).ok_or_else(invalid)?;
+            (tomorrow, tomorrow)
+        }
+        value if value.contains("-w") => parse_iso_week(value).ok_or_else(invalid)?,
+        value => {
+            let date = parse_date(value).map_err(|_| invalid())?.date_naive();
+            (date, date)
+        }
+    };
+
+    let datetime = match bound {
+        DateBound::Start => first_day.and_time(NaiveTime::MIN),
+        DateBound::End => last_day.and_hms_opt(23, 59, 59).ok_or_else(invalid)?,
+    };
+
+    Ok(DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+}
+
+/// first and last day of an ISO week (e.g. `2024-w05`)
+fn parse_iso_week(value: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let (year, week) = value.split_once("-w")?;
+    let year = year.parse::<i32>().ok()?;
+    let week = week.parse::<u32>().ok()?;
+
+    let first_day = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+    let last_day = NaiveDate::from_isoywd_opt(year, week, Weekday::Sun)?;
+
+    Some((first_day, last_day))
+}
+
+fn to_chrono_duration(value: &str) -> Option<chrono::Duration> {
+    parse_duration(value)
+        .ok()
+        .and_then(|duration| chrono::Duration::from_std(duration).ok())
+}
+
+/// parses an expiration date (see `parse_point_in_time` - relative values like `+30d`)
+pub fn parse_expiration(value: &str) -> Result<DateTime<Utc>, DcCmdError> {
+    parse_expiration_from(value, Utc::now())
+}
+
+fn parse_expiration_from(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, DcCmdError> {
+    let expire_at = parse_point_in_time_from(value, DateBound::Start, now).map_err(|_| {
+        DcCmdError::InvalidArgument(format!(
+            "Invalid expiration: {value} (expected {DATE_FORMATS})"
+        ))
+    })?;
+
+    if expire_at <= now {
+        return Err(DcCmdError::InvalidArgument(format!(
+            "Expiration must be in the future: {value}"
+        )));
+    }
+
+    Ok(expire_at)
+}
+
+/// parses a point in time in the past (age like `90d` or see `parse_point_in_time`)
+pub fn parse_cutoff(value: &str) -> Result<DateTime<Utc>, DcCmdError> {
+    parse_cutoff_from(value, Utc::now())
+}
+
+fn parse_cutoff_from(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, DcCmdError> {
+    // plain ages (e.g. 90d) point to the past
+    if let Some(age) = to_chrono_duration(value.trim()) {
+        return now
+            .checked_sub_signed(age)
+            .ok_or_else(|| DcCmdError::InvalidArgument(format!("Invalid age: {value} (too old)")));
+    }
+
+    parse_point_in_time_from(value, DateBound::Start, now).map_err(|_| {
+        DcCmdError::InvalidArgument(format!(
+            "Invalid age: {value} (expected e.g. 90d or {DATE_FORMATS})"
+        ))
+    })
+}
+
+/// parses a duration like `30s`, `5m`, `1h` or `2d` (plain numbers are seconds)
+pub fn parse_duration(value: &str) -> Result<Duration, DcCmdError> {
+    let value = value.trim();
+    let invalid = || {
+        DcCmdError::InvalidArgument(format!(
+            "Invalid duration: {value} (expected e.g. 30s, 5m, 1h, 2d)"
+        ))
+    };
+
+    let (amount, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => value.split_at(idx),
+        None => (value, "s"),
+    };
+
+    let amount = amount.parse::<u64>().map_err(|_| invalid())?;
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(invalid()),
+    };
+
+    // large amounts would overflow
+    let seconds = amount.checked_mul(multiplier).ok_or_else(invalid)?;
+
+    if seconds == 0 {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let date = parse_date("2025-12-31").unwrap();
+        assert_eq!("2025-12-31T00:00:00+00:00", date.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_date_invalid() {
+        assert!(parse_date("31.12.2025").is_err());
+        assert!(parse_date("2025-13-01").is_err());
+    }
+
+    fn now() -> DateTime<Utc> {
+        parse_date("2025-01-01").unwrap()
+    }
+
+    #[test]
+    fn test_parse_expiration_relative() {
+        let expire_at = parse_expiration_from("+30d", now()).unwrap();
+        assert_eq!("2025-01-31T00:00:00+00:00", expire_at.to_rfc3339());
+
+        let expire_at = parse_expiration_from("+12h", now()).unwrap();
+        assert_eq!("2025-01-01T12:00:00+00:00", expire_at.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_expiration_rfc3339() {
+        let expire_at = parse_expiration_from("2025-06-30T12:00:00+02:00", now()).unwrap();
+        assert_eq!("2025-06-30T10:00:00+00:00", expire_at.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_expiration_date() {
+        let expire_at = parse_expiration_from("2025-12-31", now()).unwrap();
+        assert_eq!("2025-12-31T00:00:00+00:00", expire_at.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_expiration_invalid() {
+        assert!(parse_expiration_from("+30x", now()).is_err());
+        assert!(parse_expiration_from("+", now()).is_err());
+        assert!(parse_expiration_from("next week", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_expiration_in_past() {
+        assert!(parse_expiration_from("2024-12-31", now()).is_err());
+        assert!(parse_expiration_from("2025-01-01", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_cutoff_age() {
+        let cutoff = parse_cutoff_from("90d", now()).unwrap();
+        assert_eq!("2024-10-03T00:00:00+00:00", cutoff.to_rfc3339());
+
+        let cutoff = parse_cutoff_from("6h", now()).unwrap();
+        assert_eq!("2024-12-31T18:00:00+00:00", cutoff.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_cutoff_date() {
+        let cutoff = parse_cutoff_from("2024-06-30", now()).unwrap();
+        assert_eq!("2024-06-30T00:00:00+00:00", cutoff.to_rfc3339());
+
+        let cutoff = parse_cutoff_from("2024-06-30T12:00:00+02:00", now()).unwrap();
+        assert_eq!("2024-06-30T10:00:00+00:00", cutoff.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_cutoff_invalid() {
+        assert!(parse_cutoff_from("90x", now()).is_err());
+        assert!(parse_cutoff_from("last year", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_point_in_time_keywords() {
+        let date = parse_point_in_time_from("yesterday", DateBound::Start, now()).unwrap();
+        assert_eq!("2024-12-31T00:00:00+00:00", date.to_rfc3339());
+
+        let date = parse_point_in_time_from("Today", DateBound::End, now()).unwrap();
+        assert_eq!("2025-01-01T23:59:59+00:00", date.to_rfc3339());
+
+        let date = parse_point_in_time_from("tomorrow", DateBound::Start, now()).unwrap();
+        assert_eq!("2025-01-02T00:00:00+00:00", date.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_point_in_time_relative() {
+        let date = parse_point_in_time_from("-7d", DateBound::Start, now()).unwrap();
+        assert_eq!("2024-12-25T00:00:00+00:00", date.to_rfc3339());
+
+        let date = parse_point_in_time_from("+12h", DateBound::End, now()).unwrap();
+        assert_eq!("2025-01-01T12:00:00+00:00", date.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_point_in_time_iso_week() {
+        let date = parse_point_in_time_from("2024-W05", DateBound::Start, now()).unwrap();
+        assert_eq!("2024-01-29T00:00:00+00:00", date.to_rfc3339());
+
+        let date = parse_point_in_time_from("2024-W05", DateBound::End, now()).unwrap();
+        assert_eq!("2024-02-04T23:59:59+00:00", date.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_point_in_time_date_bounds() {
+        let date = parse_point_in_time_from("2024-06-30", DateBound::End, now()).unwrap();
+        assert_eq!("2024-06-30T23:59:59+00:00", date.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_point_in_time_invalid() {
+        assert!(parse_point_in_time_from("2024-W54", DateBound::Start, now()).is_err());
+        assert!(parse_point_in_time_from("-7x", DateBound::Start, now()).is_err());
+        assert!(parse_point_in_time_from("last week", DateBound::Start, now()).is_err());
+
+        let err = parse_point_in_time_from("31.12.2024", DateBound::Start, now()).unwrap_err();
+        assert_eq!(
+            err,
+            DcCmdError::InvalidArgument(format!(
+                "Invalid date: 31.12.2024 (expected {DATE_FORMATS})"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_cutoff_keywords() {
+        let cutoff = parse_cutoff_from("yesterday", now()).unwrap();
+        assert_eq!("2024-12-31T00:00:00+00:00", cutoff.to_rfc3339());
+
+        let cutoff = parse_cutoff_from("-7d", now()).unwrap();
+        assert_eq!("2024-12-25T00:00:00+00:00", cutoff.to_rfc3339());
+    }
+
+    #[test]
+    fn test_parse_expiration_keywords() {
+        let expire_at = parse_expiration_from("tomorrow", now()).unwrap();
+        assert_eq!("2025-01-02T00:00:00+00:00", expire_at.to_rfc3339());
+
+        assert!(parse_expiration_from("yesterday", now()).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_seconds() {
+        assert_eq!(Duration::from_secs(30), parse_duration("30s").unwrap());
+        assert_eq!(Duration::from_secs(45), parse_duration("45").unwrap());
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(Duration::from_secs(300), parse_duration("5m").unwrap());
+        assert_eq!(Duration::from_secs(7200), parse_duration("2h").unwrap());
+        assert_eq!(Duration::from_secs(86400), parse_duration("1d").unwrap());
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration(&format!("{}d", u64::MAX)).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+use crate::cmd::models::DcCmdError;
+
+/// exit code if a command exceeds its deadline (like `timeout`)
+pub const EXIT_CODE_DEADLINE: i32 = 124;
+
+static DEADLINE: OnceLock<Instant> = OnceLock::new();
+static SKIPPED: AtomicU64 = AtomicU64::new(0);
+
+/// sets the deadline of the command (from now)
+pub fn init_deadline(duration: Duration) {
+    info!("Deadline set to {} seconds.", duration.as_secs());
+    let _ = DEADLINE.set(Instant::now() + duration);
+}
+
+/// time left until the deadline (None without deadline)
+pub fn remaining() -> Option<Duration> {
+    DEADLINE
+        .get()
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+pub fn is_exceeded() -> bool {
+    remaining().is_some_and(|remaining| remaining.is_zero())
+}
+
+/// fails if the deadline is exceeded - called before starting a new item (file, user),
+/// running items are finished
+pub fn check_deadline() -> Result<(), DcCmdError> {
+    if !is_exceeded() {
+        return Ok(());
+    }
+
+    SKIPPED.fetch_add(1, Ordering::Relaxed);
+
+    Err(DcCmdError::DeadlineExceeded(
+        "Deadline exceeded (not started).".to_string(),
+    ))
+}
+
+/// waits until the deadline (forever without deadline)
+pub async fn wait_for_deadline() {
+    match remaining() {
+        Some(remaining) => tokio::time::sleep(remaining).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// fails with `DeadlineExceeded` if items were skipped due to the deadline
+/// (the command result is logged - skipped items are part of the transfer errors)
+pub fn finish_with_deadline(res: Result<(), DcCmdError>) -> Result<(), DcCmdError> {
+    let skipped = SKIPPED.load(Ordering::Relaxed);
+
+    if skipped == 0 {
+        return res;
+    }
+
+    if let Err(e) = res {
+        warn!("Command incomplete: {}", e);
+    }
+
+    Err(DcCmdError::DeadlineExceeded(format!(
+        "Deadline exceeded - {skipped} item(s) not started."
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_without_skipped_items() {
+        assert_eq!(finish_with_deadline(Ok(())), Ok(()));
+        assert_eq!(
+            finish_with_deadline(Err(DcCmdError::IoError)),
+            Err(DcCmdError::IoError)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::sync::OnceLock;
+
+use console::Term;
+use tracing::warn;
+
+use crate::cmd::models::DcCmdError;
+
+static CSV_FIELDS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// sets the columns of CSV outputs (can only be set once - without calling this, all columns are printed)
+pub fn init_csv_fields(fields: &str) {
+    let _ = CSV_FIELDS.set(parse_fields(fields));
+}
+
+fn parse_fields(fields: &str) -> Vec<String> {
+    fields
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// writes CSV lines to the terminal - only columns selected via `--fields` are written
+pub struct CsvOutput<'a> {
+    term: &'a Term,
+    header_len: usize,
+    columns: Option<Vec<usize>>,
+}
+
+impl<'a> CsvOutput<'a> {
+    /// writes the header (selected columns only) - fails if a selected field is unknown
+    pub fn new(term: &'a Term, header: &str) -> Result<Self, DcCmdError> {
+        let output = Self::without_header(term, header)?;
+        output.write_line(header)?;
+
+        Ok(output)
+    }
+
+    /// for lines continuing a previous output (e.g. further pages or follow mode)
+    pub fn without_header(term: &'a Term, header: &str) -> Result<Self, DcCmdError> {
+        let columns = CSV_FIELDS
+            .get()
+            .map(|fields| select_columns(header, fields))
+            .transpose()?;
+
+        Ok(Self {
+            term,
+            header_len: header.split(',').count(),
+            columns,
+        })
+    }
+
+    pub fn write_line(&self, line: &str) -> Result<(), DcCmdError> {
+        let line = match &self.columns {
+            Some(columns) => project_line(line, self.header_len, columns),
+            None => line.to_string(),
+        };
+
+        self.term.write_line(&line).map_err(|_| DcCmdError::IoError)
+    }
+}
+
+/// returns the indices of the selected fields in the header
+fn select_columns(header: &str, fields: &[String]) -> Result<Vec<usize>, DcCmdError> {
+    let columns = header.split(',').collect::<Vec<_>>();
+
+    fields
+        .iter()
+        .map(|field| {
+            columns
+                .iter()
+                .position(|column| column == field)
+                .ok_or_else(|| {
+                    DcCmdError::InvalidArgument(format!(
+                        "Unknown field: {field} (available: {header})"
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// selects columns of a CSV line - lines not matching the header are kept as they are
+fn project_line(line: &str, header_len: usize, columns: &[usize]) -> String {
+    let record = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(line.as_bytes())
+        .records()
+        .next()
+        .and_then(Result::ok);
+
+    let Some(record) = record.filter(|record| record.len() == header_len) else {
+        warn!(
+            "CSV line does not match header - printing all fields: {}",
+            line
+        );
+        return line.to_string();
+    };
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let projected = columns
+        .iter()
+        .map(|idx| record.get(*idx).unwrap_or_default());
+
+    if writer.write_record(projected).is_err() {
+        return line.to_string();
+    }
+
+    writer
+        .into_inner()
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .map_or_else(|| line.to_string(), |line| line.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(vec!["id", "user_name"], parse_fields(" id, user_name ,"));
+    }
+
+    #[test]
+    fn test_select_columns() {
+        let fields = parse_fields("email,id");
+
+        assert_eq!(
+            vec![2, 0],
+            select_columns("id,user_name,email", &fields).unwrap()
+        );
+        assert!(select_columns("id,user_name", &fields).is_err());
+    }
+
+    #[test]
+    fn test_project_line() {
+        assert_eq!(
+            "foo@bar.com,1",
+            project_line("1,foo,foo@bar.com", 3, &[2, 0])
+        );
+        assert_eq!(
+            "\"Finance, Legal\",7",
+            project_line("7,\"Finance, Legal\",/", 3, &[1, 0])
+        );
+        // unquoted separators in values - line is kept
+        assert_eq!(
+            "7,Finance, Legal,/",
+            project_line("7,Finance, Legal,/", 3, &[1])
+        );
+    }
+}
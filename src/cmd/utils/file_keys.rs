@@ -0,0 +1,118 @@
+use std::future::Future;
+
+use tracing::warn;
+
+use crate::cmd::models::DcCmdError;
+
+/// maximum number of file keys distributed per request (set by DRACOON)
+pub const MISSING_KEYS_BATCH_SIZE: u64 = 100;
+
+/// result of distributing missing file keys
+#[derive(Debug, Default, PartialEq)]
+pub struct KeyDistribution {
+    pub batches: u64,
+    pub distributed: u64,
+    pub remaining: u64,
+}
+
+/// calls `distribute` until all missing keys are distributed
+///
+/// each call distributes up to `MISSING_KEYS_BATCH_SIZE` keys and returns the total of missing
+/// keys before the call - stops if the total does not decrease (e.g. keys rejected)
+pub async fn distribute_missing_keys_batched<F, Fut>(
+    distribute: F,
+) -> Result<KeyDistribution, DcCmdError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<u64, DcCmdError>>,
+{
+    let mut distribution = KeyDistribution::default();
+    let mut initial_total = None;
+    let mut previous_total = u64::MAX;
+
+    loop {
+        let total = distribute().await?;
+        distribution.batches += 1;
+        let initial_total = *initial_total.get_or_insert(total);
+
+        if total <= MISSING_KEYS_BATCH_SIZE {
+            distribution.distributed = initial_total;
+            return Ok(distribution);
+        }
+
+        if total >= previous_total {
+            warn!("{} file keys could not be distributed.", total);
+            distribution.distributed = initial_total - total;
+            distribution.remaining = total;
+            return Ok(distribution);
+        }
+
+        previous_total = total;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// returns the given totals of missing keys (one per call)
+    fn missing_keys(totals: Vec<u64>) -> impl Fn() -> std::future::Ready<Result<u64, DcCmdError>> {
+        let totals = Mutex::new(totals.into_iter());
+        move || std::future::ready(Ok(totals.lock().unwrap().next().unwrap_or(0)))
+    }
+
+    #[tokio::test]
+    async fn test_distribute_missing_keys_single_batch() {
+        let distribution = distribute_missing_keys_batched(missing_keys(vec![3]))
+            .await
+            .unwrap();
+        assert_eq!(
+            distribution,
+            KeyDistribution {
+                batches: 1,
+                distributed: 3,
+                remaining: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_distribute_missing_keys_multiple_batches() {
+        let distribution = distribute_missing_keys_batched(missing_keys(vec![250, 150, 50]))
+            .await
+            .unwrap();
+        assert_eq!(
+            distribution,
+            KeyDistribution {
+                batches: 3,
+                distributed: 250,
+                remaining: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_distribute_missing_keys_stops_without_progress() {
+        let distribution = distribute_missing_keys_batched(missing_keys(vec![250, 150, 150]))
+            .await
+            .unwrap();
+        assert_eq!(
+            distribution,
+            KeyDistribution {
+                batches: 3,
+                distributed: 100,
+                remaining: 150
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_distribute_missing_keys_error() {
+        let res =
+            distribute_missing_keys_batched(|| std::future::ready(Err::<u64, _>(DcCmdError::Unknown)))
+                .await;
+        assert!(res.is_err());
+    }
+}
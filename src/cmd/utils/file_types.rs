@@ -0,0 +1,172 @@
+/// file extensions per media category (usable as file type, e.g. `--file-type image`)
+const CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &["jpg", "jpeg", "png", "gif", "bmp", "tif", "tiff", "webp", "heic", "svg"],
+    ),
+    (
+        "video",
+        &["mp4", "m4v", "mov", "avi", "mkv", "webm", "wmv", "mpg", "mpeg"],
+    ),
+    ("audio", &["mp3", "wav", "flac", "aac", "m4a", "ogg", "wma"]),
+    (
+        "document",
+        &[
+            "pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "odp", "txt", "rtf",
+            "csv",
+        ],
+    ),
+];
+
+/// MIME types by file extension
+const MIME_TYPES: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("bmp", "image/bmp"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("webp", "image/webp"),
+    ("heic", "image/heic"),
+    ("svg", "image/svg+xml"),
+    ("mp4", "video/mp4"),
+    ("m4v", "video/x-m4v"),
+    ("mov", "video/quicktime"),
+    ("avi", "video/x-msvideo"),
+    ("mkv", "video/x-matroska"),
+    ("webm", "video/webm"),
+    ("mp3", "audio/mpeg"),
+    ("wav", "audio/wav"),
+    ("flac", "audio/flac"),
+    ("m4a", "audio/mp4"),
+    ("ogg", "audio/ogg"),
+    ("pdf", "application/pdf"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("xls", "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("ppt", "application/vnd.ms-powerpoint"),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("txt", "text/plain"),
+    ("csv", "text/csv"),
+    ("json", "application/json"),
+    ("xml", "application/xml"),
+    ("zip", "application/zip"),
+    ("gz", "application/gzip"),
+];
+
+/// normalizes file types (lowercase extensions without dot) - categories are expanded
+pub fn parse_file_types(file_types: &[String]) -> Vec<String> {
+    let mut extensions = Vec::new();
+
+    for file_type in file_types {
+        let file_type = file_type.trim().trim_start_matches('.').to_lowercase();
+
+        if file_type.is_empty() {
+            continue;
+        }
+
+        let expanded = match CATEGORIES.iter().find(|(category, _)| *category == file_type) {
+            Some((_, category_extensions)) => {
+                category_extensions.iter().map(ToString::to_string).collect()
+            }
+            None => vec![file_type],
+        };
+
+        for extension in expanded {
+            if !extensions.contains(&extension) {
+                extensions.push(extension);
+            }
+        }
+    }
+
+    extensions
+}
+
+/// returns the (lowercase) extension of a file name
+pub fn file_extension(name: &str) -> Option<String> {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() && !extension.is_empty() => {
+            Some(extension.to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+/// checks if a file name has one of the given file types (all names match without types)
+pub fn matches_file_type(name: &str, file_types: &[String]) -> bool {
+    file_types.is_empty()
+        || file_extension(name).is_some_and(|extension| file_types.contains(&extension))
+}
+
+/// detects the MIME type of a file by its extension
+pub fn detect_mime_type(name: &str) -> Option<&'static str> {
+    let extension = file_extension(name)?;
+
+    MIME_TYPES
+        .iter()
+        .find(|(mime_extension, _)| *mime_extension == extension)
+        .map(|(_, mime_type)| *mime_type)
+}
+
+/// server-side search filter for a single file type (the API only supports one value)
+pub fn file_type_filter(file_types: &[String]) -> Option<String> {
+    match file_types {
+        [file_type] => Some(format!("fileType:eq:{file_type}")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_file_types() {
+        let file_types = parse_file_types(&["PDF".into(), ".docx".into(), " ".into()]);
+        assert_eq!(file_types, vec!["pdf", "docx"]);
+
+        let file_types = parse_file_types(&["audio".into()]);
+        assert!(file_types.contains(&"mp3".to_string()));
+        assert!(!file_types.contains(&"audio".to_string()));
+    }
+
+    #[test]
+    fn test_matches_file_type() {
+        let file_types = vec!["jpg".to_string(), "png".to_string()];
+
+        assert!(matches_file_type("photo.JPG", &file_types));
+        assert!(matches_file_type("some.archive.png", &file_types));
+        assert!(!matches_file_type("report.pdf", &file_types));
+        assert!(!matches_file_type("jpg", &file_types));
+        assert!(matches_file_type("report.pdf", &[]));
+    }
+
+    #[test]
+    fn test_detect_mime_type() {
+        assert_eq!(detect_mime_type("report.PDF"), Some("application/pdf"));
+        assert_eq!(detect_mime_type("clip.mov"), Some("video/quicktime"));
+        assert_eq!(detect_mime_type("unknown.xyz"), None);
+        assert_eq!(detect_mime_type(".env"), None);
+    }
+
+    #[test]
+    fn test_file_type_filter() {
+        assert_eq!(
+            file_type_filter(&["pdf".to_string()]),
+            Some("fileType:eq:pdf".to_string())
+        );
+        assert_eq!(file_type_filter(&[]), None);
+        assert_eq!(file_type_filter(&parse_file_types(&["image".into()])), None);
+    }
+}
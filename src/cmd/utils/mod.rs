@@ -1,2 +1,15 @@
+pub mod api;
+pub mod cancel;
+pub mod confirm;
 pub mod dates;
+pub mod deadline;
+pub mod fields;
+pub mod file_keys;
+pub mod file_types;
+pub mod pager;
+pub mod pagination;
+pub mod progress;
+pub mod rate_limit;
+pub mod remote_path;
+pub mod stats;
 pub mod strings;
@@ -0,0 +1,97 @@
+use console::{style, Key, Term};
+
+use crate::cmd::models::DcCmdError;
+
+/// writes lines to the terminal and pauses after each screen (interactive terminals only)
+pub struct Pager<'t> {
+    term: &'t Term,
+    page_size: Option<usize>,
+    lines: usize,
+    quit: bool,
+}
+
+impl<'t> Pager<'t> {
+    /// paging is disabled if not enabled or if the output is not a terminal (e.g. piped)
+    pub fn new(term: &'t Term, enabled: bool) -> Self {
+        let page_size = if enabled && term.is_term() {
+            // keep one line for the prompt
+            page_size(term.size().0)
+        } else {
+            None
+        };
+
+        Self {
+            term,
+            page_size,
+            lines: 0,
+            quit: false,
+        }
+    }
+
+    /// writes a line (ignored after the user quit paging)
+    pub fn write_line(&mut self, line: &str) -> Result<(), DcCmdError> {
+        if self.quit {
+            return Ok(());
+        }
+
+        if let Some(page_size) = self.page_size {
+            if self.lines > 0 && self.lines.is_multiple_of(page_size) && !self.prompt()? {
+                self.quit = true;
+                return Ok(());
+            }
+        }
+
+        self.term
+            .write_line(line)
+            .map_err(|_| DcCmdError::IoError)?;
+        self.lines += 1;
+
+        Ok(())
+    }
+
+    /// true if the user quit paging (remaining lines are not printed)
+    pub fn is_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// waits for a key press - returns false if the user quits (q / Esc)
+    fn prompt(&self) -> Result<bool, DcCmdError> {
+        let prompt = style("-- more -- (any key to continue, q to quit)").dim();
+        self.term
+            .write_str(&prompt.to_string())
+            .map_err(|_| DcCmdError::IoError)?;
+
+        let key = self.term.read_key().map_err(|_| DcCmdError::IoError)?;
+
+        self.term
+            .clear_line()
+            .map_err(|_| DcCmdError::IoError)?;
+
+        Ok(!matches!(key, Key::Char('q' | 'Q') | Key::Escape))
+    }
+}
+
+/// lines per page for a terminal height (None if too small to page)
+fn page_size(rows: u16) -> Option<usize> {
+    match usize::from(rows).saturating_sub(1) {
+        0 => None,
+        size => Some(size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_size() {
+        assert_eq!(page_size(25), Some(24));
+        assert_eq!(page_size(2), Some(1));
+    }
+
+    #[test]
+    fn test_page_size_too_small() {
+        assert_eq!(page_size(1), None);
+        assert_eq!(page_size(0), None);
+    }
+}
@@ -0,0 +1,185 @@
+use std::{future::Future, sync::Arc};
+
+use dco3::RangedItems;
+use futures_util::{stream, StreamExt};
+use tokio::sync::Semaphore;
+use tracing::{error, warn};
+
+use crate::cmd::{config::MAX_CONCURRENT_REQUESTS, models::DcCmdError};
+
+use super::rate_limit::{retry_delay, with_rate_limit};
+
+/// default (and maximum) page size of the DRACOON API
+pub const PAGE_SIZE: u64 = 500;
+
+/// maximum number of retries of a page failing with a server error (5xx) or connection error
+pub const MAX_PAGE_RETRIES: u32 = 3;
+
+/// fetches all remaining pages of a list concurrently and appends them to the first page
+///
+/// `fetch_page` receives the offset of the page to fetch - the first page is
+/// expected to be fetched by the caller (with custom offset / limit)
+/// pages are fetched respecting the global rate limit (see `with_rate_limit`) and
+/// retried on server errors (see `fetch_page_with_retry`)
+pub async fn fetch_all_paginated<T, F, Fut>(
+    first_page: RangedItems<T>,
+    fetch_page: F,
+) -> Result<RangedItems<T>, DcCmdError>
+where
+    T: Send + 'static,
+    F: Fn(u64) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<RangedItems<T>, DcCmdError>> + Send + 'static,
+{
+    let mut results = first_page;
+    let total = results.range.total;
+
+    if total <= PAGE_SIZE {
+        return Ok(results);
+    }
+
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let fetch_page = Arc::new(fetch_page);
+    let mut handles = Vec::new();
+
+    for offset in (PAGE_SIZE..total).step_by(PAGE_SIZE as usize) {
+        let semaphore = semaphore.clone();
+        let fetch_page = fetch_page.clone();
+
+        let handle = tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.map_err(|_| {
+                error!("Failed to acquire semaphore permit.");
+                DcCmdError::IoError
+            })?;
+
+            fetch_page_with_retry(&*fetch_page, offset).await
+        });
+
+        handles.push(handle);
+    }
+
+    let mut totals_changed = false;
+
+    // join in order to keep the API sort order
+    for handle in handles {
+        let page = handle.await.map_err(|e| {
+            error!("Failed to join task: {}", e);
+            DcCmdError::IoError
+        })??;
+
+        totals_changed |= page.range.total != total;
+        results.items.extend(page.items);
+    }
+
+    check_consistency(total, results.items.len() as u64, totals_changed);
+
+    Ok(results)
+}
+
+/// fetches all remaining pages of a list and passes each page (in API order) to `on_page`
+///
+/// in contrast to `fetch_all_paginated`, pages are not accumulated - at most
+/// `MAX_CONCURRENT_REQUESTS` pages are held in memory at once
+pub async fn for_each_page<T, F, Fut, P>(
+    first_page: RangedItems<T>,
+    fetch_page: F,
+    mut on_page: P,
+) -> Result<(), DcCmdError>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<RangedItems<T>, DcCmdError>>,
+    P: FnMut(RangedItems<T>) -> Result<(), DcCmdError>,
+{
+    let total = first_page.range.total;
+    let mut count = first_page.items.len() as u64;
+    let mut totals_changed = false;
+
+    on_page(first_page)?;
+
+    let fetch_page = &fetch_page;
+    let mut pages = stream::iter((PAGE_SIZE..total).step_by(PAGE_SIZE as usize))
+        .map(|offset| fetch_page_with_retry(fetch_page, offset))
+        .buffered(MAX_CONCURRENT_REQUESTS);
+
+    while let Some(page) = pages.next().await {
+        let page = page?;
+        count += page.items.len() as u64;
+        totals_changed |= page.range.total != total;
+        on_page(page)?;
+    }
+
+    if total > PAGE_SIZE {
+        check_consistency(total, count, totals_changed);
+    }
+
+    Ok(())
+}
+
+/// fetches a single page respecting the global rate limit - pages failing with
+/// a server error (5xx) or a connection error are retried with exponential backoff
+/// (a single failing page would otherwise fail the whole listing)
+pub async fn fetch_page_with_retry<T, F, Fut>(
+    fetch_page: &F,
+    offset: u64,
+) -> Result<T, DcCmdError>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<T, DcCmdError>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        match with_rate_limit(|| fetch_page(offset)).await {
+            Err(ref err) if is_retryable(err) && attempt < MAX_PAGE_RETRIES => {
+                let delay = retry_delay(attempt);
+                warn!(
+                    "Fetching page at offset {} failed: {} - retrying in {} seconds ({}/{}).",
+                    offset,
+                    err,
+                    delay.as_secs(),
+                    attempt + 1,
+                    MAX_PAGE_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+fn is_retryable(err: &DcCmdError) -> bool {
+    match err {
+        DcCmdError::DracoonError(err) => err.is_server_error(),
+        DcCmdError::ConnectionFailed => true,
+        _ => false,
+    }
+}
+
+/// warns if the number of fetched items does not match the total of the first page
+/// (e.g. items were created or deleted during the fetch)
+fn check_consistency(expected: u64, actual: u64, totals_changed: bool) {
+    if totals_changed {
+        warn!("Total item count changed during fetch - listing might be incomplete.");
+    }
+
+    if expected != actual {
+        warn!(
+            "Total item count mismatch - expected: {}, actual: {}, difference: {}",
+            expected,
+            actual,
+            expected.abs_diff(actual)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(is_retryable(&DcCmdError::ConnectionFailed));
+        assert!(!is_retryable(&DcCmdError::IoError));
+        assert!(!is_retryable(&DcCmdError::InvalidArgument("foo".into())));
+    }
+}
@@ -0,0 +1,39 @@
+use std::sync::OnceLock;
+
+use console::Term;
+use indicatif::{MultiProgress, ProgressBar};
+
+use crate::cmd::models::DcCmdError;
+
+/// all progress bars are drawn by a single multi progress - concurrent bars (e.g. folder
+/// creation and file uploads) and terminal output would otherwise overwrite each other
+static PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
+
+fn multi_progress() -> &'static MultiProgress {
+    PROGRESS.get_or_init(MultiProgress::new)
+}
+
+/// creates a progress bar (drawn below all active progress bars)
+pub fn progress_bar(len: u64) -> ProgressBar {
+    multi_progress().add(ProgressBar::new(len))
+}
+
+/// creates a spinner (drawn below all active progress bars)
+pub fn spinner() -> ProgressBar {
+    multi_progress().add(ProgressBar::new_spinner())
+}
+
+/// creates a line drawn directly below a progress bar (e.g. stats)
+pub fn progress_line_after(progress_bar: &ProgressBar) -> ProgressBar {
+    multi_progress().insert_after(progress_bar, ProgressBar::new_spinner())
+}
+
+/// hides all active progress bars while running `f` (e.g. prompts) and redraws them
+pub fn suspend<F: FnOnce() -> R, R>(f: F) -> R {
+    multi_progress().suspend(f)
+}
+
+/// writes a line to the terminal without corrupting active progress bars
+pub fn write_line(term: &Term, line: &str) -> Result<(), DcCmdError> {
+    suspend(|| term.write_line(line)).map_err(|_| DcCmdError::IoError)
+}
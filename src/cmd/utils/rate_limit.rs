@@ -0,0 +1,201 @@
+use std::{
+    future::Future,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::{header, RequestBuilder, Response, StatusCode};
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+use crate::cmd::models::DcCmdError;
+
+/// maximum number of retries of a request rejected with 429 (Too Many Requests)
+pub const MAX_RETRIES: u32 = 5;
+
+/// maximum backoff between two retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+static RATE_LIMITER: OnceLock<RateLimiter> = OnceLock::new();
+
+tokio::task_local! {
+    /// set while a request runs via `with_rate_limit` (raw requests don't wait for another slot)
+    static RATE_LIMITED: ();
+}
+
+/// global client-side rate limiter (fixed minimum interval between requests)
+struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / requests_per_second.max(1),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let wait_until = {
+            let mut next_slot = self
+                .next_slot
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+
+        tokio::time::sleep_until(wait_until).await;
+    }
+}
+
+/// limits all rate limited requests to given number of requests per second
+/// (can only be set once - without calling this, requests are not throttled)
+pub fn init_rate_limit(requests_per_second: u32) {
+    debug!("Limiting requests to {} per second.", requests_per_second);
+    let _ = RATE_LIMITER.set(RateLimiter::new(requests_per_second));
+}
+
+/// sends a request respecting the global rate limit - requests rejected with
+/// 429 (Too Many Requests) are retried with exponential backoff
+pub async fn with_rate_limit<T, F, Fut>(request: F) -> Result<T, DcCmdError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, DcCmdError>>,
+{
+    let mut attempt = 0;
+
+    loop {
+        if let Some(rate_limiter) = RATE_LIMITER.get() {
+            rate_limiter.acquire().await;
+        }
+
+        match RATE_LIMITED.scope((), request()).await {
+            Err(DcCmdError::DracoonError(ref err))
+                if err.is_too_many_requests() && attempt < MAX_RETRIES =>
+            {
+                let delay = retry_delay(attempt);
+                warn!(
+                    "Rate limit exceeded - retrying in {} seconds ({}/{}).",
+                    delay.as_secs(),
+                    attempt + 1,
+                    MAX_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// sends a request not provided by dco3 respecting the global rate limit - requests rejected
+/// with 429 (Too Many Requests) are retried after the delay of the `Retry-After` header
+/// (exponential backoff without header, requests with streamed bodies are not retried)
+pub async fn send_rate_limited(request: RequestBuilder) -> Result<Response, reqwest::Error> {
+    let mut request = request;
+    let mut attempt = 0;
+
+    loop {
+        if RATE_LIMITED.try_with(|()| ()).is_err() {
+            if let Some(rate_limiter) = RATE_LIMITER.get() {
+                rate_limiter.acquire().await;
+            }
+        }
+
+        let retry = request.try_clone();
+        let response = request.send().await?;
+
+        match retry {
+            Some(retry)
+                if response.status() == StatusCode::TOO_MANY_REQUESTS && attempt < MAX_RETRIES =>
+            {
+                let delay = retry_after(&response).unwrap_or_else(|| retry_delay(attempt));
+                warn!(
+                    "Rate limit exceeded - retrying in {} seconds ({}/{}).",
+                    delay.as_secs(),
+                    attempt + 1,
+                    MAX_RETRIES
+                );
+                tokio::time::sleep(delay).await;
+                request = retry;
+                attempt += 1;
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// delay requested via `Retry-After` (seconds or HTTP date - capped at 60 seconds)
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+
+    parse_retry_after(value, Utc::now())
+}
+
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let delay = match value.trim().parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+            (date.with_timezone(&Utc) - now)
+                .to_std()
+                .unwrap_or_default()
+        }
+    };
+
+    Some(delay.min(MAX_RETRY_DELAY))
+}
+
+/// exponential backoff (1, 2, 4 ... seconds - capped at 60 seconds)
+pub fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt)).min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+
+    use super::{parse_retry_after, retry_delay, RateLimiter};
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0), Duration::from_secs(1));
+        assert_eq!(retry_delay(1), Duration::from_secs(2));
+        assert_eq!(retry_delay(4), Duration::from_secs(16));
+        assert_eq!(retry_delay(10), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(parse_retry_after("5", now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Mon, 01 Jan 2024 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        // dates in the past and long delays
+        assert_eq!(
+            parse_retry_after("Mon, 01 Jan 2024 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            parse_retry_after("3600", now),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_rate_limiter_interval() {
+        assert_eq!(RateLimiter::new(10).interval, Duration::from_millis(100));
+        assert_eq!(RateLimiter::new(0).interval, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,233 @@
+use std::fmt::Display;
+
+use crate::cmd::{alias::resolve_alias, base_url_override, models::DcCmdError};
+
+use super::strings::NODE_ID_SCHEME;
+
+/// normalized path of a node in DRACOON (without domain)
+///
+/// empty segments (double / trailing slashes) and `.` are removed, `..` refers to
+/// the parent - an empty path is the root node
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemotePath {
+    segments: Vec<String>,
+}
+
+impl RemotePath {
+    /// parses a path including domain (e.g. `some.domain.com/room/folder/`) or alias (e.g. `proj1:/folder/`)
+    pub fn parse(path: &str, base_url: &str) -> Result<Self, DcCmdError> {
+        let domain = base_url
+            .trim_start_matches("https://")
+            .trim_end_matches('/');
+
+        let path = resolve_alias(path);
+        let path = path.trim();
+        let path = path
+            .strip_prefix("https://")
+            .or_else(|| path.strip_prefix(NODE_ID_SCHEME))
+            .unwrap_or(path);
+
+        // with --base-url, paths keep the domain of the instance (first segment)
+        let path = match base_url_override() {
+            Some(_) => path.split_once('/').map_or("", |(_, path)| path),
+            None => path.strip_prefix(domain).unwrap_or(path),
+        };
+
+        Self::from_node_path(path)
+    }
+
+    /// parses a path without domain (e.g. `/room/folder/`)
+    fn from_node_path(path: &str) -> Result<Self, DcCmdError> {
+        let mut segments: Vec<String> = Vec::new();
+
+        for segment in path.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    if segments.pop().is_none() {
+                        return Err(DcCmdError::InvalidPath(format!(
+                            "{path} (points above the root node)"
+                        )));
+                    }
+                }
+                segment if segment.chars().any(char::is_control) => {
+                    return Err(DcCmdError::InvalidPath(format!(
+                        "{path} (contains control characters)"
+                    )));
+                }
+                segment => segments.push(segment.to_string()),
+            }
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// name of the node (empty for the root node)
+    pub fn name(&self) -> &str {
+        self.segments.last().map_or("", String::as_str)
+    }
+
+    /// depth of the parent (0 for nodes on root level and the root node)
+    pub fn depth(&self) -> u64 {
+        self.segments.len().saturating_sub(1) as u64
+    }
+
+    /// path of the parent with leading and trailing slash (e.g. `/room/`)
+    pub fn parent_path(&self) -> String {
+        match self.segments.len() {
+            0 | 1 => String::from("/"),
+            len => format!("/{}/", self.segments[..len - 1].join("/")),
+        }
+    }
+}
+
+impl Display for RemotePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "/{}", self.segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemotePath;
+
+    const BASE_URL: &str = "https://some.domain.com/";
+
+    fn parse(path: &str) -> RemotePath {
+        RemotePath::parse(path, BASE_URL).unwrap()
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let path = parse("some.domain.com/room/folder/file.txt");
+
+        assert_eq!(path.name(), "file.txt");
+        assert_eq!(path.parent_path(), "/room/folder/");
+        assert_eq!(path.depth(), 2);
+        assert_eq!(path.to_string(), "/room/folder/file.txt");
+    }
+
+    #[test]
+    fn test_parse_https() {
+        assert_eq!(
+            parse("https://some.domain.com/room/folder"),
+            parse("some.domain.com/room/folder")
+        );
+    }
+
+    #[test]
+    fn test_parse_node_id_scheme() {
+        assert_eq!(
+            parse("dracoon://some.domain.com/room"),
+            parse("some.domain.com/room")
+        );
+    }
+
+    #[test]
+    fn test_parse_base_url_without_slash() {
+        let path = RemotePath::parse("some.domain.com/room", "https://some.domain.com").unwrap();
+
+        assert_eq!(path.name(), "room");
+        assert_eq!(path.depth(), 0);
+    }
+
+    #[test]
+    fn test_parse_root() {
+        for root in ["some.domain.com", "some.domain.com/", "some.domain.com//"] {
+            let path = parse(root);
+
+            assert_eq!(path.name(), "");
+            assert_eq!(path.parent_path(), "/");
+            assert_eq!(path.depth(), 0);
+            assert_eq!(path.to_string(), "/");
+        }
+    }
+
+    #[test]
+    fn test_parse_root_level() {
+        let path = parse("some.domain.com/room/");
+
+        assert_eq!(path.name(), "room");
+        assert_eq!(path.parent_path(), "/");
+        assert_eq!(path.depth(), 0);
+    }
+
+    #[test]
+    fn test_trailing_slashes() {
+        assert_eq!(
+            parse("some.domain.com/room/folder///"),
+            parse("some.domain.com/room/folder")
+        );
+    }
+
+    #[test]
+    fn test_double_slashes() {
+        let path = parse("some.domain.com//room//folder/file.txt");
+
+        assert_eq!(path.parent_path(), "/room/folder/");
+        assert_eq!(path.name(), "file.txt");
+        assert_eq!(path.depth(), 2);
+    }
+
+    #[test]
+    fn test_current_dir_segments() {
+        assert_eq!(
+            parse("some.domain.com/./room/./folder/."),
+            parse("some.domain.com/room/folder")
+        );
+    }
+
+    #[test]
+    fn test_parent_dir_segments() {
+        assert_eq!(
+            parse("some.domain.com/room/folder/../other"),
+            parse("some.domain.com/room/other")
+        );
+        assert_eq!(parse("some.domain.com/room/.."), parse("some.domain.com/"));
+    }
+
+    #[test]
+    fn test_parent_dir_above_root() {
+        assert!(RemotePath::parse("some.domain.com/..", BASE_URL).is_err());
+        assert!(RemotePath::parse("some.domain.com/room/../..", BASE_URL).is_err());
+    }
+
+    #[test]
+    fn test_control_characters() {
+        assert!(RemotePath::parse("some.domain.com/room/fo\nlder", BASE_URL).is_err());
+        assert!(RemotePath::parse("some.domain.com/room/fo\tlder", BASE_URL).is_err());
+    }
+
+    #[test]
+    fn test_whitespace_in_names() {
+        let path = parse("some.domain.com/my room/my file.txt");
+
+        assert_eq!(path.name(), "my file.txt");
+        assert_eq!(path.parent_path(), "/my room/");
+    }
+
+    #[test]
+    fn test_surrounding_whitespace() {
+        assert_eq!(
+            parse("  some.domain.com/room/folder \n"),
+            parse("some.domain.com/room/folder")
+        );
+    }
+
+    #[test]
+    fn test_search_query() {
+        let path = parse("some.domain.com/room/*.pdf");
+
+        assert_eq!(path.name(), "*.pdf");
+        assert_eq!(path.parent_path(), "/room/");
+    }
+
+    #[test]
+    fn test_from_node_path() {
+        let path = RemotePath::from_node_path("/room/folder/").unwrap();
+
+        assert_eq!(path.name(), "folder");
+        assert_eq!(path.parent_path(), "/room/");
+        assert_eq!(path.to_string(), "/room/folder");
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
+use serde_json::json;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use super::progress::progress_line_after;
+
+/// interval of stats line updates
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// counters of long-running batch jobs (imports, recursive uploads)
+#[derive(Debug)]
+pub struct BatchStats {
+    total: u64,
+    processed: AtomicU64,
+    errors: AtomicU64,
+    started: Instant,
+}
+
+impl BatchStats {
+    pub fn new(total: u64) -> Arc<Self> {
+        Arc::new(Self {
+            total,
+            processed: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            started: Instant::now(),
+        })
+    }
+
+    /// counts a processed item (failed items are counted as errors)
+    pub fn record(&self, success: bool) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        if !success {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> StatsSnapshot {
+        StatsSnapshot {
+            total: self.total,
+            processed: self.processed.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    /// shows a periodically updated stats line below the progress bar (on stderr)
+    ///
+    /// the progress bar is expected to be created via `progress::progress_bar`
+    pub fn attach(self: &Arc<Self>, progress_bar: &ProgressBar) -> StatsLine {
+        let line = progress_line_after(progress_bar);
+        line.set_style(ProgressStyle::with_template("  {msg}").unwrap());
+
+        let stats = self.clone();
+        let stats_line = line.clone();
+        let reporter = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(STATS_INTERVAL);
+            loop {
+                interval.tick().await;
+                stats_line.set_message(stats.snapshot().format_line());
+            }
+        });
+
+        StatsLine { line, reporter }
+    }
+
+    /// logs a final summary as JSON (e.g. for log aggregation of batch jobs)
+    pub fn log_summary(&self, operation: &str) {
+        info!("Summary: {}", self.snapshot().to_json(operation));
+    }
+}
+
+/// stats line of a batch job (stops updating on finish)
+pub struct StatsLine {
+    line: ProgressBar,
+    reporter: JoinHandle<()>,
+}
+
+impl StatsLine {
+    /// stops updates and keeps the last stats line
+    pub fn finish(self, stats: &BatchStats) {
+        self.reporter.abort();
+        self.line.finish_with_message(stats.snapshot().format_line());
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StatsSnapshot {
+    total: u64,
+    processed: u64,
+    errors: u64,
+    elapsed: Duration,
+}
+
+impl StatsSnapshot {
+    /// processed items per second
+    fn rate(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.processed as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// estimated remaining time (None without progress)
+    fn eta(&self) -> Option<Duration> {
+        let rate = self.rate();
+        if rate > 0.0 {
+            let remaining = self.total.saturating_sub(self.processed) as f64;
+            Some(Duration::from_secs_f64(remaining / rate))
+        } else {
+            None
+        }
+    }
+
+    fn format_line(&self) -> String {
+        let eta = self
+            .eta()
+            .map_or_else(|| "-".to_string(), |eta| HumanDuration(eta).to_string());
+
+        format!(
+            "{}/{} processed | {:.1}/s | ETA {eta} | {} errors",
+            self.processed,
+            self.total,
+            self.rate(),
+            self.errors
+        )
+    }
+
+    fn to_json(self, operation: &str) -> serde_json::Value {
+        json!({
+            "operation": operation,
+            "total": self.total,
+            "processed": self.processed,
+            "errors": self.errors,
+            "elapsed_secs": self.elapsed.as_secs_f64(),
+            "rate_per_sec": self.rate(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(processed: u64, elapsed_secs: u64) -> StatsSnapshot {
+        StatsSnapshot {
+            total: 100,
+            processed,
+            errors: 2,
+            elapsed: Duration::from_secs(elapsed_secs),
+        }
+    }
+
+    #[test]
+    fn test_rate() {
+        assert!((snapshot(50, 10).rate() - 5.0).abs() < f64::EPSILON);
+        assert!(snapshot(0, 0).rate().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_eta() {
+        assert_eq!(snapshot(50, 10).eta(), Some(Duration::from_secs(10)));
+        assert_eq!(snapshot(0, 10).eta(), None);
+    }
+
+    #[test]
+    fn test_format_line() {
+        assert_eq!(
+            "0/100 processed | 0.0/s | ETA - | 2 errors",
+            snapshot(0, 10).format_line()
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let stats = BatchStats::new(3);
+        stats.record(true);
+        stats.record(false);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.processed, 2);
+        assert_eq!(snapshot.errors, 1);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = snapshot(100, 10).to_json("users import");
+        assert_eq!(json["operation"], "users import");
+        assert_eq!(json["processed"], 100);
+        assert_eq!(json["errors"], 2);
+    }
+}
@@ -1,10 +1,15 @@
 use std::cmp::min;
 
-use crate::cmd::models::DcCmdError;
+use crate::cmd::{
+    models::DcCmdError,
+    nodes::virus::{take_scan_verdict, VirusProtectionVerdict},
+};
+
+use super::remote_path::RemotePath;
 
 use dco3::nodes::models::{Node, NodeType};
 
-use console::{style, Term};
+use console::style;
 use tracing::debug;
 
 const ERROR_PREFIX: &str = "Error: ";
@@ -22,7 +27,24 @@ pub fn format_success_message(message: &str) -> String {
     format!("{succ_prefix_green} {message}")
 }
 
-pub fn print_node(term: &Term, node: &Node, long: Option<bool>, human_readable: Option<bool>) {
+/// header line of the long node output (column names aligned with `format_node`)
+pub fn format_node_header(human_readable: bool) -> String {
+    let size_width = if human_readable { 8 } else { 16 };
+
+    format!(
+        "{:<12} {:<15} {:<15} {:<15} {:<size_width$} {:<16} {:<10} {}",
+        "id",
+        "permissions",
+        "first name",
+        "last name",
+        "size",
+        "modified",
+        "scan",
+        "name"
+    )
+}
+
+pub fn format_node(node: &Node, long: Option<bool>, human_readable: Option<bool>) -> String {
     let mut node_str = String::new();
 
     let long = long.unwrap_or(false);
@@ -66,6 +88,12 @@ pub fn print_node(term: &Term, node: &Node, long: Option<bool>, human_readable:
             }
             None => node_str.push_str("n/a"),
         }
+
+        // add virus scan status (files only)
+        match virus_scan_status(take_scan_verdict(node.id)) {
+            "malicious" => node_str.push_str(&format!("{:<10} ", style("malicious").red().bold())),
+            status => node_str.push_str(&format!("{status:<10} ")),
+        }
     }
 
     // add node name
@@ -77,8 +105,17 @@ pub fn print_node(term: &Term, node: &Node, long: Option<bool>, human_readable:
         )),
     }
 
-    term.write_line(&node_str)
-        .expect("Could not write to terminal");
+    node_str
+}
+
+/// virus scan status of a file (`-` for containers or if virus protection is disabled)
+pub fn virus_scan_status(verdict: Option<VirusProtectionVerdict>) -> &'static str {
+    match verdict {
+        Some(VirusProtectionVerdict::Malicious) => "malicious",
+        Some(VirusProtectionVerdict::Clean) => "clean",
+        Some(VirusProtectionVerdict::InProgress) => "scanning",
+        Some(VirusProtectionVerdict::NoScanning) | None => "-",
+    }
 }
 
 fn to_printable_permissions(node: &Node) -> String {
@@ -135,34 +172,178 @@ pub fn to_readable_size(size: u64) -> String {
     }
 }
 
-type ParsedPath = (String, String, u64);
-pub fn parse_path(path: &str, base_url: &str) -> Result<ParsedPath, DcCmdError> {
-    let base_url = base_url.trim_start_matches("https://");
-    let path = path.trim_start_matches("https://");
-    let path = path.trim_start_matches(base_url).trim_start_matches('/');
+/// parses a size with optional unit (e.g. `500MB`, `2 GB`, `1024`) to bytes
+pub fn parse_size(size: &str) -> Result<u64, DcCmdError> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(size.len());
+    let (value, unit) = size.split_at(unit_start);
+
+    let value = value
+        .parse::<u64>()
+        .map_err(|_| DcCmdError::InvalidArgument(format!("Invalid size: {size}")))?;
+
+    let exp = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" => 1,
+        "M" | "MB" => 2,
+        "G" | "GB" => 3,
+        "T" | "TB" => 4,
+        _ => {
+            return Err(DcCmdError::InvalidArgument(format!(
+                "Invalid size unit: {unit} (use B, KB, MB, GB or TB)"
+            )))
+        }
+    };
 
-    debug!("path: {}", path);
+    value
+        .checked_mul(1u64 << (exp * 10))
+        .ok_or(DcCmdError::InvalidArgument(format!(
+            "Size too large: {size}"
+        )))
+}
 
-    let path_parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
-    debug!("path parts: {:?}", path_parts);
+// characters not allowed in file names on Windows
+const INVALID_WINDOWS_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const RESERVED_WINDOWS_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
 
-    let name = (*path_parts
-        .last()
-        .ok_or(DcCmdError::InvalidPath(path.to_string()))?)
-    .to_string();
-    let depth = path_parts.len().saturating_sub(1) as u64;
+/// checks if a node name is a valid file name on the current OS
+pub fn is_valid_file_name(name: &str) -> bool {
+    sanitize_file_name(name) == name
+}
+
+/// replaces characters invalid on the current OS with `_`
+pub fn sanitize_file_name(name: &str) -> String {
+    sanitize_file_name_for_os(name, cfg!(windows))
+}
+
+/// sanitizes every segment of a relative path (separated by `/`)
+pub fn sanitize_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.is_empty() {
+                segment.to_string()
+            } else {
+                sanitize_file_name(segment)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn sanitize_file_name_for_os(name: &str, is_windows: bool) -> String {
+    if !is_windows {
+        return name.replace(['/', '\0'], "_");
+    }
 
-    let parent_path = if depth == 0 {
-        String::from("/")
+    let sanitized = name
+        .chars()
+        .map(|c| {
+            if c.is_control() || INVALID_WINDOWS_CHARS.contains(&c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+
+    // trailing dots and spaces are stripped by Windows
+    let trimmed = sanitized.trim_end_matches(['.', ' ']);
+    let mut sanitized = if trimmed.len() == sanitized.len() {
+        sanitized
     } else {
-        format!("/{}/", path_parts[..path_parts.len() - 1].join("/"))
+        format!("{trimmed}_")
     };
 
-    debug!("parent path: {}", parent_path);
-    debug!("name: {}", name);
-    debug!("depth: {}", depth);
+    // reserved device names are invalid (also with extension, e.g. NUL.txt)
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if RESERVED_WINDOWS_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        sanitized.insert(stem.len(), '_');
+    }
 
-    Ok((parent_path, name, depth))
+    sanitized
+}
+
+/// matches a name against a glob pattern (`*` matches any sequence, `?` any single character)
+///
+/// matching is case insensitive (e.g. `*.pdf` matches `report.PDF`)
+pub fn matches_glob(name: &str, pattern: &str) -> bool {
+    let name = name.to_lowercase().chars().collect::<Vec<_>>();
+    let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+
+    let (mut n, mut p) = (0, 0);
+    // position of the last `*` in pattern and the name position it was matched at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                n += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                // let the last `*` consume one more character
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+type ParsedPath = (String, String, u64);
+
+/// scheme to address nodes by id instead of path
+pub const NODE_ID_SCHEME: &str = "dracoon://";
+/// returns the node id if a node is addressed by id (`dracoon://<domain>/<node-id>`)
+pub fn parse_node_id(path: &str) -> Option<u64> {
+    let path = path.strip_prefix(NODE_ID_SCHEME)?;
+    let (_, node_id) = path.trim_end_matches('/').split_once('/')?;
+
+    node_id.parse().ok()
+}
+
+/// addresses a node by id on the DRACOON instance of a path (`--node-id`)
+pub fn with_node_id(source: String, node_id: Option<u64>) -> String {
+    let Some(node_id) = node_id else {
+        return source;
+    };
+
+    let domain = source
+        .strip_prefix(NODE_ID_SCHEME)
+        .or_else(|| source.strip_prefix("https://"))
+        .unwrap_or(&source);
+    let domain = domain.split('/').next().unwrap_or_default();
+
+    format!("{NODE_ID_SCHEME}{domain}/{node_id}")
+}
+
+/// parses a path (including domain) into parent path, name and depth (see `RemotePath`)
+pub fn parse_path(path: &str, base_url: &str) -> Result<ParsedPath, DcCmdError> {
+    let path = RemotePath::parse(path, base_url)?;
+
+    debug!("path: {}", path);
+    debug!("parent path: {}", path.parent_path());
+    debug!("name: {}", path.name());
+    debug!("depth: {}", path.depth());
+
+    Ok((path.parent_path(), path.name().to_string(), path.depth()))
 }
 
 pub fn build_node_path(path: ParsedPath) -> String {
@@ -288,6 +469,101 @@ mod tests {
         assert_eq!(2, depth);
     }
 
+    #[test]
+    fn test_parse_node_id() {
+        assert_eq!(Some(123), parse_node_id("dracoon://bla.dracoon.com/123"));
+        assert_eq!(Some(123), parse_node_id("dracoon://bla.dracoon.com/123/"));
+    }
+
+    #[test]
+    fn test_parse_node_id_path() {
+        assert_eq!(None, parse_node_id("bla.dracoon.com/123"));
+        assert_eq!(None, parse_node_id("https://bla.dracoon.com/room"));
+        assert_eq!(None, parse_node_id("dracoon://bla.dracoon.com/room"));
+        assert_eq!(None, parse_node_id("dracoon://bla.dracoon.com/room/123"));
+        assert_eq!(None, parse_node_id("dracoon://123"));
+    }
+
+    #[test]
+    fn test_with_node_id() {
+        let expected = "dracoon://bla.dracoon.com/123";
+        assert_eq!(expected, with_node_id("bla.dracoon.com/".into(), Some(123)));
+        assert_eq!(expected, with_node_id("https://bla.dracoon.com/room".into(), Some(123)));
+        assert_eq!(expected, with_node_id("dracoon://bla.dracoon.com/456".into(), Some(123)));
+        assert_eq!(expected, with_node_id("bla.dracoon.com".into(), Some(123)));
+    }
+
+    #[test]
+    fn test_with_node_id_none() {
+        assert_eq!(
+            "bla.dracoon.com/room",
+            with_node_id("bla.dracoon.com/room".into(), None)
+        );
+    }
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("report.pdf", "*.pdf"));
+        assert!(matches_glob("report.PDF", "*.pdf"));
+        assert!(matches_glob("report.pdf", "report*"));
+        assert!(matches_glob("report.pdf", "*"));
+        assert!(matches_glob("report.pdf", "rep?rt.*"));
+        assert!(matches_glob("a.b.tmp", "*.tmp"));
+        assert!(matches_glob("report.pdf", "report.pdf"));
+        assert!(matches_glob("2024-report-final.pdf", "*report*.pdf"));
+    }
+
+    #[test]
+    fn test_matches_glob_no_match() {
+        assert!(!matches_glob("report.pdf", "*.tmp"));
+        assert!(!matches_glob("report.pdf.bak", "*.pdf"));
+        assert!(!matches_glob("report.pdf", "?.pdf"));
+        assert!(!matches_glob("report.pdf", "report"));
+        assert!(!matches_glob("", "?"));
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(1024, parse_size("1024").unwrap());
+        assert_eq!(1024, parse_size("1KB").unwrap());
+        assert_eq!(500 * 1024 * 1024, parse_size("500MB").unwrap());
+        assert_eq!(2 * 1024 * 1024 * 1024, parse_size("2 gb").unwrap());
+        assert_eq!(3 * 1024 * 1024 * 1024 * 1024, parse_size("3T").unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid_size() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("MB").is_err());
+        assert!(parse_size("5 XB").is_err());
+        assert!(parse_size("1.5GB").is_err());
+    }
+
+    #[test]
+    fn test_sanitize_file_name_windows() {
+        assert_eq!("a_b.txt", sanitize_file_name_for_os("a:b.txt", true));
+        assert_eq!("a_b_c_", sanitize_file_name_for_os("a<b>c?", true));
+        assert_eq!("report_", sanitize_file_name_for_os("report. ", true));
+        assert_eq!("CON_", sanitize_file_name_for_os("CON", true));
+        assert_eq!("nul_.txt", sanitize_file_name_for_os("nul.txt", true));
+        assert_eq!(
+            "console.txt",
+            sanitize_file_name_for_os("console.txt", true)
+        );
+    }
+
+    #[test]
+    fn test_sanitize_file_name_unix() {
+        assert_eq!("a:b.txt", sanitize_file_name_for_os("a:b.txt", false));
+        assert_eq!("CON", sanitize_file_name_for_os("CON", false));
+    }
+
+    #[test]
+    fn test_sanitize_valid_file_name() {
+        assert_eq!("file.txt", sanitize_file_name_for_os("file.txt", true));
+        assert!(is_valid_file_name("file.txt"));
+    }
+
     #[test]
     fn test_root_path() {
         let path = "some.domain.com/";
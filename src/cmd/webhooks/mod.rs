@@ -0,0 +1,188 @@
+use console::Term;
+use dco3::{auth::Connected, Dracoon, RangedItems};
+use reqwest::Method;
+use tabled::{
+    settings::{object::Segment, Modify, Panel, Style, Width},
+    Table,
+};
+
+mod models;
+
+use models::{CreateWebhookRequest, Webhook, WebhookEventType, WebhookEventTypeList, WebhookInfo};
+
+use super::{
+    init_dracoon,
+    models::{DcCmdError, WebhooksCommand},
+    utils::{
+        api::{api_request, send_api_json, send_api_request},
+        fields::CsvOutput,
+        pagination::{fetch_all_paginated, PAGE_SIZE},
+        strings::format_success_message,
+    },
+};
+
+/// customer admin webhooks (dco3 does not provide a webhooks API)
+pub struct WebhookCommandHandler {
+    client: Dracoon<Connected>,
+    term: Term,
+}
+
+impl WebhookCommandHandler {
+    pub async fn try_new(target_domain: String, term: Term) -> Result<Self, DcCmdError> {
+        let client = init_dracoon(&target_domain, None, false).await?;
+
+        Ok(Self { client, term })
+    }
+
+    async fn list_webhooks(&self, csv: bool) -> Result<(), DcCmdError> {
+        let webhooks = get_webhooks_page(&self.client, 0).await?;
+        let client = self.client.clone();
+
+        let webhooks = fetch_all_paginated(webhooks, move |offset| {
+            let client = client.clone();
+
+            async move { get_webhooks_page(&client, offset).await }
+        })
+        .await?;
+
+        self.print_webhooks(webhooks.items, csv)
+    }
+
+    async fn list_event_types(&self) -> Result<(), DcCmdError> {
+        let request =
+            api_request(&self.client, Method::GET, "settings/webhooks/event_types").await?;
+        let event_types = send_api_json::<WebhookEventTypeList>(request).await?;
+
+        event_types
+            .items
+            .iter()
+            .filter(|event_type| event_type.usable_customer_admin_webhook)
+            .try_for_each(|event_type| self.print_event_type(event_type))
+    }
+
+    async fn create_webhook(&self, req: CreateWebhookRequest) -> Result<(), DcCmdError> {
+        let request = api_request(&self.client, Method::POST, "settings/webhooks")
+            .await?
+            .json(&req);
+        let webhook = send_api_json::<Webhook>(request).await?;
+
+        let msg = format!("Webhook {} ({}) created", webhook.name, webhook.id);
+
+        self.term
+            .write_line(format_success_message(&msg).as_str())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    async fn delete_webhook(&self, webhook_id: u64) -> Result<(), DcCmdError> {
+        let request = api_request(
+            &self.client,
+            Method::DELETE,
+            &format!("settings/webhooks/{webhook_id}"),
+        )
+        .await?;
+        send_api_request(request).await?;
+
+        let msg = format!("Webhook {webhook_id} deleted");
+
+        self.term
+            .write_line(format_success_message(&msg).as_str())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    fn print_webhooks(&self, webhooks: Vec<Webhook>, csv: bool) -> Result<(), DcCmdError> {
+        if csv {
+            let output = CsvOutput::new(&self.term, "id,name,url,event_types,enabled,expire_at")?;
+
+            for webhook in webhooks {
+                output.write_line(&format!(
+                    "{},{},{},{},{},{}",
+                    webhook.id,
+                    webhook.name,
+                    webhook.url,
+                    webhook.event_type_names.join(";"),
+                    webhook.is_enabled,
+                    webhook.expire_at.unwrap_or_else(|| "N/A".to_string())
+                ))?;
+            }
+
+            return Ok(());
+        }
+
+        let webhooks = webhooks
+            .into_iter()
+            .map(WebhookInfo::from)
+            .collect::<Vec<_>>();
+        let count = webhooks.len();
+
+        let mut table = Table::new(webhooks);
+        table
+            .with(Panel::footer(format!("{count} webhooks")))
+            .with(Style::modern())
+            .with(Modify::new(Segment::all()).with(Width::wrap(32)));
+
+        self.term
+            .write_line(&table.to_string())
+            .map_err(|_| DcCmdError::IoError)
+    }
+
+    fn print_event_type(&self, event_type: &WebhookEventType) -> Result<(), DcCmdError> {
+        let scope = if event_type.usable_node_webhook {
+            "customer, room"
+        } else {
+            "customer"
+        };
+
+        self.term
+            .write_line(&format!(
+                "{:<6} {:<40} {scope}",
+                event_type.id, event_type.name
+            ))
+            .map_err(|_| DcCmdError::IoError)
+    }
+}
+
+async fn get_webhooks_page(
+    dracoon: &Dracoon<Connected>,
+    offset: u64,
+) -> Result<RangedItems<Webhook>, DcCmdError> {
+    let request = api_request(dracoon, Method::GET, "settings/webhooks")
+        .await?
+        .query(&[("offset", offset), ("limit", PAGE_SIZE)]);
+
+    send_api_json(request).await
+}
+
+pub async fn handle_webhooks_cmd(cmd: WebhooksCommand, term: Term) -> Result<(), DcCmdError> {
+    let target = match &cmd {
+        WebhooksCommand::Ls { target, .. }
+        | WebhooksCommand::EventTypes { target }
+        | WebhooksCommand::Create { target, .. }
+        | WebhooksCommand::Rm { target, .. } => target,
+    };
+
+    let handler = WebhookCommandHandler::try_new(target.to_string(), term).await?;
+
+    match cmd {
+        WebhooksCommand::Ls { csv, .. } => handler.list_webhooks(csv).await,
+        WebhooksCommand::EventTypes { .. } => handler.list_event_types().await,
+        WebhooksCommand::Create {
+            name,
+            url,
+            event_types,
+            secret,
+            disabled,
+            ..
+        } => {
+            handler
+                .create_webhook(CreateWebhookRequest {
+                    name,
+                    event_type_names: event_types,
+                    url,
+                    secret,
+                    is_enabled: !disabled,
+                })
+                .await
+        }
+        WebhooksCommand::Rm { webhook_id, .. } => handler.delete_webhook(webhook_id).await,
+    }
+}
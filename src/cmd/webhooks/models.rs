@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use tabled::Tabled;
+
+/// webhooks are not provided by dco3 and requested directly
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: u64,
+    pub name: String,
+    pub url: String,
+    pub is_enabled: bool,
+    #[serde(default)]
+    pub event_type_names: Vec<String>,
+    pub expire_at: Option<String>,
+    pub fail_status: Option<u16>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEventType {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub usable_customer_admin_webhook: bool,
+    #[serde(default)]
+    pub usable_node_webhook: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WebhookEventTypeList {
+    pub items: Vec<WebhookEventType>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateWebhookRequest {
+    pub name: String,
+    pub event_type_names: Vec<String>,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    pub is_enabled: bool,
+}
+
+#[derive(Tabled)]
+pub struct WebhookInfo {
+    pub id: u64,
+    pub name: String,
+    pub url: String,
+    pub event_types: String,
+    pub enabled: bool,
+    #[tabled(display_with = "crate::cmd::users::display_option")]
+    pub expire_at: Option<String>,
+    #[tabled(display_with = "crate::cmd::users::display_option")]
+    pub fail_status: Option<u16>,
+}
+
+impl From<Webhook> for WebhookInfo {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id,
+            name: webhook.name,
+            url: webhook.url,
+            event_types: webhook.event_type_names.join(", "),
+            enabled: webhook.is_enabled,
+            expire_at: webhook.expire_at,
+            fail_status: webhook.fail_status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_webhook() {
+        let json = r#"{
+            "id": 1,
+            "name": "audit",
+            "url": "https://hooks.example.com/dracoon",
+            "isEnabled": true,
+            "eventTypeNames": ["file.created", "file.deleted"],
+            "expireAt": null,
+            "createdAt": "2024-01-01T00:00:00Z",
+            "failStatus": 500
+        }"#;
+
+        let webhook = serde_json::from_str::<Webhook>(json).unwrap();
+        let info = WebhookInfo::from(webhook);
+
+        assert_eq!(info.id, 1);
+        assert_eq!(info.event_types, "file.created, file.deleted");
+        assert!(info.enabled);
+        assert_eq!(info.fail_status, Some(500));
+        assert!(info.expire_at.is_none());
+    }
+
+    #[test]
+    fn test_parse_webhook_without_event_types() {
+        let json = r#"{"id": 2, "name": "n", "url": "https://x", "isEnabled": false}"#;
+
+        let webhook = serde_json::from_str::<Webhook>(json).unwrap();
+
+        assert!(webhook.event_type_names.is_empty());
+        assert!(!webhook.is_enabled);
+    }
+
+    #[test]
+    fn test_create_webhook_request() {
+        let req = CreateWebhookRequest {
+            name: "audit".to_string(),
+            event_type_names: vec!["file.created".to_string()],
+            url: "https://hooks.example.com".to_string(),
+            secret: None,
+            is_enabled: true,
+        };
+
+        let json = serde_json::to_value(&req).unwrap();
+
+        assert_eq!(json["eventTypeNames"][0], "file.created");
+        assert_eq!(json["isEnabled"], true);
+        assert!(json.get("secret").is_none());
+
+        let req = CreateWebhookRequest {
+            secret: Some("s3cr3t".to_string()),
+            ..req
+        };
+
+        assert_eq!(serde_json::to_value(&req).unwrap()["secret"], "s3cr3t");
+    }
+
+    #[test]
+    fn test_parse_event_types() {
+        let json = r#"{"items": [
+            {"id": 1, "name": "file.created", "usableCustomerAdminWebhook": true, "usableNodeWebhook": true},
+            {"id": 2, "name": "user.created", "usableCustomerAdminWebhook": true}
+        ]}"#;
+
+        let event_types = serde_json::from_str::<WebhookEventTypeList>(json).unwrap();
+
+        assert_eq!(event_types.items.len(), 2);
+        assert!(event_types.items[0].usable_node_webhook);
+        assert!(!event_types.items[1].usable_node_webhook);
+    }
+}
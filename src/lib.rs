@@ -0,0 +1,91 @@
+//! dccmd-rs - a command line client for DRACOON
+//!
+//! Besides the `dccmd-rs` binary, the high-level operations (upload, download, sync and
+//! user import) are exposed as a library to embed them in other Rust tools without
+//! shelling out.
+//!
+//! All operations take a DRACOON path (e.g. `your.dracoon.domain/some/room`) and options
+//! built via builders (e.g. [`CmdUploadOptions::builder`]) - the same types used by the CLI,
+//! but independent of command line parsing.
+//! Authentication works like in the CLI: pass a [`PasswordAuth`] in the options or
+//! use the refresh token stored in the credential store (authorization code flow).
+//!
+//! ```no_run
+//! use std::{path::PathBuf, time::Duration};
+//!
+//! use dccmd_rs::{sync, CmdSyncOptions, DcCmdError};
+//!
+//! #[tokio::main]
+//! async fn main() -> Result<(), DcCmdError> {
+//!     let opts = CmdSyncOptions::builder()
+//!         .with_interval(Duration::from_secs(300))
+//!         .with_assume_yes(true)
+//!         .build();
+//!
+//!     sync(
+//!         PathBuf::from("/path/to/folder"),
+//!         "your.dracoon.domain/some/room".to_string(),
+//!         opts,
+//!     )
+//!     .await
+//! }
+//! ```
+//!
+//! Status messages are written to stdout. Large recursive operations ask for confirmation
+//! on the terminal unless confirmed via the options (`with_assume_yes` of the builders).
+//! Requests are not throttled unless a rate limit is set via [`init_rate_limit`] - all
+//! other global settings of the CLI (e.g. the credential store) use their defaults.
+
+#![allow(clippy::module_name_repetitions)]
+#![allow(clippy::struct_excessive_bools)]
+
+// the binary compiles the same modules (CLI) - the library only exposes the operations below
+#[allow(dead_code)]
+mod cmd;
+
+use std::path::PathBuf;
+
+use console::Term;
+
+pub use cmd::{
+    models::{DcCmdError, PasswordAuth},
+    nodes::{
+        download::download,
+        models::{
+            ClassificationLevel, CmdDownloadOptions, CmdDownloadOptionsBuilder, CmdSyncOptions,
+            CmdSyncOptionsBuilder, CmdUploadOptions, CmdUploadOptionsBuilder, CmdWatchOptions,
+            ShareNotification,
+        },
+    },
+    users::{models::CmdImportUsersOptionsBuilder, CmdImportUsersOptions},
+    utils::rate_limit::init_rate_limit,
+};
+
+/// uploads a file or directory (`recursive`) to DRACOON (target is the DRACOON path)
+pub async fn upload(
+    source: PathBuf,
+    target: String,
+    opts: CmdUploadOptions,
+) -> Result<(), DcCmdError> {
+    cmd::nodes::upload::upload(Term::stdout(), source, target, opts).await
+}
+
+/// watches a local directory and uploads new or changed files until cancelled
+pub async fn watch_upload(
+    source: PathBuf,
+    target: String,
+    opts: CmdUploadOptions,
+    watch_opts: CmdWatchOptions,
+) -> Result<(), DcCmdError> {
+    cmd::nodes::upload::watch_upload(Term::stdout(), source, target, opts, watch_opts).await
+}
+
+/// synchronizes a local directory into DRACOON (repeated if an interval is set)
+pub async fn sync(source: PathBuf, target: String, opts: CmdSyncOptions) -> Result<(), DcCmdError> {
+    cmd::nodes::sync::sync(Term::stdout(), source, target, opts).await
+}
+
+/// imports users from a CSV file into DRACOON (target is the DRACOON url)
+pub async fn import_users(target: &str, opts: CmdImportUsersOptions) -> Result<(), DcCmdError> {
+    cmd::users::import_users(Term::stdout(), target, opts).await
+}